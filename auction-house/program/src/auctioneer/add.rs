@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::AuctionHouseError, AuctionHouse, Auctioneer, AuthorityScope};
+
+/// Accounts for the [`add_auctioneer` handler](auction_house/fn.add_auctioneer.html).
+#[derive(Accounts)]
+pub struct AddAuctioneer<'info> {
+    // Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: The auction house authority can set this to whatever external address they wish.
+    /// The auctioneer authority - the program PDA running this auction.
+    pub auctioneer_authority: UncheckedAccount<'info>,
+
+    /// The auctioneer PDA owned by Auction House storing scopes.
+    #[account(
+        init,
+        payer = authority,
+        space = AUCTIONEER_SIZE,
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub ah_auctioneer_pda: Account<'info, Auctioneer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Delegate one more auctioneer next to the ones the Auction House already has.
+/// Its scopes are stored in its own PDA, so non-auctioneer handlers keep working for listings it does not own.
+pub fn add_auctioneer<'info>(
+    ctx: Context<'_, '_, '_, 'info, AddAuctioneer<'info>>,
+    scopes: Vec<AuthorityScope>,
+) -> Result<()> {
+    if scopes.len() > MAX_NUM_SCOPES {
+        return Err(AuctionHouseError::TooManyScopes.into());
+    }
+
+    let auction_house = &mut ctx.accounts.auction_house;
+    if auction_house.has_governance {
+        return Err(AuctionHouseError::GovernanceRequired.into());
+    }
+    auction_house.has_auctioneer = true;
+
+    let auctioneer = &mut ctx.accounts.ah_auctioneer_pda;
+    auctioneer.auctioneer_authority = ctx.accounts.auctioneer_authority.key();
+    auctioneer.auction_house = ctx.accounts.auction_house.key();
+    auctioneer.bump = *ctx
+        .bumps
+        .get("ah_auctioneer_pda")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    // Set all scopes false and then update as true the ones passed into the handler.
+    auctioneer.scopes = [false; MAX_NUM_SCOPES];
+    for scope in scopes {
+        auctioneer.scopes[scope as usize] = true;
+    }
+
+    Ok(())
+}
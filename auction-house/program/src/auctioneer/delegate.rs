@@ -51,8 +51,12 @@ pub fn delegate_auctioneer<'info>(
     }
 
     let auction_house = &mut ctx.accounts.auction_house;
+    if auction_house.has_governance {
+        return Err(AuctionHouseError::GovernanceRequired.into());
+    }
 
-    if auction_house.has_auctioneer {
+    // Auctioneers added with `add_auctioneer` do not take the place of the delegated one.
+    if auction_house.auctioneer_address != Pubkey::default() {
         return Err(AuctionHouseError::AuctionHouseAlreadyDelegated.into());
     }
 
@@ -64,6 +68,7 @@ pub fn delegate_auctioneer<'info>(
     for scope in scopes {
         auction_house.scopes[scope as usize] = true;
     }
+    let scopes = auction_house.scopes;
 
     let auctioneer = &mut ctx.accounts.ah_auctioneer_pda;
     auctioneer.auctioneer_authority = ctx.accounts.auctioneer_authority.key();
@@ -72,6 +77,7 @@ pub fn delegate_auctioneer<'info>(
         .bumps
         .get("ah_auctioneer_pda")
         .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    auctioneer.scopes = scopes;
 
     Ok(())
 }
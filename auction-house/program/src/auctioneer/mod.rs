@@ -1,4 +1,6 @@
+pub mod add;
 pub mod delegate;
 pub mod update;
+pub use add::*;
 pub use delegate::*;
 pub use update::*;
@@ -49,19 +49,29 @@ pub fn update_auctioneer<'info>(
     }
 
     let auction_house = &mut ctx.accounts.auction_house;
+    if auction_house.has_governance {
+        return Err(AuctionHouseError::GovernanceRequired.into());
+    }
     if !auction_house.has_auctioneer {
         return Err(AuctionHouseError::AuctionHouseNotDelegated.into());
     }
 
     // Set all scopes false and then update as true the ones passed into the handler.
-    auction_house.scopes = [false; MAX_NUM_SCOPES];
+    let mut new_scopes = [false; MAX_NUM_SCOPES];
     for scope in scopes {
-        auction_house.scopes[scope as usize] = true;
+        new_scopes[scope as usize] = true;
+    }
+
+    // The delegated auctioneer keeps its scopes on the Auction House, added auctioneers only in their own PDA.
+    let auctioneer_key = ctx.accounts.ah_auctioneer_pda.key();
+    if auction_house.auctioneer_address == auctioneer_key {
+        auction_house.scopes = new_scopes;
     }
 
     let auctioneer = &mut ctx.accounts.ah_auctioneer_pda;
     auctioneer.auctioneer_authority = ctx.accounts.auctioneer_authority.key();
     auctioneer.auction_house = ctx.accounts.auction_house.key();
+    auctioneer.scopes = new_scopes;
 
     Ok(())
 }
@@ -1,17 +1,25 @@
 //! Create both private and public bids.
 //! A private bid is a bid on a specific NFT *held by a specific person*. A public bid is a bid on a specific NFT *regardless of who holds it*.
+//! A collection bid is a bid on *any* NFT of a verified collection, and a trait bid is a bid on *any* NFT whose mint is in a merkle tree of eligible mints.
 
 use anchor_lang::{
     prelude::*,
     solana_program::{program::invoke, system_instruction},
     AnchorDeserialize,
 };
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token::{Mint, TokenAccount};
 use solana_program::program_memory::sol_memset;
 
 use crate::{
-    constants::*, errors::AuctionHouseError, utils::*, AuctionHouse, Auctioneer, AuthorityScope,
-    TRADE_STATE_SIZE,
+    constants::*,
+    curation::assert_collection_list_allows,
+    errors::AuctionHouseError,
+    events::BidEvent,
+    gateway::assert_gateway_token,
+    reservation::{escrow_balance, reserve_bid, split_escrow_reservation},
+    shared_escrow::{shared_escrow_balance, split_shared_escrow},
+    utils::*,
+    AuctionHouse, Auctioneer, AuthorityScope, TRADE_STATE_SIZE,
 };
 
 /// Accounts for the [`public_bid` handler](fn.public_bid.html).
@@ -32,7 +40,9 @@ pub struct PublicBuy<'info> {
     /// CHECK: Validated in public_bid_logic.
     transfer_authority: UncheckedAccount<'info>,
 
-    treasury_mint: Box<Account<'info, Mint>>,
+    /// CHECK: Validated by the auction house has_one constraint.
+    /// Auction House instance treasury mint account.
+    treasury_mint: UncheckedAccount<'info>,
     token_account: Box<Account<'info, TokenAccount>>,
 
     /// CHECK: Validated in public_bid_logic.
@@ -95,15 +105,17 @@ pub struct PublicBuy<'info> {
     )]
     buyer_trade_state: UncheckedAccount<'info>,
 
-    token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in bid_logic.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
 }
 
 /// Create a bid on a specific SPL token.
 /// Public bids are specific to the token itself, rather than the auction, and remain open indefinitely until either the user closes it or the requirements for the bid are met and it is matched with a counter bid and closed as a transaction.
-pub fn public_bid(
-    ctx: Context<PublicBuy>,
+pub fn public_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, PublicBuy<'info>>,
     trade_state_bump: u8,
     escrow_payment_bump: u8,
     buyer_price: u64,
@@ -113,7 +125,7 @@ pub fn public_bid(
         ctx.accounts.wallet.to_owned(),
         ctx.accounts.payment_account.to_owned(),
         ctx.accounts.transfer_authority.to_owned(),
-        *ctx.accounts.treasury_mint.to_owned(),
+        ctx.accounts.treasury_mint.to_owned(),
         *ctx.accounts.token_account.to_owned(),
         ctx.accounts.metadata.to_owned(),
         ctx.accounts.escrow_payment_account.to_owned(),
@@ -135,6 +147,7 @@ pub fn public_bid(
         *ctx.bumps
             .get("buyer_trade_state")
             .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        ctx.remaining_accounts,
     )
 }
 
@@ -156,7 +169,9 @@ pub struct AuctioneerPublicBuy<'info> {
     /// CHECK: Validated in public_bid_logic.
     transfer_authority: UncheckedAccount<'info>,
 
-    treasury_mint: Box<Account<'info, Mint>>,
+    /// CHECK: Validated by the auction house has_one constraint.
+    /// Auction House instance treasury mint account.
+    treasury_mint: UncheckedAccount<'info>,
 
     token_account: Box<Account<'info, TokenAccount>>,
 
@@ -235,15 +250,17 @@ pub struct AuctioneerPublicBuy<'info> {
     )]
     pub ah_auctioneer_pda: Account<'info, Auctioneer>,
 
-    token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in auctioneer_bid_logic.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
 }
 
 /// Create a bid on a specific SPL token.
 /// Public bids are specific to the token itself, rather than the auction, and remain open indefinitely until either the user closes it or the requirements for the bid are met and it is matched with a counter bid and closed as a transaction.
-pub fn auctioneer_public_bid(
-    ctx: Context<AuctioneerPublicBuy>,
+pub fn auctioneer_public_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerPublicBuy<'info>>,
     trade_state_bump: u8,
     escrow_payment_bump: u8,
     buyer_price: u64,
@@ -253,7 +270,7 @@ pub fn auctioneer_public_bid(
         ctx.accounts.wallet.to_owned(),
         ctx.accounts.payment_account.to_owned(),
         ctx.accounts.transfer_authority.to_owned(),
-        *ctx.accounts.treasury_mint.to_owned(),
+        ctx.accounts.treasury_mint.to_owned(),
         *ctx.accounts.token_account.to_owned(),
         ctx.accounts.metadata.to_owned(),
         ctx.accounts.escrow_payment_account.to_owned(),
@@ -277,6 +294,7 @@ pub fn auctioneer_public_bid(
         *ctx.bumps
             .get("buyer_trade_state")
             .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        ctx.remaining_accounts,
     )
 }
 
@@ -301,8 +319,9 @@ pub struct Buy<'info> {
     /// SPL token account transfer authority.
     transfer_authority: UncheckedAccount<'info>,
 
+    /// CHECK: Validated by the auction house has_one constraint.
     /// Auction House instance treasury mint account.
-    treasury_mint: Account<'info, Mint>,
+    treasury_mint: UncheckedAccount<'info>,
 
     /// SPL token account.
     token_account: Box<Account<'info, TokenAccount>>,
@@ -373,7 +392,9 @@ pub struct Buy<'info> {
     )]
     buyer_trade_state: UncheckedAccount<'info>,
 
-    token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in bid_logic.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
 }
@@ -412,6 +433,7 @@ pub fn private_bid<'info>(
         *ctx.bumps
             .get("buyer_trade_state")
             .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        ctx.remaining_accounts,
     )
 }
 
@@ -436,8 +458,9 @@ pub struct AuctioneerBuy<'info> {
     /// SPL token account transfer authority.
     transfer_authority: UncheckedAccount<'info>,
 
+    /// CHECK: Validated by the auction house has_one constraint.
     /// Auction House instance treasury mint account.
-    treasury_mint: Box<Account<'info, Mint>>,
+    treasury_mint: UncheckedAccount<'info>,
 
     /// SPL token account.
     token_account: Box<Account<'info, TokenAccount>>,
@@ -520,7 +543,9 @@ pub struct AuctioneerBuy<'info> {
     )]
     pub ah_auctioneer_pda: Account<'info, Auctioneer>,
 
-    token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in auctioneer_bid_logic.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
 }
@@ -537,7 +562,7 @@ pub fn auctioneer_private_bid<'info>(
         ctx.accounts.wallet.to_owned(),
         ctx.accounts.payment_account.to_owned(),
         ctx.accounts.transfer_authority.to_owned(),
-        *ctx.accounts.treasury_mint.to_owned(),
+        ctx.accounts.treasury_mint.to_owned(),
         *ctx.accounts.token_account.to_owned(),
         ctx.accounts.metadata.to_owned(),
         ctx.accounts.escrow_payment_account.to_owned(),
@@ -561,6 +586,7 @@ pub fn auctioneer_private_bid<'info>(
         *ctx.bumps
             .get("buyer_trade_state")
             .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        ctx.remaining_accounts,
     )
 }
 
@@ -570,7 +596,7 @@ pub fn bid_logic<'info>(
     wallet: Signer<'info>,
     payment_account: UncheckedAccount<'info>,
     transfer_authority: UncheckedAccount<'info>,
-    treasury_mint: Account<'info, Mint>,
+    treasury_mint: UncheckedAccount<'info>,
     token_account: Account<'info, TokenAccount>,
     metadata: UncheckedAccount<'info>,
     escrow_payment_account: UncheckedAccount<'info>,
@@ -578,7 +604,7 @@ pub fn bid_logic<'info>(
     auction_house: Account<'info, AuctionHouse>,
     auction_house_fee_account: UncheckedAccount<'info>,
     buyer_trade_state: UncheckedAccount<'info>,
-    token_program: Program<'info, Token>,
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
     trade_state_bump: u8,
@@ -588,6 +614,7 @@ pub fn bid_logic<'info>(
     public: bool,
     escrow_canonical_bump: u8,
     trade_state_canonical_bump: u8,
+    remaining_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
     // If it has an auctioneer authority delegated must use auctioneer_* handler.
     if (auction_house.scopes[AuthorityScope::PublicBuy as usize] || !public)
@@ -630,6 +657,16 @@ pub fn bid_logic<'info>(
     )?;
 
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    assert_treasury_token_program(&treasury_mint, &token_program)?;
+
+    // Buyers that approved the Auction House back their bids with their shared escrow first, and only fund the
+    // escrow payment account for what it lacks.
+    let (shared_escrow, remaining_accounts) =
+        split_shared_escrow(&auction_house, &wallet.key(), remaining_accounts)?;
+    let shared_escrow_funds = shared_escrow_balance(shared_escrow.as_ref(), is_native)?;
+    // The bid covers the buyer fee of the sale that fills it, as maker or taker.
+    let bid_total = get_bid_total(&auction_house, buyer_price)?;
+    let escrow_price = bid_total.saturating_sub(shared_escrow_funds);
 
     let auction_house_key = auction_house.key();
     let wallet_key = wallet.key();
@@ -655,11 +692,11 @@ pub fn bid_logic<'info>(
         assert_keys_equal(wallet.key(), payment_account.key())?;
 
         if escrow_payment_account.lamports()
-            < buyer_price
+            < escrow_price
                 .checked_add(rent.minimum_balance(escrow_payment_account.data_len()))
                 .ok_or(AuctionHouseError::NumericalOverflow)?
         {
-            let diff = buyer_price
+            let diff = escrow_price
                 .checked_add(rent.minimum_balance(escrow_payment_account.data_len()))
                 .ok_or(AuctionHouseError::NumericalOverflow)?
                 .checked_sub(escrow_payment_account.lamports())
@@ -679,32 +716,34 @@ pub fn bid_logic<'info>(
             )?;
         }
     } else {
-        let escrow_payment_loaded: spl_token::state::Account =
-            assert_initialized(&escrow_payment_account)?;
+        let escrow_payment_loaded = get_treasury_token_account(&escrow_payment_account)?;
 
-        if escrow_payment_loaded.amount < buyer_price {
-            let diff = buyer_price
+        if escrow_payment_loaded.amount < escrow_price {
+            let diff = escrow_price
                 .checked_sub(escrow_payment_loaded.amount)
                 .ok_or(AuctionHouseError::NumericalOverflow)?;
-            invoke(
-                &spl_token::instruction::transfer(
-                    &token_program.key(),
-                    &payment_account.key(),
-                    &escrow_payment_account.key(),
-                    &transfer_authority.key(),
-                    &[],
-                    diff,
-                )?,
-                &[
-                    transfer_authority.to_account_info(),
-                    payment_account.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    token_program.to_account_info(),
-                ],
+            // The buyer covers any transfer fee so the escrow is credited exactly `diff`.
+            transfer_treasury_tokens(
+                &payment_account,
+                &escrow_payment_account,
+                &transfer_authority,
+                &treasury_mint,
+                &token_program,
+                get_amount_with_transfer_fee(&treasury_mint, diff)?,
+                &[],
             )?;
         }
     }
     assert_metadata_valid(&metadata, &token_account)?;
+    let remaining_accounts = assert_gateway_token(
+        &auction_house,
+        &wallet.to_account_info(),
+        remaining_accounts,
+    )?;
+    let remaining_accounts =
+        assert_collection_list_allows(&auction_house, &metadata, remaining_accounts)?;
+    let (escrow_reservation, _) =
+        split_escrow_reservation(&auction_house, &wallet.key(), remaining_accounts)?;
 
     let ts_info = buyer_trade_state.to_account_info();
     if ts_info.data_is_empty() {
@@ -760,6 +799,33 @@ pub fn bid_logic<'info>(
             TRADE_STATE_SIZE,
         );
     }
+    if let Some(escrow_reservation) = escrow_reservation {
+        reserve_bid(
+            escrow_reservation,
+            &auction_house,
+            &wallet.key(),
+            &buyer_trade_state.key(),
+            bid_total,
+            escrow_balance(&escrow_payment_account, is_native)?
+                .checked_add(shared_escrow_funds)
+                .ok_or(AuctionHouseError::NumericalOverflow)?,
+            &fee_payer,
+            fee_seeds,
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+        )?;
+    }
+
+    emit!(BidEvent {
+        auction_house: auction_house.key(),
+        buyer: wallet.key(),
+        token_account: token_account.key(),
+        target: token_account.mint,
+        trade_state: buyer_trade_state.key(),
+        price: buyer_price,
+        token_size,
+    });
+
     // Allow The same bid to be sent with no issues
     Ok(())
 }
@@ -770,7 +836,7 @@ pub fn auctioneer_bid_logic<'info>(
     wallet: Signer<'info>,
     payment_account: UncheckedAccount<'info>,
     transfer_authority: UncheckedAccount<'info>,
-    treasury_mint: Account<'info, Mint>,
+    treasury_mint: UncheckedAccount<'info>,
     token_account: Account<'info, TokenAccount>,
     metadata: UncheckedAccount<'info>,
     escrow_payment_account: UncheckedAccount<'info>,
@@ -780,7 +846,7 @@ pub fn auctioneer_bid_logic<'info>(
     authority: UncheckedAccount<'info>,
     auctioneer_authority: Signer<'info>,
     ah_auctioneer_pda: Account<'info, Auctioneer>,
-    token_program: Program<'info, Token>,
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
     trade_state_bump: u8,
@@ -790,6 +856,7 @@ pub fn auctioneer_bid_logic<'info>(
     public: bool,
     escrow_canonical_bump: u8,
     trade_state_canonical_bump: u8,
+    remaining_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
     if !auction_house.has_auctioneer {
         return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
@@ -834,6 +901,16 @@ pub fn auctioneer_bid_logic<'info>(
     )?;
 
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    assert_treasury_token_program(&treasury_mint, &token_program)?;
+
+    // Buyers that approved the Auction House back their bids with their shared escrow first, and only fund the
+    // escrow payment account for what it lacks.
+    let (shared_escrow, remaining_accounts) =
+        split_shared_escrow(auction_house, &wallet.key(), remaining_accounts)?;
+    let shared_escrow_funds = shared_escrow_balance(shared_escrow.as_ref(), is_native)?;
+    // The bid covers the buyer fee of the sale that fills it, as maker or taker.
+    let bid_total = get_bid_total(auction_house, buyer_price)?;
+    let escrow_price = bid_total.saturating_sub(shared_escrow_funds);
 
     let auction_house_key = auction_house.key();
     let wallet_key = wallet.key();
@@ -859,11 +936,11 @@ pub fn auctioneer_bid_logic<'info>(
         assert_keys_equal(wallet.key(), payment_account.key())?;
 
         if escrow_payment_account.lamports()
-            < buyer_price
+            < escrow_price
                 .checked_add(rent.minimum_balance(escrow_payment_account.data_len()))
                 .ok_or(AuctionHouseError::NumericalOverflow)?
         {
-            let diff = buyer_price
+            let diff = escrow_price
                 .checked_add(rent.minimum_balance(escrow_payment_account.data_len()))
                 .ok_or(AuctionHouseError::NumericalOverflow)?
                 .checked_sub(escrow_payment_account.lamports())
@@ -883,34 +960,38 @@ pub fn auctioneer_bid_logic<'info>(
             )?;
         }
     } else {
-        let escrow_payment_loaded: spl_token::state::Account =
-            assert_initialized(&escrow_payment_account)?;
+        let escrow_payment_loaded = get_treasury_token_account(&escrow_payment_account)?;
 
-        if escrow_payment_loaded.amount < buyer_price {
-            let diff = buyer_price
+        if escrow_payment_loaded.amount < escrow_price {
+            let diff = escrow_price
                 .checked_sub(escrow_payment_loaded.amount)
                 .ok_or(AuctionHouseError::NumericalOverflow)?;
-            invoke(
-                &spl_token::instruction::transfer(
-                    &token_program.key(),
-                    &payment_account.key(),
-                    &escrow_payment_account.key(),
-                    &transfer_authority.key(),
-                    &[],
-                    diff,
-                )?,
-                &[
-                    transfer_authority.to_account_info(),
-                    payment_account.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    token_program.to_account_info(),
-                ],
+            // The buyer covers any transfer fee so the escrow is credited exactly `diff`.
+            transfer_treasury_tokens(
+                &payment_account,
+                &escrow_payment_account,
+                &transfer_authority,
+                &treasury_mint,
+                &token_program,
+                get_amount_with_transfer_fee(&treasury_mint, diff)?,
+                &[],
             )?;
         }
     }
     assert_metadata_valid(&metadata, &token_account)?;
-
+    let remaining_accounts =
+        assert_gateway_token(auction_house, &wallet.to_account_info(), remaining_accounts)?;
+    let remaining_accounts =
+        assert_collection_list_allows(auction_house, &metadata, remaining_accounts)?;
+    let (escrow_reservation, _) =
+        split_escrow_reservation(auction_house, &wallet.key(), remaining_accounts)?;
+
+    // New bids record the auctioneer that created them.
     let ts_info = buyer_trade_state.to_account_info();
+    let ts_fields = TradeStateFields {
+        auctioneer: Some(ah_auctioneer_pda.key()),
+        ..Default::default()
+    };
     if ts_info.data_is_empty() {
         let wallet_key = wallet.key();
         let token_account_key = token_account.key();
@@ -921,7 +1002,7 @@ pub fn auctioneer_bid_logic<'info>(
                 &rent.to_account_info(),
                 &system_program,
                 &fee_payer,
-                TRADE_STATE_SIZE,
+                ts_fields.size(),
                 fee_seeds,
                 &[
                     PREFIX.as_bytes(),
@@ -941,7 +1022,7 @@ pub fn auctioneer_bid_logic<'info>(
                 &rent.to_account_info(),
                 &system_program,
                 &fee_payer,
-                TRADE_STATE_SIZE,
+                ts_fields.size(),
                 fee_seeds,
                 &[
                     PREFIX.as_bytes(),
@@ -956,6 +1037,532 @@ pub fn auctioneer_bid_logic<'info>(
                 ],
             )?;
         }
+        let mut data = ts_info.try_borrow_mut_data()?;
+        data[0] = trade_state_bump;
+        ts_fields.write(&mut data);
+    }
+    if let Some(escrow_reservation) = escrow_reservation {
+        reserve_bid(
+            escrow_reservation,
+            auction_house,
+            &wallet.key(),
+            &buyer_trade_state.key(),
+            bid_total,
+            escrow_balance(&escrow_payment_account, is_native)?
+                .checked_add(shared_escrow_funds)
+                .ok_or(AuctionHouseError::NumericalOverflow)?,
+            &fee_payer,
+            fee_seeds,
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+        )?;
+    }
+
+    emit!(BidEvent {
+        auction_house: auction_house.key(),
+        buyer: wallet.key(),
+        token_account: token_account.key(),
+        target: token_account.mint,
+        trade_state: buyer_trade_state.key(),
+        price: buyer_price,
+        token_size,
+    });
+
+    // Allow The same bid to be sent with no issues
+    Ok(())
+}
+
+/// Accounts for the [`collection_bid` handler](fn.collection_bid.html).
+#[derive(Accounts)]
+#[instruction(
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct CollectionBuy<'info> {
+    /// User wallet account.
+    wallet: Signer<'info>,
+
+    /// CHECK: Validated in collection_bid.
+    /// User SOL or SPL account to transfer funds from.
+    #[account(mut)]
+    payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in collection_bid.
+    /// SPL token account transfer authority.
+    transfer_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the auction house has_one constraint.
+    /// Auction House instance treasury mint account.
+    treasury_mint: UncheckedAccount<'info>,
+
+    /// Mint account of the collection NFT.
+    collection_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Validated in collection_bid.
+    /// Metaplex metadata account decorating the collection mint account.
+    collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account PDA.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified with has_one constraint on auction house account.
+    /// Auction House instance authority account.
+    authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = authority,
+        has_one = treasury_mint,
+        has_one = auction_house_fee_account
+    )]
+    auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer trade state PDA keyed by the collection mint.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            COLLECTION.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            treasury_mint.key().as_ref(),
+            collection_mint.key().as_ref(),
+            buyer_price.to_le_bytes().as_ref(),
+            token_size.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    buyer_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the treasury mint in targeted_bid_logic.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    token_program: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+/// Create a bid that can be filled by any token of a verified collection.
+/// The buyer trade state is keyed by the collection mint instead of a specific token mint, so a single bid replaces one public bid per item.
+pub fn collection_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, CollectionBuy<'info>>,
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let collection_mint = &ctx.accounts.collection_mint;
+    let collection_metadata = &ctx.accounts.collection_metadata;
+    let auction_house = &ctx.accounts.auction_house;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::PublicBuy as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let escrow_canonical_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let trade_state_canonical_bump = *ctx
+        .bumps
+        .get("buyer_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    if (escrow_canonical_bump != escrow_payment_bump)
+        || (trade_state_canonical_bump != trade_state_bump)
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    assert_derivation(
+        &mpl_token_metadata::id(),
+        &collection_metadata.to_account_info(),
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            collection_mint.key().as_ref(),
+        ],
+    )?;
+
+    if collection_metadata.data_is_empty() {
+        return Err(AuctionHouseError::MetadataDoesntExist.into());
+    }
+
+    let accounts = &ctx.accounts;
+    targeted_bid_logic(
+        TargetedBidAccounts {
+            wallet: &accounts.wallet,
+            payment_account: &accounts.payment_account,
+            transfer_authority: &accounts.transfer_authority,
+            treasury_mint: &accounts.treasury_mint,
+            escrow_payment_account: &accounts.escrow_payment_account,
+            authority: &accounts.authority,
+            auction_house: &accounts.auction_house,
+            auction_house_fee_account: &accounts.auction_house_fee_account,
+            buyer_trade_state: &accounts.buyer_trade_state,
+            token_program: &accounts.token_program,
+            system_program: &accounts.system_program,
+            rent: &accounts.rent,
+        },
+        COLLECTION,
+        &collection_mint.key().to_bytes(),
+        trade_state_bump,
+        escrow_payment_bump,
+        buyer_price,
+        token_size,
+        ctx.remaining_accounts,
+    )
+}
+
+/// Accounts for the [`trait_bid` handler](fn.trait_bid.html).
+#[derive(Accounts)]
+#[instruction(
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    root: [u8; 32]
+)]
+pub struct TraitBuy<'info> {
+    /// User wallet account.
+    wallet: Signer<'info>,
+
+    /// CHECK: Validated in trait_bid.
+    /// User SOL or SPL account to transfer funds from.
+    #[account(mut)]
+    payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in trait_bid.
+    /// SPL token account transfer authority.
+    transfer_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the auction house has_one constraint.
+    /// Auction House instance treasury mint account.
+    treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account PDA.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified with has_one constraint on auction house account.
+    /// Auction House instance authority account.
+    authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = authority,
+        has_one = treasury_mint,
+        has_one = auction_house_fee_account
+    )]
+    auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer trade state PDA keyed by the merkle root of eligible mints.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            TRAIT.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            treasury_mint.key().as_ref(),
+            root.as_ref(),
+            buyer_price.to_le_bytes().as_ref(),
+            token_size.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    buyer_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the treasury mint in targeted_bid_logic.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    token_program: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+/// Create a bid that can be filled by any token whose mint is a leaf of the merkle tree defined by `root`.
+/// The seller supplies the proof for their mint when executing the sale.
+pub fn trait_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, TraitBuy<'info>>,
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    root: [u8; 32],
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::PublicBuy as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let escrow_canonical_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let trade_state_canonical_bump = *ctx
+        .bumps
+        .get("buyer_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    if (escrow_canonical_bump != escrow_payment_bump)
+        || (trade_state_canonical_bump != trade_state_bump)
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    let accounts = &ctx.accounts;
+    targeted_bid_logic(
+        TargetedBidAccounts {
+            wallet: &accounts.wallet,
+            payment_account: &accounts.payment_account,
+            transfer_authority: &accounts.transfer_authority,
+            treasury_mint: &accounts.treasury_mint,
+            escrow_payment_account: &accounts.escrow_payment_account,
+            authority: &accounts.authority,
+            auction_house: &accounts.auction_house,
+            auction_house_fee_account: &accounts.auction_house_fee_account,
+            buyer_trade_state: &accounts.buyer_trade_state,
+            token_program: &accounts.token_program,
+            system_program: &accounts.system_program,
+            rent: &accounts.rent,
+        },
+        TRAIT,
+        &root,
+        trade_state_bump,
+        escrow_payment_bump,
+        buyer_price,
+        token_size,
+        ctx.remaining_accounts,
+    )
+}
+
+/// Accounts shared by bids that target a set of tokens rather than a single mint.
+struct TargetedBidAccounts<'a, 'info> {
+    wallet: &'a Signer<'info>,
+    payment_account: &'a UncheckedAccount<'info>,
+    transfer_authority: &'a UncheckedAccount<'info>,
+    treasury_mint: &'a UncheckedAccount<'info>,
+    escrow_payment_account: &'a UncheckedAccount<'info>,
+    authority: &'a UncheckedAccount<'info>,
+    auction_house: &'a Account<'info, AuctionHouse>,
+    auction_house_fee_account: &'a UncheckedAccount<'info>,
+    buyer_trade_state: &'a UncheckedAccount<'info>,
+    token_program: &'a UncheckedAccount<'info>,
+    system_program: &'a Program<'info, System>,
+    rent: &'a Sysvar<'info, Rent>,
+}
+
+/// Fund the buyer escrow and create a trade state keyed by `target` under the `kind` seed.
+#[allow(clippy::too_many_arguments)]
+fn targeted_bid_logic<'info>(
+    accounts: TargetedBidAccounts<'_, 'info>,
+    kind: &str,
+    target: &[u8; 32],
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let TargetedBidAccounts {
+        wallet,
+        payment_account,
+        transfer_authority,
+        treasury_mint,
+        escrow_payment_account,
+        authority,
+        auction_house,
+        auction_house_fee_account,
+        buyer_trade_state,
+        token_program,
+        system_program,
+        rent,
+    } = accounts;
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+    let (fee_payer, fee_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    assert_treasury_token_program(treasury_mint, token_program)?;
+
+    // Buyers that approved the Auction House back their bids with their shared escrow first, and only fund the
+    // escrow payment account for what it lacks.
+    let (shared_escrow, remaining_accounts) =
+        split_shared_escrow(auction_house, &wallet.key(), remaining_accounts)?;
+    let shared_escrow_funds = shared_escrow_balance(shared_escrow.as_ref(), is_native)?;
+    // The bid covers the buyer fee of the sale that fills it, as maker or taker.
+    let bid_total = get_bid_total(auction_house, buyer_price)?;
+    let escrow_price = bid_total.saturating_sub(shared_escrow_funds);
+
+    let wallet_key = wallet.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        wallet_key.as_ref(),
+        &[escrow_payment_bump],
+    ];
+    create_program_token_account_if_not_present(
+        escrow_payment_account,
+        system_program,
+        &fee_payer,
+        token_program,
+        treasury_mint,
+        &auction_house.to_account_info(),
+        rent,
+        &escrow_signer_seeds,
+        fee_seeds,
+        is_native,
+    )?;
+    if is_native {
+        assert_keys_equal(wallet.key(), payment_account.key())?;
+
+        if escrow_payment_account.lamports()
+            < escrow_price
+                .checked_add(rent.minimum_balance(escrow_payment_account.data_len()))
+                .ok_or(AuctionHouseError::NumericalOverflow)?
+        {
+            let diff = escrow_price
+                .checked_add(rent.minimum_balance(escrow_payment_account.data_len()))
+                .ok_or(AuctionHouseError::NumericalOverflow)?
+                .checked_sub(escrow_payment_account.lamports())
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+            invoke(
+                &system_instruction::transfer(
+                    &payment_account.key(),
+                    &escrow_payment_account.key(),
+                    diff,
+                ),
+                &[
+                    payment_account.to_account_info(),
+                    escrow_payment_account.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+            )?;
+        }
+    } else {
+        let escrow_payment_loaded = get_treasury_token_account(escrow_payment_account)?;
+
+        if escrow_payment_loaded.amount < escrow_price {
+            let diff = escrow_price
+                .checked_sub(escrow_payment_loaded.amount)
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+            // The buyer covers any transfer fee so the escrow is credited exactly `diff`.
+            transfer_treasury_tokens(
+                payment_account,
+                escrow_payment_account,
+                transfer_authority,
+                treasury_mint,
+                token_program,
+                get_amount_with_transfer_fee(treasury_mint, diff)?,
+                &[],
+            )?;
+        }
+    }
+
+    let remaining_accounts =
+        assert_gateway_token(auction_house, &wallet.to_account_info(), remaining_accounts)?;
+    let (escrow_reservation, _) =
+        split_escrow_reservation(auction_house, &wallet.key(), remaining_accounts)?;
+
+    let ts_info = buyer_trade_state.to_account_info();
+    if ts_info.data_is_empty() {
+        create_or_allocate_account_raw(
+            crate::id(),
+            &ts_info,
+            &rent.to_account_info(),
+            system_program,
+            &fee_payer,
+            TRADE_STATE_SIZE,
+            fee_seeds,
+            &[
+                PREFIX.as_bytes(),
+                kind.as_bytes(),
+                wallet_key.as_ref(),
+                auction_house_key.as_ref(),
+                auction_house.treasury_mint.as_ref(),
+                target,
+                &buyer_price.to_le_bytes(),
+                &token_size.to_le_bytes(),
+                &[trade_state_bump],
+            ],
+        )?;
+
         #[allow(clippy::explicit_auto_deref)]
         sol_memset(
             *ts_info.try_borrow_mut_data()?,
@@ -963,6 +1570,33 @@ pub fn auctioneer_bid_logic<'info>(
             TRADE_STATE_SIZE,
         );
     }
+    if let Some(escrow_reservation) = escrow_reservation {
+        reserve_bid(
+            escrow_reservation,
+            auction_house,
+            &wallet.key(),
+            &buyer_trade_state.key(),
+            bid_total,
+            escrow_balance(escrow_payment_account, is_native)?
+                .checked_add(shared_escrow_funds)
+                .ok_or(AuctionHouseError::NumericalOverflow)?,
+            &fee_payer,
+            fee_seeds,
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+        )?;
+    }
+
+    emit!(BidEvent {
+        auction_house: auction_house.key(),
+        buyer: wallet.key(),
+        token_account: Pubkey::default(),
+        target: Pubkey::new_from_array(*target),
+        trade_state: buyer_trade_state.key(),
+        price: buyer_price,
+        token_size,
+    });
+
     // Allow The same bid to be sent with no issues
     Ok(())
 }
@@ -0,0 +1,811 @@
+//! Bundle listings sell several NFTs as one lot at a single price.
+//! The whole bundle is bought atomically, and creator royalties are paid per item on a share of the price
+//! prorated by the weight of each item.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, program::invoke_signed, system_instruction},
+    AnchorDeserialize, AnchorSerialize,
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Token, TokenAccount},
+};
+use mpl_utils::token::{spl_token_transfer, TokenTransferParams};
+use solana_program::program_pack::Pack;
+use spl_token::{
+    instruction::{approve, revoke},
+    state::Account as SplAccount,
+};
+
+use crate::{
+    constants::*,
+    curation::split_collection_list,
+    errors::AuctionHouseError,
+    fee_tier::{fee_tier_basis_points, split_fee_tier_holder},
+    gateway::assert_gateway_token,
+    shared_escrow::{draw_shared_escrow, split_shared_escrow},
+    stats::{record_sale, split_auction_house_stats},
+    utils::*,
+    AuctionHouse, AuthorityScope,
+};
+
+pub const BUNDLE_ITEM_SIZE: usize = 32 + // token_account
+32 + // mint
+8; // weight
+
+pub const BUNDLE_LISTING_BASE_SIZE: usize = 8 + // key
+32 + // auction_house
+32 + // seller
+8 + // price
+1 + // bump
+4; // items length
+
+/// A single item of a bundle listing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct BundleItem {
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+    /// Relative share of the bundle price this item's royalties are computed on.
+    pub weight: u64,
+}
+
+/// Listing of several tokens sold together at one price.
+#[account]
+pub struct BundleListing {
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+    pub bump: u8,
+    pub items: Vec<BundleItem>,
+}
+
+impl BundleListing {
+    /// Share of the bundle price allocated to each item, in item order. The last item receives the rounding dust
+    /// so the shares always add up to the bundle price.
+    pub fn item_prices(&self) -> Result<Vec<u64>> {
+        let total_weight = self
+            .items
+            .iter()
+            .try_fold(0u128, |total, item| total.checked_add(item.weight as u128))
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+        let mut allocated: u64 = 0;
+        let mut prices = Vec::with_capacity(self.items.len());
+        for (index, item) in self.items.iter().enumerate() {
+            let item_price = if index == self.items.len() - 1 {
+                self.price
+                    .checked_sub(allocated)
+                    .ok_or(AuctionHouseError::NumericalOverflow)?
+            } else {
+                (self.price as u128)
+                    .checked_mul(item.weight as u128)
+                    .ok_or(AuctionHouseError::NumericalOverflow)?
+                    .checked_div(total_weight)
+                    .ok_or(AuctionHouseError::NumericalOverflow)? as u64
+            };
+            allocated = allocated
+                .checked_add(item_price)
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+            prices.push(item_price);
+        }
+
+        Ok(prices)
+    }
+}
+
+/// Accounts for the [`sell_bundle` handler](auction_house/fn.sell_bundle.html).
+#[derive(Accounts)]
+pub struct SellBundle<'info> {
+    /// User wallet account listing the bundle. Pays for the bundle listing account.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Validated as a signer in sell_bundle when the Auction House requires sign off.
+    /// Auction House authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Seeds are checked in sell_bundle.
+    /// Bundle listing PDA account.
+    #[account(mut)]
+    pub bundle_listing: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    // remaining accounts, for each item:
+    // token_account, metadata
+    // then [collection list if curated], [...gateway accounts if gated]
+}
+
+/// Accounts for the [`execute_bundle_sale` handler](auction_house/fn.execute_bundle_sale.html).
+#[derive(Accounts)]
+pub struct ExecuteBundleSale<'info> {
+    /// Buyer user wallet account.
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Validated by the bundle listing constraint.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the auction house constraint.
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_bundle_sale.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_bundle_sale.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// Bundle listing PDA account. Closed to the seller once the sale is executed.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            BUNDLE.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            bundle_listing.items[0].token_account.as_ref()
+        ],
+        bump=bundle_listing.bump,
+        has_one=auction_house,
+        has_one=seller,
+        close=seller
+    )]
+    pub bundle_listing: Box<Account<'info, BundleListing>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    // remaining accounts:
+    // the Token-2022 program for Token-2022 treasury mints, then for each item:
+    // token_account, token_mint, metadata, buyer_receipt_token_account, ...creator accounts
+    // then the optional referrer wallet and its treasury mint ATA, [stats if tracked], [collection list if curated],
+    // [...fee tier holder accounts if tiered], [...shared escrow accounts of the buyer if accepted]
+}
+
+/// Accounts for the [`cancel_bundle` handler](auction_house/fn.cancel_bundle.html).
+#[derive(Accounts)]
+pub struct CancelBundle<'info> {
+    /// User wallet account that listed the bundle.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Bundle listing PDA account. Closed to the seller.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            BUNDLE.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            bundle_listing.items[0].token_account.as_ref()
+        ],
+        bump=bundle_listing.bump,
+        has_one=auction_house,
+        constraint = bundle_listing.seller == wallet.key() @ AuctionHouseError::PublicKeyMismatch,
+        close=wallet
+    )]
+    pub bundle_listing: Box<Account<'info, BundleListing>>,
+
+    pub token_program: Program<'info, Token>,
+    // remaining accounts, for each item:
+    // token_account
+}
+
+/// List the tokens passed in the remaining accounts as one bundle at `price`. `weights` holds one entry per item
+/// and prorates the price between items when paying royalties; equal weights split the price evenly.
+/// Only SPL tokens are supported, so programmable NFTs can not be bundled.
+pub fn sell_bundle<'info>(
+    ctx: Context<'_, '_, '_, 'info, SellBundle<'info>>,
+    price: u64,
+    weights: Vec<u64>,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let bundle_listing = &ctx.accounts.bundle_listing;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Sell as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if auction_house.requires_sign_off && !authority.is_signer {
+        return Err(AuctionHouseError::CannotTakeThisActionWithoutAuctionHouseSignOff.into());
+    }
+
+    if weights.len() < 2 || weights.len() > MAX_BUNDLE_ITEMS || weights.contains(&0) {
+        return Err(AuctionHouseError::InvalidBundleItems.into());
+    }
+
+    let remaining_accounts = assert_gateway_token(
+        auction_house,
+        &wallet.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    let (collection_list, remaining_accounts) =
+        split_collection_list(auction_house, remaining_accounts)?;
+    let remaining_accounts = &mut remaining_accounts.iter();
+    let mut items: Vec<BundleItem> = Vec::with_capacity(weights.len());
+
+    for weight in weights {
+        let token_account_info = next_account_info(remaining_accounts)?;
+        let metadata = next_account_info(remaining_accounts)?;
+
+        if items
+            .iter()
+            .any(|item| item.token_account == token_account_info.key())
+        {
+            return Err(AuctionHouseError::InvalidBundleItems.into());
+        }
+
+        let token_account: Account<TokenAccount> = Account::try_from(token_account_info)?;
+        assert_is_ata(token_account_info, &wallet.key(), &token_account.mint)?;
+        assert_metadata_valid(
+            &UncheckedAccount::try_from(metadata.clone()),
+            &token_account,
+        )?;
+        if let Some(collection_list) = &collection_list {
+            collection_list.assert_metadata_allowed(metadata)?;
+        }
+
+        if token_account.amount < 1 {
+            return Err(AuctionHouseError::InvalidTokenAmount.into());
+        }
+
+        invoke(
+            &approve(
+                &token_program.key(),
+                &token_account_info.key(),
+                &program_as_signer.key(),
+                &wallet.key(),
+                &[],
+                1,
+            )?,
+            &[
+                token_program.to_account_info(),
+                token_account_info.clone(),
+                program_as_signer.to_account_info(),
+                wallet.to_account_info(),
+            ],
+        )?;
+
+        items.push(BundleItem {
+            token_account: token_account_info.key(),
+            mint: token_account.mint,
+            weight,
+        });
+    }
+
+    let wallet_key = wallet.key();
+    let auction_house_key = auction_house.key();
+    let first_token_account = items[0].token_account;
+    let bundle_path = [
+        PREFIX.as_bytes(),
+        BUNDLE.as_bytes(),
+        wallet_key.as_ref(),
+        auction_house_key.as_ref(),
+        first_token_account.as_ref(),
+    ];
+    let bump = assert_derivation(ctx.program_id, bundle_listing, &bundle_path)?;
+
+    if !bundle_listing.data_is_empty() {
+        return Err(AuctionHouseError::BundleListingAlreadyExists.into());
+    }
+
+    let bump_seed = [bump];
+    let bundle_seeds = [&bundle_path[..], &[&bump_seed]].concat();
+    create_or_allocate_account_raw(
+        *ctx.program_id,
+        bundle_listing,
+        &rent.to_account_info(),
+        system_program,
+        wallet,
+        BUNDLE_LISTING_BASE_SIZE + items.len() * BUNDLE_ITEM_SIZE,
+        &[],
+        &bundle_seeds,
+    )?;
+
+    let listing = BundleListing {
+        auction_house: auction_house_key,
+        seller: wallet_key,
+        price,
+        bump,
+        items,
+    };
+
+    listing.try_serialize(&mut *bundle_listing.try_borrow_mut_data()?)?;
+
+    Ok(())
+}
+
+/// Buy every item of a bundle listing from the buyer escrow. Royalties are paid per item on its share of the
+/// bundle price, the Auction House and referral fees are taken on the full price and the seller receives the rest.
+pub fn execute_bundle_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteBundleSale<'info>>,
+) -> Result<()> {
+    let buyer = &ctx.accounts.buyer;
+    let seller = &ctx.accounts.seller;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let escrow_payment_account = &ctx.accounts.escrow_payment_account;
+    let seller_payment_receipt_account = &ctx.accounts.seller_payment_receipt_account;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let auction_house_treasury = &ctx.accounts.auction_house_treasury;
+    let bundle_listing = &ctx.accounts.bundle_listing;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let ata_program = &ctx.accounts.ata_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let rent = &ctx.accounts.rent;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let escrow_payment_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    let escrow_clone = escrow_payment_account.to_account_info();
+    let auction_house_clone = auction_house.to_account_info();
+    let treasury_mint_clone = treasury_mint.to_account_info();
+    let ata_clone = ata_program.to_account_info();
+    let token_clone = token_program.to_account_info();
+    let sys_clone = system_program.to_account_info();
+    let rent_clone = rent.to_account_info();
+    let treasury_clone = auction_house_treasury.to_account_info();
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    let price = bundle_listing.price;
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        buyer.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    // Whoever signed the sale took the bundle listing, or the bid of the buyer. A taker holding a membership token
+    // passes its fee tier holder accounts after every other remaining account but the shared escrow of the buyer.
+    let taker = get_sale_taker(buyer, seller);
+    let (shared_escrow, remaining_accounts) =
+        split_shared_escrow(auction_house, &buyer.key(), ctx.remaining_accounts)?;
+    let (fee_tier_holder, remaining_accounts) =
+        split_fee_tier_holder(auction_house, remaining_accounts)?;
+    let taker_fee_tier = fee_tier_basis_points(
+        auction_house,
+        taker.map(|(_, key)| key),
+        fee_tier_holder.as_ref(),
+    )?;
+    let mut sale_fees = get_sale_fees(
+        auction_house,
+        price,
+        taker.map(|(side, _)| side),
+        taker_fee_tier,
+    )?;
+    let buyer_total = price
+        .checked_add(sale_fees.buyer_fee)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    // A buyer that approved the Auction House tops up its escrow from its shared escrow. Token-2022 treasury
+    // mints find their token program among the remaining accounts.
+    if let Some(shared_escrow) = shared_escrow {
+        let treasury_token_program = remaining_accounts
+            .iter()
+            .find(|account| account.key() == *treasury_mint_clone.owner)
+            .unwrap_or(&token_clone);
+        draw_shared_escrow(
+            &shared_escrow,
+            &escrow_clone,
+            &auction_house_key,
+            &buyer.key(),
+            &treasury_mint_clone,
+            treasury_token_program,
+            &sys_clone,
+            buyer_total,
+        )?;
+    }
+
+    // For native purchases, verify that the amount in escrow is sufficient to actually purchase the bundle.
+    if is_native {
+        let rent_shortfall =
+            verify_withdrawal(escrow_payment_account.to_account_info(), buyer_total)?;
+        if rent_shortfall > 0 {
+            invoke_signed(
+                &system_instruction::transfer(
+                    fee_payer.key,
+                    escrow_payment_account.key,
+                    rent_shortfall,
+                ),
+                &[
+                    fee_payer.to_account_info(),
+                    escrow_payment_account.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+                &[fee_payer_seeds],
+            )?;
+        }
+    }
+
+    let buyer_key = buyer.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        buyer_key.as_ref(),
+        &[escrow_payment_bump],
+    ];
+
+    let ah_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    // with the native account, the escrow is its own owner,
+    // whereas with token, it is the auction house that is owner.
+    let signer_seeds_for_royalties = if is_native {
+        escrow_signer_seeds
+    } else {
+        ah_seeds
+    };
+
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    let (collection_list, remaining_accounts) =
+        split_collection_list(auction_house, remaining_accounts)?;
+    let (auction_house_stats, remaining_accounts) =
+        split_auction_house_stats(auction_house, remaining_accounts)?;
+    let (treasury_token_clone, remaining_accounts) =
+        split_treasury_token_program(&treasury_mint_clone, &token_clone, remaining_accounts)?;
+    let remaining_accounts = &mut remaining_accounts.iter();
+    let mut seller_leftover_after_royalties: u64 = 0;
+
+    for (item, item_price) in bundle_listing
+        .items
+        .iter()
+        .zip(bundle_listing.item_prices()?)
+    {
+        let token_account = next_account_info(remaining_accounts)?;
+        let token_mint = next_account_info(remaining_accounts)?;
+        let metadata = next_account_info(remaining_accounts)?;
+        let buyer_receipt_token_account = next_account_info(remaining_accounts)?;
+
+        if token_account.key() != item.token_account || token_mint.key() != item.mint {
+            return Err(AuctionHouseError::BundleItemMismatch.into());
+        }
+
+        let token_account_data = assert_is_ata(token_account, &seller.key(), &item.mint)?;
+        if token_account_data.delegate != Some(program_as_signer.key()).into() {
+            msg!("No delegate detected on token account.");
+            return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+        }
+        if token_account_data.amount < 1 {
+            return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+        }
+
+        assert_derivation(
+            &mpl_token_metadata::id(),
+            metadata,
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                item.mint.as_ref(),
+            ],
+        )?;
+        if metadata.data_is_empty() {
+            return Err(AuctionHouseError::MetadataDoesntExist.into());
+        }
+        if let Some(collection_list) = &collection_list {
+            collection_list.assert_metadata_allowed(metadata)?;
+        }
+
+        let item_leftover = pay_creator_fees(
+            remaining_accounts,
+            metadata,
+            &escrow_clone,
+            &auction_house_clone,
+            &fee_payer,
+            &treasury_mint_clone,
+            &ata_clone,
+            &treasury_token_clone,
+            &sys_clone,
+            &rent_clone,
+            &signer_seeds_for_royalties,
+            fee_payer_seeds,
+            item_price,
+            is_native,
+            &auction_house.royalty_policy,
+            None,
+        )?;
+        seller_leftover_after_royalties = seller_leftover_after_royalties
+            .checked_add(item_leftover)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+        if buyer_receipt_token_account.data_is_empty() {
+            make_ata(
+                buyer_receipt_token_account.to_account_info(),
+                buyer.to_account_info(),
+                token_mint.to_account_info(),
+                fee_payer.to_account_info(),
+                ata_program.to_account_info(),
+                token_program.to_account_info(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                fee_payer_seeds,
+            )?;
+        }
+
+        let buyer_rec_acct = assert_is_ata(buyer_receipt_token_account, &buyer_key, &item.mint)?;
+
+        // make sure you cant get rugged
+        if buyer_rec_acct.delegate.is_some() {
+            return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+        }
+
+        spl_token_transfer(TokenTransferParams {
+            mint: token_mint.clone(),
+            source: token_account.clone(),
+            destination: buyer_receipt_token_account.clone(),
+            amount: 1,
+            authority: program_as_signer.to_account_info(),
+            authority_signer_seeds: Some(&program_as_signer_seeds),
+            token_program: token_clone.clone(),
+        })?;
+    }
+
+    // The accounts left after the items are the optional referrer wallet and, for SPL treasury mints, its
+    // treasury mint ATA.
+    pay_sale_fees(
+        auction_house,
+        &mut sale_fees,
+        remaining_accounts.as_slice(),
+        &buyer_key,
+        &seller.key(),
+        &treasury_clone,
+        &escrow_clone,
+        &treasury_mint_clone,
+        &treasury_token_clone,
+        &sys_clone,
+        &ata_clone,
+        &rent_clone,
+        &fee_payer,
+        fee_payer_seeds,
+        &signer_seeds_for_royalties,
+        is_native,
+    )?;
+
+    let seller_leftover_after_royalties_and_house_fee = seller_leftover_after_royalties
+        .checked_sub(sale_fees.seller_fee)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    if !is_native {
+        if seller_payment_receipt_account.data_is_empty() {
+            make_ata(
+                seller_payment_receipt_account.to_account_info(),
+                seller.to_account_info(),
+                treasury_mint.to_account_info(),
+                fee_payer.to_account_info(),
+                ata_program.to_account_info(),
+                treasury_token_clone.clone(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                fee_payer_seeds,
+            )?;
+        }
+
+        let seller_rec_acct = assert_is_treasury_ata(
+            &seller_payment_receipt_account.to_account_info(),
+            &seller.key(),
+            &treasury_mint_clone,
+        )?;
+
+        // make sure you cant get rugged
+        if seller_rec_acct.delegate.is_some() {
+            return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
+        }
+
+        transfer_treasury_tokens(
+            &escrow_clone,
+            &seller_payment_receipt_account.to_account_info(),
+            &auction_house_clone,
+            &treasury_mint_clone,
+            &treasury_token_clone,
+            seller_leftover_after_royalties_and_house_fee,
+            &[&ah_seeds],
+        )?;
+    } else {
+        assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
+        invoke_signed(
+            &system_instruction::transfer(
+                escrow_payment_account.key,
+                seller_payment_receipt_account.key,
+                seller_leftover_after_royalties_and_house_fee,
+            ),
+            &[
+                escrow_payment_account.to_account_info(),
+                seller_payment_receipt_account.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            &[&escrow_signer_seeds],
+        )?;
+    }
+
+    let royalty_paid = price
+        .checked_sub(seller_leftover_after_royalties)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if let Some(auction_house_stats) = auction_house_stats {
+        record_sale(
+            auction_house_stats,
+            price,
+            royalty_paid,
+            sale_fees.treasury_fee()?,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Cancel a bundle listing, revoking the program delegate of every item the seller still holds.
+pub fn cancel_bundle<'info>(ctx: Context<'_, '_, '_, 'info, CancelBundle<'info>>) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let auction_house = &ctx.accounts.auction_house;
+    let bundle_listing = &ctx.accounts.bundle_listing;
+    let token_program = &ctx.accounts.token_program;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Cancel as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
+    for item in &bundle_listing.items {
+        let token_account = next_account_info(remaining_accounts)?;
+        if token_account.key() != item.token_account {
+            return Err(AuctionHouseError::BundleItemMismatch.into());
+        }
+
+        // Items that were moved or closed since listing have nothing left to revoke.
+        let token_account_data = match SplAccount::unpack(&token_account.data.borrow()) {
+            Ok(data) if data.owner == wallet.key() && data.delegate.is_some() => data,
+            _ => continue,
+        };
+        assert_keys_equal(token_account_data.mint, item.mint)?;
+
+        invoke(
+            &revoke(
+                &token_program.key(),
+                &token_account.key(),
+                &wallet.key(),
+                &[],
+            )?,
+            &[
+                token_program.to_account_info(),
+                token_account.clone(),
+                wallet.to_account_info(),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
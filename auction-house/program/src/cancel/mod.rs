@@ -174,6 +174,142 @@ pub struct AuctioneerCancel<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+/// Accounts for the [`cancel_collection_bid` handler](auction_house/fn.cancel_collection_bid.html).
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct CancelCollectionBuy<'info> {
+    /// CHECK: Verified in cancel_collection_bid.
+    /// User wallet account.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Used as a trade state seed only.
+    /// Mint account of the collection NFT the bid was placed on.
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated as a signer in cancel_collection_bid.
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in cancel_collection_bid.
+    /// Collection bid trade state PDA account to be canceled.
+    #[account(mut)]
+    pub trade_state: UncheckedAccount<'info>,
+}
+
+/// Accounts for the [`cancel_trait_bid` handler](auction_house/fn.cancel_trait_bid.html).
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, token_size: u64, root: [u8; 32])]
+pub struct CancelTraitBuy<'info> {
+    /// CHECK: Verified in cancel_trait_bid.
+    /// User wallet account.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Validated as a signer in cancel_trait_bid.
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in cancel_trait_bid.
+    /// Trait bid trade state PDA account to be canceled.
+    #[account(mut)]
+    pub trade_state: UncheckedAccount<'info>,
+}
+
+/// Accounts for the [`batch_cancel` handler](auction_house/fn.batch_cancel.html).
+#[derive(Accounts)]
+pub struct BatchCancel<'info> {
+    /// User wallet account owning the listed tokens.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Validated as a signer in get_fee_payer.
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    // remaining accounts, for each order:
+    // token_account, metadata, seller_trade_state, [...CancelRemainingAccounts if programmable]
+}
+
 // Cancel a bid or ask by revoking the token delegate, transferring all lamports from the trade state account to the fee payer, and setting the trade state account data to zero so it can be garbage collected.
 pub fn cancel<'info>(
     ctx: Context<'_, '_, '_, 'info, Cancel<'info>>,
@@ -186,6 +322,7 @@ pub fn cancel<'info>(
     if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Cancel as usize] {
         return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
     }
+    assert_no_trade_state_auctioneer(&ctx.accounts.trade_state)?;
 
     cancel_logic(
         ctx.accounts,
@@ -214,6 +351,7 @@ pub fn auctioneer_cancel<'info>(
         ah_auctioneer_pda,
         AuthorityScope::Cancel,
     )?;
+    assert_trade_state_auctioneer(auction_house, &ctx.accounts.trade_state, ah_auctioneer_pda)?;
 
     let mut accounts: Cancel<'info> = (*ctx.accounts).clone().into();
 
@@ -257,6 +395,15 @@ fn cancel_logic<'c, 'info>(
         return Err(AuctionHouseError::NoValidSignerPresent.into());
     }
 
+    // Only bids reserve escrow funds, they are made on token accounts of other wallets.
+    if token_account.owner != wallet.key() {
+        let (escrow_reservation, _) =
+            split_escrow_reservation(auction_house, &wallet.key(), remaining_accounts)?;
+        if let Some(escrow_reservation) = escrow_reservation {
+            release_bid(escrow_reservation, &trade_state.key())?;
+        }
+    }
+
     let auction_house_key = auction_house.key();
     let seeds = [
         PREFIX.as_bytes(),
@@ -273,86 +420,238 @@ fn cancel_logic<'c, 'info>(
         &seeds,
     )?;
 
-    let remaining_accounts = &mut remaining_accounts.iter();
-
     if token_account.owner == wallet.key() && wallet.is_signer {
-        match next_account_info(remaining_accounts) {
-            Ok(metadata_program) => {
-                require!(
-                    metadata_program.key() == mpl_token_metadata::ID,
-                    AuctionHouseError::PublicKeyMismatch
-                );
-
-                let delegate_record = next_account_info(remaining_accounts)?;
-                let program_as_signer = next_account_info(remaining_accounts)?;
-                let metadata = next_account_info(remaining_accounts)?;
-                let edition = next_account_info(remaining_accounts)?;
-                let token_record = next_account_info(remaining_accounts)?;
-                let token_mint = next_account_info(remaining_accounts)?;
-                let auth_rules_program = next_account_info(remaining_accounts)?;
-                let auth_rules = next_account_info(remaining_accounts)?;
-                let sysvar_instructions = next_account_info(remaining_accounts)?;
-                let system_program = next_account_info(remaining_accounts)?;
-
-                let revoke = RevokeBuilder::new()
-                    .delegate_record(delegate_record.key())
-                    .delegate(program_as_signer.key())
-                    .metadata(metadata.key())
-                    .master_edition(edition.key())
-                    .token_record(token_record.key())
-                    .mint(token_mint.key())
-                    .token(token_account.key())
-                    .authority(wallet.key())
-                    .payer(wallet.key())
-                    .system_program(system_program.key())
-                    .sysvar_instructions(sysvar_instructions.key())
-                    .spl_token_program(token_program.key())
-                    .authorization_rules_program(auth_rules_program.key())
-                    .authorization_rules(auth_rules.key())
-                    .build(RevokeArgs::SaleV1)
-                    .unwrap()
-                    .instruction();
-
-                let revoke_accounts = [
-                    wallet.to_account_info(),
-                    program_as_signer.to_account_info(),
-                    metadata_program.to_account_info(),
-                    delegate_record.to_account_info(),
-                    authority.to_account_info(),
-                    metadata.to_account_info(),
-                    token_record.to_account_info(),
-                    edition.to_account_info(),
-                    token_account.to_account_info(),
-                    wallet.to_account_info(),
-                    token_mint.to_account_info(),
-                    system_program.to_account_info(),
-                    sysvar_instructions.to_account_info(),
-                    token_program.to_account_info(),
-                    auth_rules_program.to_account_info(),
-                    auth_rules.to_account_info(),
-                ];
-
-                invoke(&revoke, &revoke_accounts)?;
-            }
-            Err(_) => {
-                invoke(
-                    &revoke(
-                        &token_program.key(),
-                        &token_account.key(),
-                        &wallet.key(),
-                        &[],
-                    )
-                    .unwrap(),
-                    &[
-                        token_program.to_account_info(),
-                        token_account.to_account_info(),
-                        wallet.to_account_info(),
-                    ],
-                )?;
-            }
-        }
+        revoke_sale_delegate(
+            &wallet.to_account_info(),
+            &authority.to_account_info(),
+            &token_account.to_account_info(),
+            &token_program.to_account_info(),
+            &mut remaining_accounts.iter(),
+        )?;
+    }
+
+    let curr_lamp = trade_state.lamports();
+    **trade_state.lamports.borrow_mut() = 0;
+
+    **fee_payer.lamports.borrow_mut() = fee_payer
+        .lamports()
+        .checked_add(curr_lamp)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    #[allow(clippy::explicit_auto_deref)]
+    sol_memset(*trade_state.try_borrow_mut_data()?, 0, TRADE_STATE_SIZE);
+
+    emit!(CancelEvent {
+        auction_house: auction_house.key(),
+        wallet: wallet.key(),
+        target: token_account.mint,
+        trade_state: trade_state.key(),
+        price: buyer_price,
+        token_size,
+    });
+
+    Ok(())
+}
+
+/// Cancel every listing in `orders` by revoking the token delegate and closing its `seller_trade_state` account.
+/// Each order reads a `(token_account, metadata, seller_trade_state)` triple from the remaining accounts,
+/// followed by the [`CancelRemainingAccounts`] when the order is for a programmable NFT.
+pub fn batch_cancel<'info>(
+    ctx: Context<'_, '_, '_, 'info, BatchCancel<'info>>,
+    orders: Vec<BatchOrder>,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let token_program = &ctx.accounts.token_program;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Cancel as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let wallet_key = wallet.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, _) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
+    for order in orders {
+        let token_account_info = next_account_info(remaining_accounts)?;
+        let metadata = next_account_info(remaining_accounts)?;
+        let trade_state = next_account_info(remaining_accounts)?;
+
+        let token_account: Account<TokenAccount> = Account::try_from(token_account_info)?;
+        assert_is_ata(token_account_info, &wallet_key, &token_account.mint)?;
+        assert_metadata_valid(
+            &UncheckedAccount::try_from(metadata.clone()),
+            &token_account,
+        )?;
+
+        assert_no_trade_state_auctioneer(trade_state)?;
+        let ts_bump = trade_state.try_borrow_data()?[0];
+        assert_valid_trade_state(
+            &wallet_key,
+            auction_house,
+            order.buyer_price,
+            order.token_size,
+            trade_state,
+            &token_account.mint,
+            token_account_info.key,
+            ts_bump,
+        )?;
+
+        let mut no_remaining_accounts = [].iter();
+        revoke_sale_delegate(
+            &wallet.to_account_info(),
+            &authority.to_account_info(),
+            token_account_info,
+            &token_program.to_account_info(),
+            if order.programmable {
+                remaining_accounts
+            } else {
+                &mut no_remaining_accounts
+            },
+        )?;
+
+        let curr_lamp = trade_state.lamports();
+        **trade_state.lamports.borrow_mut() = 0;
+
+        **fee_payer.lamports.borrow_mut() = fee_payer
+            .lamports()
+            .checked_add(curr_lamp)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+        #[allow(clippy::explicit_auto_deref)]
+        sol_memset(*trade_state.try_borrow_mut_data()?, 0, TRADE_STATE_SIZE);
+
+        emit!(CancelEvent {
+            auction_house: auction_house.key(),
+            wallet: wallet_key,
+            target: token_account.mint,
+            trade_state: trade_state.key(),
+            price: order.buyer_price,
+            token_size: order.token_size,
+        });
+    }
+
+    Ok(())
+}
+
+/// Cancel a collection bid by transferring all lamports from the trade state account to the fee payer and setting the trade state account data to zero so it can be garbage collected.
+pub fn cancel_collection_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelCollectionBuy<'info>>,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+
+    cancel_targeted_bid_logic(
+        &accounts.wallet,
+        &accounts.authority,
+        &accounts.auction_house,
+        &accounts.auction_house_fee_account,
+        &accounts.trade_state,
+        COLLECTION,
+        &accounts.collection_mint.key().to_bytes(),
+        buyer_price,
+        token_size,
+        ctx.remaining_accounts,
+    )
+}
+
+/// Cancel a trait bid by transferring all lamports from the trade state account to the fee payer and setting the trade state account data to zero so it can be garbage collected.
+pub fn cancel_trait_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelTraitBuy<'info>>,
+    buyer_price: u64,
+    token_size: u64,
+    root: [u8; 32],
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+
+    cancel_targeted_bid_logic(
+        &accounts.wallet,
+        &accounts.authority,
+        &accounts.auction_house,
+        &accounts.auction_house_fee_account,
+        &accounts.trade_state,
+        TRAIT,
+        &root,
+        buyer_price,
+        token_size,
+        ctx.remaining_accounts,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cancel_targeted_bid_logic<'info>(
+    wallet: &UncheckedAccount<'info>,
+    authority: &UncheckedAccount<'info>,
+    auction_house: &Account<'info, AuctionHouse>,
+    auction_house_fee_account: &UncheckedAccount<'info>,
+    trade_state: &UncheckedAccount<'info>,
+    kind: &str,
+    target: &[u8; 32],
+    buyer_price: u64,
+    token_size: u64,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Cancel as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let ts_bump = trade_state.try_borrow_data()?[0];
+    assert_valid_targeted_trade_state(
+        &wallet.key(),
+        auction_house,
+        buyer_price,
+        token_size,
+        &trade_state.to_account_info(),
+        kind,
+        target,
+        ts_bump,
+    )?;
+    if !wallet.to_account_info().is_signer && !authority.to_account_info().is_signer {
+        return Err(AuctionHouseError::NoValidSignerPresent.into());
+    }
+
+    let (escrow_reservation, _) =
+        split_escrow_reservation(auction_house, &wallet.key(), remaining_accounts)?;
+    if let Some(escrow_reservation) = escrow_reservation {
+        release_bid(escrow_reservation, &trade_state.key())?;
     }
 
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, _) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
     let curr_lamp = trade_state.lamports();
     **trade_state.lamports.borrow_mut() = 0;
 
@@ -364,5 +663,102 @@ fn cancel_logic<'c, 'info>(
     #[allow(clippy::explicit_auto_deref)]
     sol_memset(*trade_state.try_borrow_mut_data()?, 0, TRADE_STATE_SIZE);
 
+    emit!(CancelEvent {
+        auction_house: auction_house.key(),
+        wallet: wallet.key(),
+        target: Pubkey::new_from_array(*target),
+        trade_state: trade_state.key(),
+        price: buyer_price,
+        token_size,
+    });
+
+    Ok(())
+}
+
+/// Revoke the sale delegate of `token_account`. The Token Metadata revoke is used when the
+/// [`CancelRemainingAccounts`] of a programmable NFT are next in `remaining_accounts`, otherwise an SPL token revoke.
+pub(crate) fn revoke_sale_delegate<'info>(
+    wallet: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    remaining_accounts: &mut std::slice::Iter<AccountInfo<'info>>,
+) -> Result<()> {
+    match next_account_info(remaining_accounts) {
+        Ok(metadata_program) => {
+            require!(
+                metadata_program.key() == mpl_token_metadata::ID,
+                AuctionHouseError::PublicKeyMismatch
+            );
+
+            let delegate_record = next_account_info(remaining_accounts)?;
+            let program_as_signer = next_account_info(remaining_accounts)?;
+            let metadata = next_account_info(remaining_accounts)?;
+            let edition = next_account_info(remaining_accounts)?;
+            let token_record = next_account_info(remaining_accounts)?;
+            let token_mint = next_account_info(remaining_accounts)?;
+            let auth_rules_program = next_account_info(remaining_accounts)?;
+            let auth_rules = next_account_info(remaining_accounts)?;
+            let sysvar_instructions = next_account_info(remaining_accounts)?;
+            let system_program = next_account_info(remaining_accounts)?;
+
+            let revoke = RevokeBuilder::new()
+                .delegate_record(delegate_record.key())
+                .delegate(program_as_signer.key())
+                .metadata(metadata.key())
+                .master_edition(edition.key())
+                .token_record(token_record.key())
+                .mint(token_mint.key())
+                .token(token_account.key())
+                .authority(wallet.key())
+                .payer(wallet.key())
+                .system_program(system_program.key())
+                .sysvar_instructions(sysvar_instructions.key())
+                .spl_token_program(token_program.key())
+                .authorization_rules_program(auth_rules_program.key())
+                .authorization_rules(auth_rules.key())
+                .build(RevokeArgs::SaleV1)
+                .unwrap()
+                .instruction();
+
+            let revoke_accounts = [
+                wallet.to_account_info(),
+                program_as_signer.to_account_info(),
+                metadata_program.to_account_info(),
+                delegate_record.to_account_info(),
+                authority.to_account_info(),
+                metadata.to_account_info(),
+                token_record.to_account_info(),
+                edition.to_account_info(),
+                token_account.to_account_info(),
+                wallet.to_account_info(),
+                token_mint.to_account_info(),
+                system_program.to_account_info(),
+                sysvar_instructions.to_account_info(),
+                token_program.to_account_info(),
+                auth_rules_program.to_account_info(),
+                auth_rules.to_account_info(),
+            ];
+
+            invoke(&revoke, &revoke_accounts)?;
+        }
+        Err(_) => {
+            invoke(
+                &revoke(
+                    &token_program.key(),
+                    &token_account.key(),
+                    &wallet.key(),
+                    &[],
+                )
+                .unwrap(),
+                &[
+                    token_program.to_account_info(),
+                    token_account.to_account_info(),
+                    wallet.to_account_info(),
+                ],
+            )?;
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,264 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program, sysvar},
+    InstructionData, ToAccountMetas,
+};
+use mpl_token_metadata::{pda::find_metadata_account, state::Metadata};
+
+use super::{
+    gateway_accounts, shared_escrow_accounts, sign_off, trailing_accounts, treasury_payment_account,
+};
+use crate::{
+    accounts, instruction,
+    pda::{
+        find_auctioneer_pda, find_escrow_payment_address, find_public_bid_trade_state_address,
+        find_trade_state_address,
+    },
+    state::AuctionHouse,
+};
+
+/// Builds a `buy` instruction, `public_buy` for a public bid, or their auctioneer variants when an auctioneer
+/// authority is set.
+pub struct BuyBuilder<'a> {
+    auction_house_key: Pubkey,
+    auction_house: &'a AuctionHouse,
+    metadata: &'a Metadata,
+    wallet: Pubkey,
+    token_account: Pubkey,
+    price: u64,
+    token_size: u64,
+    public: bool,
+    auctioneer_authority: Option<Pubkey>,
+    treasury_token_program: Pubkey,
+}
+
+impl<'a> BuyBuilder<'a> {
+    /// Bid `price` for one token of `metadata` held in `token_account`.
+    pub fn new(
+        auction_house_key: Pubkey,
+        auction_house: &'a AuctionHouse,
+        metadata: &'a Metadata,
+        wallet: Pubkey,
+        token_account: Pubkey,
+        price: u64,
+    ) -> Self {
+        Self {
+            auction_house_key,
+            auction_house,
+            metadata,
+            wallet,
+            token_account,
+            price,
+            token_size: 1,
+            public: false,
+            auctioneer_authority: None,
+            treasury_token_program: spl_token::id(),
+        }
+    }
+
+    pub fn token_size(mut self, token_size: u64) -> Self {
+        self.token_size = token_size;
+        self
+    }
+
+    /// Bid on the mint rather than on the token account, so the bid follows the token when it moves.
+    pub fn public(mut self) -> Self {
+        self.public = true;
+        self
+    }
+
+    /// Bid through `auctioneer_authority`.
+    pub fn auctioneer(mut self, auctioneer_authority: Pubkey) -> Self {
+        self.auctioneer_authority = Some(auctioneer_authority);
+        self
+    }
+
+    /// Token program of the treasury mint, when it is a Token-2022 mint.
+    pub fn treasury_token_program(mut self, treasury_token_program: Pubkey) -> Self {
+        self.treasury_token_program = treasury_token_program;
+        self
+    }
+
+    /// Buyer trade state of the bid.
+    pub fn buyer_trade_state(&self) -> Pubkey {
+        self.find_buyer_trade_state().0
+    }
+
+    /// Escrow payment account the bid is funded from.
+    pub fn escrow_payment_account(&self) -> Pubkey {
+        find_escrow_payment_address(&self.auction_house_key, &self.wallet).0
+    }
+
+    fn find_buyer_trade_state(&self) -> (Pubkey, u8) {
+        if self.public {
+            find_public_bid_trade_state_address(
+                &self.wallet,
+                &self.auction_house_key,
+                &self.auction_house.treasury_mint,
+                &self.metadata.mint,
+                self.price,
+                self.token_size,
+            )
+        } else {
+            find_trade_state_address(
+                &self.wallet,
+                &self.auction_house_key,
+                &self.token_account,
+                &self.auction_house.treasury_mint,
+                &self.metadata.mint,
+                self.price,
+                self.token_size,
+            )
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let (buyer_trade_state, trade_state_bump) = self.find_buyer_trade_state();
+        let (escrow_payment_account, escrow_payment_bump) =
+            find_escrow_payment_address(&self.auction_house_key, &self.wallet);
+        let (metadata, _) = find_metadata_account(&self.metadata.mint);
+        let payment_account = treasury_payment_account(
+            self.auction_house,
+            &self.wallet,
+            &self.treasury_token_program,
+        );
+        let ah = self.auction_house;
+
+        let (mut accounts, data) = match (self.auctioneer_authority, self.public) {
+            (None, false) => (
+                accounts::Buy {
+                    wallet: self.wallet,
+                    payment_account,
+                    transfer_authority: self.wallet,
+                    treasury_mint: ah.treasury_mint,
+                    token_account: self.token_account,
+                    metadata,
+                    escrow_payment_account,
+                    authority: ah.authority,
+                    auction_house: self.auction_house_key,
+                    auction_house_fee_account: ah.auction_house_fee_account,
+                    buyer_trade_state,
+                    token_program: self.treasury_token_program,
+                    system_program: system_program::id(),
+                    rent: sysvar::rent::id(),
+                }
+                .to_account_metas(None),
+                instruction::Buy {
+                    trade_state_bump,
+                    escrow_payment_bump,
+                    buyer_price: self.price,
+                    token_size: self.token_size,
+                }
+                .data(),
+            ),
+            (None, true) => (
+                accounts::PublicBuy {
+                    wallet: self.wallet,
+                    payment_account,
+                    transfer_authority: self.wallet,
+                    treasury_mint: ah.treasury_mint,
+                    token_account: self.token_account,
+                    metadata,
+                    escrow_payment_account,
+                    authority: ah.authority,
+                    auction_house: self.auction_house_key,
+                    auction_house_fee_account: ah.auction_house_fee_account,
+                    buyer_trade_state,
+                    token_program: self.treasury_token_program,
+                    system_program: system_program::id(),
+                    rent: sysvar::rent::id(),
+                }
+                .to_account_metas(None),
+                instruction::PublicBuy {
+                    trade_state_bump,
+                    escrow_payment_bump,
+                    buyer_price: self.price,
+                    token_size: self.token_size,
+                }
+                .data(),
+            ),
+            (Some(auctioneer_authority), false) => (
+                accounts::AuctioneerBuy {
+                    wallet: self.wallet,
+                    payment_account,
+                    transfer_authority: self.wallet,
+                    treasury_mint: ah.treasury_mint,
+                    token_account: self.token_account,
+                    metadata,
+                    escrow_payment_account,
+                    authority: ah.authority,
+                    auctioneer_authority,
+                    auction_house: self.auction_house_key,
+                    auction_house_fee_account: ah.auction_house_fee_account,
+                    buyer_trade_state,
+                    ah_auctioneer_pda: find_auctioneer_pda(
+                        &self.auction_house_key,
+                        &auctioneer_authority,
+                    )
+                    .0,
+                    token_program: self.treasury_token_program,
+                    system_program: system_program::id(),
+                    rent: sysvar::rent::id(),
+                }
+                .to_account_metas(None),
+                instruction::AuctioneerBuy {
+                    trade_state_bump,
+                    escrow_payment_bump,
+                    buyer_price: self.price,
+                    token_size: self.token_size,
+                }
+                .data(),
+            ),
+            (Some(auctioneer_authority), true) => (
+                accounts::AuctioneerPublicBuy {
+                    wallet: self.wallet,
+                    payment_account,
+                    transfer_authority: self.wallet,
+                    treasury_mint: ah.treasury_mint,
+                    token_account: self.token_account,
+                    metadata,
+                    escrow_payment_account,
+                    authority: ah.authority,
+                    auctioneer_authority,
+                    auction_house: self.auction_house_key,
+                    auction_house_fee_account: ah.auction_house_fee_account,
+                    buyer_trade_state,
+                    ah_auctioneer_pda: find_auctioneer_pda(
+                        &self.auction_house_key,
+                        &auctioneer_authority,
+                    )
+                    .0,
+                    token_program: self.treasury_token_program,
+                    system_program: system_program::id(),
+                    rent: sysvar::rent::id(),
+                }
+                .to_account_metas(None),
+                instruction::AuctioneerPublicBuy {
+                    trade_state_bump,
+                    escrow_payment_bump,
+                    buyer_price: self.price,
+                    token_size: self.token_size,
+                }
+                .data(),
+            ),
+        };
+        sign_off(&mut accounts, ah);
+        accounts.append(&mut trailing_accounts(
+            &self.auction_house_key,
+            ah,
+            Some(&self.wallet),
+        ));
+        accounts.append(&mut gateway_accounts(ah, &self.wallet));
+        accounts.append(&mut shared_escrow_accounts(
+            &self.auction_house_key,
+            ah,
+            &self.wallet,
+        ));
+
+        Instruction {
+            program_id: crate::id(),
+            accounts,
+            data,
+        }
+    }
+}
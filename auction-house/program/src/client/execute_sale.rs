@@ -0,0 +1,390 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program, sysvar,
+    },
+    InstructionData, ToAccountMetas,
+};
+use mpl_token_metadata::{
+    pda::{find_master_edition_account, find_metadata_account, find_token_record_account},
+    state::Metadata,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use super::{
+    auth_rules, fee_tier_holder_accounts, is_native, is_programmable, royalty_election_account,
+    set_writable, shared_escrow_accounts, sign_off, trailing_accounts, treasury_payment_account,
+};
+use crate::{
+    accounts, instruction,
+    pda::{
+        find_auction_house_stats_address, find_auctioneer_pda, find_auctioneer_trade_state_address,
+        find_escrow_payment_address, find_program_as_signer_address,
+        find_public_bid_trade_state_address, find_trade_state_address,
+    },
+    state::AuctionHouse,
+};
+
+/// Builds an `execute_sale` instruction, `execute_partial_sale` when the bid is for part of the listing, or
+/// their auctioneer variants when an auctioneer authority is set.
+pub struct ExecuteSaleBuilder<'a> {
+    auction_house_key: Pubkey,
+    auction_house: &'a AuctionHouse,
+    metadata: &'a Metadata,
+    buyer: Pubkey,
+    seller: Pubkey,
+    token_account: Pubkey,
+    price: u64,
+    token_size: u64,
+    partial_order: Option<(u64, u64)>,
+    public_bid: bool,
+    auctioneer_authority: Option<Pubkey>,
+    referrer: Option<Pubkey>,
+    treasury_token_program: Pubkey,
+    fee_tier_holder: Option<(Pubkey, Pubkey)>,
+}
+
+impl<'a> ExecuteSaleBuilder<'a> {
+    /// Sell one token of `metadata` listed by `seller` from `token_account` to `buyer` at `price`.
+    pub fn new(
+        auction_house_key: Pubkey,
+        auction_house: &'a AuctionHouse,
+        metadata: &'a Metadata,
+        buyer: Pubkey,
+        seller: Pubkey,
+        token_account: Pubkey,
+        price: u64,
+    ) -> Self {
+        Self {
+            auction_house_key,
+            auction_house,
+            metadata,
+            buyer,
+            seller,
+            token_account,
+            price,
+            token_size: 1,
+            partial_order: None,
+            public_bid: false,
+            auctioneer_authority: None,
+            referrer: None,
+            treasury_token_program: spl_token::id(),
+            fee_tier_holder: None,
+        }
+    }
+
+    /// Number of tokens in the listing.
+    pub fn token_size(mut self, token_size: u64) -> Self {
+        self.token_size = token_size;
+        self
+    }
+
+    /// Fill a bid for `size` of the listed tokens at `price`.
+    pub fn partial_order(mut self, size: u64, price: u64) -> Self {
+        self.partial_order = Some((size, price));
+        self
+    }
+
+    /// The bid was placed with `public_buy`.
+    pub fn public_bid(mut self) -> Self {
+        self.public_bid = true;
+        self
+    }
+
+    /// Execute through `auctioneer_authority`, against a listing made through it.
+    pub fn auctioneer(mut self, auctioneer_authority: Pubkey) -> Self {
+        self.auctioneer_authority = Some(auctioneer_authority);
+        self
+    }
+
+    /// Pay the referral fee of the Auction House to `referrer`.
+    pub fn referrer(mut self, referrer: Pubkey) -> Self {
+        self.referrer = Some(referrer);
+        self
+    }
+
+    /// Token program of the treasury mint, when it is a Token-2022 mint.
+    pub fn treasury_token_program(mut self, treasury_token_program: Pubkey) -> Self {
+        self.treasury_token_program = treasury_token_program;
+        self
+    }
+
+    /// Claim a fee tier of the Auction House for the taker, holding `mint` in `token_account`.
+    pub fn fee_tier_holder(mut self, token_account: Pubkey, mint: Pubkey) -> Self {
+        self.fee_tier_holder = Some((token_account, mint));
+        self
+    }
+
+    /// Seller trade state of the listing.
+    pub fn seller_trade_state(&self) -> Pubkey {
+        match self.auctioneer_authority {
+            Some(_) => find_auctioneer_trade_state_address(
+                &self.seller,
+                &self.auction_house_key,
+                &self.token_account,
+                &self.auction_house.treasury_mint,
+                &self.metadata.mint,
+                self.token_size,
+            ),
+            None => find_trade_state_address(
+                &self.seller,
+                &self.auction_house_key,
+                &self.token_account,
+                &self.auction_house.treasury_mint,
+                &self.metadata.mint,
+                self.price,
+                self.token_size,
+            ),
+        }
+        .0
+    }
+
+    /// Buyer trade state of the bid.
+    pub fn buyer_trade_state(&self) -> Pubkey {
+        let (size, price) = self.partial_order.unwrap_or((self.token_size, self.price));
+        if self.public_bid {
+            find_public_bid_trade_state_address(
+                &self.buyer,
+                &self.auction_house_key,
+                &self.auction_house.treasury_mint,
+                &self.metadata.mint,
+                price,
+                size,
+            )
+        } else {
+            find_trade_state_address(
+                &self.buyer,
+                &self.auction_house_key,
+                &self.token_account,
+                &self.auction_house.treasury_mint,
+                &self.metadata.mint,
+                price,
+                size,
+            )
+        }
+        .0
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let ah = self.auction_house;
+        let (free_trade_state, free_trade_state_bump) = find_trade_state_address(
+            &self.seller,
+            &self.auction_house_key,
+            &self.token_account,
+            &ah.treasury_mint,
+            &self.metadata.mint,
+            0,
+            self.token_size,
+        );
+        let (escrow_payment_account, escrow_payment_bump) =
+            find_escrow_payment_address(&self.auction_house_key, &self.buyer);
+        let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+        let (metadata, _) = find_metadata_account(&self.metadata.mint);
+        let buyer_receipt_token_account =
+            get_associated_token_address(&self.buyer, &self.metadata.mint);
+        let seller_payment_receipt_account =
+            treasury_payment_account(ah, &self.seller, &self.treasury_token_program);
+        let seller_trade_state = self.seller_trade_state();
+        let buyer_trade_state = self.buyer_trade_state();
+        let (partial_order_size, partial_order_price) = self.partial_order.unzip();
+
+        let execute_sale = accounts::ExecuteSale {
+            buyer: self.buyer,
+            seller: self.seller,
+            token_account: self.token_account,
+            token_mint: self.metadata.mint,
+            metadata,
+            treasury_mint: ah.treasury_mint,
+            escrow_payment_account,
+            seller_payment_receipt_account,
+            buyer_receipt_token_account,
+            authority: ah.authority,
+            auction_house: self.auction_house_key,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            auction_house_treasury: ah.auction_house_treasury,
+            buyer_trade_state,
+            seller_trade_state,
+            free_trade_state,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            ata_program: spl_associated_token_account::id(),
+            program_as_signer,
+            rent: sysvar::rent::id(),
+        };
+
+        let (mut accounts, data) = match (self.auctioneer_authority, self.partial_order) {
+            (None, None) => (
+                execute_sale.to_account_metas(None),
+                instruction::ExecuteSale {
+                    escrow_payment_bump,
+                    _free_trade_state_bump: free_trade_state_bump,
+                    program_as_signer_bump,
+                    buyer_price: self.price,
+                    token_size: self.token_size,
+                }
+                .data(),
+            ),
+            (None, Some(_)) => (
+                execute_sale.to_account_metas(None),
+                instruction::ExecutePartialSale {
+                    escrow_payment_bump,
+                    _free_trade_state_bump: free_trade_state_bump,
+                    program_as_signer_bump,
+                    buyer_price: self.price,
+                    token_size: self.token_size,
+                    partial_order_size,
+                    partial_order_price,
+                }
+                .data(),
+            ),
+            (Some(auctioneer_authority), partial_order) => {
+                let ah_auctioneer_pda =
+                    find_auctioneer_pda(&self.auction_house_key, &auctioneer_authority).0;
+                let accounts = accounts::AuctioneerExecuteSale {
+                    buyer: execute_sale.buyer,
+                    seller: execute_sale.seller,
+                    token_account: execute_sale.token_account,
+                    token_mint: execute_sale.token_mint,
+                    metadata: execute_sale.metadata,
+                    treasury_mint: execute_sale.treasury_mint,
+                    escrow_payment_account: execute_sale.escrow_payment_account,
+                    seller_payment_receipt_account: execute_sale.seller_payment_receipt_account,
+                    buyer_receipt_token_account: execute_sale.buyer_receipt_token_account,
+                    authority: execute_sale.authority,
+                    auctioneer_authority,
+                    auction_house: execute_sale.auction_house,
+                    auction_house_fee_account: execute_sale.auction_house_fee_account,
+                    auction_house_treasury: execute_sale.auction_house_treasury,
+                    buyer_trade_state: execute_sale.buyer_trade_state,
+                    seller_trade_state: execute_sale.seller_trade_state,
+                    free_trade_state: execute_sale.free_trade_state,
+                    ah_auctioneer_pda,
+                    token_program: execute_sale.token_program,
+                    system_program: execute_sale.system_program,
+                    ata_program: execute_sale.ata_program,
+                    program_as_signer: execute_sale.program_as_signer,
+                    rent: execute_sale.rent,
+                }
+                .to_account_metas(None);
+                let data = match partial_order {
+                    None => instruction::AuctioneerExecuteSale {
+                        escrow_payment_bump,
+                        _free_trade_state_bump: free_trade_state_bump,
+                        program_as_signer_bump,
+                        buyer_price: self.price,
+                        token_size: self.token_size,
+                    }
+                    .data(),
+                    Some(_) => instruction::AuctioneerExecutePartialSale {
+                        escrow_payment_bump,
+                        _free_trade_state_bump: free_trade_state_bump,
+                        program_as_signer_bump,
+                        buyer_price: self.price,
+                        token_size: self.token_size,
+                        partial_order_size,
+                        partial_order_price,
+                    }
+                    .data(),
+                };
+                (accounts, data)
+            }
+        };
+        sign_off(&mut accounts, ah);
+        accounts.append(&mut self.remaining_accounts(&buyer_receipt_token_account));
+        if is_programmable(self.metadata) {
+            set_writable(&mut accounts, &metadata);
+        }
+
+        Instruction {
+            program_id: crate::id(),
+            accounts,
+            data,
+        }
+    }
+
+    /// Remaining accounts in the order the sale consumes them: the Token-2022 treasury token program, the
+    /// creators and their payment accounts, the token metadata accounts of a programmable NFT, the referrer
+    /// and its payment account, then the royalty election, stats, escrow reservation, collection list, fee tier
+    /// holder and shared escrow.
+    fn remaining_accounts(&self, buyer_receipt_token_account: &Pubkey) -> Vec<AccountMeta> {
+        let ah = self.auction_house;
+        let native = is_native(ah);
+        let mut accounts = Vec::new();
+
+        if self.treasury_token_program == spl_token_2022::id() {
+            accounts.push(AccountMeta::new_readonly(spl_token_2022::id(), false));
+        }
+
+        for creator in self.metadata.data.creators.iter().flatten() {
+            accounts.push(AccountMeta::new(creator.address, false));
+            if !native {
+                accounts.push(AccountMeta::new(
+                    treasury_payment_account(ah, &creator.address, &self.treasury_token_program),
+                    false,
+                ));
+            }
+        }
+
+        if is_programmable(self.metadata) {
+            accounts.append(
+                &mut accounts::ExecuteSaleRemainingAccounts {
+                    metadata_program: mpl_token_metadata::id(),
+                    edition: find_master_edition_account(&self.metadata.mint).0,
+                    owner_tr: find_token_record_account(&self.metadata.mint, &self.token_account).0,
+                    destination_tr: find_token_record_account(
+                        &self.metadata.mint,
+                        buyer_receipt_token_account,
+                    )
+                    .0,
+                    auth_rules_program: mpl_token_auth_rules::id(),
+                    auth_rules: auth_rules(self.metadata),
+                    sysvar_instructions: sysvar::instructions::id(),
+                }
+                .to_account_metas(None),
+            );
+        }
+
+        if let Some(referrer) = self.referrer {
+            accounts.push(AccountMeta::new(referrer, false));
+            if !native {
+                accounts.push(AccountMeta::new(
+                    treasury_payment_account(ah, &referrer, &self.treasury_token_program),
+                    false,
+                ));
+            }
+        }
+
+        accounts.extend(royalty_election_account(
+            &self.auction_house_key,
+            ah,
+            &self.buyer,
+        ));
+
+        if ah.has_stats {
+            let (auction_house_stats, _) =
+                find_auction_house_stats_address(&self.auction_house_key);
+            accounts.push(AccountMeta::new(auction_house_stats, false));
+        }
+
+        accounts.append(&mut trailing_accounts(
+            &self.auction_house_key,
+            ah,
+            Some(&self.buyer),
+        ));
+
+        accounts.append(&mut fee_tier_holder_accounts(
+            ah,
+            &self.buyer,
+            self.fee_tier_holder,
+        ));
+
+        accounts.append(&mut shared_escrow_accounts(
+            &self.auction_house_key,
+            ah,
+            &self.buyer,
+        ));
+
+        accounts
+    }
+}
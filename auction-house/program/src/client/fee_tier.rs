@@ -0,0 +1,46 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::instruction::{AccountMeta, Instruction},
+    InstructionData, ToAccountMetas,
+};
+use mpl_token_metadata::pda::find_metadata_account;
+
+use crate::{accounts, fee_tier::FeeTier, instruction, state::AuctionHouse};
+
+/// Replace the fee tiers of the Auction House as its `authority`.
+pub fn set_fee_tiers(
+    auction_house_key: &Pubkey,
+    authority: &Pubkey,
+    fee_tiers: Vec<FeeTier>,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::SetFeeTiers {
+            authority: *authority,
+            auction_house: *auction_house_key,
+        }
+        .to_account_metas(None),
+        data: instruction::SetFeeTiers { fee_tiers }.data(),
+    }
+}
+
+/// Remaining accounts of sales on Auction Houses with fee tiers, right before the shared escrow: the token account
+/// the taker holds `mint` in and the metadata of `mint`. Takers claiming no tier pass the empty `wallet` account twice instead.
+pub fn fee_tier_holder_accounts(
+    auction_house: &AuctionHouse,
+    wallet: &Pubkey,
+    holder: Option<(Pubkey, Pubkey)>,
+) -> Vec<AccountMeta> {
+    if auction_house.fee_tiers.is_empty() {
+        return Vec::new();
+    }
+
+    let (token_account, metadata) = match holder {
+        Some((token_account, mint)) => (token_account, find_metadata_account(&mint).0),
+        None => (*wallet, *wallet),
+    };
+    vec![
+        AccountMeta::new_readonly(token_account, false),
+        AccountMeta::new_readonly(metadata, false),
+    ]
+}
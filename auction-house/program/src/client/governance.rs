@@ -0,0 +1,151 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program},
+    AccountDeserialize, InstructionData, Result, ToAccountMetas,
+};
+
+use crate::{
+    accounts,
+    governance::{Governance, GovernedChange, PendingChange},
+    instruction,
+    pda::{find_governance_address, find_pending_change_address},
+    state::AuctionHouse,
+};
+
+/// Put the Auction House under governance, paid by its `authority`.
+pub fn enable_governance(
+    auction_house_key: &Pubkey,
+    authority: &Pubkey,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+    delay: i64,
+    withdrawal_threshold: u64,
+    fee_withdrawal_threshold: u64,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::EnableGovernance {
+            authority: *authority,
+            auction_house: *auction_house_key,
+            governance: find_governance_address(auction_house_key).0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::EnableGovernance {
+            signers,
+            threshold,
+            delay,
+            withdrawal_threshold,
+            fee_withdrawal_threshold,
+        }
+        .data(),
+    }
+}
+
+/// Propose `change` as the next pending change of the governance, paid by `proposer`.
+pub fn propose_change(
+    auction_house_key: &Pubkey,
+    governance: &Governance,
+    proposer: &Pubkey,
+    change: GovernedChange,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::ProposeChange {
+            proposer: *proposer,
+            auction_house: *auction_house_key,
+            governance: find_governance_address(auction_house_key).0,
+            pending_change: find_pending_change_address(
+                auction_house_key,
+                governance.proposal_count,
+            )
+            .0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::ProposeChange { change }.data(),
+    }
+}
+
+fn review_change_accounts(
+    auction_house_key: &Pubkey,
+    pending_change: &PendingChange,
+    signer: &Pubkey,
+) -> accounts::ReviewChange {
+    accounts::ReviewChange {
+        signer: *signer,
+        proposer: pending_change.proposer,
+        auction_house: *auction_house_key,
+        governance: find_governance_address(auction_house_key).0,
+        pending_change: find_pending_change_address(auction_house_key, pending_change.nonce).0,
+    }
+}
+
+/// Approve the pending change as the governance signer `signer`.
+pub fn approve_change(
+    auction_house_key: &Pubkey,
+    pending_change: &PendingChange,
+    signer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: review_change_accounts(auction_house_key, pending_change, signer)
+            .to_account_metas(None),
+        data: instruction::ApproveChange {}.data(),
+    }
+}
+
+/// Cancel the pending change as the governance signer `signer`, refunding its rent to the proposer.
+pub fn cancel_change(
+    auction_house_key: &Pubkey,
+    pending_change: &PendingChange,
+    signer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: review_change_accounts(auction_house_key, pending_change, signer)
+            .to_account_metas(None),
+        data: instruction::CancelChange {}.data(),
+    }
+}
+
+/// Apply the pending change. Changes of the treasury withdrawal destination are applied to the proposed
+/// destination, every other change passes the current one.
+pub fn apply_change(
+    auction_house_key: &Pubkey,
+    auction_house: &AuctionHouse,
+    pending_change: &PendingChange,
+    treasury_token_program: &Pubkey,
+) -> Instruction {
+    let treasury_withdrawal_destination = match pending_change.change {
+        GovernedChange::TreasuryWithdrawalDestination { destination, .. } => destination,
+        _ => auction_house.treasury_withdrawal_destination,
+    };
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::ApplyChange {
+            proposer: pending_change.proposer,
+            treasury_mint: auction_house.treasury_mint,
+            treasury_withdrawal_destination,
+            auction_house_treasury: auction_house.auction_house_treasury,
+            auction_house: *auction_house_key,
+            governance: find_governance_address(auction_house_key).0,
+            pending_change: find_pending_change_address(auction_house_key, pending_change.nonce).0,
+            token_program: *treasury_token_program,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::ApplyChange {}.data(),
+    }
+}
+
+/// Decode the data of a governance account fetched from the cluster.
+pub fn read_governance(data: &[u8]) -> Result<Governance> {
+    Governance::try_deserialize(&mut &data[..])
+}
+
+/// Decode the data of a pending change account fetched from the cluster.
+pub fn read_pending_change(data: &[u8]) -> Result<PendingChange> {
+    PendingChange::try_deserialize(&mut &data[..])
+}
@@ -0,0 +1,21 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program},
+    InstructionData, ToAccountMetas,
+};
+
+use crate::{accounts, instruction};
+
+/// Grow an Auction House created before versioning to the latest layout, paid by its `authority`.
+pub fn migrate_auction_house(auction_house_key: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::MigrateAuctionHouse {
+            authority: *authority,
+            auction_house: *auction_house_key,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::MigrateAuctionHouse {}.data(),
+    }
+}
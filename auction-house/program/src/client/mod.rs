@@ -0,0 +1,162 @@
+//! Instruction builders for Rust clients of the Auction House.
+//!
+//! Builders derive every PDA and bump of an instruction and append the remaining accounts the program
+//! expects for creators, programmable NFTs, royalty elections, stats, escrow reservations, collection lists,
+//! gateway tokens, fee tier holders and shared escrows, based on the Auction House and token metadata accounts. Callers only provide the parties and terms of an order.
+
+pub mod bid;
+pub mod execute_sale;
+pub mod fee_tier;
+pub mod governance;
+pub mod migrate;
+pub mod receipt;
+pub mod royalty;
+pub mod sell;
+pub mod shared_escrow;
+pub mod stats;
+
+pub use bid::*;
+pub use execute_sale::*;
+pub use fee_tier::*;
+pub use governance::*;
+pub use migrate::*;
+pub use receipt::*;
+pub use royalty::*;
+pub use sell::*;
+pub use shared_escrow::*;
+pub use stats::*;
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::AccountMeta};
+use mpl_token_metadata::state::{Metadata, ProgrammableConfig, TokenStandard};
+use solana_gateway::state::{get_expire_address_with_seed, get_gateway_token_address_with_seed};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::{
+    gateway::GATEWAY_PROGRAM_ID,
+    pda::{
+        find_collection_list_address, find_escrow_reservation_address,
+        find_royalty_election_address,
+    },
+    reservation::EscrowReservationMode,
+    royalty::RoyaltyPolicy,
+    state::AuctionHouse,
+};
+
+/// Whether the Auction House trades in native SOL.
+pub fn is_native(auction_house: &AuctionHouse) -> bool {
+    auction_house.treasury_mint == spl_token::native_mint::id()
+}
+
+/// Account `wallet` pays from or is paid at in the treasury mint of the Auction House: the wallet itself for
+/// native SOL, its associated token account otherwise.
+pub fn treasury_payment_account(
+    auction_house: &AuctionHouse,
+    wallet: &Pubkey,
+    treasury_token_program: &Pubkey,
+) -> Pubkey {
+    if is_native(auction_house) {
+        *wallet
+    } else {
+        get_associated_token_address_with_program_id(
+            wallet,
+            &auction_house.treasury_mint,
+            treasury_token_program,
+        )
+    }
+}
+
+/// Whether the token is a programmable NFT, moved through token metadata instead of the token program.
+pub fn is_programmable(metadata: &Metadata) -> bool {
+    matches!(
+        metadata.token_standard,
+        Some(TokenStandard::ProgrammableNonFungible)
+    )
+}
+
+/// Authorization rules of a programmable NFT. Token metadata takes its own program id when there are none.
+pub fn auth_rules(metadata: &Metadata) -> Pubkey {
+    match metadata.programmable_config {
+        Some(ProgrammableConfig::V1 {
+            rule_set: Some(rule_set),
+        }) => rule_set,
+        _ => mpl_token_metadata::id(),
+    }
+}
+
+/// Trailing remaining accounts of orders and sales: the escrow reservation of `wallet` when the Auction House
+/// tracks bids, followed by its collection list when it is curated.
+fn trailing_accounts(
+    auction_house_key: &Pubkey,
+    auction_house: &AuctionHouse,
+    wallet: Option<&Pubkey>,
+) -> Vec<AccountMeta> {
+    let mut accounts = Vec::new();
+    if let Some(wallet) = wallet {
+        if auction_house.escrow_reservation_mode != EscrowReservationMode::Disabled {
+            let (escrow_reservation, _) =
+                find_escrow_reservation_address(auction_house_key, wallet);
+            accounts.push(AccountMeta::new(escrow_reservation, false));
+        }
+    }
+    if auction_house.has_collection_list {
+        let (collection_list, _) = find_collection_list_address(auction_house_key);
+        accounts.push(AccountMeta::new_readonly(collection_list, false));
+    }
+
+    accounts
+}
+
+/// Royalty election of `buyer` read by sales on Auction Houses that do not enforce royalties. Buyers that never
+/// elected a share pass the empty account.
+fn royalty_election_account(
+    auction_house_key: &Pubkey,
+    auction_house: &AuctionHouse,
+    buyer: &Pubkey,
+) -> Option<AccountMeta> {
+    if auction_house.royalty_policy == RoyaltyPolicy::Enforced {
+        return None;
+    }
+
+    let (royalty_election, _) = find_royalty_election_address(auction_house_key, buyer);
+    Some(AccountMeta::new_readonly(royalty_election, false))
+}
+
+/// Remaining accounts of gated Auction Houses, last but for the shared escrow of a bidder: the gateway token of
+/// `wallet`, followed by the gateway program and the network expire feature when the token expires on use.
+fn gateway_accounts(auction_house: &AuctionHouse, wallet: &Pubkey) -> Vec<AccountMeta> {
+    let mut accounts = Vec::new();
+    if let Some(gatekeeper) = auction_house.gatekeeper {
+        let (gateway_token, _) =
+            get_gateway_token_address_with_seed(wallet, &None, &gatekeeper.gatekeeper_network);
+        if gatekeeper.expire_on_use {
+            let (expire_feature, _) = get_expire_address_with_seed(&gatekeeper.gatekeeper_network);
+            accounts.push(AccountMeta::new(gateway_token, false));
+            accounts.push(AccountMeta::new_readonly(GATEWAY_PROGRAM_ID, false));
+            accounts.push(AccountMeta::new_readonly(expire_feature, false));
+        } else {
+            accounts.push(AccountMeta::new_readonly(gateway_token, false));
+        }
+    }
+
+    accounts
+}
+
+/// Mark the Auction House authority as a signer when the house requires its sign off.
+fn sign_off(accounts: &mut [AccountMeta], auction_house: &AuctionHouse) {
+    if auction_house.requires_sign_off {
+        set_signer(accounts, &auction_house.authority);
+    }
+}
+
+fn set_signer(accounts: &mut [AccountMeta], key: &Pubkey) {
+    for account in accounts.iter_mut().filter(|a| a.pubkey == *key) {
+        account.is_signer = true;
+    }
+}
+
+/// Token metadata writes to the metadata account when it moves or delegates a programmable NFT.
+fn set_writable(accounts: &mut [AccountMeta], key: &Pubkey) {
+    for account in accounts.iter_mut().filter(|a| a.pubkey == *key) {
+        account.is_writable = true;
+    }
+}
@@ -0,0 +1,193 @@
+//! Receipt instructions read the instruction that precedes them in the transaction, so `print_*` and
+//! `cancel_*` instructions go right after the order instruction they record.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program, sysvar,
+    },
+    InstructionData, Result, ToAccountMetas,
+};
+
+use crate::{
+    accounts, instruction,
+    pda::{
+        find_auction_house_stats_address, find_bid_receipt_address, find_listing_receipt_address,
+        find_purchase_receipt_address,
+    },
+    receipt::{load_purchase_receipt, PurchaseReceipt},
+    state::AuctionHouse,
+};
+
+/// Record the listing made by the previous `sell` instruction, paid by `bookkeeper`.
+pub fn print_listing_receipt(seller_trade_state: &Pubkey, bookkeeper: &Pubkey) -> Instruction {
+    let (receipt, receipt_bump) = find_listing_receipt_address(seller_trade_state);
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::PrintListingReceipt {
+            receipt,
+            bookkeeper: *bookkeeper,
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            instruction: sysvar::instructions::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::PrintListingReceipt { receipt_bump }.data(),
+    }
+}
+
+/// Record the cancellation of a listing by the previous `cancel` instruction.
+pub fn cancel_listing_receipt(seller_trade_state: &Pubkey) -> Instruction {
+    let (receipt, _) = find_listing_receipt_address(seller_trade_state);
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::CancelListingReceipt {
+            receipt,
+            system_program: system_program::id(),
+            instruction: sysvar::instructions::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::CancelListingReceipt {}.data(),
+    }
+}
+
+/// Record the bid made by the previous `buy` or `public_buy` instruction, paid by `bookkeeper`.
+pub fn print_bid_receipt(buyer_trade_state: &Pubkey, bookkeeper: &Pubkey) -> Instruction {
+    let (receipt, receipt_bump) = find_bid_receipt_address(buyer_trade_state);
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::PrintBidReceipt {
+            receipt,
+            bookkeeper: *bookkeeper,
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            instruction: sysvar::instructions::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::PrintBidReceipt { receipt_bump }.data(),
+    }
+}
+
+/// Record the cancellation of a bid by the previous `cancel` instruction.
+pub fn cancel_bid_receipt(buyer_trade_state: &Pubkey) -> Instruction {
+    let (receipt, _) = find_bid_receipt_address(buyer_trade_state);
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::CancelBidReceipt {
+            receipt,
+            system_program: system_program::id(),
+            instruction: sysvar::instructions::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::CancelBidReceipt {}.data(),
+    }
+}
+
+/// Record the sale made by the previous `execute_sale` instruction, paid by `bookkeeper`.
+/// Append [`purchase_royalty_accounts`] to record the royalty paid on the sale.
+pub fn print_purchase_receipt(
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    bookkeeper: &Pubkey,
+) -> Instruction {
+    let (purchase_receipt, purchase_receipt_bump) =
+        find_purchase_receipt_address(seller_trade_state, buyer_trade_state);
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::PrintPurchaseReceipt {
+            purchase_receipt,
+            listing_receipt: find_listing_receipt_address(seller_trade_state).0,
+            bid_receipt: find_bid_receipt_address(buyer_trade_state).0,
+            bookkeeper: *bookkeeper,
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            instruction: sysvar::instructions::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::PrintPurchaseReceipt {
+            purchase_receipt_bump,
+        }
+        .data(),
+    }
+}
+
+/// Remaining accounts of `print_purchase_receipt` recording the royalty paid on the sale: the stats account of the
+/// Auction House, empty when it keeps no stats.
+pub fn purchase_royalty_accounts(
+    auction_house_key: &Pubkey,
+    auction_house: &AuctionHouse,
+) -> Vec<AccountMeta> {
+    if !auction_house.has_stats {
+        return Vec::new();
+    }
+
+    let (auction_house_stats, _) = find_auction_house_stats_address(auction_house_key);
+    vec![AccountMeta::new_readonly(auction_house_stats, false)]
+}
+
+/// Close the receipt of a filled or cancelled listing and refund its rent to `bookkeeper`.
+pub fn close_listing_receipt(
+    seller_trade_state: &Pubkey,
+    bookkeeper: &Pubkey,
+    emit_event: bool,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::CloseOrderReceipt {
+            receipt: find_listing_receipt_address(seller_trade_state).0,
+            trade_state: *seller_trade_state,
+            bookkeeper: *bookkeeper,
+        }
+        .to_account_metas(None),
+        data: instruction::CloseListingReceipt { emit_event }.data(),
+    }
+}
+
+/// Close the receipt of a filled or cancelled bid and refund its rent to `bookkeeper`.
+pub fn close_bid_receipt(
+    buyer_trade_state: &Pubkey,
+    bookkeeper: &Pubkey,
+    emit_event: bool,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::CloseOrderReceipt {
+            receipt: find_bid_receipt_address(buyer_trade_state).0,
+            trade_state: *buyer_trade_state,
+            bookkeeper: *bookkeeper,
+        }
+        .to_account_metas(None),
+        data: instruction::CloseBidReceipt { emit_event }.data(),
+    }
+}
+
+/// Close a purchase receipt and refund its rent to `bookkeeper`.
+pub fn close_purchase_receipt(
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    bookkeeper: &Pubkey,
+    emit_event: bool,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::ClosePurchaseReceipt {
+            purchase_receipt: find_purchase_receipt_address(seller_trade_state, buyer_trade_state)
+                .0,
+            bookkeeper: *bookkeeper,
+        }
+        .to_account_metas(None),
+        data: instruction::ClosePurchaseReceipt { emit_event }.data(),
+    }
+}
+
+/// Decode the data of a purchase receipt fetched from the cluster, including receipts printed before the royalty
+/// was recorded.
+pub fn read_purchase_receipt(data: &[u8]) -> Result<PurchaseReceipt> {
+    load_purchase_receipt(data)
+}
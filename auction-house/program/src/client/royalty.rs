@@ -0,0 +1,53 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program, sysvar},
+    AccountDeserialize, InstructionData, Result, ToAccountMetas,
+};
+
+use crate::{
+    accounts, instruction,
+    pda::find_royalty_election_address,
+    royalty::{RoyaltyElection, RoyaltyPolicy},
+};
+
+/// Set the royalty policy of the Auction House as its `authority`.
+pub fn set_royalty_policy(
+    auction_house_key: &Pubkey,
+    authority: &Pubkey,
+    royalty_policy: RoyaltyPolicy,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::SetRoyaltyPolicy {
+            authority: *authority,
+            auction_house: *auction_house_key,
+        }
+        .to_account_metas(None),
+        data: instruction::SetRoyaltyPolicy { royalty_policy }.data(),
+    }
+}
+
+/// Elect the share of the royalty `wallet` pays on its purchases, in basis points of the royalty.
+pub fn set_royalty_election(
+    auction_house_key: &Pubkey,
+    wallet: &Pubkey,
+    basis_points: u16,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::SetRoyaltyElection {
+            wallet: *wallet,
+            auction_house: *auction_house_key,
+            royalty_election: find_royalty_election_address(auction_house_key, wallet).0,
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::SetRoyaltyElection { basis_points }.data(),
+    }
+}
+
+/// Decode the data of a royalty election account fetched from the cluster.
+pub fn read_royalty_election(data: &[u8]) -> Result<RoyaltyElection> {
+    RoyaltyElection::try_deserialize(&mut &data[..])
+}
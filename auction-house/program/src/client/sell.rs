@@ -0,0 +1,213 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program, sysvar},
+    InstructionData, ToAccountMetas,
+};
+use mpl_token_metadata::{
+    pda::{find_master_edition_account, find_metadata_account, find_token_record_account},
+    state::Metadata,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use super::{
+    auth_rules, gateway_accounts, is_programmable, set_writable, sign_off, trailing_accounts,
+};
+use crate::{
+    accounts, instruction,
+    pda::{
+        find_auctioneer_pda, find_auctioneer_trade_state_address, find_program_as_signer_address,
+        find_trade_state_address,
+    },
+    state::AuctionHouse,
+};
+
+/// Builds a `sell` instruction, or `auctioneer_sell` when an auctioneer authority is set.
+pub struct SellBuilder<'a> {
+    auction_house_key: Pubkey,
+    auction_house: &'a AuctionHouse,
+    metadata: &'a Metadata,
+    wallet: Pubkey,
+    token_account: Pubkey,
+    price: u64,
+    token_size: u64,
+    auctioneer_authority: Option<Pubkey>,
+}
+
+impl<'a> SellBuilder<'a> {
+    /// List one token of `metadata` held in the associated token account of `wallet` at `price`.
+    pub fn new(
+        auction_house_key: Pubkey,
+        auction_house: &'a AuctionHouse,
+        metadata: &'a Metadata,
+        wallet: Pubkey,
+        price: u64,
+    ) -> Self {
+        Self {
+            auction_house_key,
+            auction_house,
+            metadata,
+            wallet,
+            token_account: get_associated_token_address(&wallet, &metadata.mint),
+            price,
+            token_size: 1,
+            auctioneer_authority: None,
+        }
+    }
+
+    /// Token account holding the listed tokens, when it is not the associated token account of the seller.
+    pub fn token_account(mut self, token_account: Pubkey) -> Self {
+        self.token_account = token_account;
+        self
+    }
+
+    pub fn token_size(mut self, token_size: u64) -> Self {
+        self.token_size = token_size;
+        self
+    }
+
+    /// List through `auctioneer_authority`. Auctioneer listings have no price, which is set at execution.
+    pub fn auctioneer(mut self, auctioneer_authority: Pubkey) -> Self {
+        self.auctioneer_authority = Some(auctioneer_authority);
+        self
+    }
+
+    /// Seller trade state of the listing.
+    pub fn seller_trade_state(&self) -> Pubkey {
+        self.find_seller_trade_state().0
+    }
+
+    fn find_seller_trade_state(&self) -> (Pubkey, u8) {
+        match self.auctioneer_authority {
+            Some(_) => find_auctioneer_trade_state_address(
+                &self.wallet,
+                &self.auction_house_key,
+                &self.token_account,
+                &self.auction_house.treasury_mint,
+                &self.metadata.mint,
+                self.token_size,
+            ),
+            None => find_trade_state_address(
+                &self.wallet,
+                &self.auction_house_key,
+                &self.token_account,
+                &self.auction_house.treasury_mint,
+                &self.metadata.mint,
+                self.price,
+                self.token_size,
+            ),
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let (seller_trade_state, trade_state_bump) = self.find_seller_trade_state();
+        let (free_seller_trade_state, free_trade_state_bump) = find_trade_state_address(
+            &self.wallet,
+            &self.auction_house_key,
+            &self.token_account,
+            &self.auction_house.treasury_mint,
+            &self.metadata.mint,
+            0,
+            self.token_size,
+        );
+        let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+        let (metadata, _) = find_metadata_account(&self.metadata.mint);
+
+        let (mut accounts, data) = match self.auctioneer_authority {
+            Some(auctioneer_authority) => (
+                accounts::AuctioneerSell {
+                    wallet: self.wallet,
+                    token_account: self.token_account,
+                    metadata,
+                    authority: self.auction_house.authority,
+                    auctioneer_authority,
+                    auction_house: self.auction_house_key,
+                    auction_house_fee_account: self.auction_house.auction_house_fee_account,
+                    seller_trade_state,
+                    free_seller_trade_state,
+                    ah_auctioneer_pda: find_auctioneer_pda(
+                        &self.auction_house_key,
+                        &auctioneer_authority,
+                    )
+                    .0,
+                    program_as_signer,
+                    token_program: spl_token::id(),
+                    system_program: system_program::id(),
+                    rent: sysvar::rent::id(),
+                }
+                .to_account_metas(None),
+                instruction::AuctioneerSell {
+                    trade_state_bump,
+                    free_trade_state_bump,
+                    program_as_signer_bump,
+                    token_size: self.token_size,
+                }
+                .data(),
+            ),
+            None => (
+                accounts::Sell {
+                    wallet: self.wallet,
+                    token_account: self.token_account,
+                    metadata,
+                    authority: self.auction_house.authority,
+                    auction_house: self.auction_house_key,
+                    auction_house_fee_account: self.auction_house.auction_house_fee_account,
+                    seller_trade_state,
+                    free_seller_trade_state,
+                    token_program: spl_token::id(),
+                    system_program: system_program::id(),
+                    program_as_signer,
+                    rent: sysvar::rent::id(),
+                }
+                .to_account_metas(None),
+                instruction::Sell {
+                    trade_state_bump,
+                    free_trade_state_bump,
+                    program_as_signer_bump,
+                    buyer_price: self.price,
+                    token_size: self.token_size,
+                }
+                .data(),
+            ),
+        };
+        sign_off(&mut accounts, self.auction_house);
+
+        if is_programmable(self.metadata) {
+            set_writable(&mut accounts, &metadata);
+            let program_as_signer_token =
+                get_associated_token_address(&program_as_signer, &self.metadata.mint);
+            accounts.append(
+                &mut accounts::SellRemainingAccounts {
+                    metadata_program: mpl_token_metadata::id(),
+                    delegate_record: find_token_record_account(
+                        &self.metadata.mint,
+                        &program_as_signer_token,
+                    )
+                    .0,
+                    token_record: find_token_record_account(
+                        &self.metadata.mint,
+                        &self.token_account,
+                    )
+                    .0,
+                    token_mint: self.metadata.mint,
+                    edition: find_master_edition_account(&self.metadata.mint).0,
+                    auth_rules_program: mpl_token_auth_rules::id(),
+                    auth_rules: auth_rules(self.metadata),
+                    sysvar_instructions: sysvar::instructions::id(),
+                }
+                .to_account_metas(None),
+            );
+        }
+        accounts.append(&mut trailing_accounts(
+            &self.auction_house_key,
+            self.auction_house,
+            None,
+        ));
+        accounts.append(&mut gateway_accounts(self.auction_house, &self.wallet));
+
+        Instruction {
+            program_id: crate::id(),
+            accounts,
+            data,
+        }
+    }
+}
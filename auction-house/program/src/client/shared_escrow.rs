@@ -0,0 +1,148 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program, sysvar,
+    },
+    InstructionData, ToAccountMetas,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::{
+    accounts, instruction,
+    pda::{find_shared_escrow_address, find_shared_escrow_approval_address},
+    state::AuctionHouse,
+};
+
+/// Set whether sales of the Auction House draw from shared escrows, as its `authority`.
+pub fn set_accepts_shared_escrow(
+    auction_house_key: &Pubkey,
+    authority: &Pubkey,
+    accepts_shared_escrow: bool,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::SetAcceptsSharedEscrow {
+            authority: *authority,
+            auction_house: *auction_house_key,
+        }
+        .to_account_metas(None),
+        data: instruction::SetAcceptsSharedEscrow {
+            accepts_shared_escrow,
+        }
+        .data(),
+    }
+}
+
+/// Account `wallet` pays from or is paid at in `treasury_mint`: the wallet itself for native SOL, its associated
+/// token account otherwise.
+fn payment_account(
+    wallet: &Pubkey,
+    treasury_mint: &Pubkey,
+    treasury_token_program: &Pubkey,
+) -> Pubkey {
+    if *treasury_mint == spl_token::native_mint::id() {
+        *wallet
+    } else {
+        get_associated_token_address_with_program_id(wallet, treasury_mint, treasury_token_program)
+    }
+}
+
+/// Deposit `amount` of `treasury_mint` into the shared escrow of `wallet`.
+pub fn deposit_shared_escrow(
+    wallet: &Pubkey,
+    treasury_mint: &Pubkey,
+    treasury_token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::DepositSharedEscrow {
+            wallet: *wallet,
+            payment_account: payment_account(wallet, treasury_mint, treasury_token_program),
+            transfer_authority: *wallet,
+            treasury_mint: *treasury_mint,
+            shared_escrow: find_shared_escrow_address(treasury_mint, wallet).0,
+            token_program: *treasury_token_program,
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::DepositSharedEscrow { amount }.data(),
+    }
+}
+
+/// Withdraw `amount` of `treasury_mint` from the shared escrow of `wallet`.
+pub fn withdraw_shared_escrow(
+    wallet: &Pubkey,
+    treasury_mint: &Pubkey,
+    treasury_token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::WithdrawSharedEscrow {
+            wallet: *wallet,
+            receipt_account: payment_account(wallet, treasury_mint, treasury_token_program),
+            treasury_mint: *treasury_mint,
+            shared_escrow: find_shared_escrow_address(treasury_mint, wallet).0,
+            token_program: *treasury_token_program,
+            system_program: system_program::id(),
+            ata_program: spl_associated_token_account::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::WithdrawSharedEscrow { amount }.data(),
+    }
+}
+
+/// Approve the Auction House to draw from the shared escrow of `wallet` on its sales.
+pub fn approve_shared_escrow(auction_house_key: &Pubkey, wallet: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::ApproveSharedEscrow {
+            wallet: *wallet,
+            auction_house: *auction_house_key,
+            shared_escrow_approval: find_shared_escrow_approval_address(auction_house_key, wallet)
+                .0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::ApproveSharedEscrow {}.data(),
+    }
+}
+
+/// Revoke the approval of the Auction House to draw from the shared escrow of `wallet`.
+pub fn revoke_shared_escrow(auction_house_key: &Pubkey, wallet: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::RevokeSharedEscrow {
+            wallet: *wallet,
+            auction_house: *auction_house_key,
+            shared_escrow_approval: find_shared_escrow_approval_address(auction_house_key, wallet)
+                .0,
+        }
+        .to_account_metas(None),
+        data: instruction::RevokeSharedEscrow {}.data(),
+    }
+}
+
+/// Remaining accounts of bids and sales on Auction Houses accepting shared escrows, last but for the gateway accounts
+/// of the seller accepting a bid or the taker accepting a swap offer: the shared escrow of `buyer`, followed by its
+/// approval of the Auction House, empty when the buyer has not approved it.
+pub fn shared_escrow_accounts(
+    auction_house_key: &Pubkey,
+    auction_house: &AuctionHouse,
+    buyer: &Pubkey,
+) -> Vec<AccountMeta> {
+    if !auction_house.accepts_shared_escrow {
+        return Vec::new();
+    }
+
+    let (shared_escrow, _) = find_shared_escrow_address(&auction_house.treasury_mint, buyer);
+    let (approval, _) = find_shared_escrow_approval_address(auction_house_key, buyer);
+    vec![
+        AccountMeta::new(shared_escrow, false),
+        AccountMeta::new_readonly(approval, false),
+    ]
+}
@@ -0,0 +1,29 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program},
+    AccountDeserialize, InstructionData, Result, ToAccountMetas,
+};
+
+use crate::{
+    accounts, instruction, pda::find_auction_house_stats_address, stats::AuctionHouseStats,
+};
+
+/// Create the stats account of the Auction House, paid by its `authority`.
+pub fn create_auction_house_stats(auction_house_key: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: accounts::CreateAuctionHouseStats {
+            authority: *authority,
+            auction_house: *auction_house_key,
+            auction_house_stats: find_auction_house_stats_address(auction_house_key).0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::CreateAuctionHouseStats {}.data(),
+    }
+}
+
+/// Decode the data of an Auction House stats account fetched from the cluster.
+pub fn read_auction_house_stats(data: &[u8]) -> Result<AuctionHouseStats> {
+    AuctionHouseStats::try_deserialize(&mut &data[..])
+}
@@ -6,13 +6,78 @@ pub const PURCHASE_RECEIPT_PREFIX: &str = "purchase_receipt";
 pub const BID_RECEIPT_PREFIX: &str = "bid_receipt";
 pub const LISTING_RECEIPT_PREFIX: &str = "listing_receipt";
 pub const AUCTIONEER: &str = "auctioneer";
+pub const COLLECTION: &str = "collection";
+pub const TRAIT: &str = "trait";
+pub const BUNDLE: &str = "bundle";
+pub const SWAP: &str = "swap";
+pub const DUTCH: &str = "dutch";
+pub const COLLECTION_LIST: &str = "collection_list";
+pub const ESCROW_RESERVATION: &str = "escrow_reservation";
+pub const STATS: &str = "stats";
+pub const GOVERNANCE: &str = "governance";
+pub const ROYALTY_ELECTION: &str = "royalty_election";
+pub const SHARED_ESCROW: &str = "shared_escrow";
+pub const SHARED_ESCROW_APPROVAL: &str = "shared_escrow_approval";
 pub const TRADE_STATE_SIZE: usize = 1;
+/// Trade states with optional fields follow the bump with a tag of `TRADE_STATE_HAS_*` flags, and then the fields
+/// of the set flags in the order of the flags.
+pub const TRADE_STATE_TAGGED_SIZE: usize = TRADE_STATE_SIZE + // bump
+1                                                           // tag
+;
+pub const TRADE_STATE_HAS_EXPIRY: u8 = 1 << 0;
+pub const TRADE_STATE_HAS_AUCTIONEER: u8 = 1 << 1;
+pub const TRADE_STATE_HAS_ALLOWED_BUYER: u8 = 1 << 2;
+pub const TRADE_STATE_EXPIRY_SIZE: usize = 8 +              // expires_at
+32                                                          // rent payer
+;
+pub const TRADE_STATE_AUCTIONEER_SIZE: usize = 32; // auctioneer
+pub const TRADE_STATE_ALLOWED_BUYER_SIZE: usize = 32; // allowed buyer
 pub const MAX_NUM_SCOPES: usize = 7;
+pub const MAX_BUNDLE_ITEMS: usize = 8;
+pub const MAX_FEE_TIERS: usize = 8;
+pub const FEE_TIER_SIZE: usize = 32 +                      // mint
+1 + 8 +                                                     // requirement
+2                                                           // fee basis points
+;
 pub const AUCTIONEER_SIZE: usize = 8 +                      // Anchor discriminator/sighash
 32 +                                                        // Auctioneer authority
 32 +                                                        // Auction house instance
 1 +                                                         // bump
-63                                                          // Padding
+MAX_NUM_SCOPES +                                            // Array of AuthorityScope bools
+56                                                          // Padding
+;
+
+pub const AUCTION_HOUSE_VERSION: u8 = 1;
+
+/// Size of Auction Houses created before versioning, which read version 0 and the defaults of later fields from
+/// their zeroed padding. `migrate_auction_house` grows them to `AUCTION_HOUSE_SIZE`.
+pub const AUCTION_HOUSE_V0_SIZE: usize = 8 +                // key
+32 +                                                        // fee Payer
+32 +                                                        // treasury
+32 +                                                        // treasury_withdrawal_destination
+32 +                                                        // fee withdrawal destination
+32 +                                                        // treasury mint
+32 +                                                        // authority
+32 +                                                        // creator
+1 +                                                         // bump
+1 +                                                         // treasury_bump
+1 +                                                         // fee_payer_bump
+2 +                                                         // seller fee basis points
+1 +                                                         // requires sign off
+1 +                                                         // can change sale price
+8 +                                                         // escrow payment bump
+1 +                                                         // has external auctioneer program as an authority
+32 +                                                         // auctioneer address
+MAX_NUM_SCOPES +                                            // Array of AuthorityScope bools
+2 +                                                         // maker fee basis points
+2 +                                                         // taker fee basis points
+2 +                                                         // referral fee basis points
+1 +                                                         // has collection list
+1 +                                                         // escrow reservation mode
+1 +                                                         // has stats
+1 +                                                         // has governance
+1 +                                                         // version
+161                                                         // padding
 ;
 
 pub const AUCTION_HOUSE_SIZE: usize = 8 +                   // key
@@ -33,5 +98,18 @@ pub const AUCTION_HOUSE_SIZE: usize = 8 +                   // key
 1 +                                                         // has external auctioneer program as an authority
 32 +                                                         // auctioneer address
 MAX_NUM_SCOPES +                                            // Array of AuthorityScope bools
-172                                                         // padding
+2 +                                                         // maker fee basis points
+2 +                                                         // taker fee basis points
+2 +                                                         // referral fee basis points
+1 +                                                         // has collection list
+1 +                                                         // escrow reservation mode
+1 +                                                         // has stats
+1 +                                                         // has governance
+1 +                                                         // version
+// Version 1 fields, decoded from the zeroed padding of version 0 accounts until they are migrated.
+1 +                                                         // accepts shared escrow
+1 + 32 + 1 +                                                // gatekeeper
+1 + 2 +                                                     // royalty policy
+4 + MAX_FEE_TIERS * FEE_TIER_SIZE +                         // fee tiers
+256                                                         // padding
 ;
@@ -0,0 +1,224 @@
+//! Curated auction houses restrict trading to NFTs of approved verified collections, or exclude denied ones.
+//! Once a collection list exists, listings, bids and sales on the Auction House pass it among their trailing remaining
+//! accounts, in the position listed in the [crate docs](crate#remaining-accounts).
+
+use anchor_lang::prelude::*;
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+
+use crate::{constants::*, errors::AuctionHouseError, AuctionHouse};
+
+pub const MAX_COLLECTION_LIST_SIZE: usize = 64;
+
+pub const COLLECTION_LIST_SIZE: usize = 8 + // key
+32 + // auction_house
+1 + // mode
+4 + 32 * MAX_COLLECTION_LIST_SIZE + // collections
+1; // bump
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CollectionListMode {
+    /// Only tokens of a listed verified collection can be traded.
+    Allowlist,
+    /// Tokens of a listed verified collection can not be traded.
+    Denylist,
+}
+
+/// Verified collection mints an Auction House allows or denies.
+#[account]
+pub struct CollectionList {
+    pub auction_house: Pubkey,
+    pub mode: CollectionListMode,
+    pub collections: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl CollectionList {
+    /// Check that a token with the verified collection `collection` can be traded.
+    pub fn assert_collection_allowed(&self, collection: Option<&Pubkey>) -> Result<()> {
+        let listed =
+            matches!(collection, Some(collection) if self.collections.contains(collection));
+        let allowed = match self.mode {
+            CollectionListMode::Allowlist => listed,
+            CollectionListMode::Denylist => !listed,
+        };
+
+        if !allowed {
+            return Err(AuctionHouseError::CollectionNotAllowed.into());
+        }
+
+        Ok(())
+    }
+
+    /// Check that the token decorated by `metadata` can be traded.
+    pub fn assert_metadata_allowed(&self, metadata: &AccountInfo) -> Result<()> {
+        let metadata = Metadata::from_account_info(metadata)?;
+        let collection = metadata
+            .collection
+            .filter(|collection| collection.verified)
+            .map(|collection| collection.key);
+
+        self.assert_collection_allowed(collection.as_ref())
+    }
+}
+
+/// Split the collection list off the end of `remaining_accounts` when the Auction House has one.
+/// Returns the list and the remaining accounts left for the handler.
+pub fn split_collection_list<'c, 'info>(
+    auction_house: &Account<'info, AuctionHouse>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<(
+    Option<Account<'info, CollectionList>>,
+    &'c [AccountInfo<'info>],
+)> {
+    if !auction_house.has_collection_list {
+        return Ok((None, remaining_accounts));
+    }
+
+    let (collection_list_info, remaining_accounts) = remaining_accounts
+        .split_last()
+        .ok_or(AuctionHouseError::InvalidCollectionList)?;
+    let collection_list: Account<CollectionList> = Account::try_from(collection_list_info)?;
+    if collection_list.auction_house != auction_house.key() {
+        return Err(AuctionHouseError::InvalidCollectionList.into());
+    }
+
+    Ok((Some(collection_list), remaining_accounts))
+}
+
+/// Check the token decorated by `metadata` against the collection list of the Auction House, if any.
+/// Returns the remaining accounts without the collection list.
+pub fn assert_collection_list_allows<'c, 'info>(
+    auction_house: &Account<'info, AuctionHouse>,
+    metadata: &AccountInfo,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<&'c [AccountInfo<'info>]> {
+    let (collection_list, remaining_accounts) =
+        split_collection_list(auction_house, remaining_accounts)?;
+    if let Some(collection_list) = collection_list {
+        collection_list.assert_metadata_allowed(metadata)?;
+    }
+
+    Ok(remaining_accounts)
+}
+
+/// Accounts for the [`create_collection_list` handler](auction_house/fn.create_collection_list.html).
+#[derive(Accounts)]
+pub struct CreateCollectionList<'info> {
+    /// Auction House authority. Pays for the collection list account.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Collection list PDA account.
+    #[account(
+        init,
+        payer = authority,
+        space = COLLECTION_LIST_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            COLLECTION_LIST.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump
+    )]
+    pub collection_list: Box<Account<'info, CollectionList>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the [`add_collection` handler](auction_house/fn.add_collection.html)
+/// and the [`remove_collection` handler](auction_house/fn.remove_collection.html).
+#[derive(Accounts)]
+pub struct UpdateCollectionList<'info> {
+    /// Auction House authority.
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Collection list PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            COLLECTION_LIST.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump=collection_list.bump,
+        has_one=auction_house
+    )]
+    pub collection_list: Box<Account<'info, CollectionList>>,
+}
+
+/// Create the collection list of an Auction House. Trading is restricted by it from then on.
+pub fn create_collection_list(
+    ctx: Context<CreateCollectionList>,
+    mode: CollectionListMode,
+) -> Result<()> {
+    let auction_house = &mut ctx.accounts.auction_house;
+    let collection_list = &mut ctx.accounts.collection_list;
+
+    collection_list.auction_house = auction_house.key();
+    collection_list.mode = mode;
+    collection_list.collections = Vec::new();
+    collection_list.bump = *ctx
+        .bumps
+        .get("collection_list")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    auction_house.has_collection_list = true;
+
+    Ok(())
+}
+
+/// Add a verified collection mint to the collection list.
+pub fn add_collection(ctx: Context<UpdateCollectionList>, collection_mint: Pubkey) -> Result<()> {
+    let collection_list = &mut ctx.accounts.collection_list;
+
+    if collection_list.collections.contains(&collection_mint)
+        || collection_list.collections.len() >= MAX_COLLECTION_LIST_SIZE
+    {
+        return Err(AuctionHouseError::InvalidCollectionList.into());
+    }
+    collection_list.collections.push(collection_mint);
+
+    Ok(())
+}
+
+/// Remove a verified collection mint from the collection list.
+pub fn remove_collection(
+    ctx: Context<UpdateCollectionList>,
+    collection_mint: Pubkey,
+) -> Result<()> {
+    let collection_list = &mut ctx.accounts.collection_list;
+
+    let index = collection_list
+        .collections
+        .iter()
+        .position(|collection| *collection == collection_mint)
+        .ok_or(AuctionHouseError::InvalidCollectionList)?;
+    collection_list.collections.swap_remove(index);
+
+    Ok(())
+}
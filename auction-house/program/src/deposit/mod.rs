@@ -31,8 +31,9 @@ pub struct Deposit<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Validated by the auction house has_one constraint.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in deposit_logic.
     /// Auction House instance authority account.
@@ -65,7 +66,9 @@ pub struct Deposit<'info> {
     )]
     pub auction_house_fee_account: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in deposit_logic.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -141,8 +144,9 @@ pub struct AuctioneerDeposit<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Validated by the auction house has_one constraint.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in deposit_logic.
     /// Auction House instance authority account.
@@ -191,7 +195,9 @@ pub struct AuctioneerDeposit<'info> {
     )]
     pub ah_auctioneer_pda: Account<'info, Auctioneer>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in deposit_logic.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -274,6 +280,7 @@ fn deposit_logic<'info>(
     )?;
 
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    assert_treasury_token_program(treasury_mint, token_program)?;
 
     create_program_token_account_if_not_present(
         escrow_payment_account,
@@ -289,22 +296,16 @@ fn deposit_logic<'info>(
     )?;
 
     if !is_native {
-        assert_is_ata(payment_account, &wallet.key(), &treasury_mint.key())?;
-        invoke(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &payment_account.key(),
-                &escrow_payment_account.key(),
-                &transfer_authority.key(),
-                &[],
-                amount,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                payment_account.to_account_info(),
-                token_program.to_account_info(),
-                transfer_authority.to_account_info(),
-            ],
+        assert_is_treasury_ata(payment_account, &wallet.key(), treasury_mint)?;
+        // The depositor covers any transfer fee so the escrow is credited exactly `amount`.
+        transfer_treasury_tokens(
+            payment_account,
+            escrow_payment_account,
+            transfer_authority,
+            treasury_mint,
+            token_program,
+            get_amount_with_transfer_fee(treasury_mint, amount)?,
+            &[],
         )?;
     } else {
         assert_keys_equal(payment_account.key(), wallet.key())?;
@@ -329,5 +330,12 @@ fn deposit_logic<'info>(
         )?;
     }
 
+    emit!(DepositEvent {
+        auction_house: auction_house.key(),
+        wallet: wallet.key(),
+        escrow_payment_account: escrow_payment_account.key(),
+        amount,
+    });
+
     Ok(())
 }
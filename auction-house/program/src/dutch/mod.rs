@@ -0,0 +1,301 @@
+//! Dutch listings decline in price from a start price to a floor price over a time window.
+//! The price decays linearly, or in steps when a step interval is set, and stays at the floor once the window ends.
+//! Sales are executed with `execute_dutch_sale` against any bid at or above the current price.
+
+use anchor_lang::{prelude::*, solana_program::program::invoke};
+use anchor_spl::token::{Token, TokenAccount};
+use solana_program::program_pack::Pack;
+use spl_token::{
+    instruction::{approve, revoke},
+    state::Account as SplAccount,
+};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, gateway::assert_gateway_token, utils::*, AuctionHouse,
+    AuthorityScope,
+};
+
+pub const DUTCH_LISTING_SIZE: usize = 8 + // key
+32 + // auction_house
+32 + // seller
+32 + // token_account
+32 + // token_mint
+8 + // start_price
+8 + // floor_price
+8 + // start_time
+8 + // end_time
+8 + // step_interval
+8 + // token_size
+1; // bump
+
+/// Listing whose price declines from `start_price` at `start_time` to `floor_price` at `end_time`.
+#[account]
+pub struct DutchListing {
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub token_account: Pubkey,
+    pub token_mint: Pubkey,
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Seconds between price drops. Zero decays the price linearly.
+    pub step_interval: i64,
+    pub token_size: u64,
+    pub bump: u8,
+}
+
+impl DutchListing {
+    /// Price of the listing at unix timestamp `now`.
+    pub fn current_price(&self, now: i64) -> Result<u64> {
+        if now <= self.start_time {
+            return Ok(self.start_price);
+        }
+        if now >= self.end_time {
+            return Ok(self.floor_price);
+        }
+
+        let mut elapsed = now - self.start_time;
+        if self.step_interval > 0 {
+            elapsed -= elapsed % self.step_interval;
+        }
+
+        let decline = (self.start_price - self.floor_price) as u128;
+        let drop = decline
+            .checked_mul(elapsed as u128)
+            .ok_or(AuctionHouseError::NumericalOverflow)?
+            .checked_div((self.end_time - self.start_time) as u128)
+            .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+
+        self.start_price
+            .checked_sub(drop)
+            .ok_or_else(|| AuctionHouseError::NumericalOverflow.into())
+    }
+}
+
+/// Accounts for the [`sell_dutch` handler](auction_house/fn.sell_dutch.html).
+#[derive(Accounts)]
+#[instruction(dutch_listing_bump: u8)]
+pub struct SellDutch<'info> {
+    /// User wallet account listing the token. Pays for the dutch listing account.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// Token account of the listed token.
+    #[account(mut)]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated by assert_metadata_valid.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated as a signer in sell_dutch when the Auction House requires sign off.
+    /// Auction House authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// Dutch listing PDA account.
+    #[account(
+        init,
+        payer = wallet,
+        space = DUTCH_LISTING_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            DUTCH.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub dutch_listing: Box<Account<'info, DutchListing>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the [`cancel_dutch` handler](auction_house/fn.cancel_dutch.html).
+#[derive(Accounts)]
+pub struct CancelDutch<'info> {
+    /// User wallet account that created the listing.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Validated by the dutch listing constraint.
+    /// Token account of the listed token.
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Dutch listing PDA account. Closed to the seller.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            DUTCH.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref()
+        ],
+        bump=dutch_listing.bump,
+        has_one=auction_house,
+        has_one=token_account,
+        constraint = dutch_listing.seller == wallet.key() @ AuctionHouseError::PublicKeyMismatch,
+        close=wallet
+    )]
+    pub dutch_listing: Box<Account<'info, DutchListing>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// List `token_size` tokens at a price declining from `start_price` to `floor_price` between `start_time` and
+/// `end_time`. A `step_interval` of zero decays the price linearly, otherwise it drops every `step_interval` seconds.
+/// Only SPL tokens are supported, so programmable NFTs can not be listed this way.
+#[allow(clippy::too_many_arguments)]
+pub fn sell_dutch<'info>(
+    ctx: Context<'_, '_, '_, 'info, SellDutch<'info>>,
+    dutch_listing_bump: u8,
+    start_price: u64,
+    floor_price: u64,
+    start_time: i64,
+    end_time: i64,
+    step_interval: i64,
+    token_size: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let token_account = &ctx.accounts.token_account;
+    let metadata = &ctx.accounts.metadata;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let dutch_listing = &mut ctx.accounts.dutch_listing;
+    let token_program = &ctx.accounts.token_program;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Sell as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if auction_house.requires_sign_off && !authority.is_signer {
+        return Err(AuctionHouseError::CannotTakeThisActionWithoutAuctionHouseSignOff.into());
+    }
+
+    let dutch_listing_canonical_bump = *ctx
+        .bumps
+        .get("dutch_listing")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    if dutch_listing_canonical_bump != dutch_listing_bump {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    if start_price < floor_price || end_time <= start_time || step_interval < 0 {
+        return Err(AuctionHouseError::InvalidDutchListing.into());
+    }
+
+    assert_is_ata(
+        &token_account.to_account_info(),
+        &wallet.key(),
+        &token_account.mint,
+    )?;
+    assert_metadata_valid(metadata, token_account)?;
+    assert_gateway_token(
+        auction_house,
+        &wallet.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    if token_size == 0 || token_account.amount < token_size {
+        return Err(AuctionHouseError::InvalidTokenAmount.into());
+    }
+
+    invoke(
+        &approve(
+            &token_program.key(),
+            &token_account.key(),
+            &program_as_signer.key(),
+            &wallet.key(),
+            &[],
+            token_size,
+        )?,
+        &[
+            token_program.to_account_info(),
+            token_account.to_account_info(),
+            program_as_signer.to_account_info(),
+            wallet.to_account_info(),
+        ],
+    )?;
+
+    dutch_listing.auction_house = auction_house.key();
+    dutch_listing.seller = wallet.key();
+    dutch_listing.token_account = token_account.key();
+    dutch_listing.token_mint = token_account.mint;
+    dutch_listing.start_price = start_price;
+    dutch_listing.floor_price = floor_price;
+    dutch_listing.start_time = start_time;
+    dutch_listing.end_time = end_time;
+    dutch_listing.step_interval = step_interval;
+    dutch_listing.token_size = token_size;
+    dutch_listing.bump = dutch_listing_bump;
+
+    Ok(())
+}
+
+/// Cancel a dutch listing, revoking the program delegate if the seller still holds the token.
+pub fn cancel_dutch<'info>(ctx: Context<'_, '_, '_, 'info, CancelDutch<'info>>) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let token_account = &ctx.accounts.token_account;
+    let auction_house = &ctx.accounts.auction_house;
+    let token_program = &ctx.accounts.token_program;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Cancel as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    // A token that was moved or closed since listing has nothing left to revoke.
+    match SplAccount::unpack(&token_account.data.borrow()) {
+        Ok(data) if data.owner == wallet.key() && data.delegate.is_some() => {}
+        _ => return Ok(()),
+    };
+
+    invoke(
+        &revoke(
+            &token_program.key(),
+            &token_account.key(),
+            &wallet.key(),
+            &[],
+        )?,
+        &[
+            token_program.to_account_info(),
+            token_account.to_account_info(),
+            wallet.to_account_info(),
+        ],
+    )?;
+
+    Ok(())
+}
@@ -181,4 +181,148 @@ pub enum AuctionHouseError {
     // 6044
     #[msg("This sale requires exactly one signer: either the seller or the authority.")]
     SaleRequiresExactlyOneSigner,
+
+    // 6045
+    #[msg("The metadata does not belong to the verified collection of this bid.")]
+    CollectionMismatch,
+
+    // 6046
+    #[msg("The token mint is not part of the merkle tree of this trait bid.")]
+    InvalidTraitProof,
+
+    // 6047
+    #[msg("The expiry must be in the future.")]
+    InvalidExpiry,
+
+    // 6048
+    #[msg("The listing or bid has expired.")]
+    TradeStateExpired,
+
+    // 6049
+    #[msg("The listing or bid has not expired yet.")]
+    TradeStateNotExpired,
+
+    // 6050
+    #[msg("The trade state has no expiry.")]
+    TradeStateHasNoExpiry,
+
+    // 6051
+    #[msg("The account is not a trade state created by this program.")]
+    InvalidTradeState,
+
+    // 6052
+    #[msg("A bundle needs between 2 and MAX_BUNDLE_ITEMS distinct items, each with a non-zero weight.")]
+    InvalidBundleItems,
+
+    // 6053
+    #[msg("The account does not match the item recorded in the bundle listing.")]
+    BundleItemMismatch,
+
+    // 6054
+    #[msg("A bundle listing already exists for this seller and first token account.")]
+    BundleListingAlreadyExists,
+
+    // 6055
+    #[msg("A swap offer can not request the mint it offers.")]
+    InvalidSwapOffer,
+
+    // 6056
+    #[msg("A dutch listing must end after it starts and its start price can not be below its floor price.")]
+    InvalidDutchListing,
+
+    // 6057
+    #[msg("The bid price is below the current price of the dutch listing.")]
+    BidBelowDutchPrice,
+
+    // 6058
+    #[msg("The referrer accounts are invalid.")]
+    InvalidReferrer,
+
+    // 6059
+    #[msg("The token program does not own the treasury mint.")]
+    InvalidTreasuryTokenProgram,
+
+    // 6060
+    #[msg("The collection of this token is not allowed on this Auction House.")]
+    CollectionNotAllowed,
+
+    // 6061
+    #[msg("The collection list is missing, invalid or can not be updated this way.")]
+    InvalidCollectionList,
+
+    // 6062
+    #[msg("This listing is reserved for another buyer.")]
+    BuyerNotAllowed,
+
+    // 6063
+    #[msg("The escrow reservation is missing, invalid or full.")]
+    InvalidEscrowReservation,
+
+    // 6064
+    #[msg("The escrow does not cover all open bids of the buyer.")]
+    EscrowOvercommitted,
+
+    // 6065
+    #[msg("The escrow funds are reserved by open bids.")]
+    EscrowFundsReserved,
+
+    // 6066
+    #[msg("The receipt belongs to an order that is still open.")]
+    ReceiptNotFinalized,
+
+    // 6067
+    #[msg("The Auction House stats account is missing or invalid.")]
+    InvalidAuctionHouseStats,
+
+    // 6068
+    #[msg("This change must go through the governance of the Auction House.")]
+    GovernanceRequired,
+
+    // 6069
+    #[msg("The governance account or configuration is missing or invalid.")]
+    InvalidGovernance,
+
+    // 6070
+    #[msg("The signer is not a governance signer of the Auction House.")]
+    NotGovernanceSigner,
+
+    // 6071
+    #[msg("The signer already approved this change.")]
+    ChangeAlreadyApproved,
+
+    // 6072
+    #[msg("The change does not have enough approvals.")]
+    ChangeNotApproved,
+
+    // 6073
+    #[msg("The change can not be applied before its delay has passed.")]
+    ChangeTimelocked,
+
+    // 6074
+    #[msg("The wallet does not hold a valid gateway token for the gatekeeper network.")]
+    InvalidGatewayToken,
+
+    // 6075
+    #[msg("The royalty election account is missing or invalid.")]
+    InvalidRoyaltyElection,
+
+    // 6076
+    #[msg("The fee tier holder account is missing or does not qualify for a fee tier.")]
+    InvalidFeeTierHolder,
+
+    // 6077
+    #[msg("The Auction House cannot have this many fee tiers.")]
+    TooManyFeeTiers,
+
+    // 6078
+    #[msg("The Auction House is already at the latest version.")]
+    AuctionHouseAlreadyMigrated,
+
+    // 6079
+    #[msg("The Auction House must be migrated to the latest version first.")]
+    AuctionHouseNotMigrated,
+
+    // 6080
+    #[msg("The shared escrow accounts are missing or invalid.")]
+    InvalidSharedEscrow,
 }
@@ -0,0 +1,128 @@
+//! Events emitted on every state transition of an Auction House, so indexers can follow order books without
+//! parsing instructions and receipts.
+
+use anchor_lang::prelude::*;
+
+/// A listing was created or updated by `sell`, `auctioneer_sell` or `batch_sell`.
+#[event]
+pub struct ListingEvent {
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub token_account: Pubkey,
+    pub token_mint: Pubkey,
+    /// Seller trade state of the listing.
+    pub trade_state: Pubkey,
+    pub price: u64,
+    pub token_size: u64,
+}
+
+/// A bid was placed on a token, a collection or a trait.
+#[event]
+pub struct BidEvent {
+    pub auction_house: Pubkey,
+    pub buyer: Pubkey,
+    /// Token account bid on, or the default key for collection and trait bids.
+    pub token_account: Pubkey,
+    /// Mint bid on, the collection mint of a collection bid or the merkle root of a trait bid.
+    pub target: Pubkey,
+    /// Buyer trade state of the bid.
+    pub trade_state: Pubkey,
+    pub price: u64,
+    pub token_size: u64,
+}
+
+/// A listing or a bid was cancelled.
+#[event]
+pub struct CancelEvent {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    /// Mint of the order, the collection mint of a collection bid or the merkle root of a trait bid.
+    pub target: Pubkey,
+    /// Trade state of the cancelled order.
+    pub trade_state: Pubkey,
+    pub price: u64,
+    pub token_size: u64,
+}
+
+/// Funds were deposited into a buyer escrow.
+#[event]
+pub struct DepositEvent {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub escrow_payment_account: Pubkey,
+    pub amount: u64,
+}
+
+/// Funds were withdrawn from a buyer escrow.
+#[event]
+pub struct WithdrawEvent {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub escrow_payment_account: Pubkey,
+    pub amount: u64,
+}
+
+/// Funds were deposited into or withdrawn from the shared escrow of a wallet.
+#[event]
+pub struct SharedEscrowEvent {
+    pub wallet: Pubkey,
+    pub treasury_mint: Pubkey,
+    pub shared_escrow: Pubkey,
+    pub amount: u64,
+    /// Whether the funds were deposited rather than withdrawn.
+    pub deposit: bool,
+}
+
+/// A sale drew funds from the shared escrow of the buyer into its escrow on the Auction House.
+#[event]
+pub struct SharedEscrowDrawEvent {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub shared_escrow: Pubkey,
+    pub amount: u64,
+}
+
+/// A sale was executed, in full or for part of a listing.
+#[event]
+pub struct SaleEvent {
+    pub auction_house: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub token_mint: Pubkey,
+    pub buyer_trade_state: Pubkey,
+    pub seller_trade_state: Pubkey,
+    /// Price paid for the `token_size` tokens sold.
+    pub price: u64,
+    pub token_size: u64,
+    /// Royalties paid to the creators out of `price`.
+    pub royalty_paid: u64,
+    /// Fees taken by the Auction House from both sides, including any referral fee.
+    pub auction_house_fee: u64,
+    /// Whether only part of the listed tokens was sold.
+    pub partial: bool,
+}
+
+/// Funds were withdrawn from the Auction House fee account.
+#[event]
+pub struct FeeWithdrawalEvent {
+    pub auction_house: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+/// Funds were withdrawn from the Auction House treasury.
+#[event]
+pub struct TreasuryWithdrawalEvent {
+    pub auction_house: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+/// A receipt was closed with `emit_event` set.
+#[event]
+pub struct ReceiptClosedEvent {
+    pub receipt: Pubkey,
+    pub bookkeeper: Pubkey,
+    /// Account data of the receipt, including its discriminator.
+    pub data: Vec<u8>,
+}
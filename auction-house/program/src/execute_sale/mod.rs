@@ -4,7 +4,9 @@ use mpl_token_auth_rules::payload::{Payload, PayloadType, SeedsVec};
 use mpl_token_metadata::{
     instruction::{builders::TransferBuilder, InstructionBuilder, TransferArgs},
     processor::AuthorizationData,
+    state::{Metadata, TokenMetadataAccount},
 };
+use mpl_utils::token::{spl_token_transfer, TokenTransferParams};
 use spl_token::state::Account as SplAccount;
 
 /// Accounts for the [`execute_sale` handler](auction_house/fn.execute_sale.html).
@@ -247,6 +249,9 @@ pub fn execute_sale<'info>(
     if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
         return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
     }
+    // Listings and bids recorded to an auctioneer can only be filled through its auctioneer handlers.
+    assert_no_trade_state_auctioneer(&ctx.accounts.seller_trade_state)?;
+    assert_no_trade_state_auctioneer(&ctx.accounts.buyer_trade_state)?;
 
     let escrow_canonical_bump = *ctx
         .bumps
@@ -271,6 +276,7 @@ pub fn execute_sale<'info>(
     execute_sale_logic(
         ctx.accounts,
         ctx.remaining_accounts,
+        BidTarget::Token,
         escrow_payment_bump,
         free_trade_state_bump,
         program_as_signer_bump,
@@ -281,33 +287,33 @@ pub fn execute_sale<'info>(
     )
 }
 
-/// Accounts for the [`execute_sale` handler](auction_house/fn.execute_sale.html).
+/// Accounts for the [`accept_bid` handler](auction_house/fn.accept_bid.html).
 #[derive(Accounts, Clone)]
 #[instruction(
+    trade_state_bump: u8,
     escrow_payment_bump: u8,
     free_trade_state_bump: u8,
     program_as_signer_bump: u8,
     buyer_price: u64,
     token_size: u64
 )]
-pub struct ExecutePartialSale<'info> {
+pub struct AcceptBid<'info> {
     /// CHECK: Validated in execute_sale_logic.
     /// Buyer user wallet account.
     #[account(mut)]
     pub buyer: UncheckedAccount<'info>,
 
-    /// CHECK: Validated in execute_sale_logic.
+    /// CHECK: Validated as a signer in accept_bid.
     /// Seller user wallet account.
     #[account(mut)]
     pub seller: UncheckedAccount<'info>,
 
-    /// CHECK: Validated in execute_sale_logic.
-    // cannot mark these as real Accounts or else we blow stack size limit
+    /// CHECK: Validated in accept_bid.
     ///Token account where the SPL token is stored.
     #[account(mut)]
     pub token_account: UncheckedAccount<'info>,
 
-    /// CHECK: Validated in execute_sale_logic.
+    /// CHECK: Validated in accept_bid.
     /// Token mint account for the SPL token.
     pub token_mint: UncheckedAccount<'info>,
 
@@ -316,7 +322,6 @@ pub struct ExecutePartialSale<'info> {
     pub metadata: UncheckedAccount<'info>,
 
     /// CHECK: Validated in execute_sale_logic.
-    // cannot mark these as real Accounts or else we blow stack size limit
     /// Auction House treasury mint account.
     pub treasury_mint: UncheckedAccount<'info>,
 
@@ -394,7 +399,7 @@ pub struct ExecutePartialSale<'info> {
     pub buyer_trade_state: UncheckedAccount<'info>,
 
     /// CHECK: Not dangerous. Account seeds checked in constraint.
-    /// Seller trade state PDA account encoding the sell order.
+    /// Seller trade state PDA account encoding a sell order at the bid price, created by accept_bid.
     #[account(
         mut,
         seeds = [
@@ -407,7 +412,7 @@ pub struct ExecutePartialSale<'info> {
             &buyer_price.to_le_bytes(),
             &token_size.to_le_bytes()
         ],
-        bump=seller_trade_state.to_account_info().data.borrow()[0]
+        bump
     )]
     pub seller_trade_state: UncheckedAccount<'info>,
 
@@ -438,10 +443,12 @@ pub struct ExecutePartialSale<'info> {
     pub program_as_signer: UncheckedAccount<'info>,
 
     pub rent: Sysvar<'info, Rent>,
+    // remaining accounts:
+    // [...SellRemainingAccounts if programmable], ...ExecuteSaleRemainingAccounts, [...gateway accounts if gated]
 }
 
-impl<'info> From<ExecutePartialSale<'info>> for ExecuteSale<'info> {
-    fn from(a: ExecutePartialSale<'info>) -> ExecuteSale<'info> {
+impl<'info> From<AcceptBid<'info>> for ExecuteSale<'info> {
+    fn from(a: AcceptBid<'info>) -> ExecuteSale<'info> {
         ExecuteSale {
             buyer: a.buyer,
             seller: a.seller,
@@ -468,23 +475,49 @@ impl<'info> From<ExecutePartialSale<'info>> for ExecuteSale<'info> {
     }
 }
 
-pub fn execute_partial_sale<'info>(
-    ctx: Context<'_, '_, '_, 'info, ExecutePartialSale<'info>>,
+/// Accept a bid without a prior listing. The seller approves the program as the sale delegate, a seller trade state
+/// is created at the bid price and the sale is executed against the bid, all in one instruction.
+/// A programmable NFT passes the [`SellRemainingAccounts`] before the usual execute sale remaining accounts, and a
+/// gated Auction House takes the gateway accounts of the seller after them.
+#[allow(clippy::too_many_arguments)]
+pub fn accept_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, AcceptBid<'info>>,
+    trade_state_bump: u8,
     escrow_payment_bump: u8,
     free_trade_state_bump: u8,
     program_as_signer_bump: u8,
     buyer_price: u64,
     token_size: u64,
-    partial_order_size: Option<u64>,
-    partial_order_price: Option<u64>,
+    programmable: bool,
 ) -> Result<()> {
+    let seller = &ctx.accounts.seller;
+    let token_account = &ctx.accounts.token_account;
+    let token_mint = &ctx.accounts.token_mint;
+    let metadata = &ctx.accounts.metadata;
+    let authority = &ctx.accounts.authority;
     let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let rent = &ctx.accounts.rent;
 
     // If it has an auctioneer authority delegated must use auctioneer_* handler.
-    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+    if auction_house.has_auctioneer
+        && (auction_house.scopes[AuthorityScope::Sell as usize]
+            || auction_house.scopes[AuthorityScope::ExecuteSale as usize])
+    {
         return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
     }
+    // Listings and bids recorded to an auctioneer can only be filled through its auctioneer handlers.
+    assert_no_trade_state_auctioneer(&ctx.accounts.seller_trade_state)?;
+    assert_no_trade_state_auctioneer(&ctx.accounts.buyer_trade_state)?;
 
+    let trade_state_canonical_bump = *ctx
+        .bumps
+        .get("seller_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
     let escrow_canonical_bump = *ctx
         .bumps
         .get("escrow_payment_account")
@@ -498,29 +531,113 @@ pub fn execute_partial_sale<'info>(
         .get("program_as_signer")
         .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
 
-    if (escrow_canonical_bump != escrow_payment_bump)
+    if (trade_state_canonical_bump != trade_state_bump)
+        || (escrow_canonical_bump != escrow_payment_bump)
         || (free_trade_state_canonical_bump != free_trade_state_bump)
         || (program_as_signer_canonical_bump != program_as_signer_bump)
     {
         return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
     }
 
+    if !seller.is_signer {
+        return Err(AuctionHouseError::SaleRequiresSigner.into());
+    }
+
+    let token_account_data = assert_is_ata(
+        &token_account.to_account_info(),
+        &seller.key(),
+        &token_mint.key(),
+    )?;
+    if token_size > token_account_data.amount {
+        return Err(AuctionHouseError::InvalidTokenAmount.into());
+    }
+
+    // Accepting a bid lists the token, so gated Auction Houses check the gateway token of the seller.
+    let remaining_accounts = assert_gateway_token(
+        auction_house,
+        &seller.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    let (sale_delegate_accounts, remaining_accounts) = if programmable {
+        if remaining_accounts.len() < 8 {
+            return Err(ErrorCode::AccountNotEnoughKeys.into());
+        }
+        remaining_accounts.split_at(8)
+    } else {
+        remaining_accounts.split_at(0)
+    };
+    approve_sale_delegate(
+        &seller.to_account_info(),
+        &token_account.to_account_info(),
+        &metadata.to_account_info(),
+        &program_as_signer.to_account_info(),
+        &token_program.to_account_info(),
+        &system_program.to_account_info(),
+        &mut sale_delegate_accounts.iter(),
+        token_size,
+    )?;
+
+    let ts_info = seller_trade_state.to_account_info();
+    if ts_info.data_is_empty() {
+        let auction_house_key = auction_house.key();
+        let seeds = [
+            PREFIX.as_bytes(),
+            auction_house_key.as_ref(),
+            FEE_PAYER.as_bytes(),
+            &[auction_house.fee_payer_bump],
+        ];
+        let (fee_payer, fee_seeds) = get_fee_payer(
+            authority,
+            auction_house,
+            seller.to_account_info(),
+            auction_house_fee_account.to_account_info(),
+            &seeds,
+        )?;
+
+        let seller_key = seller.key();
+        let token_account_key = token_account.key();
+        let token_mint_key = token_mint.key();
+        create_or_allocate_account_raw(
+            *ctx.program_id,
+            &ts_info,
+            &rent.to_account_info(),
+            system_program,
+            &fee_payer,
+            TRADE_STATE_SIZE,
+            fee_seeds,
+            &[
+                PREFIX.as_bytes(),
+                seller_key.as_ref(),
+                auction_house_key.as_ref(),
+                token_account_key.as_ref(),
+                auction_house.treasury_mint.as_ref(),
+                token_mint_key.as_ref(),
+                &buyer_price.to_le_bytes(),
+                &token_size.to_le_bytes(),
+                &[trade_state_bump],
+            ],
+        )?;
+    }
+    ts_info.try_borrow_mut_data()?[0] = trade_state_bump;
+
     let mut accounts: ExecuteSale<'info> = (*ctx.accounts).clone().into();
 
     execute_sale_logic(
         &mut accounts,
-        ctx.remaining_accounts,
+        remaining_accounts,
+        BidTarget::Token,
         escrow_payment_bump,
         free_trade_state_bump,
         program_as_signer_bump,
         buyer_price,
         token_size,
-        partial_order_size,
-        partial_order_price,
+        None,
+        None,
     )
 }
 
-#[derive(Accounts)]
+/// Accounts for the [`execute_sale` handler](auction_house/fn.execute_sale.html).
+#[derive(Accounts, Clone)]
 #[instruction(
     escrow_payment_bump: u8,
     free_trade_state_bump: u8,
@@ -528,7 +645,7 @@ pub fn execute_partial_sale<'info>(
     buyer_price: u64,
     token_size: u64
 )]
-pub struct AuctioneerExecuteSale<'info> {
+pub struct ExecutePartialSale<'info> {
     /// CHECK: Validated in execute_sale_logic.
     /// Buyer user wallet account.
     #[account(mut)]
@@ -551,7 +668,6 @@ pub struct AuctioneerExecuteSale<'info> {
 
     /// CHECK: Validated in execute_sale_logic.
     /// Metaplex metadata account decorating SPL mint account.
-    //@TODO: re-enable this later #[account(mut)]
     pub metadata: UncheckedAccount<'info>,
 
     /// CHECK: Validated in execute_sale_logic.
@@ -586,10 +702,6 @@ pub struct AuctioneerExecuteSale<'info> {
     /// Auction House instance authority.
     pub authority: UncheckedAccount<'info>,
 
-    /// CHECK: Validated in ah_auctioneer_pda seeds and execute_sale_logic.
-    /// The auctioneer authority - typically a PDA of the Auctioneer program running this action.
-    pub auctioneer_authority: Signer<'info>,
-
     /// Auction House instance PDA account.
     #[account(
         seeds = [
@@ -647,7 +759,7 @@ pub struct AuctioneerExecuteSale<'info> {
             token_account.key().as_ref(),
             auction_house.treasury_mint.as_ref(),
             token_mint.key().as_ref(),
-            &u64::MAX.to_le_bytes(),
+            &buyer_price.to_le_bytes(),
             &token_size.to_le_bytes()
         ],
         bump=seller_trade_state.to_account_info().data.borrow()[0]
@@ -672,58 +784,64 @@ pub struct AuctioneerExecuteSale<'info> {
     )]
     pub free_trade_state: UncheckedAccount<'info>,
 
-    /// CHECK: Not dangerous. Account seeds checked in constraint.
-    /// The auctioneer PDA owned by Auction House storing scopes.
-    #[account(
-        seeds = [
-            AUCTIONEER.as_bytes(),
-            auction_house.key().as_ref(),
-            auctioneer_authority.key().as_ref()
-        ],
-        bump = ah_auctioneer_pda.bump
-    )]
-    pub ah_auctioneer_pda: Box<Account<'info, Auctioneer>>,
-
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
 
     /// CHECK: Not dangerous. Account seeds checked in constraint.
-    #[account(
-        seeds=[
-            PREFIX.as_bytes(), SIGNER.as_bytes()
-        ],
-        bump
-    )]
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
     pub program_as_signer: UncheckedAccount<'info>,
 
     pub rent: Sysvar<'info, Rent>,
-    // we are at the absolute stack limit, but if we had them, it'd look something like this
-    // ...ExecuteSaleRemainingAccounts
 }
 
-pub fn auctioneer_execute_sale<'info>(
-    ctx: Context<'_, '_, '_, 'info, AuctioneerExecuteSale<'info>>,
+impl<'info> From<ExecutePartialSale<'info>> for ExecuteSale<'info> {
+    fn from(a: ExecutePartialSale<'info>) -> ExecuteSale<'info> {
+        ExecuteSale {
+            buyer: a.buyer,
+            seller: a.seller,
+            token_account: a.token_account,
+            token_mint: a.token_mint,
+            metadata: a.metadata,
+            treasury_mint: a.treasury_mint,
+            escrow_payment_account: a.escrow_payment_account,
+            seller_payment_receipt_account: a.seller_payment_receipt_account,
+            buyer_receipt_token_account: a.buyer_receipt_token_account,
+            authority: a.authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            auction_house_treasury: a.auction_house_treasury,
+            buyer_trade_state: a.buyer_trade_state,
+            seller_trade_state: a.seller_trade_state,
+            free_trade_state: a.free_trade_state,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            ata_program: a.ata_program,
+            program_as_signer: a.program_as_signer,
+            rent: a.rent,
+        }
+    }
+}
+
+pub fn execute_partial_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecutePartialSale<'info>>,
     escrow_payment_bump: u8,
     free_trade_state_bump: u8,
     program_as_signer_bump: u8,
     buyer_price: u64,
     token_size: u64,
+    partial_order_size: Option<u64>,
+    partial_order_price: Option<u64>,
 ) -> Result<()> {
     let auction_house = &ctx.accounts.auction_house;
-    let auctioneer_authority = &ctx.accounts.auctioneer_authority;
-    let ah_auctioneer_pda = &ctx.accounts.ah_auctioneer_pda;
 
-    if !auction_house.has_auctioneer {
-        return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
     }
-
-    assert_valid_auctioneer_and_scope(
-        auction_house,
-        &auctioneer_authority.key(),
-        ah_auctioneer_pda,
-        AuthorityScope::ExecuteSale,
-    )?;
+    // Listings and bids recorded to an auctioneer can only be filled through its auctioneer handlers.
+    assert_no_trade_state_auctioneer(&ctx.accounts.seller_trade_state)?;
+    assert_no_trade_state_auctioneer(&ctx.accounts.buyer_trade_state)?;
 
     let escrow_canonical_bump = *ctx
         .bumps
@@ -745,21 +863,23 @@ pub fn auctioneer_execute_sale<'info>(
         return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
     }
 
-    // Duplicate the logic methods to avoid going over the compute limit.
-    auctioneer_execute_sale_logic(
-        ctx.accounts,
+    let mut accounts: ExecuteSale<'info> = (*ctx.accounts).clone().into();
+
+    execute_sale_logic(
+        &mut accounts,
         ctx.remaining_accounts,
+        BidTarget::Token,
         escrow_payment_bump,
         free_trade_state_bump,
         program_as_signer_bump,
         buyer_price,
         token_size,
-        None,
-        None,
+        partial_order_size,
+        partial_order_price,
     )
 }
 
-#[derive(Accounts, Clone)]
+#[derive(Accounts)]
 #[instruction(
     escrow_payment_bump: u8,
     free_trade_state_bump: u8,
@@ -767,7 +887,7 @@ pub fn auctioneer_execute_sale<'info>(
     buyer_price: u64,
     token_size: u64
 )]
-pub struct AuctioneerExecutePartialSale<'info> {
+pub struct AuctioneerExecuteSale<'info> {
     /// CHECK: Validated in execute_sale_logic.
     /// Buyer user wallet account.
     #[account(mut)]
@@ -790,6 +910,7 @@ pub struct AuctioneerExecutePartialSale<'info> {
 
     /// CHECK: Validated in execute_sale_logic.
     /// Metaplex metadata account decorating SPL mint account.
+    //@TODO: re-enable this later #[account(mut)]
     pub metadata: UncheckedAccount<'info>,
 
     /// CHECK: Validated in execute_sale_logic.
@@ -885,7 +1006,7 @@ pub struct AuctioneerExecutePartialSale<'info> {
             token_account.key().as_ref(),
             auction_house.treasury_mint.as_ref(),
             token_mint.key().as_ref(),
-            &buyer_price.to_le_bytes(),
+            &u64::MAX.to_le_bytes(),
             &token_size.to_le_bytes()
         ],
         bump=seller_trade_state.to_account_info().data.borrow()[0]
@@ -895,18 +1016,18 @@ pub struct AuctioneerExecutePartialSale<'info> {
     /// CHECK: Not dangerous. Account seeds checked in constraint.
     /// Free seller trade state PDA account encoding a free sell order.
     #[account(
-    mut,
-    seeds = [
-        PREFIX.as_bytes(),
-        seller.key().as_ref(),
-        auction_house.key().as_ref(),
-        token_account.key().as_ref(),
-        auction_house.treasury_mint.as_ref(),
-        token_mint.key().as_ref(),
-        &0u64.to_le_bytes(),
-        &token_size.to_le_bytes()
-    ],
-    bump
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
     )]
     pub free_trade_state: UncheckedAccount<'info>,
 
@@ -927,7 +1048,12 @@ pub struct AuctioneerExecutePartialSale<'info> {
     pub ata_program: Program<'info, AssociatedToken>,
 
     /// CHECK: Not dangerous. Account seeds checked in constraint.
-    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    #[account(
+        seeds=[
+            PREFIX.as_bytes(), SIGNER.as_bytes()
+        ],
+        bump
+    )]
     pub program_as_signer: UncheckedAccount<'info>,
 
     pub rent: Sysvar<'info, Rent>,
@@ -935,45 +1061,13 @@ pub struct AuctioneerExecutePartialSale<'info> {
     // ...ExecuteSaleRemainingAccounts
 }
 
-impl<'info> From<AuctioneerExecutePartialSale<'info>> for AuctioneerExecuteSale<'info> {
-    fn from(a: AuctioneerExecutePartialSale<'info>) -> AuctioneerExecuteSale<'info> {
-        AuctioneerExecuteSale {
-            buyer: a.buyer,
-            seller: a.seller,
-            token_account: a.token_account,
-            token_mint: a.token_mint,
-            metadata: a.metadata,
-            treasury_mint: a.treasury_mint,
-            escrow_payment_account: a.escrow_payment_account,
-            seller_payment_receipt_account: a.seller_payment_receipt_account,
-            buyer_receipt_token_account: a.buyer_receipt_token_account,
-            authority: a.authority,
-            auctioneer_authority: a.auctioneer_authority,
-            auction_house: a.auction_house,
-            auction_house_fee_account: a.auction_house_fee_account,
-            auction_house_treasury: a.auction_house_treasury,
-            buyer_trade_state: a.buyer_trade_state,
-            seller_trade_state: a.seller_trade_state,
-            free_trade_state: a.free_trade_state,
-            ah_auctioneer_pda: a.ah_auctioneer_pda,
-            token_program: a.token_program,
-            system_program: a.system_program,
-            ata_program: a.ata_program,
-            program_as_signer: a.program_as_signer,
-            rent: a.rent,
-        }
-    }
-}
-
-pub fn auctioneer_execute_partial_sale<'info>(
-    ctx: Context<'_, '_, '_, 'info, AuctioneerExecutePartialSale<'info>>,
+pub fn auctioneer_execute_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerExecuteSale<'info>>,
     escrow_payment_bump: u8,
     free_trade_state_bump: u8,
     program_as_signer_bump: u8,
     buyer_price: u64,
     token_size: u64,
-    partial_order_size: Option<u64>,
-    partial_order_price: Option<u64>,
 ) -> Result<()> {
     let auction_house = &ctx.accounts.auction_house;
     let auctioneer_authority = &ctx.accounts.auctioneer_authority;
@@ -989,6 +1083,16 @@ pub fn auctioneer_execute_partial_sale<'info>(
         ah_auctioneer_pda,
         AuthorityScope::ExecuteSale,
     )?;
+    assert_trade_state_auctioneer(
+        auction_house,
+        &ctx.accounts.seller_trade_state,
+        ah_auctioneer_pda,
+    )?;
+    assert_trade_state_auctioneer(
+        auction_house,
+        &ctx.accounts.buyer_trade_state,
+        ah_auctioneer_pda,
+    )?;
 
     let escrow_canonical_bump = *ctx
         .bumps
@@ -1010,316 +1114,666 @@ pub fn auctioneer_execute_partial_sale<'info>(
         return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
     }
 
-    let mut accounts: AuctioneerExecuteSale<'info> = (*ctx.accounts).clone().into();
-
     // Duplicate the logic methods to avoid going over the compute limit.
     auctioneer_execute_sale_logic(
-        &mut accounts,
+        ctx.accounts,
         ctx.remaining_accounts,
         escrow_payment_bump,
         free_trade_state_bump,
         program_as_signer_bump,
         buyer_price,
         token_size,
-        partial_order_size,
-        partial_order_price,
+        None,
+        None,
     )
 }
 
-/// Execute sale between provided buyer and seller trade state accounts transferring funds to seller wallet and token to buyer wallet.
-#[inline(never)]
-fn auctioneer_execute_sale_logic<'c, 'info>(
-    accounts: &mut AuctioneerExecuteSale<'info>,
-    remaining_accounts: &'c [AccountInfo<'info>],
+#[derive(Accounts, Clone)]
+#[instruction(
     escrow_payment_bump: u8,
-    _free_trade_state_bump: u8,
+    free_trade_state_bump: u8,
     program_as_signer_bump: u8,
     buyer_price: u64,
-    token_size: u64,
-    partial_order_size: Option<u64>,
-    partial_order_price: Option<u64>,
-) -> Result<()> {
-    let buyer = &accounts.buyer;
-    let seller = &accounts.seller;
-    let token_account = &accounts.token_account;
-    let token_mint = &accounts.token_mint;
-    let metadata = &accounts.metadata;
-    let treasury_mint = &accounts.treasury_mint;
-    let seller_payment_receipt_account = &accounts.seller_payment_receipt_account;
-    let buyer_receipt_token_account = &accounts.buyer_receipt_token_account;
-    let escrow_payment_account = &accounts.escrow_payment_account;
-    let authority = &accounts.authority;
-    let auction_house = &accounts.auction_house;
-    let auction_house_fee_account = &accounts.auction_house_fee_account;
-    let auction_house_treasury = &accounts.auction_house_treasury;
-    let buyer_trade_state = &accounts.buyer_trade_state;
-    let seller_trade_state = &accounts.seller_trade_state;
-    let free_trade_state = &accounts.free_trade_state;
-    let token_program = &accounts.token_program;
-    let system_program = &accounts.system_program;
-    let ata_program = &accounts.ata_program;
-    let program_as_signer = &accounts.program_as_signer;
-    let rent = &accounts.rent;
+    token_size: u64
+)]
+pub struct AuctioneerExecutePartialSale<'info> {
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer user wallet account.
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
 
-    let metadata_clone = metadata.to_account_info();
-    let escrow_clone = escrow_payment_account.to_account_info();
-    let auction_house_clone = auction_house.to_account_info();
-    let ata_clone = ata_program.to_account_info();
-    let token_clone = token_program.to_account_info();
-    let sys_clone = system_program.to_account_info();
-    let rent_clone = rent.to_account_info();
-    let treasury_clone = auction_house_treasury.to_account_info();
-    let authority_clone = authority.to_account_info();
-    let buyer_receipt_clone = buyer_receipt_token_account.to_account_info();
-    let token_account_clone = token_account.to_account_info();
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
 
-    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    /// CHECK: Validated in execute_sale_logic.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    ///Token account where the SPL token is stored.
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
 
-    if buyer_price == 0 && !authority_clone.is_signer && !seller.is_signer {
-        return Err(
-            AuctionHouseError::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into(),
-        );
-    }
+    /// CHECK: Validated in execute_sale_logic.
+    /// Token mint account for the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
 
-    let token_account_mint = get_mint_from_token_account(&token_account_clone)?;
+    /// CHECK: Validated in execute_sale_logic.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
 
-    assert_keys_equal(token_mint.key(), token_account_mint)?;
-    let delegate = get_delegate_from_token_account(&token_account_clone)?;
-    if let Some(d) = delegate {
-        assert_keys_equal(program_as_signer.key(), d)?;
-    } else {
-        msg!("No delegate detected on token account.");
-        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
-    }
+    /// CHECK: Validated in execute_sale_logic.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
 
-    let ts_bump = if buyer_trade_state.data_len() > 0 {
-        buyer_trade_state.try_borrow_data()?[0]
-    } else {
-        return Err(AuctionHouseError::BuyerTradeStateNotValid.into());
-    };
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
 
-    if ts_bump == 0
-        || seller_trade_state.data_len() == 0
-        || seller_trade_state.try_borrow_data()?[0] == 0
-    {
-        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
-    }
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
 
-    let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer SPL token account to receive purchased item at.
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
 
-    let (size, price): (u64, u64) = match (partial_order_size, partial_order_price) {
-        (Some(size), Some(price)) => {
-            assert_valid_trade_state(
-                &buyer.key(),
-                auction_house,
-                price,
-                size,
-                buyer_trade_state,
-                &token_mint.key(),
-                &token_account.key(),
-                ts_bump,
-            )?;
+    /// CHECK: Validated in execute_sale_logic.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
 
-            if ((buyer_price / token_size) * size) != price {
-                return Err(AuctionHouseError::PartialPriceMismatch.into());
-            }
+    /// CHECK: Validated in ah_auctioneer_pda seeds and execute_sale_logic.
+    /// The auctioneer authority - typically a PDA of the Auctioneer program running this action.
+    pub auctioneer_authority: Signer<'info>,
 
-            if token_account_data.amount < size {
-                return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
-            };
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
 
-            if token_account_data.delegated_amount < size {
-                return Err(ProgramError::InvalidAccountData.into());
-            };
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
 
-            (size, price)
-        }
-        (None, None) => {
-            assert_valid_trade_state(
-                &buyer.key(),
-                auction_house,
-                buyer_price,
-                token_size,
-                buyer_trade_state,
-                &token_mint.key(),
-                &token_account.key(),
-                ts_bump,
-            )?;
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
 
-            if token_account_data.amount < token_size {
-                return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
-            };
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer trade state PDA account encoding the buy order.
+    #[account(mut)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
 
-            (token_size, buyer_price)
-        }
-        _ => {
-            return Err(AuctionHouseError::MissingElementForPartialOrder.into());
-        }
-    };
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Seller trade state PDA account encoding the sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump=seller_trade_state.to_account_info().data.borrow()[0]
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
 
-    let auction_house_key = auction_house.key();
-    let seeds = [
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Free seller trade state PDA account encoding a free sell order.
+    #[account(
+    mut,
+    seeds = [
         PREFIX.as_bytes(),
-        auction_house_key.as_ref(),
-        FEE_PAYER.as_bytes(),
-        &[auction_house.fee_payer_bump],
-    ];
+        seller.key().as_ref(),
+        auction_house.key().as_ref(),
+        token_account.key().as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        token_mint.key().as_ref(),
+        &0u64.to_le_bytes(),
+        &token_size.to_le_bytes()
+    ],
+    bump
+    )]
+    pub free_trade_state: UncheckedAccount<'info>,
 
-    let wallet_to_use = if buyer.is_signer { buyer } else { seller };
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// The auctioneer PDA owned by Auction House storing scopes.
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        bump = ah_auctioneer_pda.bump
+    )]
+    pub ah_auctioneer_pda: Box<Account<'info, Auctioneer>>,
 
-    let (fee_payer, fee_payer_seeds) = get_fee_payer(
-        authority,
-        auction_house,
-        wallet_to_use.to_account_info(),
-        auction_house_fee_account.to_account_info(),
-        &seeds,
-    )?;
-    let fee_payer_clone = fee_payer.to_account_info();
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
 
-    assert_is_ata(
-        &token_account.to_account_info(),
-        &seller.key(),
-        &token_account_mint,
-    )?;
-    assert_derivation(
-        &mpl_token_metadata::id(),
-        &metadata.to_account_info(),
-        &[
-            mpl_token_metadata::state::PREFIX.as_bytes(),
-            mpl_token_metadata::id().as_ref(),
-            token_account_mint.as_ref(),
-        ],
-    )?;
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
 
-    // For native purchases, verify that the amount in escrow is sufficient to actually purchase the
-    // token.  This is intended to cover the migration from pre-rent-exemption checked accounts to
-    // rent-exemption checked accounts.  The fee payer makes up the shortfall up to the amount of
-    // rent for an empty account.
-    if is_native {
-        let rent_shortfall =
-            verify_withdrawal(escrow_payment_account.to_account_info(), buyer_price)?;
-        if rent_shortfall > 0 {
-            invoke_signed(
-                &system_instruction::transfer(
-                    fee_payer.key,
-                    escrow_payment_account.key,
-                    rent_shortfall,
-                ),
-                &[
-                    fee_payer.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    system_program.to_account_info(),
-                ],
-                &[fee_payer_seeds],
-            )?;
+    pub rent: Sysvar<'info, Rent>,
+    // we are at the absolute stack limit, but if we had them, it'd look something like this
+    // ...ExecuteSaleRemainingAccounts
+}
+
+impl<'info> From<AuctioneerExecutePartialSale<'info>> for AuctioneerExecuteSale<'info> {
+    fn from(a: AuctioneerExecutePartialSale<'info>) -> AuctioneerExecuteSale<'info> {
+        AuctioneerExecuteSale {
+            buyer: a.buyer,
+            seller: a.seller,
+            token_account: a.token_account,
+            token_mint: a.token_mint,
+            metadata: a.metadata,
+            treasury_mint: a.treasury_mint,
+            escrow_payment_account: a.escrow_payment_account,
+            seller_payment_receipt_account: a.seller_payment_receipt_account,
+            buyer_receipt_token_account: a.buyer_receipt_token_account,
+            authority: a.authority,
+            auctioneer_authority: a.auctioneer_authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            auction_house_treasury: a.auction_house_treasury,
+            buyer_trade_state: a.buyer_trade_state,
+            seller_trade_state: a.seller_trade_state,
+            free_trade_state: a.free_trade_state,
+            ah_auctioneer_pda: a.ah_auctioneer_pda,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            ata_program: a.ata_program,
+            program_as_signer: a.program_as_signer,
+            rent: a.rent,
         }
     }
+}
 
-    if metadata.data_is_empty() {
-        return Err(AuctionHouseError::MetadataDoesntExist.into());
+pub fn auctioneer_execute_partial_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerExecutePartialSale<'info>>,
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    partial_order_size: Option<u64>,
+    partial_order_price: Option<u64>,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let auctioneer_authority = &ctx.accounts.auctioneer_authority;
+    let ah_auctioneer_pda = &ctx.accounts.ah_auctioneer_pda;
+
+    if !auction_house.has_auctioneer {
+        return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
     }
 
-    let auction_house_key = auction_house.key();
-    let wallet_key = buyer.key();
-    let escrow_signer_seeds = [
-        PREFIX.as_bytes(),
-        auction_house_key.as_ref(),
-        wallet_key.as_ref(),
-        &[escrow_payment_bump],
-    ];
+    assert_valid_auctioneer_and_scope(
+        auction_house,
+        &auctioneer_authority.key(),
+        ah_auctioneer_pda,
+        AuthorityScope::ExecuteSale,
+    )?;
+    assert_trade_state_auctioneer(
+        auction_house,
+        &ctx.accounts.seller_trade_state,
+        ah_auctioneer_pda,
+    )?;
+    assert_trade_state_auctioneer(
+        auction_house,
+        &ctx.accounts.buyer_trade_state,
+        ah_auctioneer_pda,
+    )?;
 
-    let ah_seeds = [
-        PREFIX.as_bytes(),
-        auction_house.creator.as_ref(),
-        auction_house.treasury_mint.as_ref(),
-        &[auction_house.bump],
-    ];
+    let escrow_canonical_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let free_trade_state_canonical_bump = *ctx
+        .bumps
+        .get("free_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_canonical_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
 
-    // with the native account, the escrow is its own owner,
-    // whereas with token, it is the auction house that is owner.
-    let signer_seeds_for_royalties = if is_native {
-        escrow_signer_seeds
-    } else {
-        ah_seeds
-    };
+    if (escrow_canonical_bump != escrow_payment_bump)
+        || (free_trade_state_canonical_bump != free_trade_state_bump)
+        || (program_as_signer_canonical_bump != program_as_signer_bump)
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
 
-    let remaining_accounts = &mut remaining_accounts.iter();
+    let mut accounts: AuctioneerExecuteSale<'info> = (*ctx.accounts).clone().into();
 
-    let buyer_leftover_after_royalties = pay_creator_fees(
-        remaining_accounts,
-        &metadata_clone,
-        &escrow_clone,
-        &auction_house_clone,
-        &fee_payer_clone,
-        treasury_mint,
-        &ata_clone,
-        &token_clone,
-        &sys_clone,
-        &rent_clone,
-        &signer_seeds_for_royalties,
-        fee_payer_seeds,
-        price,
-        is_native,
-    )?;
+    // Duplicate the logic methods to avoid going over the compute limit.
+    auctioneer_execute_sale_logic(
+        &mut accounts,
+        ctx.remaining_accounts,
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        partial_order_size,
+        partial_order_price,
+    )
+}
 
-    let auction_house_fee_paid = pay_auction_house_fees(
-        auction_house,
-        &treasury_clone,
-        &escrow_clone,
-        &token_clone,
-        &sys_clone,
-        &signer_seeds_for_royalties,
-        price,
-        is_native,
-    )?;
+/// Execute sale between provided buyer and seller trade state accounts transferring funds to seller wallet and token to buyer wallet.
+#[inline(never)]
+fn auctioneer_execute_sale_logic<'c, 'info>(
+    accounts: &mut AuctioneerExecuteSale<'info>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+    escrow_payment_bump: u8,
+    _free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    partial_order_size: Option<u64>,
+    partial_order_price: Option<u64>,
+) -> Result<()> {
+    let buyer = &accounts.buyer;
+    let seller = &accounts.seller;
+    let token_account = &accounts.token_account;
+    let token_mint = &accounts.token_mint;
+    let metadata = &accounts.metadata;
+    let treasury_mint = &accounts.treasury_mint;
+    let seller_payment_receipt_account = &accounts.seller_payment_receipt_account;
+    let buyer_receipt_token_account = &accounts.buyer_receipt_token_account;
+    let escrow_payment_account = &accounts.escrow_payment_account;
+    let authority = &accounts.authority;
+    let auction_house = &accounts.auction_house;
+    let auction_house_fee_account = &accounts.auction_house_fee_account;
+    let auction_house_treasury = &accounts.auction_house_treasury;
+    let buyer_trade_state = &accounts.buyer_trade_state;
+    let seller_trade_state = &accounts.seller_trade_state;
+    let free_trade_state = &accounts.free_trade_state;
+    let token_program = &accounts.token_program;
+    let system_program = &accounts.system_program;
+    let ata_program = &accounts.ata_program;
+    let program_as_signer = &accounts.program_as_signer;
+    let rent = &accounts.rent;
 
-    let buyer_leftover_after_royalties_and_house_fee = buyer_leftover_after_royalties
-        .checked_sub(auction_house_fee_paid)
-        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    let metadata_clone = metadata.to_account_info();
+    let escrow_clone = escrow_payment_account.to_account_info();
+    let auction_house_clone = auction_house.to_account_info();
+    let ata_clone = ata_program.to_account_info();
+    let token_clone = token_program.to_account_info();
+    let sys_clone = system_program.to_account_info();
+    let rent_clone = rent.to_account_info();
+    let treasury_clone = auction_house_treasury.to_account_info();
+    let authority_clone = authority.to_account_info();
+    let buyer_receipt_clone = buyer_receipt_token_account.to_account_info();
+    let token_account_clone = token_account.to_account_info();
 
-    if !is_native {
-        if seller_payment_receipt_account.data_is_empty() {
-            make_ata(
-                seller_payment_receipt_account.to_account_info(),
-                seller.to_account_info(),
-                treasury_mint.to_account_info(),
-                fee_payer.to_account_info(),
-                ata_program.to_account_info(),
-                token_program.to_account_info(),
-                system_program.to_account_info(),
-                rent.to_account_info(),
-                fee_payer_seeds,
-            )?;
-        }
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
 
-        let seller_rec_acct = assert_is_ata(
-            &seller_payment_receipt_account.to_account_info(),
-            &seller.key(),
-            &treasury_mint.key(),
-        )?;
+    if buyer_price == 0 && !authority_clone.is_signer && !seller.is_signer {
+        return Err(
+            AuctionHouseError::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into(),
+        );
+    }
 
-        // make sure you cant get rugged
-        if seller_rec_acct.delegate.is_some() {
-            return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
-        }
+    let token_account_mint = get_mint_from_token_account(&token_account_clone)?;
 
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &seller_payment_receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                buyer_leftover_after_royalties_and_house_fee,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                seller_payment_receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
-            &[&ah_seeds],
-        )?;
+    assert_keys_equal(token_mint.key(), token_account_mint)?;
+    let delegate = get_delegate_from_token_account(&token_account_clone)?;
+    if let Some(d) = delegate {
+        assert_keys_equal(program_as_signer.key(), d)?;
+    } else {
+        msg!("No delegate detected on token account.");
+        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+    }
+
+    let ts_bump = if buyer_trade_state.data_len() > 0 {
+        buyer_trade_state.try_borrow_data()?[0]
+    } else {
+        return Err(AuctionHouseError::BuyerTradeStateNotValid.into());
+    };
+
+    if ts_bump == 0
+        || seller_trade_state.data_len() == 0
+        || seller_trade_state.try_borrow_data()?[0] == 0
+    {
+        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+    }
+
+    assert_trade_state_not_expired(buyer_trade_state)?;
+    assert_trade_state_not_expired(seller_trade_state)?;
+    assert_allowed_buyer(seller_trade_state, &buyer.key())?;
+
+    let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
+
+    let (size, price): (u64, u64) = match (partial_order_size, partial_order_price) {
+        (Some(size), Some(price)) => {
+            assert_valid_trade_state(
+                &buyer.key(),
+                auction_house,
+                price,
+                size,
+                buyer_trade_state,
+                &token_mint.key(),
+                &token_account.key(),
+                ts_bump,
+            )?;
+
+            if ((buyer_price / token_size) * size) != price {
+                return Err(AuctionHouseError::PartialPriceMismatch.into());
+            }
+
+            if token_account_data.amount < size {
+                return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+            };
+
+            if token_account_data.delegated_amount < size {
+                return Err(ProgramError::InvalidAccountData.into());
+            };
+
+            (size, price)
+        }
+        (None, None) => {
+            assert_valid_trade_state(
+                &buyer.key(),
+                auction_house,
+                buyer_price,
+                token_size,
+                buyer_trade_state,
+                &token_mint.key(),
+                &token_account.key(),
+                ts_bump,
+            )?;
+
+            if token_account_data.amount < token_size {
+                return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+            };
+
+            (token_size, buyer_price)
+        }
+        _ => {
+            return Err(AuctionHouseError::MissingElementForPartialOrder.into());
+        }
+    };
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let wallet_to_use = if buyer.is_signer { buyer } else { seller };
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet_to_use.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+    let fee_payer_clone = fee_payer.to_account_info();
+
+    assert_is_ata(
+        &token_account.to_account_info(),
+        &seller.key(),
+        &token_account_mint,
+    )?;
+    assert_derivation(
+        &mpl_token_metadata::id(),
+        &metadata.to_account_info(),
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            token_account_mint.as_ref(),
+        ],
+    )?;
+
+    // Whoever signed the sale took the resting order on the other side. A taker holding a membership token
+    // passes its fee tier holder accounts after every other remaining account but the shared escrow of the buyer.
+    let taker = get_sale_taker(buyer, seller);
+    let (shared_escrow, remaining_accounts) =
+        split_shared_escrow(auction_house, &buyer.key(), remaining_accounts)?;
+    let (fee_tier_holder, remaining_accounts) =
+        split_fee_tier_holder(auction_house, remaining_accounts)?;
+    let taker_fee_tier = fee_tier_basis_points(
+        auction_house,
+        taker.map(|(_, key)| key),
+        fee_tier_holder.as_ref(),
+    )?;
+    let mut sale_fees = get_sale_fees(
+        auction_house,
+        price,
+        taker.map(|(side, _)| side),
+        taker_fee_tier,
+    )?;
+    if metadata.data_is_empty() {
+        return Err(AuctionHouseError::MetadataDoesntExist.into());
+    }
+
+    // The buyer pays the royalty of its election, split off with the accounts after it.
+    let remaining_accounts =
+        assert_collection_list_allows(auction_house, &metadata_clone, remaining_accounts)?;
+    let (escrow_reservation, remaining_accounts) =
+        split_escrow_reservation(auction_house, &buyer.key(), remaining_accounts)?;
+    if let Some(escrow_reservation) = escrow_reservation {
+        release_bid(escrow_reservation, &buyer_trade_state.key())?;
+    }
+    let (auction_house_stats, remaining_accounts) =
+        split_auction_house_stats(auction_house, remaining_accounts)?;
+    let (elected_royalty_basis_points, remaining_accounts) =
+        split_royalty_election(auction_house, &buyer.key(), remaining_accounts)?;
+    let buyer_payment = auction_house.royalty_policy.buyer_payment(
+        &Metadata::from_account_info(&metadata_clone)?,
+        elected_royalty_basis_points,
+        price,
+    )?;
+    let buyer_total = buyer_payment
+        .checked_add(sale_fees.buyer_fee)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    // A buyer that approved the Auction House tops up its escrow from its shared escrow. Token-2022 treasury
+    // mints find their token program among the remaining accounts.
+    if let Some(shared_escrow) = shared_escrow {
+        let treasury_token_program = remaining_accounts
+            .iter()
+            .find(|account| account.key() == *treasury_mint.owner)
+            .unwrap_or(&token_clone);
+        draw_shared_escrow(
+            &shared_escrow,
+            &escrow_clone,
+            &auction_house.key(),
+            &buyer.key(),
+            treasury_mint,
+            treasury_token_program,
+            &sys_clone,
+            buyer_total,
+        )?;
+    }
+
+    // For native purchases, verify that the amount in escrow is sufficient to actually purchase the
+    // token.  This is intended to cover the migration from pre-rent-exemption checked accounts to
+    // rent-exemption checked accounts.  The fee payer makes up the shortfall up to the amount of
+    // rent for an empty account.
+    if is_native {
+        let rent_shortfall =
+            verify_withdrawal(escrow_payment_account.to_account_info(), buyer_total)?;
+        if rent_shortfall > 0 {
+            invoke_signed(
+                &system_instruction::transfer(
+                    fee_payer.key,
+                    escrow_payment_account.key,
+                    rent_shortfall,
+                ),
+                &[
+                    fee_payer.to_account_info(),
+                    escrow_payment_account.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+                &[fee_payer_seeds],
+            )?;
+        }
+    }
+
+    let auction_house_key = auction_house.key();
+    let wallet_key = buyer.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        wallet_key.as_ref(),
+        &[escrow_payment_bump],
+    ];
+
+    let ah_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    // with the native account, the escrow is its own owner,
+    // whereas with token, it is the auction house that is owner.
+    let signer_seeds_for_royalties = if is_native {
+        escrow_signer_seeds
+    } else {
+        ah_seeds
+    };
+
+    let (treasury_token_clone, remaining_accounts) =
+        split_treasury_token_program(treasury_mint, &token_clone, remaining_accounts)?;
+    let remaining_accounts = &mut remaining_accounts.iter();
+
+    let buyer_leftover_after_royalties = pay_creator_fees(
+        remaining_accounts,
+        &metadata_clone,
+        &escrow_clone,
+        &auction_house_clone,
+        &fee_payer_clone,
+        treasury_mint,
+        &ata_clone,
+        &treasury_token_clone,
+        &sys_clone,
+        &rent_clone,
+        &signer_seeds_for_royalties,
+        fee_payer_seeds,
+        price,
+        is_native,
+        &auction_house.royalty_policy,
+        elected_royalty_basis_points,
+    )?;
+
+    // Programmable NFTs pass the token metadata transfer accounts after the creators, followed by
+    // the optional referrer wallet and, for SPL treasury mints, its treasury mint ATA.
+    let (pnft_accounts, referrer_accounts) = match remaining_accounts.as_slice() {
+        rest @ [metadata_program, ..] if metadata_program.key() == mpl_token_metadata::ID => {
+            rest.split_at(rest.len().min(7))
+        }
+        rest => rest.split_at(0),
+    };
+
+    pay_sale_fees(
+        auction_house,
+        &mut sale_fees,
+        referrer_accounts,
+        &buyer.key(),
+        &seller.key(),
+        &treasury_clone,
+        &escrow_clone,
+        treasury_mint,
+        &treasury_token_clone,
+        &sys_clone,
+        &ata_clone,
+        &rent_clone,
+        &fee_payer_clone,
+        fee_payer_seeds,
+        &signer_seeds_for_royalties,
+        is_native,
+    )?;
+
+    let buyer_leftover_after_royalties_and_house_fee = buyer_leftover_after_royalties
+        .checked_sub(sale_fees.seller_fee)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    if !is_native {
+        if seller_payment_receipt_account.data_is_empty() {
+            make_ata(
+                seller_payment_receipt_account.to_account_info(),
+                seller.to_account_info(),
+                treasury_mint.to_account_info(),
+                fee_payer.to_account_info(),
+                ata_program.to_account_info(),
+                treasury_token_clone.clone(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                fee_payer_seeds,
+            )?;
+        }
+
+        let seller_rec_acct = assert_is_treasury_ata(
+            &seller_payment_receipt_account.to_account_info(),
+            &seller.key(),
+            treasury_mint,
+        )?;
+
+        // make sure you cant get rugged
+        if seller_rec_acct.delegate.is_some() {
+            return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
+        }
+
+        transfer_treasury_tokens(
+            &escrow_clone,
+            seller_payment_receipt_account,
+            &auction_house_clone,
+            treasury_mint,
+            &treasury_token_clone,
+            buyer_leftover_after_royalties_and_house_fee,
+            &[&ah_seeds],
+        )?;
     } else {
         assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
         invoke_signed(
@@ -1337,163 +1791,760 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
         )?;
     }
 
-    if buyer_receipt_token_account.data_is_empty() {
-        make_ata(
-            buyer_receipt_token_account.to_account_info(),
-            buyer.to_account_info(),
-            token_mint.to_account_info(),
-            fee_payer.to_account_info(),
-            ata_program.to_account_info(),
-            token_program.to_account_info(),
-            system_program.to_account_info(),
-            rent.to_account_info(),
-            fee_payer_seeds,
-        )?;
-    } else {
-        let data = buyer_receipt_token_account.try_borrow_data()?;
-        let token_account = TokenAccount::try_deserialize(&mut data.as_ref())?;
-        if &token_account.owner != buyer.key {
-            return Err(AuctionHouseError::IncorrectOwner.into());
-        }
-    }
+    if buyer_receipt_token_account.data_is_empty() {
+        make_ata(
+            buyer_receipt_token_account.to_account_info(),
+            buyer.to_account_info(),
+            token_mint.to_account_info(),
+            fee_payer.to_account_info(),
+            ata_program.to_account_info(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+            fee_payer_seeds,
+        )?;
+    } else {
+        let data = buyer_receipt_token_account.try_borrow_data()?;
+        let token_account = TokenAccount::try_deserialize(&mut data.as_ref())?;
+        if &token_account.owner != buyer.key {
+            return Err(AuctionHouseError::IncorrectOwner.into());
+        }
+    }
+
+    let buyer_rec_acct = assert_is_ata(&buyer_receipt_clone, &buyer.key(), &token_mint.key())?;
+
+    // make sure you cant get rugged
+    if buyer_rec_acct.delegate.is_some() {
+        return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+    }
+
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    let pnft_accounts = &mut pnft_accounts.iter();
+    match next_account_info(pnft_accounts) {
+        Ok(metadata_program) => {
+            require!(
+                metadata_program.key() == mpl_token_metadata::ID,
+                AuctionHouseError::PublicKeyMismatch
+            );
+
+            let edition = next_account_info(pnft_accounts)?;
+            let owner_tr = next_account_info(pnft_accounts)?;
+            let destination_tr = next_account_info(pnft_accounts)?;
+            let auth_rules_program = next_account_info(pnft_accounts)?;
+            let auth_rules = next_account_info(pnft_accounts)?;
+            let sysvar_instructions = next_account_info(pnft_accounts)?;
+
+            let mpl_transfer = TransferBuilder::new()
+                .token(*token_account.key)
+                .token_owner(*seller.key)
+                .destination(*buyer_receipt_token_account.key)
+                .destination_owner(*buyer.key)
+                .mint(*token_mint.key)
+                .metadata(*metadata.key)
+                .edition(*edition.key)
+                .owner_token_record(*owner_tr.key)
+                .destination_token_record(*destination_tr.key)
+                .authority(program_as_signer.key())
+                .payer(*fee_payer.key)
+                .system_program(*system_program.key)
+                .sysvar_instructions(*sysvar_instructions.key)
+                .spl_token_program(*token_program.key)
+                .spl_ata_program(*ata_program.key)
+                .authorization_rules_program(*auth_rules_program.key)
+                .authorization_rules(*auth_rules.key)
+                .build(TransferArgs::V1 {
+                    amount: size,
+                    authorization_data: Some(AuthorizationData {
+                        payload: Payload::from([
+                            ("Amount".to_string(), PayloadType::Number(size)),
+                            (
+                                "Authority".to_string(),
+                                PayloadType::Pubkey(*program_as_signer.key),
+                            ),
+                            (
+                                "AuthoritySeeds".to_string(),
+                                PayloadType::Seeds(SeedsVec {
+                                    seeds: vec![
+                                        PREFIX.as_bytes().to_vec(),
+                                        SIGNER.as_bytes().to_vec(),
+                                    ],
+                                }),
+                            ),
+                        ]),
+                    }),
+                })
+                .unwrap()
+                .instruction();
+
+            let mpl_transfer_accounts = [
+                metadata_program.clone(),
+                token_account_clone,
+                seller.to_account_info(),
+                buyer_receipt_token_account.to_account_info(),
+                buyer.to_account_info(),
+                token_mint.to_account_info(),
+                metadata.to_account_info(),
+                edition.to_account_info(),
+                owner_tr.to_account_info(),
+                destination_tr.to_account_info(),
+                program_as_signer.to_account_info(),
+                fee_payer.to_account_info(),
+                system_program.to_account_info(),
+                sysvar_instructions.to_account_info(),
+                token_program.to_account_info(),
+                ata_program.to_account_info(),
+                auth_rules_program.to_account_info(),
+                auth_rules.to_account_info(),
+            ];
+
+            invoke_signed(
+                &mpl_transfer,
+                &mpl_transfer_accounts,
+                &[&program_as_signer_seeds, fee_payer_seeds],
+            )?;
+        }
+        Err(_) => {
+            spl_token_transfer(TokenTransferParams {
+                mint: token_mint.to_account_info(),
+                source: token_account.to_account_info(),
+                destination: buyer_receipt_clone,
+                amount: size,
+                authority: program_as_signer.to_account_info(),
+                authority_signer_seeds: Some(&program_as_signer_seeds),
+                token_program: token_clone,
+            })?;
+        }
+    }
+    // Close the buyer trade state account if the rest of execute sale was successful.
+    close_account(
+        &buyer_trade_state.to_account_info(),
+        &fee_payer.to_account_info(),
+    )?;
+
+    let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
+    if token_account_data.delegated_amount == 0 {
+        close_account(
+            &seller_trade_state.to_account_info(),
+            &fee_payer.to_account_info(),
+        )?;
+
+        if free_trade_state.lamports() > 0 {
+            close_account(
+                &free_trade_state.to_account_info(),
+                &fee_payer.to_account_info(),
+            )?;
+        }
+    }
+
+    let royalty_paid = buyer_payment
+        .checked_sub(buyer_leftover_after_royalties)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if let Some(auction_house_stats) = auction_house_stats {
+        record_sale(
+            auction_house_stats,
+            price,
+            royalty_paid,
+            sale_fees.treasury_fee()?,
+        )?;
+    }
+
+    emit!(SaleEvent {
+        auction_house: auction_house.key(),
+        buyer: buyer.key(),
+        seller: seller.key(),
+        token_mint: token_mint.key(),
+        buyer_trade_state: buyer_trade_state.key(),
+        seller_trade_state: seller_trade_state.key(),
+        price,
+        token_size: size,
+        royalty_paid,
+        auction_house_fee: sale_fees
+            .seller_fee
+            .checked_add(sale_fees.buyer_fee)
+            .ok_or(AuctionHouseError::NumericalOverflow)?,
+        partial: partial_order_size.is_some(),
+    });
+
+    Ok(())
+}
+
+/// Accounts for the [`execute_collection_sale` handler](auction_house/fn.execute_collection_sale.html).
+#[derive(Accounts, Clone)]
+#[instruction(
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct ExecuteCollectionSale<'info> {
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer user wallet account.
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    ///Token account where the SPL token is stored.
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Token mint account for the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Metaplex metadata account decorating SPL mint account.
+    //@TODO: re-enable this later #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer SPL token account to receive purchased item at.
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer trade state PDA account encoding the collection bid.
+    #[account(mut)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Seller trade state PDA account encoding the sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump = seller_trade_state.to_account_info().data.borrow()[0]
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Free seller trade state PDA account encoding a free sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub free_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the buyer trade state and the verified collection of the metadata.
+    /// Mint account of the collection NFT the bid was placed on.
+    pub collection_mint: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<ExecuteCollectionSale<'info>> for ExecuteSale<'info> {
+    fn from(a: ExecuteCollectionSale<'info>) -> ExecuteSale<'info> {
+        ExecuteSale {
+            buyer: a.buyer,
+            seller: a.seller,
+            token_account: a.token_account,
+            token_mint: a.token_mint,
+            metadata: a.metadata,
+            treasury_mint: a.treasury_mint,
+            escrow_payment_account: a.escrow_payment_account,
+            seller_payment_receipt_account: a.seller_payment_receipt_account,
+            buyer_receipt_token_account: a.buyer_receipt_token_account,
+            authority: a.authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            auction_house_treasury: a.auction_house_treasury,
+            buyer_trade_state: a.buyer_trade_state,
+            seller_trade_state: a.seller_trade_state,
+            free_trade_state: a.free_trade_state,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            ata_program: a.ata_program,
+            program_as_signer: a.program_as_signer,
+            rent: a.rent,
+        }
+    }
+}
+
+/// Fill a collection bid with any token whose metadata is verified as part of the bid's collection.
+pub fn execute_collection_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteCollectionSale<'info>>,
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    let collection_mint = &ctx.accounts.collection_mint;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+    // Listings and bids recorded to an auctioneer can only be filled through its auctioneer handlers.
+    assert_no_trade_state_auctioneer(&ctx.accounts.seller_trade_state)?;
+    assert_no_trade_state_auctioneer(&ctx.accounts.buyer_trade_state)?;
+
+    let escrow_canonical_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let free_trade_state_canonical_bump = *ctx
+        .bumps
+        .get("free_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_canonical_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    if (escrow_canonical_bump != escrow_payment_bump)
+        || (free_trade_state_canonical_bump != free_trade_state_bump)
+        || (program_as_signer_canonical_bump != program_as_signer_bump)
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    let collection_mint_key = collection_mint.key();
+    let token_mint_key = ctx.accounts.token_mint.key();
+    assert_derivation(
+        &mpl_token_metadata::id(),
+        &ctx.accounts.metadata.to_account_info(),
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            token_mint_key.as_ref(),
+        ],
+    )?;
+    assert_metadata_in_collection(&ctx.accounts.metadata, &collection_mint_key)?;
+
+    let mut accounts: ExecuteSale<'info> = (*ctx.accounts).clone().into();
+
+    execute_sale_logic(
+        &mut accounts,
+        ctx.remaining_accounts,
+        BidTarget::Collection(collection_mint_key),
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        None,
+        None,
+    )
+}
+
+/// Fill a trait bid with a token whose mint is proven to be a leaf of the bid's merkle tree.
+pub fn execute_trait_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteSale<'info>>,
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    root: [u8; 32],
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+    // Listings and bids recorded to an auctioneer can only be filled through its auctioneer handlers.
+    assert_no_trade_state_auctioneer(&ctx.accounts.seller_trade_state)?;
+    assert_no_trade_state_auctioneer(&ctx.accounts.buyer_trade_state)?;
+
+    let escrow_canonical_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let free_trade_state_canonical_bump = *ctx
+        .bumps
+        .get("free_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_canonical_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    if (escrow_canonical_bump != escrow_payment_bump)
+        || (free_trade_state_canonical_bump != free_trade_state_bump)
+        || (program_as_signer_canonical_bump != program_as_signer_bump)
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    assert_mint_in_trait_tree(&ctx.accounts.token_mint.key(), root, proof)?;
+
+    execute_sale_logic(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        BidTarget::Trait(root),
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        None,
+        None,
+    )
+}
+
+/// Accounts for the [`execute_dutch_sale` handler](auction_house/fn.execute_dutch_sale.html).
+#[derive(Accounts, Clone)]
+#[instruction(
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct ExecuteDutchSale<'info> {
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer user wallet account.
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    ///Token account where the SPL token is stored.
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Token mint account for the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer SPL token account to receive purchased item at.
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer trade state PDA account encoding the buy order.
+    #[account(mut)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint, data checked in execute_dutch_sale.
+    /// Dutch listing PDA account, used as the seller trade state of the sale.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            DUTCH.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub dutch_listing: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Free seller trade state PDA account encoding a free sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub free_trade_state: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
 
-    let buyer_rec_acct = assert_is_ata(&buyer_receipt_clone, &buyer.key(), &token_mint.key())?;
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
 
-    // make sure you cant get rugged
-    if buyer_rec_acct.delegate.is_some() {
-        return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<ExecuteDutchSale<'info>> for ExecuteSale<'info> {
+    fn from(a: ExecuteDutchSale<'info>) -> ExecuteSale<'info> {
+        ExecuteSale {
+            buyer: a.buyer,
+            seller: a.seller,
+            token_account: a.token_account,
+            token_mint: a.token_mint,
+            metadata: a.metadata,
+            treasury_mint: a.treasury_mint,
+            escrow_payment_account: a.escrow_payment_account,
+            seller_payment_receipt_account: a.seller_payment_receipt_account,
+            buyer_receipt_token_account: a.buyer_receipt_token_account,
+            authority: a.authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            auction_house_treasury: a.auction_house_treasury,
+            buyer_trade_state: a.buyer_trade_state,
+            seller_trade_state: a.dutch_listing,
+            free_trade_state: a.free_trade_state,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            ata_program: a.ata_program,
+            program_as_signer: a.program_as_signer,
+            rent: a.rent,
+        }
     }
+}
 
-    let program_as_signer_seeds = [
-        PREFIX.as_bytes(),
-        SIGNER.as_bytes(),
-        &[program_as_signer_bump],
-    ];
+/// Fill a bid at or above the current price of a dutch listing. The sale settles at the bid price.
+pub fn execute_dutch_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteDutchSale<'info>>,
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
 
-    match next_account_info(remaining_accounts) {
-        Ok(metadata_program) => {
-            require!(
-                metadata_program.key() == mpl_token_metadata::ID,
-                AuctionHouseError::PublicKeyMismatch
-            );
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+    // Bids recorded to an auctioneer can only be filled through its auctioneer handlers.
+    assert_no_trade_state_auctioneer(&ctx.accounts.buyer_trade_state)?;
 
-            let edition = next_account_info(remaining_accounts)?;
-            let owner_tr = next_account_info(remaining_accounts)?;
-            let destination_tr = next_account_info(remaining_accounts)?;
-            let auth_rules_program = next_account_info(remaining_accounts)?;
-            let auth_rules = next_account_info(remaining_accounts)?;
-            let sysvar_instructions = next_account_info(remaining_accounts)?;
+    let escrow_canonical_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let free_trade_state_canonical_bump = *ctx
+        .bumps
+        .get("free_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_canonical_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
 
-            let mpl_transfer = TransferBuilder::new()
-                .token(*token_account.key)
-                .token_owner(*seller.key)
-                .destination(*buyer_receipt_token_account.key)
-                .destination_owner(*buyer.key)
-                .mint(*token_mint.key)
-                .metadata(*metadata.key)
-                .edition(*edition.key)
-                .owner_token_record(*owner_tr.key)
-                .destination_token_record(*destination_tr.key)
-                .authority(program_as_signer.key())
-                .payer(*fee_payer.key)
-                .system_program(*system_program.key)
-                .sysvar_instructions(*sysvar_instructions.key)
-                .spl_token_program(*token_program.key)
-                .spl_ata_program(*ata_program.key)
-                .authorization_rules_program(*auth_rules_program.key)
-                .authorization_rules(*auth_rules.key)
-                .build(TransferArgs::V1 {
-                    amount: size,
-                    authorization_data: Some(AuthorizationData {
-                        payload: Payload::from([
-                            ("Amount".to_string(), PayloadType::Number(size)),
-                            (
-                                "Authority".to_string(),
-                                PayloadType::Pubkey(*program_as_signer.key),
-                            ),
-                            (
-                                "AuthoritySeeds".to_string(),
-                                PayloadType::Seeds(SeedsVec {
-                                    seeds: vec![
-                                        PREFIX.as_bytes().to_vec(),
-                                        SIGNER.as_bytes().to_vec(),
-                                    ],
-                                }),
-                            ),
-                        ]),
-                    }),
-                })
-                .unwrap()
-                .instruction();
+    if (escrow_canonical_bump != escrow_payment_bump)
+        || (free_trade_state_canonical_bump != free_trade_state_bump)
+        || (program_as_signer_canonical_bump != program_as_signer_bump)
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
 
-            let mpl_transfer_accounts = [
-                metadata_program.clone(),
-                token_account_clone,
-                seller.to_account_info(),
-                buyer_receipt_token_account.to_account_info(),
-                buyer.to_account_info(),
-                token_mint.to_account_info(),
-                metadata.to_account_info(),
-                edition.to_account_info(),
-                owner_tr.to_account_info(),
-                destination_tr.to_account_info(),
-                program_as_signer.to_account_info(),
-                fee_payer.to_account_info(),
-                system_program.to_account_info(),
-                sysvar_instructions.to_account_info(),
-                token_program.to_account_info(),
-                ata_program.to_account_info(),
-                auth_rules_program.to_account_info(),
-                auth_rules.to_account_info(),
-            ];
+    let dutch_listing_info = ctx.accounts.dutch_listing.to_account_info();
+    if dutch_listing_info.data_is_empty() {
+        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+    }
+    let dutch_listing: Account<DutchListing> = Account::try_from(&dutch_listing_info)?;
 
-            invoke_signed(
-                &mpl_transfer,
-                &mpl_transfer_accounts,
-                &[&program_as_signer_seeds, fee_payer_seeds],
-            )?;
-        }
-        Err(_) => {
-            invoke_signed(
-                &spl_token::instruction::transfer(
-                    token_program.key,
-                    &token_account.key(),
-                    &buyer_receipt_token_account.key(),
-                    &program_as_signer.key(),
-                    &[],
-                    size,
-                )?,
-                &[
-                    token_account.to_account_info(),
-                    buyer_receipt_clone,
-                    program_as_signer.to_account_info(),
-                    token_clone,
-                ],
-                &[&program_as_signer_seeds],
-            )?;
-        }
+    if dutch_listing.auction_house != auction_house.key()
+        || dutch_listing.token_mint != ctx.accounts.token_mint.key()
+    {
+        return Err(AuctionHouseError::PublicKeyMismatch.into());
+    }
+    if dutch_listing.token_size != token_size {
+        return Err(AuctionHouseError::InvalidTokenAmount.into());
+    }
+    if buyer_price < dutch_listing.current_price(Clock::get()?.unix_timestamp)? {
+        return Err(AuctionHouseError::BidBelowDutchPrice.into());
     }
-    // Close the buyer trade state account if the rest of execute sale was successful.
-    close_account(
-        &buyer_trade_state.to_account_info(),
-        &fee_payer.to_account_info(),
-    )?;
 
-    let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
-    if token_account_data.delegated_amount == 0 {
-        close_account(
-            &seller_trade_state.to_account_info(),
-            &fee_payer.to_account_info(),
-        )?;
+    let mut accounts: ExecuteSale<'info> = (*ctx.accounts).clone().into();
 
-        if free_trade_state.lamports() > 0 {
-            close_account(
-                &free_trade_state.to_account_info(),
-                &fee_payer.to_account_info(),
-            )?;
-        }
-    }
-    Ok(())
+    execute_sale_logic(
+        &mut accounts,
+        ctx.remaining_accounts,
+        BidTarget::Token,
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        None,
+        None,
+    )
 }
 
 /// Execute sale between provided buyer and seller trade state accounts transferring funds to seller wallet and token to buyer wallet.
@@ -1501,6 +2552,7 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
 fn execute_sale_logic<'c, 'info>(
     accounts: &mut ExecuteSale<'info>,
     remaining_accounts: &'c [AccountInfo<'info>],
+    bid_target: BidTarget,
     escrow_payment_bump: u8,
     _free_trade_state_bump: u8,
     program_as_signer_bump: u8,
@@ -1575,11 +2627,16 @@ fn execute_sale_logic<'c, 'info>(
         return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
     }
 
+    assert_trade_state_not_expired(buyer_trade_state)?;
+    assert_trade_state_not_expired(seller_trade_state)?;
+    assert_allowed_buyer(seller_trade_state, &buyer.key())?;
+
     let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
 
     let (size, price): (u64, u64) = match (partial_order_size, partial_order_price) {
         (Some(size), Some(price)) => {
-            assert_valid_trade_state(
+            assert_valid_buyer_trade_state(
+                bid_target,
                 &buyer.key(),
                 auction_house,
                 price,
@@ -1605,7 +2662,8 @@ fn execute_sale_logic<'c, 'info>(
             (size, price)
         }
         (None, None) => {
-            assert_valid_trade_state(
+            assert_valid_buyer_trade_state(
+                bid_target,
                 &buyer.key(),
                 auction_house,
                 buyer_price,
@@ -1661,12 +2719,75 @@ fn execute_sale_logic<'c, 'info>(
         ],
     )?;
 
+    // Whoever signed the sale took the resting order on the other side. A taker holding a membership token
+    // passes its fee tier holder accounts after every other remaining account but the shared escrow of the buyer.
+    let taker = get_sale_taker(buyer, seller);
+    let (shared_escrow, remaining_accounts) =
+        split_shared_escrow(auction_house, &buyer.key(), remaining_accounts)?;
+    let (fee_tier_holder, remaining_accounts) =
+        split_fee_tier_holder(auction_house, remaining_accounts)?;
+    let taker_fee_tier = fee_tier_basis_points(
+        auction_house,
+        taker.map(|(_, key)| key),
+        fee_tier_holder.as_ref(),
+    )?;
+    let mut sale_fees = get_sale_fees(
+        auction_house,
+        price,
+        taker.map(|(side, _)| side),
+        taker_fee_tier,
+    )?;
+    if metadata.data_is_empty() {
+        return Err(AuctionHouseError::MetadataDoesntExist.into());
+    }
+
+    // Curated Auction Houses pass their collection list before the fee tier holder accounts.
+    let remaining_accounts =
+        assert_collection_list_allows(auction_house, &metadata_clone, remaining_accounts)?;
+    let (escrow_reservation, remaining_accounts) =
+        split_escrow_reservation(auction_house, &buyer.key(), remaining_accounts)?;
+    if let Some(escrow_reservation) = escrow_reservation {
+        release_bid(escrow_reservation, &buyer_trade_state.key())?;
+    }
+    let (auction_house_stats, remaining_accounts) =
+        split_auction_house_stats(auction_house, remaining_accounts)?;
+    let (elected_royalty_basis_points, remaining_accounts) =
+        split_royalty_election(auction_house, &buyer.key(), remaining_accounts)?;
+    let buyer_payment = auction_house.royalty_policy.buyer_payment(
+        &Metadata::from_account_info(&metadata_clone)?,
+        elected_royalty_basis_points,
+        price,
+    )?;
+    let buyer_total = buyer_payment
+        .checked_add(sale_fees.buyer_fee)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    // A buyer that approved the Auction House tops up its escrow from its shared escrow. Token-2022 treasury
+    // mints find their token program among the remaining accounts.
+    if let Some(shared_escrow) = shared_escrow {
+        let treasury_token_program = remaining_accounts
+            .iter()
+            .find(|account| account.key() == *treasury_mint.owner)
+            .unwrap_or(&token_clone);
+        draw_shared_escrow(
+            &shared_escrow,
+            &escrow_clone,
+            &auction_house_key,
+            &buyer.key(),
+            treasury_mint,
+            treasury_token_program,
+            &sys_clone,
+            buyer_total,
+        )?;
+    }
+
     // For native purchases, verify that the amount in escrow is sufficient to actually purchase the
     // token.  This is intended to cover the migration from pre-rent-exemption checked accounts to
     // rent-exemption checked accounts.  The fee payer makes up the shortfall up to the amount of
     // rent for an empty account.
     if is_native {
-        let rent_shortfall = verify_withdrawal(escrow_payment_account.to_account_info(), price)?;
+        let rent_shortfall =
+            verify_withdrawal(escrow_payment_account.to_account_info(), buyer_total)?;
         if rent_shortfall > 0 {
             invoke_signed(
                 &system_instruction::transfer(
@@ -1684,10 +2805,6 @@ fn execute_sale_logic<'c, 'info>(
         }
     }
 
-    if metadata.data_is_empty() {
-        return Err(AuctionHouseError::MetadataDoesntExist.into());
-    }
-
     let auction_house_key = auction_house.key();
     let wallet_key = buyer.key();
     let escrow_signer_seeds = [
@@ -1712,6 +2829,8 @@ fn execute_sale_logic<'c, 'info>(
         ah_seeds
     };
 
+    let (treasury_token_clone, remaining_accounts) =
+        split_treasury_token_program(treasury_mint, &token_clone, remaining_accounts)?;
     let remaining_accounts = &mut remaining_accounts.iter();
 
     let buyer_leftover_after_royalties = pay_creator_fees(
@@ -1722,28 +2841,47 @@ fn execute_sale_logic<'c, 'info>(
         &fee_payer_clone,
         treasury_mint,
         &ata_clone,
-        &token_clone,
+        &treasury_token_clone,
         &sys_clone,
         &rent_clone,
         &signer_seeds_for_royalties,
         fee_payer_seeds,
         price,
         is_native,
+        &auction_house.royalty_policy,
+        elected_royalty_basis_points,
     )?;
 
-    let auction_house_fee_paid = pay_auction_house_fees(
+    // Programmable NFTs pass the token metadata transfer accounts after the creators, followed by
+    // the optional referrer wallet and, for SPL treasury mints, its treasury mint ATA.
+    let (pnft_accounts, referrer_accounts) = match remaining_accounts.as_slice() {
+        rest @ [metadata_program, ..] if metadata_program.key() == mpl_token_metadata::ID => {
+            rest.split_at(rest.len().min(7))
+        }
+        rest => rest.split_at(0),
+    };
+
+    pay_sale_fees(
         auction_house,
+        &mut sale_fees,
+        referrer_accounts,
+        &buyer.key(),
+        &seller.key(),
         &treasury_clone,
         &escrow_clone,
-        &token_clone,
+        treasury_mint,
+        &treasury_token_clone,
         &sys_clone,
+        &ata_clone,
+        &rent_clone,
+        &fee_payer_clone,
+        fee_payer_seeds,
         &signer_seeds_for_royalties,
-        price,
         is_native,
     )?;
 
     let buyer_leftover_after_royalties_and_house_fee = buyer_leftover_after_royalties
-        .checked_sub(auction_house_fee_paid)
+        .checked_sub(sale_fees.seller_fee)
         .ok_or(AuctionHouseError::NumericalOverflow)?;
 
     if !is_native {
@@ -1754,17 +2892,17 @@ fn execute_sale_logic<'c, 'info>(
                 treasury_mint.to_account_info(),
                 fee_payer.to_account_info(),
                 ata_program.to_account_info(),
-                token_program.to_account_info(),
+                treasury_token_clone.clone(),
                 system_program.to_account_info(),
                 rent.to_account_info(),
                 fee_payer_seeds,
             )?;
         }
 
-        let seller_rec_acct = assert_is_ata(
+        let seller_rec_acct = assert_is_treasury_ata(
             &seller_payment_receipt_account.to_account_info(),
             &seller.key(),
-            &treasury_mint.key(),
+            treasury_mint,
         )?;
 
         // make sure you cant get rugged
@@ -1772,21 +2910,13 @@ fn execute_sale_logic<'c, 'info>(
             return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
         }
 
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &seller_payment_receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                buyer_leftover_after_royalties_and_house_fee,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                seller_payment_receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
+        transfer_treasury_tokens(
+            &escrow_clone,
+            seller_payment_receipt_account,
+            &auction_house_clone,
+            treasury_mint,
+            &treasury_token_clone,
+            buyer_leftover_after_royalties_and_house_fee,
             &[&ah_seeds],
         )?;
     } else {
@@ -1833,19 +2963,20 @@ fn execute_sale_logic<'c, 'info>(
         &[program_as_signer_bump],
     ];
 
-    match next_account_info(remaining_accounts) {
+    let pnft_accounts = &mut pnft_accounts.iter();
+    match next_account_info(pnft_accounts) {
         Ok(metadata_program) => {
             require!(
                 metadata_program.key() == mpl_token_metadata::ID,
                 AuctionHouseError::PublicKeyMismatch
             );
 
-            let edition = next_account_info(remaining_accounts)?;
-            let owner_tr = next_account_info(remaining_accounts)?;
-            let destination_tr = next_account_info(remaining_accounts)?;
-            let auth_rules_program = next_account_info(remaining_accounts)?;
-            let auth_rules = next_account_info(remaining_accounts)?;
-            let sysvar_instructions = next_account_info(remaining_accounts)?;
+            let edition = next_account_info(pnft_accounts)?;
+            let owner_tr = next_account_info(pnft_accounts)?;
+            let destination_tr = next_account_info(pnft_accounts)?;
+            let auth_rules_program = next_account_info(pnft_accounts)?;
+            let auth_rules = next_account_info(pnft_accounts)?;
+            let sysvar_instructions = next_account_info(pnft_accounts)?;
 
             let mpl_transfer = TransferBuilder::new()
                 .token(*token_account.key)
@@ -1917,23 +3048,15 @@ fn execute_sale_logic<'c, 'info>(
             )?;
         }
         Err(_) => {
-            invoke_signed(
-                &spl_token::instruction::transfer(
-                    token_program.key,
-                    &token_account.key(),
-                    &buyer_receipt_token_account.key(),
-                    &program_as_signer.key(),
-                    &[],
-                    size,
-                )?,
-                &[
-                    token_account.to_account_info(),
-                    buyer_receipt_clone,
-                    program_as_signer.to_account_info(),
-                    token_clone,
-                ],
-                &[&program_as_signer_seeds],
-            )?;
+            spl_token_transfer(TokenTransferParams {
+                mint: token_mint.to_account_info(),
+                source: token_account.to_account_info(),
+                destination: buyer_receipt_clone,
+                amount: size,
+                authority: program_as_signer.to_account_info(),
+                authority_signer_seeds: Some(&program_as_signer_seeds),
+                token_program: token_clone,
+            })?;
         }
     }
 
@@ -1958,5 +3081,34 @@ fn execute_sale_logic<'c, 'info>(
         }
     }
 
+    let royalty_paid = buyer_payment
+        .checked_sub(buyer_leftover_after_royalties)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if let Some(auction_house_stats) = auction_house_stats {
+        record_sale(
+            auction_house_stats,
+            price,
+            royalty_paid,
+            sale_fees.treasury_fee()?,
+        )?;
+    }
+
+    emit!(SaleEvent {
+        auction_house: auction_house.key(),
+        buyer: buyer.key(),
+        seller: seller.key(),
+        token_mint: token_mint.key(),
+        buyer_trade_state: buyer_trade_state.key(),
+        seller_trade_state: seller_trade_state.key(),
+        price,
+        token_size: size,
+        royalty_paid,
+        auction_house_fee: sale_fees
+            .seller_fee
+            .checked_add(sale_fees.buyer_fee)
+            .ok_or(AuctionHouseError::NumericalOverflow)?,
+        partial: partial_order_size.is_some(),
+    });
+
     Ok(())
 }
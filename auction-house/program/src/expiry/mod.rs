@@ -0,0 +1,333 @@
+//! Optional expiry for listings and bids.
+//! An expiry is attached to a trade state right after the `sell`, `auctioneer_sell` or bid instruction that created it, and once it has passed anyone can close the trade state.
+
+use anchor_lang::{prelude::*, solana_program::sysvar};
+use solana_program::{program_memory::sol_memset, sysvar::instructions::get_instruction_relative};
+
+use mpl_token_metadata::pda::find_metadata_account;
+
+use crate::{
+    cancel::revoke_sale_delegate,
+    constants::*,
+    errors::AuctionHouseError,
+    id,
+    instruction::{AuctioneerSell, Buy, Sell},
+    reservation::release_bid,
+    utils::*,
+    AuctionHouse,
+};
+
+/// Accounts for the [`set_trade_state_expiry` handler](fn.set_trade_state_expiry.html).
+#[derive(Accounts)]
+pub struct SetTradeStateExpiry<'info> {
+    /// CHECK: Validated against the order instruction and the trade state seeds in set_trade_state_expiry.
+    /// User wallet account that created the trade state.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the order instruction in set_trade_state_expiry.
+    /// Trade state PDA account of the listing or bid.
+    #[account(mut)]
+    pub trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the order instruction in set_trade_state_expiry.
+    /// Token mint of the listing or bid, the collection mint of a collection bid.
+    pub token_mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Validated by the address constraint.
+    #[account(address = sysvar::instructions::id())]
+    pub instruction: UncheckedAccount<'info>,
+}
+
+/// Attach an expiry to the trade state created by the preceding `sell`, `auctioneer_sell`, `buy`, `public_buy` or
+/// `collection_buy` instruction.
+/// A receipt instruction and a `set_allowed_buyer` instruction may sit in between. The trade state must derive from `wallet` and the
+/// order arguments. It is grown to hold the expiry and the account that paid its rent,
+/// which is the same account that paid for the trade state in the order instruction.
+pub fn set_trade_state_expiry<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetTradeStateExpiry<'info>>,
+    expires_at: i64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let trade_state = &ctx.accounts.trade_state;
+    let token_mint = &ctx.accounts.token_mint;
+    let system_program = &ctx.accounts.system_program;
+    let instruction_account = &ctx.accounts.instruction;
+    let clock = Clock::get()?;
+
+    if expires_at <= clock.unix_timestamp {
+        return Err(AuctionHouseError::InvalidExpiry.into());
+    }
+
+    let mut index = -1;
+    let mut prev_instruction = get_instruction_relative(index, instruction_account)?;
+    if prev_instruction.program_id == id()
+        && is_program_print_receipt_instruction(&prev_instruction.data[..8])
+    {
+        index -= 1;
+        prev_instruction = get_instruction_relative(index, instruction_account)?;
+    }
+    if prev_instruction.program_id == id()
+        && is_program_set_allowed_buyer_instruction(&prev_instruction.data[..8])
+    {
+        index -= 1;
+        prev_instruction = get_instruction_relative(index, instruction_account)?;
+    }
+    assert_keys_equal(prev_instruction.program_id, id())?;
+
+    // (authority, auction_house, trade_state) positions in the order instruction.
+    let sighash = &prev_instruction.data[..8];
+    let (authority_index, auction_house_index, trade_state_index) =
+        match assert_program_listing_instruction(sighash) {
+            Ok(ListingType::Sell) => (3, 4, 6),
+            Ok(ListingType::AuctioneerSell) => (3, 5, 7),
+            Ok(ListingType::SellDutch) => return Err(AuctionHouseError::InstructionMismatch.into()),
+            Err(_) => match assert_program_bid_instruction(sighash)? {
+                BidType::PublicSale | BidType::PrivateSale | BidType::CollectionSale => (7, 8, 10),
+                BidType::AuctioneerPublicSale | BidType::AuctioneerPrivateSale => {
+                    return Err(AuctionHouseError::InstructionMismatch.into())
+                }
+            },
+        };
+
+    let prev_instruction_accounts = prev_instruction.accounts;
+    assert_keys_equal(prev_instruction_accounts[0].pubkey, wallet.key())?;
+    assert_keys_equal(
+        prev_instruction_accounts[auction_house_index].pubkey,
+        auction_house.key(),
+    )?;
+    assert_keys_equal(
+        prev_instruction_accounts[trade_state_index].pubkey,
+        trade_state.key(),
+    )?;
+
+    if trade_state.data_is_empty() || *trade_state.owner != id() {
+        return Err(AuctionHouseError::InvalidTradeState.into());
+    }
+
+    // Tie the wallet to the trade state through the seeds the order instruction derived it from.
+    let mut buffer = &prev_instruction.data[8..];
+    match assert_program_listing_instruction(sighash) {
+        // Auctioneer listings derive their trade state from the maximum price.
+        Ok(ListingType::AuctioneerSell) => {
+            let sell_data = AuctioneerSell::deserialize(&mut buffer)?;
+            assert_keys_equal(
+                find_metadata_account(&token_mint.key()).0,
+                prev_instruction_accounts[2].pubkey,
+            )?;
+            assert_valid_trade_state(
+                &wallet.key(),
+                auction_house,
+                u64::MAX,
+                sell_data.token_size,
+                &trade_state.to_account_info(),
+                &token_mint.key(),
+                &prev_instruction_accounts[1].pubkey,
+                sell_data.trade_state_bump,
+            )?;
+        }
+        Ok(_) => {
+            let sell_data = Sell::deserialize(&mut buffer)?;
+            assert_keys_equal(
+                find_metadata_account(&token_mint.key()).0,
+                prev_instruction_accounts[2].pubkey,
+            )?;
+            assert_valid_trade_state(
+                &wallet.key(),
+                auction_house,
+                sell_data.buyer_price,
+                sell_data.token_size,
+                &trade_state.to_account_info(),
+                &token_mint.key(),
+                &prev_instruction_accounts[1].pubkey,
+                sell_data.trade_state_bump,
+            )?;
+        }
+        // Collection bids share the argument layout of the other bids.
+        Err(_) => {
+            let buy_data = Buy::deserialize(&mut buffer)?;
+            if let BidType::CollectionSale = assert_program_bid_instruction(sighash)? {
+                assert_keys_equal(prev_instruction_accounts[4].pubkey, token_mint.key())?;
+                assert_valid_targeted_trade_state(
+                    &wallet.key(),
+                    auction_house,
+                    buy_data.buyer_price,
+                    buy_data.token_size,
+                    &trade_state.to_account_info(),
+                    COLLECTION,
+                    token_mint.key().as_ref(),
+                    buy_data.trade_state_bump,
+                )?;
+            } else {
+                assert_keys_equal(
+                    find_metadata_account(&token_mint.key()).0,
+                    prev_instruction_accounts[5].pubkey,
+                )?;
+                assert_valid_trade_state(
+                    &wallet.key(),
+                    auction_house,
+                    buy_data.buyer_price,
+                    buy_data.token_size,
+                    &trade_state.to_account_info(),
+                    &token_mint.key(),
+                    &prev_instruction_accounts[4].pubkey,
+                    buy_data.trade_state_bump,
+                )?;
+            }
+        }
+    }
+
+    // Mirror get_fee_payer in the order instruction so the same account pays for the whole trade state.
+    let auction_house_key = auction_house.key();
+    let fee_payer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+    let (payer, payer_seeds): (AccountInfo<'info>, &[&[u8]]) =
+        if prev_instruction_accounts[authority_index].is_signer {
+            (
+                auction_house_fee_account.to_account_info(),
+                &fee_payer_seeds,
+            )
+        } else if wallet.is_signer {
+            (wallet.to_account_info(), &[])
+        } else {
+            return Err(AuctionHouseError::NoPayerPresent.into());
+        };
+
+    // Keep the payer recorded the first time the expiry was set.
+    let ts_info = trade_state.to_account_info();
+    let mut fields = get_trade_state_fields(&ts_info)?;
+    let payer_key = fields.expiry.map_or(payer.key(), |(_, payer)| payer);
+    fields.expiry = Some((expires_at, payer_key));
+    set_trade_state_fields(
+        &ts_info,
+        &fields,
+        &payer,
+        payer_seeds,
+        &system_program.to_account_info(),
+    )?;
+
+    Ok(())
+}
+
+/// Accounts for the [`close_expired_trade_state` handler](fn.close_expired_trade_state.html).
+#[derive(Accounts)]
+pub struct CloseExpiredTradeState<'info> {
+    /// CHECK: Validated in close_expired_trade_state.
+    /// Expired trade state PDA account of a listing or bid.
+    #[account(mut)]
+    pub trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the payer recorded in the trade state.
+    /// Account that paid the trade state rent and receives it back.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+/// Close an expired trade state and return its rent to the account that paid for it. Anyone can call this.
+///
+/// A bid is released from the escrow reservation of the buyer passed as the only remaining account.
+///
+/// Only the owner can revoke a delegate, so the program delegate of a listing is revoked when the seller signs and
+/// passes `[seller, token_account, token_program]` as remaining accounts, followed by the `CancelRemainingAccounts`
+/// of a programmable NFT. Otherwise the delegate is left in place, but it can no longer be used because
+/// `execute_sale` requires a live seller trade state.
+pub fn close_expired_trade_state<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseExpiredTradeState<'info>>,
+) -> Result<()> {
+    let trade_state = &ctx.accounts.trade_state;
+    let payer = &ctx.accounts.payer;
+    let clock = Clock::get()?;
+
+    if *trade_state.owner != id() {
+        return Err(AuctionHouseError::InvalidTradeState.into());
+    }
+
+    let ts_info = trade_state.to_account_info();
+    let (expires_at, recorded_payer) =
+        get_trade_state_expiry(&ts_info)?.ok_or(AuctionHouseError::TradeStateHasNoExpiry)?;
+    assert_keys_equal(recorded_payer, payer.key())?;
+
+    if clock.unix_timestamp < expires_at {
+        return Err(AuctionHouseError::TradeStateNotExpired.into());
+    }
+
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    match next_account_info(remaining_accounts) {
+        // Escrow reservations are the only program accounts passed here.
+        Ok(escrow_reservation) if *escrow_reservation.owner == id() => {
+            release_bid(escrow_reservation, &trade_state.key())?;
+        }
+        Ok(seller) => {
+            let token_account = next_account_info(remaining_accounts)?;
+            let token_program = next_account_info(remaining_accounts)?;
+            assert_keys_equal(token_program.key(), spl_token::id())?;
+
+            let (program_as_signer, _) =
+                Pubkey::find_program_address(&[PREFIX.as_bytes(), SIGNER.as_bytes()], &id());
+            let token_account_data: spl_token::state::Account = assert_initialized(token_account)?;
+            // Programmable NFTs stay frozen and are thawed by Token Metadata when revoking.
+            let is_programmable = remaining_accounts.len() > 0;
+            if seller.is_signer
+                && (is_programmable || !token_account_data.is_frozen())
+                && token_account_data.owner == seller.key()
+                && token_account_data.delegate == Some(program_as_signer).into()
+            {
+                revoke_sale_delegate(
+                    seller,
+                    seller,
+                    token_account,
+                    token_program,
+                    remaining_accounts,
+                )?;
+            }
+        }
+        Err(_) => {}
+    }
+
+    let curr_lamp = ts_info.lamports();
+    **ts_info.lamports.borrow_mut() = 0;
+
+    **payer.lamports.borrow_mut() = payer
+        .lamports()
+        .checked_add(curr_lamp)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    #[allow(clippy::explicit_auto_deref)]
+    let data_len = ts_info.data_len();
+    sol_memset(*ts_info.try_borrow_mut_data()?, 0, data_len);
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+//! Fee tiers lower the Auction House fee takers pay when they hold a membership token: a minimum balance of a mint,
+//! or a token of a verified collection. Auction Houses with fee tiers expect the holder token account of the taker,
+//! followed by the metadata of the held mint, in the remaining accounts of sales at the position listed in the
+//! [crate docs](crate#remaining-accounts). An empty holder account claims no tier, and a holder account that
+//! qualifies for no tier fails the sale.
+
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, migrate::assert_migrated, utils::*, AuctionHouse,
+};
+
+/// What a taker holds to qualify for a fee tier.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeeTierRequirement {
+    /// At least `min_balance` tokens of the tier mint.
+    MinimumBalance { min_balance: u64 },
+    /// A token of the verified collection whose collection mint is the tier mint.
+    VerifiedCollection,
+}
+
+/// Auction House fee charged on sales taken by holders of the tier mint.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FeeTier {
+    /// Mint held, or collection mint of the token held.
+    pub mint: Pubkey,
+    pub requirement: FeeTierRequirement,
+    /// Auction House fee of the tier, in basis points. Caps the fee the taker pays on the sale: the seller fee plus
+    /// the taker fee for a selling taker, the taker fee for a buying one.
+    pub fee_basis_points: u16,
+}
+
+/// Token account a taker holds its membership token in, and the metadata of its mint.
+pub struct FeeTierHolder<'c, 'info> {
+    pub token_account: &'c AccountInfo<'info>,
+    pub metadata: &'c AccountInfo<'info>,
+}
+
+/// Check that `fee_tiers` fit in the Auction House and charge valid fees.
+pub fn assert_valid_fee_tiers(fee_tiers: &[FeeTier]) -> Result<()> {
+    if fee_tiers.len() > MAX_FEE_TIERS {
+        return Err(AuctionHouseError::TooManyFeeTiers.into());
+    }
+    if fee_tiers.iter().any(|tier| tier.fee_basis_points > 10000) {
+        return Err(AuctionHouseError::InvalidBasisPoints.into());
+    }
+
+    Ok(())
+}
+
+/// Split the fee tier holder accounts of the taker off the end of `remaining_accounts` when the Auction House has
+/// fee tiers. Returns `None` for an empty holder account, and the remaining accounts left for the handler.
+pub fn split_fee_tier_holder<'c, 'info>(
+    auction_house: &AuctionHouse,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<(Option<FeeTierHolder<'c, 'info>>, &'c [AccountInfo<'info>])> {
+    if auction_house.fee_tiers.is_empty() {
+        return Ok((None, remaining_accounts));
+    }
+
+    match remaining_accounts {
+        [remaining_accounts @ .., token_account, metadata] => {
+            let holder = if token_account.data_is_empty() {
+                None
+            } else {
+                Some(FeeTierHolder {
+                    token_account,
+                    metadata,
+                })
+            };
+            Ok((holder, remaining_accounts))
+        }
+        _ => Err(AuctionHouseError::InvalidFeeTierHolder.into()),
+    }
+}
+
+/// Fee tier in basis points of the taker of a sale: the lowest fee of the tiers `holder` qualifies for, or `None`
+/// without a holder. The holder token account must be a token account of `taker`, so that takers cannot claim the
+/// tier of a token someone else holds.
+pub fn fee_tier_basis_points(
+    auction_house: &AuctionHouse,
+    taker: Option<&Pubkey>,
+    holder: Option<&FeeTierHolder>,
+) -> Result<Option<u16>> {
+    let holder = match holder {
+        Some(holder) => holder,
+        None => return Ok(None),
+    };
+    let taker = taker.ok_or(AuctionHouseError::InvalidFeeTierHolder)?;
+
+    if *holder.token_account.owner != spl_token::id()
+        && *holder.token_account.owner != spl_token_2022::id()
+    {
+        return Err(AuctionHouseError::InvalidFeeTierHolder.into());
+    }
+    let token_account = get_treasury_token_account(holder.token_account)?;
+    if token_account.owner != *taker {
+        return Err(AuctionHouseError::InvalidFeeTierHolder.into());
+    }
+
+    let has_collection_tier = auction_house
+        .fee_tiers
+        .iter()
+        .any(|tier| tier.requirement == FeeTierRequirement::VerifiedCollection);
+    let collection = if has_collection_tier && token_account.amount > 0 {
+        verified_collection(holder.metadata, &token_account.mint)?
+    } else {
+        None
+    };
+
+    auction_house
+        .fee_tiers
+        .iter()
+        .filter(|tier| match tier.requirement {
+            FeeTierRequirement::MinimumBalance { min_balance } => {
+                token_account.mint == tier.mint && token_account.amount >= min_balance
+            }
+            FeeTierRequirement::VerifiedCollection => collection == Some(tier.mint),
+        })
+        .map(|tier| tier.fee_basis_points)
+        .min()
+        .map(Some)
+        .ok_or_else(|| AuctionHouseError::InvalidFeeTierHolder.into())
+}
+
+/// Verified collection of `mint`, read from its metadata account. Mints without metadata have none.
+fn verified_collection(metadata: &AccountInfo, mint: &Pubkey) -> Result<Option<Pubkey>> {
+    assert_derivation(
+        &mpl_token_metadata::id(),
+        metadata,
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            mint.as_ref(),
+        ],
+    )?;
+    if metadata.data_is_empty() {
+        return Ok(None);
+    }
+    assert_owned_by(metadata, &mpl_token_metadata::id())?;
+
+    let metadata = Metadata::from_account_info(metadata)?;
+    Ok(metadata
+        .collection
+        .filter(|collection| collection.verified)
+        .map(|collection| collection.key))
+}
+
+/// Accounts for the [`set_fee_tiers` handler](auction_house/fn.set_fee_tiers.html).
+#[derive(Accounts)]
+pub struct SetFeeTiers<'info> {
+    /// Auction House authority.
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+}
+
+/// Replace the fee tiers of the Auction House, once it is migrated. Governed Auction Houses change their fee tiers
+/// through a pending change instead.
+pub fn set_fee_tiers(ctx: Context<SetFeeTiers>, fee_tiers: Vec<FeeTier>) -> Result<()> {
+    let auction_house = &mut ctx.accounts.auction_house;
+    if auction_house.has_governance {
+        return Err(AuctionHouseError::GovernanceRequired.into());
+    }
+    assert_migrated(auction_house)?;
+
+    assert_valid_fee_tiers(&fee_tiers)?;
+    auction_house.fee_tiers = fee_tiers;
+
+    Ok(())
+}
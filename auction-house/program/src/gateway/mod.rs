@@ -0,0 +1,100 @@
+//! Gated Auction Houses only let wallets holding a valid gateway token of their gatekeeper network list, bid, offer
+//! swaps and accept bids or swap offers. They pass the gateway token of the wallet, followed by the gateway program
+//! and the network expire feature when the token expires on use, in the position listed in the
+//! [crate docs](crate#remaining-accounts).
+
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+use solana_gateway::Gateway;
+
+use crate::{constants::*, errors::AuctionHouseError, migrate::assert_migrated, AuctionHouse};
+
+/// Program id of the identity.com gateway program issuing gateway tokens.
+pub const GATEWAY_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("gatem74V238djXdzWnJf94Wo1DcnuGkfijbf3AuBhfs");
+
+/// Configurations options for the gatekeeper.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GatekeeperConfig {
+    /// The network for the gateway token required
+    pub gatekeeper_network: Pubkey,
+    /// Whether or not the token should expire after listing or bidding.
+    /// The gatekeeper network must support this if true.
+    pub expire_on_use: bool,
+}
+
+/// Check the gateway token of `wallet` against the gatekeeper network of the Auction House, if any, and expire it
+/// when the network requires it. Returns the remaining accounts without the gateway accounts.
+pub fn assert_gateway_token<'c, 'info>(
+    auction_house: &Account<'info, AuctionHouse>,
+    wallet: &AccountInfo<'info>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<&'c [AccountInfo<'info>]> {
+    let gatekeeper = match auction_house.gatekeeper {
+        Some(gatekeeper) => gatekeeper,
+        None => return Ok(remaining_accounts),
+    };
+
+    let gateway_accounts = if gatekeeper.expire_on_use { 3 } else { 1 };
+    if remaining_accounts.len() < gateway_accounts {
+        return Err(AuctionHouseError::InvalidGatewayToken.into());
+    }
+    let (remaining_accounts, gateway_accounts) =
+        remaining_accounts.split_at(remaining_accounts.len() - gateway_accounts);
+
+    let gateway_token = &gateway_accounts[0];
+    Gateway::verify_gateway_token_account_info(
+        gateway_token,
+        wallet.key,
+        &gatekeeper.gatekeeper_network,
+        None,
+    )
+    .map_err(|_| AuctionHouseError::InvalidGatewayToken)?;
+
+    if gatekeeper.expire_on_use {
+        Gateway::verify_and_expire_token(
+            gateway_accounts[1].clone(),
+            gateway_token.clone(),
+            wallet.clone(),
+            &gatekeeper.gatekeeper_network,
+            gateway_accounts[2].clone(),
+        )
+        .map_err(|_| AuctionHouseError::InvalidGatewayToken)?;
+    }
+
+    Ok(remaining_accounts)
+}
+
+/// Accounts for the [`set_gatekeeper` handler](auction_house/fn.set_gatekeeper.html).
+#[derive(Accounts)]
+pub struct SetGatekeeper<'info> {
+    /// Auction House authority.
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+}
+
+/// Require a gateway token of `gatekeeper` to list and bid on the migrated Auction House, or lift the requirement.
+pub fn set_gatekeeper(
+    ctx: Context<SetGatekeeper>,
+    gatekeeper: Option<GatekeeperConfig>,
+) -> Result<()> {
+    let auction_house = &mut ctx.accounts.auction_house;
+    if auction_house.has_governance {
+        return Err(AuctionHouseError::GovernanceRequired.into());
+    }
+    assert_migrated(auction_house)?;
+    auction_house.gatekeeper = gatekeeper;
+
+    Ok(())
+}
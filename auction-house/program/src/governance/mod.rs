@@ -0,0 +1,666 @@
+//! Governed Auction Houses change their fees, fee tiers, authority and withdrawal destinations, and withdraw from their treasury
+//! above a threshold, through pending changes that need the approval of M of N governance signers and can only be
+//! applied once a delay has passed since they were proposed.
+//! The authority can withdraw up to the threshold per withdrawal period on its own, `withdraw_from_treasury` passing
+//! the writable governance account as its last remaining account to keep the running total. `withdraw_from_fee` does the
+//! same against the fee withdrawal threshold, in lamports, and can not go above it. `update_auction_house`
+//! can then only change the sign off, sale price and escrow reservation settings of the Auction House, and the
+//! auctioneers, gatekeeper, royalty policy and shared escrow flag are fixed while governance is enabled.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    errors::AuctionHouseError,
+    events::TreasuryWithdrawalEvent,
+    fee_tier::{assert_valid_fee_tiers, FeeTier},
+    migrate::assert_migrated,
+    pda::find_governance_address,
+    utils::*,
+    AuctionHouse,
+};
+
+pub const MAX_GOVERNANCE_SIGNERS: usize = 10;
+
+/// Seconds over which treasury and fee account withdrawals of the authority add up against their thresholds.
+pub const WITHDRAWAL_PERIOD: i64 = 24 * 60 * 60;
+
+pub const GOVERNANCE_SIZE: usize = 8 + // key
+32 + // auction_house
+4 + 32 * MAX_GOVERNANCE_SIGNERS + // signers
+1 + // threshold
+8 + // delay
+8 + // withdrawal_threshold
+8 + // withdrawal_period_start
+8 + // withdrawn_in_period
+8 + // fee_withdrawal_threshold
+8 + // fee_withdrawn_in_period
+8 + // proposal_count
+1; // bump
+
+/// Size of the largest change, `GovernedChange::Governance` or `GovernedChange::FeeTiers`.
+const GOVERNED_CHANGE_SIZE: usize = {
+    let governance = 1 + 4 + 32 * MAX_GOVERNANCE_SIGNERS + 1 + 8 + 8 + 8;
+    let fee_tiers = 1 + 4 + MAX_FEE_TIERS * FEE_TIER_SIZE;
+    if governance > fee_tiers {
+        governance
+    } else {
+        fee_tiers
+    }
+};
+
+pub const PENDING_CHANGE_SIZE: usize = 8 + // key
+32 + // auction_house
+32 + // proposer
+8 + // nonce
+GOVERNED_CHANGE_SIZE + // change
+4 + 32 * MAX_GOVERNANCE_SIGNERS + // approvals
+8 + // executable_at
+1; // bump
+
+/// Signers of an Auction House and the delay and approvals its sensitive changes need.
+#[account]
+pub struct Governance {
+    pub auction_house: Pubkey,
+    pub signers: Vec<Pubkey>,
+    /// Approvals of `signers` a change needs before it can be applied.
+    pub threshold: u8,
+    /// Seconds between the proposal of a change and the earliest time it can be applied.
+    pub delay: i64,
+    /// Most the authority can withdraw from the treasury per withdrawal period without a pending change.
+    pub withdrawal_threshold: u64,
+    /// Start of the current withdrawal period.
+    pub withdrawal_period_start: i64,
+    /// Withdrawn by the authority without a pending change since the start of the withdrawal period.
+    pub withdrawn_in_period: u64,
+    /// Most lamports the authority can withdraw from the fee account per withdrawal period.
+    pub fee_withdrawal_threshold: u64,
+    /// Lamports withdrawn from the fee account by the authority since the start of the withdrawal period.
+    pub fee_withdrawn_in_period: u64,
+    /// Number of changes proposed so far, seeding the address of the next pending change.
+    pub proposal_count: u64,
+    pub bump: u8,
+}
+
+impl Governance {
+    /// Check that `signer` is one of the governance signers.
+    pub fn assert_signer(&self, signer: &Pubkey) -> Result<()> {
+        if !self.signers.contains(signer) {
+            return Err(AuctionHouseError::NotGovernanceSigner.into());
+        }
+
+        Ok(())
+    }
+
+    /// Start a new withdrawal period once the current one has passed.
+    fn roll_withdrawal_period(&mut self, now: i64) {
+        if now
+            >= self
+                .withdrawal_period_start
+                .saturating_add(WITHDRAWAL_PERIOD)
+        {
+            self.withdrawal_period_start = now;
+            self.withdrawn_in_period = 0;
+            self.fee_withdrawn_in_period = 0;
+        }
+    }
+
+    /// Add a treasury withdrawal of the authority to the running total of the withdrawal period, starting a new
+    /// period once the current one has passed. Fails when the total would exceed the withdrawal threshold.
+    pub fn record_withdrawal(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.roll_withdrawal_period(now);
+
+        let withdrawn_in_period = self
+            .withdrawn_in_period
+            .checked_add(amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        if withdrawn_in_period > self.withdrawal_threshold {
+            return Err(AuctionHouseError::GovernanceRequired.into());
+        }
+        self.withdrawn_in_period = withdrawn_in_period;
+
+        Ok(())
+    }
+
+    /// Add a fee account withdrawal of the authority to the running total of the withdrawal period, starting a new
+    /// period once the current one has passed. Fails when the total would exceed the fee withdrawal threshold.
+    pub fn record_fee_withdrawal(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.roll_withdrawal_period(now);
+
+        let fee_withdrawn_in_period = self
+            .fee_withdrawn_in_period
+            .checked_add(amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        if fee_withdrawn_in_period > self.fee_withdrawal_threshold {
+            return Err(AuctionHouseError::GovernanceRequired.into());
+        }
+        self.fee_withdrawn_in_period = fee_withdrawn_in_period;
+
+        Ok(())
+    }
+
+    fn configure(
+        &mut self,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+        delay: i64,
+        withdrawal_threshold: u64,
+        fee_withdrawal_threshold: u64,
+    ) -> Result<()> {
+        let mut unique_signers = signers.clone();
+        unique_signers.sort();
+        unique_signers.dedup();
+
+        if signers.is_empty()
+            || signers.len() > MAX_GOVERNANCE_SIGNERS
+            || unique_signers.len() != signers.len()
+            || threshold == 0
+            || threshold as usize > signers.len()
+            || delay < 0
+        {
+            return Err(AuctionHouseError::InvalidGovernance.into());
+        }
+
+        self.signers = signers;
+        self.threshold = threshold;
+        self.delay = delay;
+        self.withdrawal_threshold = withdrawal_threshold;
+        self.fee_withdrawal_threshold = fee_withdrawal_threshold;
+
+        Ok(())
+    }
+}
+
+/// A sensitive change to an Auction House.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum GovernedChange {
+    /// Set the fee basis points, `None` keeping the current value.
+    Fees {
+        seller_fee_basis_points: Option<u16>,
+        maker_fee_basis_points: Option<u16>,
+        taker_fee_basis_points: Option<u16>,
+        referral_fee_basis_points: Option<u16>,
+    },
+    Authority {
+        new_authority: Pubkey,
+    },
+    /// Replace the fee tiers.
+    FeeTiers {
+        fee_tiers: Vec<FeeTier>,
+    },
+    FeeWithdrawalDestination {
+        destination: Pubkey,
+    },
+    /// `destination` is `owner` itself for native SOL, and a treasury mint token account of `owner` otherwise.
+    TreasuryWithdrawalDestination {
+        destination: Pubkey,
+        owner: Pubkey,
+    },
+    WithdrawFromTreasury {
+        amount: u64,
+    },
+    Governance {
+        signers: Vec<Pubkey>,
+        threshold: u8,
+        delay: i64,
+        withdrawal_threshold: u64,
+        fee_withdrawal_threshold: u64,
+    },
+}
+
+/// A proposed change waiting for approvals and its delay.
+#[account]
+pub struct PendingChange {
+    pub auction_house: Pubkey,
+    /// Governance signer who proposed the change and paid for this account.
+    pub proposer: Pubkey,
+    pub nonce: u64,
+    pub change: GovernedChange,
+    pub approvals: Vec<Pubkey>,
+    pub executable_at: i64,
+    pub bump: u8,
+}
+
+/// Split the governance account off the end of `remaining_accounts` when the Auction House is governed.
+/// Returns the governance account and the remaining accounts left for the handler.
+pub fn split_governance<'c, 'info>(
+    auction_house: &Account<'info, AuctionHouse>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<(Option<&'c AccountInfo<'info>>, &'c [AccountInfo<'info>])> {
+    if !auction_house.has_governance {
+        return Ok((None, remaining_accounts));
+    }
+
+    let (governance_info, remaining_accounts) = remaining_accounts
+        .split_last()
+        .ok_or(AuctionHouseError::InvalidGovernance)?;
+    let (expected, _) = find_governance_address(&auction_house.key());
+    if governance_info.key() != expected
+        || *governance_info.owner != crate::id()
+        || !governance_info.is_writable
+    {
+        return Err(AuctionHouseError::InvalidGovernance.into());
+    }
+
+    Ok((Some(governance_info), remaining_accounts))
+}
+
+/// Record a treasury withdrawal of the authority in the governance account of the Auction House.
+pub fn record_treasury_withdrawal(governance_info: &AccountInfo, amount: u64) -> Result<()> {
+    let mut governance = read_governance(governance_info)?;
+    governance.record_withdrawal(amount, Clock::get()?.unix_timestamp)?;
+
+    let mut data = governance_info.try_borrow_mut_data()?;
+    governance.try_serialize(&mut *data)
+}
+
+/// Record a fee account withdrawal of the authority in the governance account of the Auction House.
+pub fn record_fee_withdrawal(governance_info: &AccountInfo, amount: u64) -> Result<()> {
+    let mut governance = read_governance(governance_info)?;
+    governance.record_fee_withdrawal(amount, Clock::get()?.unix_timestamp)?;
+
+    let mut data = governance_info.try_borrow_mut_data()?;
+    governance.try_serialize(&mut *data)
+}
+
+fn read_governance(governance_info: &AccountInfo) -> Result<Governance> {
+    let data = governance_info.try_borrow_data()?;
+    Governance::try_deserialize(&mut data.as_ref())
+}
+
+/// Accounts for the [`enable_governance` handler](auction_house/fn.enable_governance.html).
+#[derive(Accounts)]
+pub struct EnableGovernance<'info> {
+    /// Auction House authority. Pays for the governance account.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Governance PDA account.
+    #[account(
+        init,
+        payer = authority,
+        space = GOVERNANCE_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            GOVERNANCE.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump
+    )]
+    pub governance: Box<Account<'info, Governance>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the [`propose_change` handler](auction_house/fn.propose_change.html).
+#[derive(Accounts)]
+pub struct ProposeChange<'info> {
+    /// Governance signer proposing the change. Pays for the pending change account.
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Governance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            GOVERNANCE.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump=governance.bump,
+        has_one=auction_house
+    )]
+    pub governance: Box<Account<'info, Governance>>,
+
+    /// Pending change PDA account, seeded by the proposal count of the governance.
+    #[account(
+        init,
+        payer = proposer,
+        space = PENDING_CHANGE_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            GOVERNANCE.as_bytes(),
+            auction_house.key().as_ref(),
+            &governance.proposal_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the [`approve_change` handler](auction_house/fn.approve_change.html)
+/// and the [`cancel_change` handler](auction_house/fn.cancel_change.html).
+#[derive(Accounts)]
+pub struct ReviewChange<'info> {
+    /// Governance signer.
+    pub signer: Signer<'info>,
+
+    /// CHECK: Checked against the pending change.
+    /// Proposer of the change, refunded the rent of the pending change when it is cancelled.
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Governance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            GOVERNANCE.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump=governance.bump,
+        has_one=auction_house
+    )]
+    pub governance: Box<Account<'info, Governance>>,
+
+    /// Pending change PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            GOVERNANCE.as_bytes(),
+            auction_house.key().as_ref(),
+            &pending_change.nonce.to_le_bytes()
+        ],
+        bump=pending_change.bump,
+        has_one=auction_house,
+        has_one=proposer
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+}
+
+/// Accounts for the [`apply_change` handler](auction_house/fn.apply_change.html).
+#[derive(Accounts)]
+pub struct ApplyChange<'info> {
+    /// CHECK: Checked against the pending change.
+    /// Proposer of the change, refunded the rent of the pending change.
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the token program in the handler.
+    /// Treasury mint account, either native SOL mint or a SPL token mint.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in the handler against the current destination for withdrawals, or the proposed one.
+    /// SOL or SPL token account to receive Auction House fees.
+    #[account(mut)]
+    pub treasury_withdrawal_destination: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House treasury PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            treasury_mint.key().as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Governance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            GOVERNANCE.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump=governance.bump,
+        has_one=auction_house
+    )]
+    pub governance: Box<Account<'info, Governance>>,
+
+    /// Pending change PDA account.
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [
+            PREFIX.as_bytes(),
+            GOVERNANCE.as_bytes(),
+            auction_house.key().as_ref(),
+            &pending_change.nonce.to_le_bytes()
+        ],
+        bump=pending_change.bump,
+        has_one=auction_house,
+        has_one=proposer
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    /// CHECK: Validated against the treasury mint in the handler.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    pub token_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Put the Auction House under the governance of `signers`. Its authority can not make sensitive changes alone
+/// from then on.
+pub fn enable_governance(
+    ctx: Context<EnableGovernance>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+    delay: i64,
+    withdrawal_threshold: u64,
+    fee_withdrawal_threshold: u64,
+) -> Result<()> {
+    let auction_house = &mut ctx.accounts.auction_house;
+    let governance = &mut ctx.accounts.governance;
+
+    governance.auction_house = auction_house.key();
+    governance.configure(
+        signers,
+        threshold,
+        delay,
+        withdrawal_threshold,
+        fee_withdrawal_threshold,
+    )?;
+    governance.proposal_count = 0;
+    governance.bump = *ctx
+        .bumps
+        .get("governance")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    auction_house.has_governance = true;
+
+    Ok(())
+}
+
+/// Propose `change`, approved by its proposer. It can be applied once it has enough approvals and the delay of the
+/// governance has passed.
+pub fn propose_change(ctx: Context<ProposeChange>, change: GovernedChange) -> Result<()> {
+    let proposer = &ctx.accounts.proposer;
+    let governance = &mut ctx.accounts.governance;
+    let pending_change = &mut ctx.accounts.pending_change;
+
+    governance.assert_signer(&proposer.key())?;
+
+    pending_change.auction_house = governance.auction_house;
+    pending_change.proposer = proposer.key();
+    pending_change.nonce = governance.proposal_count;
+    pending_change.change = change;
+    pending_change.approvals = vec![proposer.key()];
+    pending_change.executable_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(governance.delay)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    pending_change.bump = *ctx
+        .bumps
+        .get("pending_change")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    governance.proposal_count = governance
+        .proposal_count
+        .checked_add(1)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    Ok(())
+}
+
+/// Approve a pending change.
+pub fn approve_change(ctx: Context<ReviewChange>) -> Result<()> {
+    let signer = &ctx.accounts.signer;
+    let governance = &ctx.accounts.governance;
+    let pending_change = &mut ctx.accounts.pending_change;
+
+    governance.assert_signer(&signer.key())?;
+    if pending_change.approvals.contains(&signer.key()) {
+        return Err(AuctionHouseError::ChangeAlreadyApproved.into());
+    }
+    pending_change.approvals.push(signer.key());
+
+    Ok(())
+}
+
+/// Drop a pending change. Any governance signer can veto a change before it is applied.
+pub fn cancel_change(ctx: Context<ReviewChange>) -> Result<()> {
+    let signer = &ctx.accounts.signer;
+    let governance = &ctx.accounts.governance;
+
+    governance.assert_signer(&signer.key())?;
+
+    ctx.accounts
+        .pending_change
+        .close(ctx.accounts.proposer.to_account_info())
+}
+
+/// Apply a pending change that has enough approvals once its delay has passed. Anyone can apply it.
+pub fn apply_change(ctx: Context<ApplyChange>) -> Result<()> {
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let treasury_withdrawal_destination = &ctx.accounts.treasury_withdrawal_destination;
+    let auction_house_treasury = &ctx.accounts.auction_house_treasury;
+    let auction_house = &mut ctx.accounts.auction_house;
+    let governance = &mut ctx.accounts.governance;
+    let pending_change = &ctx.accounts.pending_change;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    // Approvals of former signers do not count once the signers of the governance change.
+    let approvals = pending_change
+        .approvals
+        .iter()
+        .filter(|approval| governance.signers.contains(approval))
+        .count();
+    if approvals < governance.threshold as usize {
+        return Err(AuctionHouseError::ChangeNotApproved.into());
+    }
+    if Clock::get()?.unix_timestamp < pending_change.executable_at {
+        return Err(AuctionHouseError::ChangeTimelocked.into());
+    }
+
+    match pending_change.change.clone() {
+        GovernedChange::Fees {
+            seller_fee_basis_points,
+            maker_fee_basis_points,
+            taker_fee_basis_points,
+            referral_fee_basis_points,
+        } => set_fee_basis_points(
+            auction_house,
+            seller_fee_basis_points,
+            maker_fee_basis_points,
+            taker_fee_basis_points,
+            referral_fee_basis_points,
+        )?,
+        GovernedChange::Authority { new_authority } => {
+            auction_house.authority = new_authority;
+        }
+        GovernedChange::FeeTiers { fee_tiers } => {
+            assert_migrated(auction_house)?;
+            assert_valid_fee_tiers(&fee_tiers)?;
+            auction_house.fee_tiers = fee_tiers;
+        }
+        GovernedChange::FeeWithdrawalDestination { destination } => {
+            auction_house.fee_withdrawal_destination = destination;
+        }
+        GovernedChange::TreasuryWithdrawalDestination { destination, owner } => {
+            assert_keys_equal(treasury_withdrawal_destination.key(), destination)?;
+            if treasury_mint.key() == spl_token::native_mint::id() {
+                assert_keys_equal(destination, owner)?;
+            } else {
+                assert_treasury_token_program(treasury_mint, token_program)?;
+                assert_is_treasury_ata(treasury_withdrawal_destination, &owner, treasury_mint)?;
+            }
+
+            auction_house.treasury_withdrawal_destination = destination;
+        }
+        GovernedChange::WithdrawFromTreasury { amount } => {
+            assert_keys_equal(
+                treasury_withdrawal_destination.key(),
+                auction_house.treasury_withdrawal_destination,
+            )?;
+            withdraw_treasury_funds(
+                auction_house,
+                treasury_mint,
+                auction_house_treasury,
+                treasury_withdrawal_destination,
+                token_program,
+                &system_program.to_account_info(),
+                amount,
+            )?;
+
+            emit!(TreasuryWithdrawalEvent {
+                auction_house: auction_house.key(),
+                destination: treasury_withdrawal_destination.key(),
+                amount,
+            });
+        }
+        GovernedChange::Governance {
+            signers,
+            threshold,
+            delay,
+            withdrawal_threshold,
+            fee_withdrawal_threshold,
+        } => governance.configure(
+            signers,
+            threshold,
+            delay,
+            withdrawal_threshold,
+            fee_withdrawal_threshold,
+        )?,
+    }
+
+    Ok(())
+}
@@ -2,28 +2,96 @@
 //! AuctionHouse is a protocol for marketplaces to implement a decentralized sales contract. It is simple, fast and very cheap. AuctionHouse is a Solana program available on Mainnet Beta and Devnet. Anyone can create an AuctionHouse and accept any SPL token they wish.
 //!
 //! Full docs can be found [here](https://docs.metaplex.com/auction-house/definition).
+//!
+//! ## Remaining accounts
+//!
+//! Optional accounts are passed as remaining accounts, in the order below. Handlers read the leading accounts from
+//! the front and split the trailing ones off the end, so a trailing account is only passed when the Auction House
+//! uses it: the escrow reservation when it tracks bids, the collection list when it is curated, the stats account
+//! when it has one, the royalty election when it does not enforce royalties, the gateway accounts when it is gated,
+//! the fee tier holder when it has fee tiers and the shared escrow when it accepts them.
+//!
+//! - `sell`, `auctioneer_sell`: the [`SellRemainingAccounts`] of a programmable NFT, collection list, gateway accounts.
+//! - `batch_sell`: the accounts of each order, collection list, gateway accounts.
+//! - `sell_dutch`: gateway accounts.
+//! - `sell_bundle`: the token account and metadata of each item, collection list, gateway accounts.
+//! - `make_swap_offer`: collection list, gateway accounts.
+//! - `buy`, `public_buy` and their auctioneer variants: escrow reservation, collection list, gateway accounts,
+//!   shared escrow.
+//! - `collection_buy`, `trait_buy`: escrow reservation, gateway accounts, shared escrow.
+//! - `execute_sale`, `execute_partial_sale`, `execute_collection_sale`, `execute_trait_sale`, `execute_dutch_sale`
+//!   and the auctioneer variants: the Token-2022 program of a Token-2022 treasury mint, each creator followed by its
+//!   treasury mint ATA, the [`ExecuteSaleRemainingAccounts`] of a programmable NFT, the optional referrer followed by
+//!   its treasury mint ATA, royalty election, stats, escrow reservation, collection list, fee tier holder, shared
+//!   escrow.
+//! - `accept_bid`: the [`SellRemainingAccounts`] of a programmable NFT, the accounts of `execute_sale`, gateway
+//!   accounts of the seller.
+//! - `execute_bundle_sale`: the Token-2022 program of a Token-2022 treasury mint, for each item its token account,
+//!   mint, metadata, buyer receipt token account and creators, the optional referrer followed by its treasury mint
+//!   ATA, stats, collection list, fee tier holder, shared escrow.
+//! - `accept_swap_offer`: the Token-2022 program of a Token-2022 treasury mint, the creators of the requested token,
+//!   the optional referrer followed by its treasury mint ATA, stats, collection list, fee tier holder, shared escrow
+//!   of the offerer, gateway accounts of the taker.
+//! - `cancel`, `auctioneer_cancel`: the [`CancelRemainingAccounts`] of a programmable NFT listing, or the escrow
+//!   reservation of a bid.
+//! - `cancel_collection_buy`, `cancel_trait_buy`, `withdraw`, `auctioneer_withdraw`, `close_escrow_account`: escrow
+//!   reservation.
+//! - `withdraw_from_fee`, `withdraw_from_treasury`: governance account.
+//! - `print_purchase_receipt`: stats.
+//!
+//! The gateway accounts are the gateway token of the wallet, followed by the gateway program and the network expire
+//! feature when the token expires on use. The fee tier holder is the token account of the taker followed by the
+//! metadata of the held mint, and the shared escrow is the shared escrow of the buyer followed by its approval of
+//! the Auction House.
+//!
+//! ## Fees
+//!
+//! The seller pays the seller fee of the Auction House out of the sale price. A buyer or seller that signs the sale
+//! takes the other's resting order and pays the taker fee, while the other side pays the maker fee. A sale signed
+//! only by the authority has no taker and charges both sides the maker fee. Each bid escrows its price plus the
+//! larger of the maker and taker fee, so a bid covers its sale either way unless the fees are raised after it was
+//! placed, in which case the buyer has to deposit the difference.
 
 #![allow(clippy::result_large_err)]
 
 pub mod auctioneer;
 pub mod bid;
+pub mod bundle;
 pub mod cancel;
+pub mod client;
 pub mod constants;
+pub mod curation;
 pub mod deposit;
+pub mod dutch;
 pub mod errors;
+pub mod events;
 pub mod execute_sale;
+pub mod expiry;
+pub mod fee_tier;
+pub mod gateway;
+pub mod governance;
+pub mod merkle_proof;
+pub mod migrate;
 pub mod pda;
+pub mod private_listing;
 pub mod receipt;
+pub mod reservation;
+pub mod royalty;
 pub mod sell;
+pub mod shared_escrow;
 pub mod state;
+pub mod stats;
+pub mod swap;
 pub mod utils;
 pub mod withdraw;
 
 pub use state::*;
 
 use crate::{
-    auctioneer::*, bid::*, cancel::*, constants::*, deposit::*, errors::AuctionHouseError,
-    execute_sale::*, receipt::*, sell::*, utils::*, withdraw::*,
+    auctioneer::*, bid::*, bundle::*, cancel::*, constants::*, curation::*, deposit::*, dutch::*,
+    errors::AuctionHouseError, events::*, execute_sale::*, expiry::*, fee_tier::*, gateway::*,
+    governance::*, migrate::*, private_listing::*, receipt::*, reservation::*, royalty::*, sell::*,
+    shared_escrow::*, stats::*, swap::*, utils::*, withdraw::*,
 };
 
 use anchor_lang::{
@@ -53,6 +121,12 @@ pub mod auction_house {
         let auction_house = &ctx.accounts.auction_house;
         let system_program = &ctx.accounts.system_program;
 
+        // Governed Auction Houses withdraw at most their fee withdrawal threshold per period.
+        let (governance, _) = split_governance(auction_house, ctx.remaining_accounts)?;
+        if let Some(governance) = governance {
+            record_fee_withdrawal(governance, amount)?;
+        }
+
         let auction_house_key = auction_house.key();
         let seeds = [
             PREFIX.as_bytes(),
@@ -75,6 +149,12 @@ pub mod auction_house {
             &[&seeds],
         )?;
 
+        emit!(FeeWithdrawalEvent {
+            auction_house: auction_house.key(),
+            destination: fee_withdrawal_destination.key(),
+            amount,
+        });
+
         Ok(())
     }
 
@@ -90,64 +170,42 @@ pub mod auction_house {
         let token_program = &ctx.accounts.token_program;
         let system_program = &ctx.accounts.system_program;
 
-        let is_native = treasury_mint.key() == spl_token::native_mint::id();
-        let auction_house_seeds = [
-            PREFIX.as_bytes(),
-            auction_house.creator.as_ref(),
-            auction_house.treasury_mint.as_ref(),
-            &[auction_house.bump],
-        ];
-
-        let ah_key = auction_house.key();
-        let auction_house_treasury_seeds = [
-            PREFIX.as_bytes(),
-            ah_key.as_ref(),
-            TREASURY.as_bytes(),
-            &[auction_house.treasury_bump],
-        ];
-        if !is_native {
-            invoke_signed(
-                &spl_token::instruction::transfer(
-                    token_program.key,
-                    &auction_house_treasury.key(),
-                    &treasury_withdrawal_destination.key(),
-                    &auction_house.key(),
-                    &[],
-                    amount,
-                )?,
-                &[
-                    auction_house_treasury.to_account_info(),
-                    treasury_withdrawal_destination.to_account_info(),
-                    token_program.to_account_info(),
-                    auction_house.to_account_info(),
-                ],
-                &[&auction_house_seeds],
-            )?;
-        } else {
-            invoke_signed(
-                &system_instruction::transfer(
-                    &auction_house_treasury.key(),
-                    &treasury_withdrawal_destination.key(),
-                    amount,
-                ),
-                &[
-                    auction_house_treasury.to_account_info(),
-                    treasury_withdrawal_destination.to_account_info(),
-                    system_program.to_account_info(),
-                ],
-                &[&auction_house_treasury_seeds],
-            )?;
+        // Governed Auction Houses withdraw more than their threshold per period through a pending change.
+        let (governance, _) = split_governance(auction_house, ctx.remaining_accounts)?;
+        if let Some(governance) = governance {
+            record_treasury_withdrawal(governance, amount)?;
         }
 
+        withdraw_treasury_funds(
+            auction_house,
+            treasury_mint,
+            auction_house_treasury,
+            treasury_withdrawal_destination,
+            token_program,
+            &system_program.to_account_info(),
+            amount,
+        )?;
+
+        emit!(TreasuryWithdrawalEvent {
+            auction_house: auction_house.key(),
+            destination: treasury_withdrawal_destination.key(),
+            amount,
+        });
+
         Ok(())
     }
 
     /// Update Auction House values such as seller fee basis points, update authority, treasury account, etc.
+    #[allow(clippy::too_many_arguments)]
     pub fn update_auction_house<'info>(
         ctx: Context<'_, '_, '_, 'info, UpdateAuctionHouse<'info>>,
         seller_fee_basis_points: Option<u16>,
         requires_sign_off: Option<bool>,
         can_change_sale_price: Option<bool>,
+        maker_fee_basis_points: Option<u16>,
+        taker_fee_basis_points: Option<u16>,
+        referral_fee_basis_points: Option<u16>,
+        escrow_reservation_mode: Option<EscrowReservationMode>,
     ) -> Result<()> {
         let treasury_mint = &ctx.accounts.treasury_mint;
         let payer = &ctx.accounts.payer;
@@ -162,21 +220,39 @@ pub mod auction_house {
         let ata_program = &ctx.accounts.ata_program;
         let rent = &ctx.accounts.rent;
         let is_native = treasury_mint.key() == spl_token::native_mint::id();
-
-        if let Some(sfbp) = seller_fee_basis_points {
-            if sfbp > 10000 {
-                return Err(AuctionHouseError::InvalidBasisPoints.into());
-            }
-
-            auction_house.seller_fee_basis_points = sfbp;
+        assert_treasury_token_program(treasury_mint, token_program)?;
+
+        // Governed Auction Houses change fees, authority and withdrawal destinations through a pending change.
+        if auction_house.has_governance
+            && (seller_fee_basis_points.is_some()
+                || maker_fee_basis_points.is_some()
+                || taker_fee_basis_points.is_some()
+                || referral_fee_basis_points.is_some()
+                || new_authority.key() != auction_house.authority
+                || fee_withdrawal_destination.key() != auction_house.fee_withdrawal_destination
+                || treasury_withdrawal_destination.key()
+                    != auction_house.treasury_withdrawal_destination)
+        {
+            return Err(AuctionHouseError::GovernanceRequired.into());
         }
 
+        set_fee_basis_points(
+            auction_house,
+            seller_fee_basis_points,
+            maker_fee_basis_points,
+            taker_fee_basis_points,
+            referral_fee_basis_points,
+        )?;
+
         if let Some(rqf) = requires_sign_off {
             auction_house.requires_sign_off = rqf;
         }
         if let Some(chsp) = can_change_sale_price {
             auction_house.can_change_sale_price = chsp;
         }
+        if let Some(erm) = escrow_reservation_mode {
+            auction_house.escrow_reservation_mode = erm;
+        }
 
         auction_house.authority = new_authority.key();
         auction_house.treasury_withdrawal_destination = treasury_withdrawal_destination.key();
@@ -197,10 +273,10 @@ pub mod auction_house {
                 )?;
             }
 
-            assert_is_ata(
+            assert_is_treasury_ata(
                 &treasury_withdrawal_destination.to_account_info(),
                 &treasury_withdrawal_destination_owner.key(),
-                &treasury_mint.key(),
+                treasury_mint,
             )?;
         } else {
             assert_keys_equal(
@@ -276,8 +352,10 @@ pub mod auction_house {
         auction_house.auction_house_treasury = auction_house_treasury.key();
         auction_house.treasury_withdrawal_destination = treasury_withdrawal_destination.key();
         auction_house.fee_withdrawal_destination = fee_withdrawal_destination.key();
+        auction_house.version = AUCTION_HOUSE_VERSION;
 
         let is_native = treasury_mint.key() == spl_token::native_mint::id();
+        assert_treasury_token_program(treasury_mint, token_program)?;
 
         let ah_key = auction_house.key();
 
@@ -316,10 +394,10 @@ pub mod auction_house {
                 )?;
             }
 
-            assert_is_ata(
+            assert_is_treasury_ata(
                 &treasury_withdrawal_destination.to_account_info(),
                 &treasury_withdrawal_destination_owner.key(),
-                &treasury_mint.key(),
+                treasury_mint,
             )?;
         } else {
             assert_keys_equal(
@@ -398,6 +476,42 @@ pub mod auction_house {
         )
     }
 
+    /// Create a collection bid by creating a `buyer_trade_state` account keyed by the collection mint and funding the escrow with the necessary SOL or SPL token amount.
+    pub fn collection_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, CollectionBuy<'info>>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        bid::collection_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
+    /// Create a trait bid by creating a `buyer_trade_state` account keyed by the merkle root of eligible mints and funding the escrow with the necessary SOL or SPL token amount.
+    pub fn trait_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, TraitBuy<'info>>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+        root: [u8; 32],
+    ) -> Result<()> {
+        bid::trait_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
+            buyer_price,
+            token_size,
+            root,
+        )
+    }
+
     /// Cancel a bid or ask by revoking the token delegate, transferring all lamports from the trade state account to the fee payer, and setting the trade state account data to zero so it can be garbage collected.
     pub fn cancel<'info>(
         ctx: Context<'_, '_, '_, 'info, Cancel<'info>>,
@@ -416,6 +530,33 @@ pub mod auction_house {
         cancel::auctioneer_cancel(ctx, buyer_price, token_size)
     }
 
+    /// Cancel a collection bid by transferring all lamports from the trade state account to the fee payer.
+    pub fn cancel_collection_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelCollectionBuy<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        cancel::cancel_collection_bid(ctx, buyer_price, token_size)
+    }
+
+    /// Cancel a trait bid by transferring all lamports from the trade state account to the fee payer.
+    pub fn cancel_trait_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelTraitBuy<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+        root: [u8; 32],
+    ) -> Result<()> {
+        cancel::cancel_trait_bid(ctx, buyer_price, token_size, root)
+    }
+
+    /// Cancel many listings at once by revoking each token delegate and closing each seller trade state.
+    pub fn batch_cancel<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchCancel<'info>>,
+        orders: Vec<BatchOrder>,
+    ) -> Result<()> {
+        cancel::batch_cancel(ctx, orders)
+    }
+
     /// Deposit `amount` into the escrow payment account for your specific wallet.
     pub fn deposit<'info>(
         ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
@@ -452,6 +593,30 @@ pub mod auction_house {
         )
     }
 
+    /// Accept a bid in one instruction by approving the program as the token delegate and executing the sale.
+    #[allow(clippy::too_many_arguments)]
+    pub fn accept_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptBid<'info>>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+        programmable: bool,
+    ) -> Result<()> {
+        execute_sale::accept_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+            programmable,
+        )
+    }
+
     pub fn execute_partial_sale<'info>(
         ctx: Context<'_, '_, '_, 'info, ExecutePartialSale<'info>>,
         escrow_payment_bump: u8,
@@ -514,6 +679,48 @@ pub mod auction_house {
         )
     }
 
+    /// Execute a sale against a collection bid using any token verified as part of the collection.
+    pub fn execute_collection_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteCollectionSale<'info>>,
+        escrow_payment_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        execute_sale::execute_collection_sale(
+            ctx,
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
+    /// Execute a sale against a trait bid, proving the token mint is part of the bid's merkle tree.
+    pub fn execute_trait_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteSale<'info>>,
+        escrow_payment_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+        root: [u8; 32],
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        execute_sale::execute_trait_sale(
+            ctx,
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+            root,
+            proof,
+        )
+    }
+
     pub fn sell<'info>(
         ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
         trade_state_bump: u8,
@@ -548,6 +755,14 @@ pub mod auction_house {
         )
     }
 
+    /// List many tokens at once, creating a seller trade state and approving the program as delegate for each.
+    pub fn batch_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchSell<'info>>,
+        orders: Vec<BatchOrder>,
+    ) -> Result<()> {
+        sell::batch_sell(ctx, orders)
+    }
+
     /// Withdraw `amount` from the escrow payment account for your specific wallet.
     pub fn withdraw<'info>(
         ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
@@ -574,6 +789,17 @@ pub mod auction_house {
         let auction_house_key = ctx.accounts.auction_house.key();
         let wallet_key = ctx.accounts.wallet.key();
 
+        let (escrow_reservation, _) = split_escrow_reservation(
+            &ctx.accounts.auction_house,
+            &wallet_key,
+            ctx.remaining_accounts,
+        )?;
+        assert_escrow_unreserved(
+            escrow_reservation,
+            ctx.accounts.escrow_payment_account.lamports(),
+            ctx.accounts.escrow_payment_account.lamports(),
+        )?;
+
         let escrow_signer_seeds = [
             PREFIX.as_bytes(),
             auction_house_key.as_ref(),
@@ -604,6 +830,14 @@ pub mod auction_house {
         auctioneer::delegate_auctioneer(ctx, scopes)
     }
 
+    /// Delegate an additional auctioneer with its own scopes, e.g. to run English and Dutch auctions on the same Auction House.
+    pub fn add_auctioneer<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddAuctioneer<'info>>,
+        scopes: Vec<AuthorityScope>,
+    ) -> Result<()> {
+        auctioneer::add_auctioneer(ctx, scopes)
+    }
+
     pub fn update_auctioneer<'info>(
         ctx: Context<'_, '_, '_, 'info, UpdateAuctioneer<'info>>,
         scopes: Vec<AuthorityScope>,
@@ -649,6 +883,309 @@ pub mod auction_house {
         receipt::print_purchase_receipt(ctx, purchase_receipt_bump)
     }
 
+    /// Close a purchased or canceled listing receipt, returning its rent to the bookkeeper.
+    pub fn close_listing_receipt<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseOrderReceipt<'info>>,
+        emit_event: bool,
+    ) -> Result<()> {
+        receipt::close_listing_receipt(ctx, emit_event)
+    }
+
+    /// Close a purchased or canceled bid receipt, returning its rent to the bookkeeper.
+    pub fn close_bid_receipt<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseOrderReceipt<'info>>,
+        emit_event: bool,
+    ) -> Result<()> {
+        receipt::close_bid_receipt(ctx, emit_event)
+    }
+
+    /// Close a purchase receipt, returning its rent to the bookkeeper.
+    pub fn close_purchase_receipt<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClosePurchaseReceipt<'info>>,
+        emit_event: bool,
+    ) -> Result<()> {
+        receipt::close_purchase_receipt(ctx, emit_event)
+    }
+
+    /// List several tokens as one bundle sold atomically at a single price.
+    pub fn sell_bundle<'info>(
+        ctx: Context<'_, '_, '_, 'info, SellBundle<'info>>,
+        price: u64,
+        weights: Vec<u64>,
+    ) -> Result<()> {
+        bundle::sell_bundle(ctx, price, weights)
+    }
+
+    /// Buy every item of a bundle listing, paying royalties per item on its weighted share of the price.
+    pub fn execute_bundle_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteBundleSale<'info>>,
+    ) -> Result<()> {
+        bundle::execute_bundle_sale(ctx)
+    }
+
+    /// Cancel a bundle listing and revoke the program delegate of its items.
+    pub fn cancel_bundle<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelBundle<'info>>,
+    ) -> Result<()> {
+        bundle::cancel_bundle(ctx)
+    }
+
+    /// Offer a token, plus an optional top-up from escrow, in exchange for a token of another mint.
+    pub fn make_swap_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeSwapOffer<'info>>,
+        top_up: u64,
+    ) -> Result<()> {
+        swap::make_swap_offer(ctx, top_up)
+    }
+
+    /// Accept a swap offer, exchanging both tokens and paying the top-up after royalties and fees.
+    pub fn accept_swap_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptSwapOffer<'info>>,
+    ) -> Result<()> {
+        swap::accept_swap_offer(ctx)
+    }
+
+    /// Cancel a swap offer and revoke the program delegate of the offered token.
+    pub fn cancel_swap_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelSwapOffer<'info>>,
+    ) -> Result<()> {
+        swap::cancel_swap_offer(ctx)
+    }
+
+    /// List a token at a price declining from a start price to a floor price over a time window.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sell_dutch<'info>(
+        ctx: Context<'_, '_, '_, 'info, SellDutch<'info>>,
+        dutch_listing_bump: u8,
+        start_price: u64,
+        floor_price: u64,
+        start_time: i64,
+        end_time: i64,
+        step_interval: i64,
+        token_size: u64,
+    ) -> Result<()> {
+        dutch::sell_dutch(
+            ctx,
+            dutch_listing_bump,
+            start_price,
+            floor_price,
+            start_time,
+            end_time,
+            step_interval,
+            token_size,
+        )
+    }
+
+    /// Execute a sale against a dutch listing with a bid at or above its current price.
+    pub fn execute_dutch_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteDutchSale<'info>>,
+        escrow_payment_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        execute_sale::execute_dutch_sale(
+            ctx,
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
+    /// Cancel a dutch listing and revoke the program delegate of its token.
+    pub fn cancel_dutch<'info>(ctx: Context<'_, '_, '_, 'info, CancelDutch<'info>>) -> Result<()> {
+        dutch::cancel_dutch(ctx)
+    }
+
+    /// Attach an expiry to the trade state created by the preceding listing or bid instruction.
+    pub fn set_trade_state_expiry<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetTradeStateExpiry<'info>>,
+        expires_at: i64,
+    ) -> Result<()> {
+        expiry::set_trade_state_expiry(ctx, expires_at)
+    }
+
+    /// Reserve the listing created by the preceding `sell` or `auctioneer_sell` instruction for a single buyer.
+    pub fn set_allowed_buyer<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetAllowedBuyer<'info>>,
+        allowed_buyer: Pubkey,
+    ) -> Result<()> {
+        private_listing::set_allowed_buyer(ctx, allowed_buyer)
+    }
+
+    /// Close an expired trade state and return its rent to the original payer. Anyone can call this.
+    pub fn close_expired_trade_state<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseExpiredTradeState<'info>>,
+    ) -> Result<()> {
+        expiry::close_expired_trade_state(ctx)
+    }
+
+    /// Create the collection list restricting which verified collections trade on the Auction House.
+    pub fn create_collection_list<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateCollectionList<'info>>,
+        mode: CollectionListMode,
+    ) -> Result<()> {
+        curation::create_collection_list(ctx, mode)
+    }
+
+    /// Add a verified collection mint to the collection list of the Auction House.
+    pub fn add_collection<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateCollectionList<'info>>,
+        collection_mint: Pubkey,
+    ) -> Result<()> {
+        curation::add_collection(ctx, collection_mint)
+    }
+
+    /// Remove a verified collection mint from the collection list of the Auction House.
+    pub fn remove_collection<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateCollectionList<'info>>,
+        collection_mint: Pubkey,
+    ) -> Result<()> {
+        curation::remove_collection(ctx, collection_mint)
+    }
+
+    /// Release the reserved bids of an escrow reservation whose trade states have been closed.
+    pub fn prune_escrow_reservation<'info>(
+        ctx: Context<'_, '_, '_, 'info, PruneEscrowReservation<'info>>,
+    ) -> Result<()> {
+        reservation::prune_escrow_reservation(ctx)
+    }
+
+    /// Create the stats account recording the volume and fees of sales on the Auction House.
+    pub fn create_auction_house_stats<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateAuctionHouseStats<'info>>,
+    ) -> Result<()> {
+        stats::create_auction_house_stats(ctx)
+    }
+
+    /// Put the Auction House under the governance of `threshold` of `signers`, with changes applied `delay` seconds
+    /// after they are proposed, treasury withdrawals above `withdrawal_threshold` going through a pending change and
+    /// fee account withdrawals limited to `fee_withdrawal_threshold` lamports, both per withdrawal period.
+    pub fn enable_governance<'info>(
+        ctx: Context<'_, '_, '_, 'info, EnableGovernance<'info>>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+        delay: i64,
+        withdrawal_threshold: u64,
+        fee_withdrawal_threshold: u64,
+    ) -> Result<()> {
+        governance::enable_governance(
+            ctx,
+            signers,
+            threshold,
+            delay,
+            withdrawal_threshold,
+            fee_withdrawal_threshold,
+        )
+    }
+
+    /// Propose a sensitive change to a governed Auction House.
+    pub fn propose_change<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProposeChange<'info>>,
+        change: GovernedChange,
+    ) -> Result<()> {
+        governance::propose_change(ctx, change)
+    }
+
+    /// Approve a pending change as a governance signer.
+    pub fn approve_change<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReviewChange<'info>>,
+    ) -> Result<()> {
+        governance::approve_change(ctx)
+    }
+
+    /// Cancel a pending change as a governance signer.
+    pub fn cancel_change<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReviewChange<'info>>,
+    ) -> Result<()> {
+        governance::cancel_change(ctx)
+    }
+
+    /// Apply an approved pending change once its delay has passed.
+    pub fn apply_change<'info>(ctx: Context<'_, '_, '_, 'info, ApplyChange<'info>>) -> Result<()> {
+        governance::apply_change(ctx)
+    }
+
+    /// Require wallets to hold a gateway token of the gatekeeper network to list and bid, or lift the requirement.
+    pub fn set_gatekeeper<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetGatekeeper<'info>>,
+        gatekeeper: Option<GatekeeperConfig>,
+    ) -> Result<()> {
+        gateway::set_gatekeeper(ctx, gatekeeper)
+    }
+
+    /// Set the share of the metadata royalty paid to creators on sales of the Auction House.
+    pub fn set_royalty_policy<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetRoyaltyPolicy<'info>>,
+        royalty_policy: RoyaltyPolicy,
+    ) -> Result<()> {
+        royalty::set_royalty_policy(ctx, royalty_policy)
+    }
+
+    /// Elect the share of the royalty a buyer pays on Auction Houses that do not enforce royalties.
+    pub fn set_royalty_election<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetRoyaltyElection<'info>>,
+        basis_points: u16,
+    ) -> Result<()> {
+        royalty::set_royalty_election(ctx, basis_points)
+    }
+
+    /// Replace the fee tiers charging lower Auction House fees to takers holding a membership token.
+    pub fn set_fee_tiers<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetFeeTiers<'info>>,
+        fee_tiers: Vec<FeeTier>,
+    ) -> Result<()> {
+        fee_tier::set_fee_tiers(ctx, fee_tiers)
+    }
+
+    /// Grow an Auction House created before versioning to the latest layout, paid by its authority.
+    pub fn migrate_auction_house<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateAuctionHouse<'info>>,
+    ) -> Result<()> {
+        migrate::migrate_auction_house(ctx)
+    }
+
+    /// Set whether sales of the Auction House draw from the shared escrows of buyers that approved it.
+    pub fn set_accepts_shared_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetAcceptsSharedEscrow<'info>>,
+        accepts_shared_escrow: bool,
+    ) -> Result<()> {
+        shared_escrow::set_accepts_shared_escrow(ctx, accepts_shared_escrow)
+    }
+
+    /// Deposit into the escrow a wallet shares between every Auction House trading in the treasury mint.
+    pub fn deposit_shared_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositSharedEscrow<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        shared_escrow::deposit_shared_escrow(ctx, amount)
+    }
+
+    /// Withdraw from the shared escrow of a wallet.
+    pub fn withdraw_shared_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawSharedEscrow<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        shared_escrow::withdraw_shared_escrow(ctx, amount)
+    }
+
+    /// Approve an Auction House to draw from the shared escrow of a wallet on its sales.
+    pub fn approve_shared_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, ApproveSharedEscrow<'info>>,
+    ) -> Result<()> {
+        shared_escrow::approve_shared_escrow(ctx)
+    }
+
+    /// Revoke the approval of an Auction House to draw from the shared escrow of a wallet.
+    pub fn revoke_shared_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevokeSharedEscrow<'info>>,
+    ) -> Result<()> {
+        shared_escrow::revoke_shared_escrow(ctx)
+    }
+
     #[doc(hidden)]
     pub fn sell_remaining_accounts<'info>(
         _ctx: Context<'_, '_, '_, 'info, SellRemainingAccounts<'info>>,
@@ -675,8 +1212,9 @@ pub mod auction_house {
 #[derive(Accounts)]
 #[instruction(bump: u8, fee_payer_bump: u8, treasury_bump: u8)]
 pub struct CreateAuctionHouse<'info> {
+    /// CHECK: Validated against the token program in the handler.
     /// Treasury mint account, either native SOL mint or a SPL token mint.
-    pub treasury_mint: Account<'info, Mint>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// Key paying SOL fees for setting up the Auction House.
     #[account(mut)]
@@ -714,7 +1252,9 @@ pub struct CreateAuctionHouse<'info> {
     #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.key().as_ref(), TREASURY.as_bytes()], bump)]
     pub auction_house_treasury: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in create_auction_house.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -723,8 +1263,9 @@ pub struct CreateAuctionHouse<'info> {
 /// Accounts for the [`update_auction_house` handler](auction_house/fn.update_auction_house.html).
 #[derive(Accounts)]
 pub struct UpdateAuctionHouse<'info> {
+    /// CHECK: Validated against the token program in the handler.
     /// Treasury mint account, either native SOL mint or a SPL token mint.
-    pub treasury_mint: Account<'info, Mint>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// Key paying SOL fees for setting up the Auction House.
     pub payer: Signer<'info>,
@@ -754,7 +1295,9 @@ pub struct UpdateAuctionHouse<'info> {
     #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.creator.as_ref(), treasury_mint.key().as_ref()], bump=auction_house.bump, has_one=authority, has_one=treasury_mint)]
     pub auction_house: Account<'info, AuctionHouse>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in update_auction_house.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -763,8 +1306,9 @@ pub struct UpdateAuctionHouse<'info> {
 /// Accounts for the [`withdraw_from_treasury` handler](auction_house/fn.withdraw_from_treasury.html).
 #[derive(Accounts)]
 pub struct WithdrawFromTreasury<'info> {
+    /// CHECK: Validated against the token program in the handler.
     /// Treasury mint account, either native SOL mint or a SPL token mint.
-    pub treasury_mint: Account<'info, Mint>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// Authority key for the Auction House.
     pub authority: Signer<'info>,
@@ -783,7 +1327,9 @@ pub struct WithdrawFromTreasury<'info> {
     #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.creator.as_ref(), treasury_mint.key().as_ref()], bump=auction_house.bump, has_one=authority, has_one=treasury_mint, has_one=treasury_withdrawal_destination, has_one=auction_house_treasury)]
     pub auction_house: Account<'info, AuctionHouse>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in withdraw_from_treasury.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 
@@ -0,0 +1,62 @@
+//! Auction Houses record the version of their account layout. Houses created before versioning read version 0, and
+//! the defaults of the version 1 fields, from the zeroed padding of their smaller account, so every handler keeps
+//! working with them. The version 1 fields can only be set once `migrate_auction_house` has grown the account to
+//! the latest layout, which leaves room for the full fee tier list and new fields.
+
+use anchor_lang::prelude::*;
+use mpl_utils::resize_or_reallocate_account_raw;
+
+use crate::{constants::*, errors::AuctionHouseError, AuctionHouse};
+
+/// Accounts for the [`migrate_auction_house` handler](auction_house/fn.migrate_auction_house.html).
+#[derive(Accounts)]
+pub struct MigrateAuctionHouse<'info> {
+    /// Auction House authority. Pays for the rent of the larger account.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow the Auction House account to `AUCTION_HOUSE_SIZE` and bump it to `AUCTION_HOUSE_VERSION`.
+pub fn migrate_auction_house(ctx: Context<MigrateAuctionHouse>) -> Result<()> {
+    let authority = &ctx.accounts.authority;
+    let auction_house = &mut ctx.accounts.auction_house;
+    let system_program = &ctx.accounts.system_program;
+
+    if auction_house.version >= AUCTION_HOUSE_VERSION {
+        return Err(AuctionHouseError::AuctionHouseAlreadyMigrated.into());
+    }
+
+    resize_or_reallocate_account_raw(
+        &auction_house.to_account_info(),
+        &authority.to_account_info(),
+        &system_program.to_account_info(),
+        AUCTION_HOUSE_SIZE,
+    )?;
+    auction_house.version = AUCTION_HOUSE_VERSION;
+
+    Ok(())
+}
+
+/// Fail unless the Auction House has been migrated to the latest layout, which the version 1 fields need the room of.
+pub fn assert_migrated(auction_house: &AuctionHouse) -> Result<()> {
+    if auction_house.version < AUCTION_HOUSE_VERSION {
+        return Err(AuctionHouseError::AuctionHouseNotMigrated.into());
+    }
+
+    Ok(())
+}
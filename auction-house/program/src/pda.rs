@@ -96,6 +96,72 @@ pub fn find_public_bid_trade_state_address(
     )
 }
 
+/// Return collection bid trade state `Pubkey` address and bump seed.
+pub fn find_collection_bid_trade_state_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    treasury_mint: &Pubkey,
+    collection_mint: &Pubkey,
+    price: u64,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            COLLECTION.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            treasury_mint.as_ref(),
+            collection_mint.as_ref(),
+            &price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+/// Return trait bid trade state `Pubkey` address and bump seed.
+pub fn find_trait_bid_trade_state_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    treasury_mint: &Pubkey,
+    root: &[u8; 32],
+    price: u64,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            TRAIT.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            treasury_mint.as_ref(),
+            root,
+            &price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+/// Return bundle listing `Pubkey` address and bump seed. A bundle is keyed by the token account of its first item.
+pub fn find_bundle_listing_address(
+    seller: &Pubkey,
+    auction_house: &Pubkey,
+    first_token_account: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            BUNDLE.as_bytes(),
+            seller.as_ref(),
+            auction_house.as_ref(),
+            first_token_account.as_ref(),
+        ],
+        &id(),
+    )
+}
+
 /// Return bid receipt `Pubkey` address and bump seed.
 pub fn find_bid_receipt_address(trade_state: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(
@@ -161,3 +227,142 @@ pub fn find_auctioneer_trade_state_address(
         &id(),
     )
 }
+
+/// Return swap offer `Pubkey` address and bump seed.
+pub fn find_swap_offer_address(
+    offerer: &Pubkey,
+    auction_house: &Pubkey,
+    offered_token_account: &Pubkey,
+    requested_mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            SWAP.as_bytes(),
+            offerer.as_ref(),
+            auction_house.as_ref(),
+            offered_token_account.as_ref(),
+            requested_mint.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return dutch listing `Pubkey` address and bump seed.
+pub fn find_dutch_listing_address(
+    seller: &Pubkey,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            DUTCH.as_bytes(),
+            seller.as_ref(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return escrow reservation `Pubkey` address and bump seed.
+pub fn find_escrow_reservation_address(auction_house: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ESCROW_RESERVATION.as_bytes(),
+            auction_house.as_ref(),
+            wallet.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return collection list `Pubkey` address and bump seed.
+pub fn find_collection_list_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            COLLECTION_LIST.as_bytes(),
+            auction_house.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return Auction House stats `Pubkey` address and bump seed.
+pub fn find_auction_house_stats_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PREFIX.as_bytes(), STATS.as_bytes(), auction_house.as_ref()],
+        &id(),
+    )
+}
+
+/// Return governance `Pubkey` address and bump seed.
+pub fn find_governance_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            GOVERNANCE.as_bytes(),
+            auction_house.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return pending change `Pubkey` address and bump seed for the `nonce`-th proposed change.
+pub fn find_pending_change_address(auction_house: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            GOVERNANCE.as_bytes(),
+            auction_house.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+/// Return royalty election `Pubkey` address and bump seed of `wallet`.
+pub fn find_royalty_election_address(auction_house: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ROYALTY_ELECTION.as_bytes(),
+            auction_house.as_ref(),
+            wallet.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return shared escrow `Pubkey` address and bump seed of `wallet` in `treasury_mint`.
+pub fn find_shared_escrow_address(treasury_mint: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            SHARED_ESCROW.as_bytes(),
+            treasury_mint.as_ref(),
+            wallet.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return `Pubkey` address and bump seed of the approval `wallet` gave the Auction House to draw from its shared
+/// escrow.
+pub fn find_shared_escrow_approval_address(
+    auction_house: &Pubkey,
+    wallet: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            SHARED_ESCROW_APPROVAL.as_bytes(),
+            auction_house.as_ref(),
+            wallet.as_ref(),
+        ],
+        &id(),
+    )
+}
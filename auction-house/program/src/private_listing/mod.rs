@@ -0,0 +1,128 @@
+//! Private listings can only be filled by a single buyer, e.g. for an OTC deal.
+//! The allowed buyer is attached to the seller trade state right after the `sell` or `auctioneer_sell` instruction that
+//! created it.
+
+use anchor_lang::{prelude::*, solana_program::sysvar};
+use solana_program::sysvar::instructions::get_instruction_relative;
+
+use crate::{constants::*, errors::AuctionHouseError, id, utils::*, AuctionHouse};
+
+/// Accounts for the [`set_allowed_buyer` handler](fn.set_allowed_buyer.html).
+#[derive(Accounts)]
+pub struct SetAllowedBuyer<'info> {
+    /// User wallet account that created the listing.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the listing instruction in set_allowed_buyer.
+    /// Seller trade state PDA account of the listing.
+    #[account(mut)]
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Validated by the address constraint.
+    #[account(address = sysvar::instructions::id())]
+    pub instruction: UncheckedAccount<'info>,
+}
+
+/// Reserve the listing created by the preceding `sell` or `auctioneer_sell` instruction for `allowed_buyer`.
+/// A `print_listing_receipt` instruction placed after this one records the restriction on the receipt.
+///
+/// The trade state is grown to hold an expiry and the allowed buyer. A listing without an expiry never expires,
+/// and the account that paid for the trade state in the `sell` instruction pays for the growth and is recorded as the
+/// account that is refunded when the trade state is closed.
+pub fn set_allowed_buyer<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetAllowedBuyer<'info>>,
+    allowed_buyer: Pubkey,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let system_program = &ctx.accounts.system_program;
+    let instruction_account = &ctx.accounts.instruction;
+
+    let prev_instruction = get_instruction_relative(-1, instruction_account)?;
+    assert_keys_equal(prev_instruction.program_id, id())?;
+    // (auction_house, seller_trade_state) positions in the listing instruction.
+    let (auction_house_index, trade_state_index) =
+        match assert_program_listing_instruction(&prev_instruction.data[..8])? {
+            ListingType::Sell => (4, 6),
+            ListingType::AuctioneerSell => (5, 7),
+            ListingType::SellDutch => return Err(AuctionHouseError::InstructionMismatch.into()),
+        };
+
+    let prev_instruction_accounts = prev_instruction.accounts;
+    assert_keys_equal(prev_instruction_accounts[0].pubkey, wallet.key())?;
+    assert_keys_equal(
+        prev_instruction_accounts[auction_house_index].pubkey,
+        auction_house.key(),
+    )?;
+    assert_keys_equal(
+        prev_instruction_accounts[trade_state_index].pubkey,
+        seller_trade_state.key(),
+    )?;
+
+    if seller_trade_state.data_is_empty() || *seller_trade_state.owner != id() {
+        return Err(AuctionHouseError::InvalidTradeState.into());
+    }
+
+    // Mirror get_fee_payer in the listing instruction so the same account pays for the whole trade state.
+    let auction_house_key = auction_house.key();
+    let fee_payer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+    // The authority is the fourth account of both listing instructions.
+    let (payer, payer_seeds): (AccountInfo<'info>, &[&[u8]]) =
+        if prev_instruction_accounts[3].is_signer {
+            (
+                auction_house_fee_account.to_account_info(),
+                &fee_payer_seeds,
+            )
+        } else {
+            (wallet.to_account_info(), &[])
+        };
+
+    let ts_info = seller_trade_state.to_account_info();
+    let mut fields = get_trade_state_fields(&ts_info)?;
+    fields.expiry = Some(fields.expiry.unwrap_or((i64::MAX, payer.key())));
+    fields.allowed_buyer = Some(allowed_buyer);
+    set_trade_state_fields(
+        &ts_info,
+        &fields,
+        &payer,
+        payer_seeds,
+        &system_program.to_account_info(),
+    )?;
+
+    Ok(())
+}
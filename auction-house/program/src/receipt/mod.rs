@@ -2,12 +2,17 @@
 use crate::{
     constants::*,
     errors::AuctionHouseError,
+    events::ReceiptClosedEvent,
     id,
-    instruction::{Buy, ExecuteSale, Sell},
+    instruction::{Buy, ExecuteSale, Sell, SellDutch, SetAllowedBuyer},
+    pda::find_auction_house_stats_address,
+    stats::AuctionHouseStats,
     utils::*,
 };
-use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
-use solana_program::{sysvar, sysvar::instructions::get_instruction_relative};
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize, Discriminator};
+use solana_program::{
+    program::invoke, system_instruction, sysvar, sysvar::instructions::get_instruction_relative,
+};
 
 pub const BID_RECEIPT_SIZE: usize = 8 + //key
 32 + // trade_state
@@ -54,7 +59,8 @@ pub const LISTING_RECEIPT_SIZE: usize = 8 + //key
 1 + // bump
 1 + // trade_state_bump
 8 + // created_at
-1 + 8; // canceled_at;
+1 + 8 + // canceled_at
+1 + 32; // allowed_buyer
 
 /// Receipt for a listing transaction.
 #[account]
@@ -71,6 +77,29 @@ pub struct ListingReceipt {
     pub trade_state_bump: u8,
     pub created_at: i64,
     pub canceled_at: Option<i64>,
+    /// The only buyer that can fill a private listing.
+    pub allowed_buyer: Option<Pubkey>,
+}
+
+/// Listing receipts printed before `allowed_buyer` was added are shorter. They read as public listings.
+fn load_listing_receipt(data: &[u8]) -> Result<ListingReceipt> {
+    let mut padded = data.to_vec();
+    padded.resize(LISTING_RECEIPT_SIZE.max(data.len()), 0);
+    ListingReceipt::try_deserialize(&mut padded.as_slice())
+}
+
+/// Write a listing receipt, keeping the size of receipts printed before `allowed_buyer` was added.
+fn store_listing_receipt(receipt: &ListingReceipt, data: &mut [u8]) -> Result<()> {
+    let mut buffer = Vec::with_capacity(LISTING_RECEIPT_SIZE);
+    receipt.try_serialize(&mut buffer)?;
+
+    let (stored, truncated) = buffer.split_at(buffer.len().min(data.len()));
+    if truncated.iter().any(|byte| *byte != 0) {
+        return Err(ErrorCode::AccountDidNotSerialize.into());
+    }
+    data[..stored.len()].copy_from_slice(stored);
+
+    Ok(())
 }
 
 pub const PURCHASE_RECEIPT_SIZE: usize = 8 + //key
@@ -82,7 +111,8 @@ pub const PURCHASE_RECEIPT_SIZE: usize = 8 + //key
 8 + // token_size
 8 + // price
 1 + // bump
-8; // created_at
+8 + // created_at
+1 + 8; // royalty
 
 /// Receipt for a purchase transaction.
 #[account]
@@ -96,6 +126,16 @@ pub struct PurchaseReceipt {
     pub price: u64,
     pub bump: u8,
     pub created_at: i64,
+    /// Royalty paid to the creators on the purchase, recorded when the receipt is printed with the stats account of
+    /// the Auction House.
+    pub royalty: Option<u64>,
+}
+
+/// Purchase receipts printed before `royalty` was added are shorter. They read as recording no royalty.
+pub fn load_purchase_receipt(data: &[u8]) -> Result<PurchaseReceipt> {
+    let mut padded = data.to_vec();
+    padded.resize(PURCHASE_RECEIPT_SIZE.max(data.len()), 0);
+    PurchaseReceipt::try_deserialize(&mut padded.as_slice())
 }
 
 /// Accounts for the [`print_listing_receipt` hanlder](fn.print_listing_receipt.html).
@@ -135,7 +175,16 @@ pub fn print_listing_receipt<'info>(
     let system_program = &ctx.accounts.system_program;
     let clock = Clock::get()?;
 
-    let prev_instruction = get_instruction_relative(-1, instruction_account)?;
+    // A private listing has its allowed buyer set between the listing and the receipt.
+    let mut prev_instruction = get_instruction_relative(-1, instruction_account)?;
+    let mut allowed_buyer = None;
+    if prev_instruction.program_id == id()
+        && is_program_set_allowed_buyer_instruction(&prev_instruction.data[..8])
+    {
+        let mut buffer = &prev_instruction.data[8..];
+        allowed_buyer = Some(SetAllowedBuyer::deserialize(&mut buffer)?.allowed_buyer);
+        prev_instruction = get_instruction_relative(-2, instruction_account)?;
+    }
     let prev_instruction_accounts = prev_instruction.accounts;
 
     let wallet = &prev_instruction_accounts[0];
@@ -143,10 +192,28 @@ pub fn print_listing_receipt<'info>(
     let seller_trade_state = &prev_instruction_accounts[6];
     let metadata = &prev_instruction_accounts[2];
 
-    assert_program_listing_instruction(&prev_instruction.data[..8])?;
+    let listing_type = assert_program_listing_instruction(&prev_instruction.data[..8])?;
 
+    // Dutch listings record their start price.
     let mut buffer = &prev_instruction.data[8..];
-    let sell_data = Sell::deserialize(&mut buffer)?;
+    let (price, token_size, trade_state_bump) = match listing_type {
+        ListingType::SellDutch => {
+            let sell_data = SellDutch::deserialize(&mut buffer)?;
+            (
+                sell_data.start_price,
+                sell_data.token_size,
+                sell_data.dutch_listing_bump,
+            )
+        }
+        _ => {
+            let sell_data = Sell::deserialize(&mut buffer)?;
+            (
+                sell_data.buyer_price,
+                sell_data.token_size,
+                sell_data.trade_state_bump,
+            )
+        }
+    };
 
     assert_keys_equal(prev_instruction.program_id, id())?;
 
@@ -178,6 +245,22 @@ pub fn print_listing_receipt<'info>(
             &[],
             &receipt_seeds,
         )?;
+    } else if receipt_info.data_len() < LISTING_RECEIPT_SIZE {
+        // Grow receipts printed before `allowed_buyer` was added.
+        let top_up = rent
+            .minimum_balance(LISTING_RECEIPT_SIZE)
+            .saturating_sub(receipt_info.lamports());
+        if top_up > 0 {
+            invoke(
+                &system_instruction::transfer(bookkeeper_account.key, receipt_info.key, top_up),
+                &[
+                    bookkeeper_account.to_account_info(),
+                    receipt_info.clone(),
+                    system_program.to_account_info(),
+                ],
+            )?;
+        }
+        receipt_info.realloc(LISTING_RECEIPT_SIZE, true)?;
     }
 
     let receipt = ListingReceipt {
@@ -187,12 +270,13 @@ pub fn print_listing_receipt<'info>(
         seller: wallet.pubkey,
         metadata: metadata.pubkey,
         purchase_receipt: None,
-        price: sell_data.buyer_price,
-        token_size: sell_data.token_size,
+        price,
+        token_size,
         bump: receipt_bump,
-        trade_state_bump: sell_data.trade_state_bump,
+        trade_state_bump,
         created_at: clock.unix_timestamp,
         canceled_at: None,
+        allowed_buyer,
     };
 
     receipt.try_serialize(&mut *receipt_account.try_borrow_mut_data()?)?;
@@ -227,9 +311,14 @@ pub fn cancel_listing_receipt<'info>(
     let prev_instruction = get_instruction_relative(-1, instruction_account)?;
     let prev_instruction_accounts = prev_instruction.accounts;
 
-    let trade_state = &prev_instruction_accounts[6];
-
-    assert_program_cancel_instruction(&prev_instruction.data[..8])?;
+    let trade_state = match assert_program_cancel_instruction(&prev_instruction.data[..8])? {
+        // Collection bids have no listing receipt to cancel.
+        CancelType::CancelCollectionBuy => {
+            return Err(AuctionHouseError::InstructionMismatch.into());
+        }
+        CancelType::CancelDutch => &prev_instruction_accounts[3],
+        _ => &prev_instruction_accounts[6],
+    };
 
     if receipt_info.data_is_empty() {
         return Err(AuctionHouseError::ReceiptIsEmpty.into());
@@ -242,13 +331,12 @@ pub fn cancel_listing_receipt<'info>(
     )?;
 
     let mut receipt_data = receipt_info.try_borrow_mut_data()?;
-    let mut receipt_data_slice: &[u8] = &receipt_data;
 
-    let mut receipt = ListingReceipt::try_deserialize(&mut receipt_data_slice)?;
+    let mut receipt = load_listing_receipt(&receipt_data)?;
 
     receipt.canceled_at = Some(clock.unix_timestamp);
 
-    receipt.try_serialize(&mut *receipt_data)?;
+    store_listing_receipt(&receipt, &mut receipt_data)?;
 
     Ok(())
 }
@@ -311,6 +399,7 @@ pub fn print_bid_receipt<'info>(
         BidType::AuctioneerPrivateSale => Some(token_account.pubkey),
         BidType::PublicSale => None,
         BidType::AuctioneerPublicSale => None,
+        BidType::CollectionSale => None,
     };
 
     assert_derivation(
@@ -393,9 +482,13 @@ pub fn cancel_bid_receipt<'info>(
     let prev_instruction = get_instruction_relative(-1, instruction_account)?;
     let prev_instruction_accounts = prev_instruction.accounts;
 
-    let trade_state = &prev_instruction_accounts[6];
+    let cancel_type = assert_program_cancel_instruction(&prev_instruction.data[..8])?;
 
-    assert_program_cancel_instruction(&prev_instruction.data[..8])?;
+    let trade_state = match cancel_type {
+        CancelType::CancelCollectionBuy => &prev_instruction_accounts[5],
+        CancelType::CancelDutch => return Err(AuctionHouseError::InstructionMismatch.into()),
+        _ => &prev_instruction_accounts[6],
+    };
 
     if receipt_info.data_is_empty() {
         return Err(AuctionHouseError::ReceiptIsEmpty.into());
@@ -452,6 +545,9 @@ pub struct PrintPurchaseReceipt<'info> {
 /// The previous instruction is checked to ensure that it is a "Purchase" type to
 /// match the receipt type being created. Passing in an empty account results in the PDA
 /// being created; an existing account will be written over.
+///
+/// The royalty paid on the purchase is recorded when the stats account of the Auction House is passed as remaining
+/// account, the sale having recorded it there. Receipts printed before the royalty was recorded are grown.
 pub fn print_purchase_receipt<'info>(
     ctx: Context<'_, '_, '_, 'info, PrintPurchaseReceipt<'info>>,
     purchase_receipt_bump: u8,
@@ -536,8 +632,38 @@ pub fn print_purchase_receipt<'info>(
             &[],
             &purchase_receipt_seeds,
         )?;
+    } else if purchase_receipt_info.data_len() < PURCHASE_RECEIPT_SIZE {
+        // Grow receipts printed before `royalty` was added.
+        let top_up = rent
+            .minimum_balance(PURCHASE_RECEIPT_SIZE)
+            .saturating_sub(purchase_receipt_info.lamports());
+        if top_up > 0 {
+            invoke(
+                &system_instruction::transfer(bookkeeper.key, purchase_receipt_info.key, top_up),
+                &[
+                    bookkeeper.to_account_info(),
+                    purchase_receipt_info.clone(),
+                    system_program.to_account_info(),
+                ],
+            )?;
+        }
+        purchase_receipt_info.realloc(PURCHASE_RECEIPT_SIZE, true)?;
     }
 
+    // The sale, being the previous instruction, recorded the royalty it paid in the stats account.
+    let royalty = match ctx.remaining_accounts.first() {
+        Some(stats_info) => {
+            let (expected, _) = find_auction_house_stats_address(&auction_house.pubkey);
+            if stats_info.key() != expected || *stats_info.owner != id() {
+                return Err(AuctionHouseError::InvalidAuctionHouseStats.into());
+            }
+            let stats =
+                AuctionHouseStats::try_deserialize(&mut stats_info.try_borrow_data()?.as_ref())?;
+            Some(stats.last_sale_royalty)
+        }
+        None => None,
+    };
+
     let purchase = PurchaseReceipt {
         buyer: buyer.pubkey,
         seller: seller.pubkey,
@@ -548,18 +674,18 @@ pub fn print_purchase_receipt<'info>(
         price: execute_sale_data.buyer_price,
         token_size: execute_sale_data.token_size,
         created_at: timestamp,
+        royalty,
     };
 
     purchase.try_serialize(&mut *purchase_receipt_account.try_borrow_mut_data()?)?;
 
     let mut listing_receipt_data = listing_receipt_info.try_borrow_mut_data()?;
-    let mut listing_receipt_data_slice: &[u8] = &listing_receipt_data;
 
-    let mut listing_receipt = ListingReceipt::try_deserialize(&mut listing_receipt_data_slice)?;
+    let mut listing_receipt = load_listing_receipt(&listing_receipt_data)?;
 
     listing_receipt.purchase_receipt = Some(purchase_receipt_account.key());
 
-    listing_receipt.try_serialize(&mut *listing_receipt_data)?;
+    store_listing_receipt(&listing_receipt, &mut listing_receipt_data)?;
 
     let mut bid_receipt_data = bid_receipt_account.try_borrow_mut_data()?;
     let mut bid_receipt_slice: &[u8] = &bid_receipt_data;
@@ -572,3 +698,137 @@ pub fn print_purchase_receipt<'info>(
 
     Ok(())
 }
+
+/// Accounts for the [`close_listing_receipt`](fn.close_listing_receipt.html) and
+/// [`close_bid_receipt`](fn.close_bid_receipt.html) handlers.
+#[derive(Accounts)]
+pub struct CloseOrderReceipt<'info> {
+    /// CHECK: Receipt owner and type are checked in the handler.
+    #[account(mut, owner = id())]
+    pub receipt: UncheckedAccount<'info>,
+
+    /// CHECK: Checked against the receipt in the handler.
+    /// Trade state of the order, which has to be closed.
+    pub trade_state: UncheckedAccount<'info>,
+
+    /// Bookkeeper that paid the receipt rent, receives it back.
+    #[account(mut)]
+    pub bookkeeper: Signer<'info>,
+}
+
+/// Accounts for the [`close_purchase_receipt` handler](fn.close_purchase_receipt.html).
+#[derive(Accounts)]
+pub struct ClosePurchaseReceipt<'info> {
+    /// CHECK: Receipt owner and type are checked in the handler.
+    #[account(mut, owner = id())]
+    pub purchase_receipt: UncheckedAccount<'info>,
+
+    /// Bookkeeper that paid the receipt rent, receives it back.
+    #[account(mut)]
+    pub bookkeeper: Signer<'info>,
+}
+
+/// Close a listing receipt once its listing was purchased or canceled and its trade state closed,
+/// returning the rent to the bookkeeper. With `emit_event` the receipt is emitted before it is closed.
+pub fn close_listing_receipt<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseOrderReceipt<'info>>,
+    emit_event: bool,
+) -> Result<()> {
+    let receipt_info = ctx.accounts.receipt.to_account_info();
+    let receipt = load_listing_receipt(&receipt_info.try_borrow_data()?)?;
+
+    assert_order_receipt_finalized(
+        &ctx.accounts.trade_state,
+        &receipt.trade_state,
+        receipt.purchase_receipt.is_some() || receipt.canceled_at.is_some(),
+    )?;
+
+    close_receipt(
+        &receipt_info,
+        &ctx.accounts.bookkeeper,
+        receipt.bookkeeper,
+        emit_event,
+    )
+}
+
+/// Close a bid receipt once its bid was purchased or canceled and its trade state closed,
+/// returning the rent to the bookkeeper. With `emit_event` the receipt is emitted before it is closed.
+pub fn close_bid_receipt<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseOrderReceipt<'info>>,
+    emit_event: bool,
+) -> Result<()> {
+    let receipt_info = ctx.accounts.receipt.to_account_info();
+    let receipt = BidReceipt::try_deserialize(&mut receipt_info.try_borrow_data()?.as_ref())?;
+
+    assert_order_receipt_finalized(
+        &ctx.accounts.trade_state,
+        &receipt.trade_state,
+        receipt.purchase_receipt.is_some() || receipt.canceled_at.is_some(),
+    )?;
+
+    close_receipt(
+        &receipt_info,
+        &ctx.accounts.bookkeeper,
+        receipt.bookkeeper,
+        emit_event,
+    )
+}
+
+/// Close a purchase receipt, returning the rent to the bookkeeper.
+/// With `emit_event` the receipt is emitted before it is closed.
+pub fn close_purchase_receipt<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClosePurchaseReceipt<'info>>,
+    emit_event: bool,
+) -> Result<()> {
+    let receipt_info = ctx.accounts.purchase_receipt.to_account_info();
+    // Receipts printed before royalties were recorded are shorter than the current layout, so only the
+    // discriminator and the bookkeeper leading the receipt are read.
+    let bookkeeper = {
+        let data = receipt_info.try_borrow_data()?;
+        if data.len() < 40 || data[..8] != PurchaseReceipt::discriminator() {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Pubkey::try_from_slice(&data[8..40])?
+    };
+
+    close_receipt(
+        &receipt_info,
+        &ctx.accounts.bookkeeper,
+        bookkeeper,
+        emit_event,
+    )
+}
+
+/// An order receipt is finalized once it records a purchase or cancelation and the order's trade state is closed,
+/// so a partially filled listing keeps its receipt.
+fn assert_order_receipt_finalized(
+    trade_state: &AccountInfo,
+    receipt_trade_state: &Pubkey,
+    is_finalized: bool,
+) -> Result<()> {
+    assert_keys_equal(trade_state.key(), *receipt_trade_state)?;
+    if !is_finalized || trade_state.lamports() > 0 {
+        return Err(AuctionHouseError::ReceiptNotFinalized.into());
+    }
+
+    Ok(())
+}
+
+fn close_receipt<'info>(
+    receipt_info: &AccountInfo<'info>,
+    bookkeeper: &Signer<'info>,
+    receipt_bookkeeper: Pubkey,
+    emit_event: bool,
+) -> Result<()> {
+    assert_keys_equal(bookkeeper.key(), receipt_bookkeeper)?;
+
+    if emit_event {
+        emit!(ReceiptClosedEvent {
+            receipt: receipt_info.key(),
+            bookkeeper: receipt_bookkeeper,
+            data: receipt_info.try_borrow_data()?.to_vec(),
+        });
+    }
+
+    close_account(receipt_info, &bookkeeper.to_account_info())
+}
@@ -0,0 +1,252 @@
+//! Escrow reservations track how much of a buyer's escrow payment account is committed to open bids.
+//! Once an Auction House tracks them, bids, cancels of bids, sales and withdrawals pass the buyer's escrow reservation
+//! among their trailing remaining accounts, in the position listed in the [crate docs](crate#remaining-accounts).
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, pda::find_escrow_reservation_address, utils::*,
+    AuctionHouse,
+};
+
+pub const MAX_RESERVED_BIDS: usize = 32;
+
+pub const ESCROW_RESERVATION_SIZE: usize = 8 + // key
+32 + // auction_house
+32 + // wallet
+4 + (32 + 8) * MAX_RESERVED_BIDS + // bids
+1; // bump
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EscrowReservationMode {
+    /// Bids are not tracked.
+    Disabled,
+    /// Bids reserve their amount of the escrow, which can not be withdrawn while they are open.
+    Track,
+    /// Like `Track`, and a bid is rejected when the escrow does not cover every open bid.
+    Enforce,
+}
+
+/// Amount of the escrow committed to an open bid: its price plus the larger of the maker and taker fee.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ReservedBid {
+    pub trade_state: Pubkey,
+    pub amount: u64,
+}
+
+/// Open bids of a wallet that draw on its escrow payment account.
+#[account]
+pub struct EscrowReservation {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub bids: Vec<ReservedBid>,
+    pub bump: u8,
+}
+
+impl EscrowReservation {
+    /// Total amount committed to open bids.
+    pub fn reserved_amount(&self) -> Result<u64> {
+        self.bids.iter().try_fold(0u64, |total, bid| {
+            total
+                .checked_add(bid.amount)
+                .ok_or_else(|| AuctionHouseError::NumericalOverflow.into())
+        })
+    }
+
+    /// Stop tracking the bid with `trade_state`. Untracked bids are ignored.
+    pub fn release(&mut self, trade_state: &Pubkey) {
+        self.bids.retain(|bid| bid.trade_state != *trade_state);
+    }
+}
+
+/// Split the escrow reservation of `wallet` off the end of `remaining_accounts` when the Auction House tracks reservations.
+/// Returns the reservation account and the remaining accounts left for the handler.
+pub fn split_escrow_reservation<'c, 'info>(
+    auction_house: &Account<'info, AuctionHouse>,
+    wallet: &Pubkey,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<(Option<&'c AccountInfo<'info>>, &'c [AccountInfo<'info>])> {
+    if auction_house.escrow_reservation_mode == EscrowReservationMode::Disabled {
+        return Ok((None, remaining_accounts));
+    }
+
+    let (escrow_reservation, remaining_accounts) = remaining_accounts
+        .split_last()
+        .ok_or(AuctionHouseError::InvalidEscrowReservation)?;
+    let (expected, _) = find_escrow_reservation_address(&auction_house.key(), wallet);
+    if escrow_reservation.key() != expected {
+        return Err(AuctionHouseError::InvalidEscrowReservation.into());
+    }
+
+    Ok((Some(escrow_reservation), remaining_accounts))
+}
+
+/// Load the escrow reservation, or `None` if it has not been created yet.
+pub fn load_escrow_reservation(
+    escrow_reservation: &AccountInfo,
+) -> Result<Option<EscrowReservation>> {
+    if escrow_reservation.data_is_empty() {
+        return Ok(None);
+    }
+    if *escrow_reservation.owner != crate::id() {
+        return Err(AuctionHouseError::InvalidEscrowReservation.into());
+    }
+
+    let data = escrow_reservation.try_borrow_data()?;
+    Ok(Some(EscrowReservation::try_deserialize(
+        &mut data.as_ref(),
+    )?))
+}
+
+fn store_escrow_reservation(
+    escrow_reservation: &EscrowReservation,
+    escrow_reservation_info: &AccountInfo,
+) -> Result<()> {
+    let mut data = escrow_reservation_info.try_borrow_mut_data()?;
+    escrow_reservation.try_serialize(&mut *data)
+}
+
+/// Funds of the escrow payment account available to bids, excluding the rent exempt minimum of a native escrow.
+pub fn escrow_balance(escrow_payment_account: &AccountInfo, is_native: bool) -> Result<u64> {
+    if is_native {
+        let rent = Rent::get()?;
+        Ok(escrow_payment_account
+            .lamports()
+            .saturating_sub(rent.minimum_balance(escrow_payment_account.data_len())))
+    } else {
+        Ok(get_treasury_token_account(escrow_payment_account)?.amount)
+    }
+}
+
+/// Record `amount` of the escrow as committed to the bid with `trade_state`, creating the escrow reservation if needed.
+/// In [`EscrowReservationMode::Enforce`] the bid is rejected when the escrow does not cover all open bids.
+#[allow(clippy::too_many_arguments)]
+pub fn reserve_bid<'info>(
+    escrow_reservation_info: &AccountInfo<'info>,
+    auction_house: &Account<'info, AuctionHouse>,
+    wallet: &Pubkey,
+    trade_state: &Pubkey,
+    amount: u64,
+    escrow_balance: u64,
+    fee_payer: &AccountInfo<'info>,
+    fee_seeds: &[&[u8]],
+    system_program: &AccountInfo<'info>,
+    rent: &AccountInfo<'info>,
+) -> Result<()> {
+    let auction_house_key = auction_house.key();
+    let mut escrow_reservation = match load_escrow_reservation(escrow_reservation_info)? {
+        Some(escrow_reservation) => escrow_reservation,
+        None => {
+            let (_, bump) = find_escrow_reservation_address(&auction_house_key, wallet);
+            create_or_allocate_account_raw(
+                crate::id(),
+                escrow_reservation_info,
+                rent,
+                system_program,
+                fee_payer,
+                ESCROW_RESERVATION_SIZE,
+                fee_seeds,
+                &[
+                    PREFIX.as_bytes(),
+                    ESCROW_RESERVATION.as_bytes(),
+                    auction_house_key.as_ref(),
+                    wallet.as_ref(),
+                    &[bump],
+                ],
+            )?;
+
+            EscrowReservation {
+                auction_house: auction_house_key,
+                wallet: *wallet,
+                bids: Vec::new(),
+                bump,
+            }
+        }
+    };
+
+    escrow_reservation.release(trade_state);
+    if escrow_reservation.bids.len() >= MAX_RESERVED_BIDS {
+        return Err(AuctionHouseError::InvalidEscrowReservation.into());
+    }
+    escrow_reservation.bids.push(ReservedBid {
+        trade_state: *trade_state,
+        amount,
+    });
+
+    if auction_house.escrow_reservation_mode == EscrowReservationMode::Enforce
+        && escrow_reservation.reserved_amount()? > escrow_balance
+    {
+        return Err(AuctionHouseError::EscrowOvercommitted.into());
+    }
+
+    store_escrow_reservation(&escrow_reservation, escrow_reservation_info)
+}
+
+/// Release the amount committed to the bid with `trade_state`, if the escrow reservation tracks it.
+pub fn release_bid(escrow_reservation_info: &AccountInfo, trade_state: &Pubkey) -> Result<()> {
+    if let Some(mut escrow_reservation) = load_escrow_reservation(escrow_reservation_info)? {
+        escrow_reservation.release(trade_state);
+        store_escrow_reservation(&escrow_reservation, escrow_reservation_info)?;
+    }
+
+    Ok(())
+}
+
+/// Check that taking `amount` out of an escrow holding `escrow_balance` leaves the reserved amount in it.
+pub fn assert_escrow_unreserved(
+    escrow_reservation_info: Option<&AccountInfo>,
+    escrow_balance: u64,
+    amount: u64,
+) -> Result<()> {
+    let reserved_amount = match escrow_reservation_info {
+        Some(escrow_reservation_info) => match load_escrow_reservation(escrow_reservation_info)? {
+            Some(escrow_reservation) => escrow_reservation.reserved_amount()?,
+            None => 0,
+        },
+        None => 0,
+    };
+
+    if escrow_balance.saturating_sub(amount) < reserved_amount {
+        return Err(AuctionHouseError::EscrowFundsReserved.into());
+    }
+
+    Ok(())
+}
+
+/// Accounts for the [`prune_escrow_reservation` handler](auction_house/fn.prune_escrow_reservation.html).
+#[derive(Accounts)]
+pub struct PruneEscrowReservation<'info> {
+    /// Escrow reservation PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            ESCROW_RESERVATION.as_bytes(),
+            escrow_reservation.auction_house.as_ref(),
+            escrow_reservation.wallet.as_ref()
+        ],
+        bump = escrow_reservation.bump
+    )]
+    pub escrow_reservation: Box<Account<'info, EscrowReservation>>,
+}
+
+/// Release the bids whose trade states, passed as remaining accounts, have been closed without releasing them,
+/// e.g. while the Auction House did not track reservations or by `close_expired_trade_state` without the reservation.
+pub fn prune_escrow_reservation<'info>(
+    ctx: Context<'_, '_, '_, 'info, PruneEscrowReservation<'info>>,
+) -> Result<()> {
+    let escrow_reservation = &mut ctx.accounts.escrow_reservation;
+
+    for trade_state in ctx.remaining_accounts {
+        let is_open = *trade_state.owner == crate::id()
+            && !trade_state.data_is_empty()
+            && trade_state.try_borrow_data()?[0] != 0;
+        if is_open {
+            return Err(AuctionHouseError::InvalidEscrowReservation.into());
+        }
+
+        escrow_reservation.release(&trade_state.key());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,271 @@
+//! Royalty policies let an Auction House pay creators a share of the royalty set in their metadata.
+//! On houses that do not enforce royalties, buyers elect the share they pay within the bounds of the policy in a
+//! royalty election, passed to sales at the position listed in the [crate docs](crate#remaining-accounts). The
+//! seller always forgoes the royalty of a buyer without an election, so an election only changes what the buyer
+//! pays: a lower share comes off the price and a higher one is paid on top of it. Bundle and swap sales pay the
+//! share of a buyer that has not elected one. Programmable NFTs always pay the full royalty so their authorization
+//! rules still hold.
+
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+use mpl_token_metadata::state::{Metadata, TokenStandard};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, migrate::assert_migrated,
+    pda::find_royalty_election_address, utils::*, AuctionHouse,
+};
+
+pub const ROYALTY_ELECTION_SIZE: usize = 8 + // key
+32 + // auction_house
+32 + // wallet
+2 + // basis_points
+1; // bump
+
+/// Share of the metadata royalty paid to creators on sales of an Auction House, in basis points of the royalty.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RoyaltyPolicy {
+    /// Creators are paid the full royalty.
+    Enforced,
+    /// Creators are paid `basis_points` of the royalty, or more when the buyer elects to.
+    Minimum { basis_points: u16 },
+    /// Creators are paid the full royalty unless the buyer elects to pay less, down to `floor_basis_points` of it.
+    Optional { floor_basis_points: u16 },
+}
+
+impl RoyaltyPolicy {
+    pub fn assert_valid(&self) -> Result<()> {
+        match *self {
+            RoyaltyPolicy::Enforced => Ok(()),
+            RoyaltyPolicy::Minimum { basis_points: bps }
+            | RoyaltyPolicy::Optional {
+                floor_basis_points: bps,
+            } => {
+                if bps > 10000 {
+                    return Err(AuctionHouseError::InvalidBasisPoints.into());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Share of the royalty of `metadata` paid, in basis points of the royalty, given the share elected by the buyer.
+    pub fn royalty_share(&self, metadata: &Metadata, elected_basis_points: Option<u16>) -> u16 {
+        if matches!(
+            metadata.token_standard,
+            Some(TokenStandard::ProgrammableNonFungible)
+        ) {
+            return 10000;
+        }
+
+        match *self {
+            RoyaltyPolicy::Enforced => 10000,
+            RoyaltyPolicy::Minimum { basis_points } => {
+                elected_basis_points.unwrap_or(0).max(basis_points)
+            }
+            RoyaltyPolicy::Optional { floor_basis_points } => elected_basis_points
+                .unwrap_or(10000)
+                .max(floor_basis_points),
+        }
+        .min(10000)
+    }
+
+    /// Royalty paid to the creators of `metadata` on a sale at `price`.
+    pub fn royalty(
+        &self,
+        metadata: &Metadata,
+        elected_basis_points: Option<u16>,
+        price: u64,
+    ) -> Result<u64> {
+        let royalty = (price as u128)
+            .checked_mul(metadata.data.seller_fee_basis_points as u128)
+            .ok_or(AuctionHouseError::NumericalOverflow)?
+            .checked_mul(self.royalty_share(metadata, elected_basis_points) as u128)
+            .ok_or(AuctionHouseError::NumericalOverflow)?
+            .checked_div(10000 * 10000)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+        Ok(royalty as u64)
+    }
+
+    /// What the buyer of a sale at `price` pays before fees. The seller forgoes the royalty of a buyer that has not
+    /// elected a share, and the buyer pays the royalty of its elected share in its place.
+    pub fn buyer_payment(
+        &self,
+        metadata: &Metadata,
+        elected_basis_points: Option<u16>,
+        price: u64,
+    ) -> Result<u64> {
+        let payment = price
+            .checked_add(self.royalty(metadata, elected_basis_points, price)?)
+            .ok_or(AuctionHouseError::NumericalOverflow)?
+            .checked_sub(self.royalty(metadata, None, price)?)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+        Ok(payment)
+    }
+}
+
+/// Share of the royalty a wallet elects to pay on the purchases it makes on an Auction House.
+#[account]
+pub struct RoyaltyElection {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    /// Elected share of the royalty, in basis points of the royalty.
+    pub basis_points: u16,
+    pub bump: u8,
+}
+
+/// Split the royalty election of `buyer` off the end of `remaining_accounts` when the Auction House does not
+/// enforce royalties. Returns the elected share, `None` when the buyer has not elected one, and the remaining
+/// accounts left for the handler.
+pub fn split_royalty_election<'c, 'info>(
+    auction_house: &Account<'info, AuctionHouse>,
+    buyer: &Pubkey,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<(Option<u16>, &'c [AccountInfo<'info>])> {
+    if auction_house.royalty_policy == RoyaltyPolicy::Enforced {
+        return Ok((None, remaining_accounts));
+    }
+
+    let (election_info, remaining_accounts) = remaining_accounts
+        .split_last()
+        .ok_or(AuctionHouseError::InvalidRoyaltyElection)?;
+    let (expected, _) = find_royalty_election_address(&auction_house.key(), buyer);
+    if election_info.key() != expected {
+        return Err(AuctionHouseError::InvalidRoyaltyElection.into());
+    }
+    if election_info.data_is_empty() {
+        return Ok((None, remaining_accounts));
+    }
+    if *election_info.owner != crate::id() {
+        return Err(AuctionHouseError::InvalidRoyaltyElection.into());
+    }
+
+    let election =
+        RoyaltyElection::try_deserialize(&mut election_info.try_borrow_data()?.as_ref())?;
+    Ok((Some(election.basis_points), remaining_accounts))
+}
+
+/// Accounts for the [`set_royalty_policy` handler](auction_house/fn.set_royalty_policy.html).
+#[derive(Accounts)]
+pub struct SetRoyaltyPolicy<'info> {
+    /// Auction House authority.
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+}
+
+/// Accounts for the [`set_royalty_election` handler](auction_house/fn.set_royalty_election.html).
+#[derive(Accounts)]
+pub struct SetRoyaltyElection<'info> {
+    /// Buyer wallet electing its royalty share. Pays for the royalty election account.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Royalty election PDA account of the wallet, created on the first election.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            ROYALTY_ELECTION.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    pub royalty_election: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Set the royalty policy of the migrated Auction House.
+pub fn set_royalty_policy(
+    ctx: Context<SetRoyaltyPolicy>,
+    royalty_policy: RoyaltyPolicy,
+) -> Result<()> {
+    let auction_house = &mut ctx.accounts.auction_house;
+    if auction_house.has_governance {
+        return Err(AuctionHouseError::GovernanceRequired.into());
+    }
+    assert_migrated(auction_house)?;
+    royalty_policy.assert_valid()?;
+    auction_house.royalty_policy = royalty_policy;
+
+    Ok(())
+}
+
+/// Elect the share of the royalty the wallet pays on its purchases, in basis points of the royalty.
+/// The royalty policy of the Auction House bounds the share actually paid.
+pub fn set_royalty_election<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetRoyaltyElection<'info>>,
+    basis_points: u16,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let auction_house = &ctx.accounts.auction_house;
+    let royalty_election = &ctx.accounts.royalty_election;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    if basis_points > 10000 {
+        return Err(AuctionHouseError::InvalidBasisPoints.into());
+    }
+
+    let bump = *ctx
+        .bumps
+        .get("royalty_election")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let election_info = royalty_election.to_account_info();
+    if election_info.data_is_empty() {
+        let auction_house_key = auction_house.key();
+        let wallet_key = wallet.key();
+        create_or_allocate_account_raw(
+            crate::id(),
+            &election_info,
+            &rent.to_account_info(),
+            system_program,
+            wallet,
+            ROYALTY_ELECTION_SIZE,
+            &[],
+            &[
+                PREFIX.as_bytes(),
+                ROYALTY_ELECTION.as_bytes(),
+                auction_house_key.as_ref(),
+                wallet_key.as_ref(),
+                &[bump],
+            ],
+        )?;
+    }
+
+    let election = RoyaltyElection {
+        auction_house: auction_house.key(),
+        wallet: wallet.key(),
+        basis_points,
+        bump,
+    };
+    election.try_serialize(&mut *election_info.try_borrow_mut_data()?)?;
+
+    Ok(())
+}
@@ -266,6 +266,55 @@ pub struct AuctioneerSell<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Accounts for the [`batch_sell` handler](auction_house/fn.batch_sell.html).
+#[derive(Accounts)]
+pub struct BatchSell<'info> {
+    /// User wallet account.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Verified through CPI
+    /// Auction House authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    // remaining accounts, for each order:
+    // token_account, metadata, seller_trade_state, [...SellRemainingAccounts if programmable]
+}
+
 pub fn sell<'info>(
     ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
     trade_state_bump: u8,
@@ -310,6 +359,7 @@ pub fn sell<'info>(
         program_as_signer_bump,
         buyer_price,
         token_size,
+        None,
     )
 }
 
@@ -367,10 +417,12 @@ pub fn auctioneer_sell<'info>(
         program_as_signer_bump,
         u64::MAX,
         token_size,
+        Some(ah_auctioneer_pda.key()),
     )
 }
 
 /// Create a sell bid by creating a `seller_trade_state` account and approving the program as the token delegate.
+/// A new listing created by an auctioneer records it in the `seller_trade_state`.
 fn sell_logic<'c, 'info>(
     accounts: &mut Sell<'info>,
     remaining_accounts: &'c [AccountInfo<'info>],
@@ -380,6 +432,7 @@ fn sell_logic<'c, 'info>(
     _program_as_signer_bump: u8,
     buyer_price: u64,
     token_size: u64,
+    auctioneer: Option<Pubkey>,
 ) -> Result<()> {
     let wallet = &accounts.wallet;
     let token_account = &accounts.token_account;
@@ -430,110 +483,33 @@ fn sell_logic<'c, 'info>(
     )?;
 
     assert_metadata_valid(metadata, token_account)?;
+    let remaining_accounts =
+        assert_gateway_token(auction_house, &wallet.to_account_info(), remaining_accounts)?;
+    let remaining_accounts =
+        assert_collection_list_allows(auction_house, metadata, remaining_accounts)?;
 
     if token_size > token_account.amount {
         return Err(AuctionHouseError::InvalidTokenAmount.into());
     }
 
-    let remaining_accounts = &mut remaining_accounts.iter();
-
     if wallet.is_signer {
-        match next_account_info(remaining_accounts) {
-            Ok(metadata_program) => {
-                require!(
-                    metadata_program.key() == mpl_token_metadata::ID,
-                    AuctionHouseError::PublicKeyMismatch
-                );
-
-                let delegate_record = next_account_info(remaining_accounts)?;
-                let token_record = next_account_info(remaining_accounts)?;
-                let token_mint = next_account_info(remaining_accounts)?;
-                let edition = next_account_info(remaining_accounts)?;
-                let auth_rules_program = next_account_info(remaining_accounts)?;
-                let auth_rules = next_account_info(remaining_accounts)?;
-                let sysvar_instructions = next_account_info(remaining_accounts)?;
-
-                let delegate = DelegateBuilder::new()
-                    .delegate_record(delegate_record.key())
-                    .delegate(program_as_signer.key())
-                    .metadata(metadata.key())
-                    .master_edition(edition.key())
-                    .token_record(token_record.key())
-                    .mint(token_mint.key())
-                    .token(token_account.key())
-                    .authority(wallet.key())
-                    .payer(wallet.key())
-                    .system_program(system_program.key())
-                    .sysvar_instructions(sysvar_instructions.key())
-                    .spl_token_program(token_program.key())
-                    .authorization_rules_program(auth_rules_program.key())
-                    .authorization_rules(auth_rules.key())
-                    .build(DelegateArgs::SaleV1 {
-                        amount: token_size,
-                        authorization_data: Some(AuthorizationData {
-                            payload: Payload::from([
-                                ("Amount".to_string(), PayloadType::Number(token_size)),
-                                (
-                                    "Delegate".to_string(),
-                                    PayloadType::Pubkey(*program_as_signer.key),
-                                ),
-                                (
-                                    "DelegateSeeds".to_string(),
-                                    PayloadType::Seeds(SeedsVec {
-                                        seeds: vec![
-                                            PREFIX.as_bytes().to_vec(),
-                                            SIGNER.as_bytes().to_vec(),
-                                        ],
-                                    }),
-                                ),
-                            ]),
-                        }),
-                    })
-                    .unwrap()
-                    .instruction();
-
-                let delegate_accounts = [
-                    wallet.to_account_info(),
-                    metadata_program.to_account_info(),
-                    delegate_record.to_account_info(),
-                    token_record.to_account_info(),
-                    token_account.to_account_info(),
-                    token_mint.to_account_info(),
-                    metadata.to_account_info(),
-                    edition.to_account_info(),
-                    program_as_signer.to_account_info(),
-                    system_program.to_account_info(),
-                    token_program.to_account_info(),
-                    auth_rules_program.to_account_info(),
-                    auth_rules.to_account_info(),
-                    sysvar_instructions.to_account_info(),
-                ];
-
-                invoke(&delegate, &delegate_accounts)?;
-            }
-            Err(_) => {
-                invoke(
-                    &approve(
-                        &token_program.key(),
-                        &token_account.key(),
-                        &program_as_signer.key(),
-                        &wallet.key(),
-                        &[],
-                        token_size,
-                    )
-                    .unwrap(),
-                    &[
-                        token_program.to_account_info(),
-                        token_account.to_account_info(),
-                        program_as_signer.to_account_info(),
-                        wallet.to_account_info(),
-                    ],
-                )?;
-            }
-        }
+        approve_sale_delegate(
+            &wallet.to_account_info(),
+            &token_account.to_account_info(),
+            &metadata.to_account_info(),
+            &program_as_signer.to_account_info(),
+            &token_program.to_account_info(),
+            &system_program.to_account_info(),
+            &mut remaining_accounts.iter(),
+            token_size,
+        )?;
     }
 
     let ts_info = seller_trade_state.to_account_info();
+    let ts_fields = TradeStateFields {
+        auctioneer,
+        ..Default::default()
+    };
     if ts_info.data_is_empty() {
         let token_account_key = token_account.key();
         let wallet_key = wallet.key();
@@ -554,14 +530,262 @@ fn sell_logic<'c, 'info>(
             &rent.to_account_info(),
             system_program,
             &fee_payer,
-            TRADE_STATE_SIZE,
+            ts_fields.size(),
             fee_seeds,
             &ts_seeds,
         )?;
+        ts_fields.write(&mut ts_info.data.borrow_mut());
     }
 
     let data = &mut ts_info.data.borrow_mut();
     data[0] = trade_state_bump;
 
+    emit!(ListingEvent {
+        auction_house: auction_house_key,
+        seller: wallet.key(),
+        token_account: token_account.key(),
+        token_mint: token_account.mint,
+        trade_state: seller_trade_state.key(),
+        price: buyer_price,
+        token_size,
+    });
+
+    Ok(())
+}
+
+/// Create a sell order for every entry of `orders` in one instruction.
+/// Each order reads a `(token_account, metadata, seller_trade_state)` triple from the remaining accounts,
+/// followed by the [`SellRemainingAccounts`] when the order is for a programmable NFT.
+pub fn batch_sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, BatchSell<'info>>,
+    orders: Vec<BatchOrder>,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let rent = &ctx.accounts.rent;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Sell as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let wallet_key = wallet.key();
+
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, fee_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let remaining_accounts = assert_gateway_token(
+        auction_house,
+        &wallet.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    let (collection_list, remaining_accounts) =
+        split_collection_list(auction_house, remaining_accounts)?;
+    let remaining_accounts = &mut remaining_accounts.iter();
+
+    for order in orders {
+        let token_account_info = next_account_info(remaining_accounts)?;
+        let metadata = next_account_info(remaining_accounts)?;
+        let seller_trade_state = next_account_info(remaining_accounts)?;
+
+        let token_account: Account<TokenAccount> = Account::try_from(token_account_info)?;
+        assert_is_ata(token_account_info, &wallet_key, &token_account.mint)?;
+        assert_metadata_valid(
+            &UncheckedAccount::try_from(metadata.clone()),
+            &token_account,
+        )?;
+        if let Some(collection_list) = &collection_list {
+            collection_list.assert_metadata_allowed(metadata)?;
+        }
+
+        if order.token_size > token_account.amount {
+            return Err(AuctionHouseError::InvalidTokenAmount.into());
+        }
+
+        let buyer_price = order.buyer_price.to_le_bytes();
+        let token_size = order.token_size.to_le_bytes();
+        let ts_path = [
+            PREFIX.as_bytes(),
+            wallet_key.as_ref(),
+            auction_house_key.as_ref(),
+            token_account_info.key.as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price,
+            &token_size,
+        ];
+        let trade_state_bump = assert_derivation(ctx.program_id, seller_trade_state, &ts_path)?;
+
+        let mut no_remaining_accounts = [].iter();
+        approve_sale_delegate(
+            &wallet.to_account_info(),
+            token_account_info,
+            metadata,
+            &program_as_signer.to_account_info(),
+            &token_program.to_account_info(),
+            &system_program.to_account_info(),
+            if order.programmable {
+                remaining_accounts
+            } else {
+                &mut no_remaining_accounts
+            },
+            order.token_size,
+        )?;
+
+        if seller_trade_state.data_is_empty() {
+            let bump = [trade_state_bump];
+            let ts_seeds = [&ts_path[..], &[&bump]].concat();
+            create_or_allocate_account_raw(
+                *ctx.program_id,
+                seller_trade_state,
+                &rent.to_account_info(),
+                system_program,
+                &fee_payer,
+                TRADE_STATE_SIZE,
+                fee_seeds,
+                &ts_seeds,
+            )?;
+        }
+
+        seller_trade_state.try_borrow_mut_data()?[0] = trade_state_bump;
+
+        emit!(ListingEvent {
+            auction_house: auction_house_key,
+            seller: wallet_key,
+            token_account: token_account.key(),
+            token_mint: token_account.mint,
+            trade_state: seller_trade_state.key(),
+            price: order.buyer_price,
+            token_size: order.token_size,
+        });
+    }
+
+    Ok(())
+}
+
+/// Approve the program as the sale delegate of `token_account`. The Token Metadata delegate is used when the
+/// [`SellRemainingAccounts`] of a programmable NFT are next in `remaining_accounts`, otherwise an SPL token approve.
+pub fn approve_sale_delegate<'info>(
+    wallet: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    metadata: &AccountInfo<'info>,
+    program_as_signer: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    remaining_accounts: &mut std::slice::Iter<AccountInfo<'info>>,
+    token_size: u64,
+) -> Result<()> {
+    match next_account_info(remaining_accounts) {
+        Ok(metadata_program) => {
+            require!(
+                metadata_program.key() == mpl_token_metadata::ID,
+                AuctionHouseError::PublicKeyMismatch
+            );
+
+            let delegate_record = next_account_info(remaining_accounts)?;
+            let token_record = next_account_info(remaining_accounts)?;
+            let token_mint = next_account_info(remaining_accounts)?;
+            let edition = next_account_info(remaining_accounts)?;
+            let auth_rules_program = next_account_info(remaining_accounts)?;
+            let auth_rules = next_account_info(remaining_accounts)?;
+            let sysvar_instructions = next_account_info(remaining_accounts)?;
+
+            let delegate = DelegateBuilder::new()
+                .delegate_record(delegate_record.key())
+                .delegate(program_as_signer.key())
+                .metadata(metadata.key())
+                .master_edition(edition.key())
+                .token_record(token_record.key())
+                .mint(token_mint.key())
+                .token(token_account.key())
+                .authority(wallet.key())
+                .payer(wallet.key())
+                .system_program(system_program.key())
+                .sysvar_instructions(sysvar_instructions.key())
+                .spl_token_program(token_program.key())
+                .authorization_rules_program(auth_rules_program.key())
+                .authorization_rules(auth_rules.key())
+                .build(DelegateArgs::SaleV1 {
+                    amount: token_size,
+                    authorization_data: Some(AuthorizationData {
+                        payload: Payload::from([
+                            ("Amount".to_string(), PayloadType::Number(token_size)),
+                            (
+                                "Delegate".to_string(),
+                                PayloadType::Pubkey(*program_as_signer.key),
+                            ),
+                            (
+                                "DelegateSeeds".to_string(),
+                                PayloadType::Seeds(SeedsVec {
+                                    seeds: vec![
+                                        PREFIX.as_bytes().to_vec(),
+                                        SIGNER.as_bytes().to_vec(),
+                                    ],
+                                }),
+                            ),
+                        ]),
+                    }),
+                })
+                .unwrap()
+                .instruction();
+
+            let delegate_accounts = [
+                wallet.to_account_info(),
+                metadata_program.to_account_info(),
+                delegate_record.to_account_info(),
+                token_record.to_account_info(),
+                token_account.to_account_info(),
+                token_mint.to_account_info(),
+                metadata.to_account_info(),
+                edition.to_account_info(),
+                program_as_signer.to_account_info(),
+                system_program.to_account_info(),
+                token_program.to_account_info(),
+                auth_rules_program.to_account_info(),
+                auth_rules.to_account_info(),
+                sysvar_instructions.to_account_info(),
+            ];
+
+            invoke(&delegate, &delegate_accounts)?;
+        }
+        Err(_) => {
+            invoke(
+                &approve(
+                    &token_program.key(),
+                    &token_account.key(),
+                    &program_as_signer.key(),
+                    &wallet.key(),
+                    &[],
+                    token_size,
+                )
+                .unwrap(),
+                &[
+                    token_program.to_account_info(),
+                    token_account.to_account_info(),
+                    program_as_signer.to_account_info(),
+                    wallet.to_account_info(),
+                ],
+            )?;
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,561 @@
+//! Shared escrows hold the funds of a wallet in a treasury mint for every Auction House trading in it. Houses that
+//! accept shared escrows draw what the escrow payment account of a buyer lacks from its shared escrow when a sale
+//! executes, provided the buyer approved them, and bids of the buyer only fund the escrow payment account for what
+//! the shared escrow does not cover. Their bids and sales pass the shared escrow of the buyer, followed by its
+//! approval of the Auction House, in the position listed in the [crate docs](crate#remaining-accounts). Buyers that
+//! did not approve the house pass the empty approval account.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program::{invoke, invoke_signed},
+        system_instruction,
+    },
+    AnchorDeserialize,
+};
+use anchor_spl::associated_token::AssociatedToken;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, migrate::assert_migrated,
+    pda::find_shared_escrow_address, reservation::escrow_balance, utils::*, AuctionHouse,
+    SharedEscrowDrawEvent, SharedEscrowEvent,
+};
+
+pub const SHARED_ESCROW_APPROVAL_SIZE: usize = 8 + // key
+32 + // auction_house
+32 + // wallet
+1; // bump
+
+/// Approval a wallet gives an Auction House to draw from its shared escrow.
+#[account]
+pub struct SharedEscrowApproval {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub bump: u8,
+}
+
+/// Shared escrow of a buyer that approved the Auction House of a sale.
+pub struct ApprovedSharedEscrow<'c, 'info> {
+    pub shared_escrow: &'c AccountInfo<'info>,
+    pub bump: u8,
+}
+
+/// Split the shared escrow of `buyer` and its approval off the end of `remaining_accounts` when the Auction House
+/// accepts shared escrows. Returns `None` when the buyer has not approved the Auction House, and the remaining
+/// accounts left for the handler.
+pub fn split_shared_escrow<'c, 'info>(
+    auction_house: &Account<'info, AuctionHouse>,
+    buyer: &Pubkey,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<(
+    Option<ApprovedSharedEscrow<'c, 'info>>,
+    &'c [AccountInfo<'info>],
+)> {
+    if !auction_house.accepts_shared_escrow {
+        return Ok((None, remaining_accounts));
+    }
+
+    let (shared_escrow, approval, remaining_accounts) = match remaining_accounts {
+        [remaining_accounts @ .., shared_escrow, approval] => {
+            (shared_escrow, approval, remaining_accounts)
+        }
+        _ => return Err(AuctionHouseError::InvalidSharedEscrow.into()),
+    };
+    let (expected, bump) = find_shared_escrow_address(&auction_house.treasury_mint, buyer);
+    if shared_escrow.key() != expected {
+        return Err(AuctionHouseError::InvalidSharedEscrow.into());
+    }
+    if approval.data_is_empty() {
+        return Ok((None, remaining_accounts));
+    }
+    if *approval.owner != crate::id() {
+        return Err(AuctionHouseError::InvalidSharedEscrow.into());
+    }
+
+    let approval =
+        SharedEscrowApproval::try_deserialize(&mut approval.try_borrow_data()?.as_ref())?;
+    if approval.auction_house != auction_house.key() || approval.wallet != *buyer {
+        return Err(AuctionHouseError::InvalidSharedEscrow.into());
+    }
+
+    Ok((
+        Some(ApprovedSharedEscrow {
+            shared_escrow,
+            bump,
+        }),
+        remaining_accounts,
+    ))
+}
+
+/// Funds of an approved shared escrow, excluding the rent exempt minimum of a native shared escrow. Buyers without
+/// one have none.
+pub fn shared_escrow_balance(
+    shared_escrow: Option<&ApprovedSharedEscrow>,
+    is_native: bool,
+) -> Result<u64> {
+    match shared_escrow {
+        Some(ApprovedSharedEscrow { shared_escrow, .. })
+            if is_native || !shared_escrow.data_is_empty() =>
+        {
+            escrow_balance(shared_escrow, is_native)
+        }
+        _ => Ok(0),
+    }
+}
+
+/// Move what the escrow payment account of `wallet` lacks to pay `amount` from its shared escrow.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_shared_escrow<'info>(
+    shared_escrow: &ApprovedSharedEscrow<'_, 'info>,
+    escrow_payment_account: &AccountInfo<'info>,
+    auction_house: &Pubkey,
+    wallet: &Pubkey,
+    treasury_mint: &AccountInfo<'info>,
+    treasury_token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    let shortfall = amount.saturating_sub(escrow_balance(escrow_payment_account, is_native)?);
+    if shortfall == 0 {
+        return Ok(());
+    }
+
+    // Token-2022 transfer fees are withheld from what the escrow payment account is credited.
+    let draw = get_amount_with_transfer_fee(treasury_mint, shortfall)?;
+    if shared_escrow_balance(Some(shared_escrow), is_native)? < draw {
+        return Err(AuctionHouseError::InsufficientFunds.into());
+    }
+
+    let treasury_mint_key = treasury_mint.key();
+    let shared_escrow_seeds = [
+        PREFIX.as_bytes(),
+        SHARED_ESCROW.as_bytes(),
+        treasury_mint_key.as_ref(),
+        wallet.as_ref(),
+        &[shared_escrow.bump],
+    ];
+    if is_native {
+        invoke_signed(
+            &system_instruction::transfer(
+                shared_escrow.shared_escrow.key,
+                escrow_payment_account.key,
+                draw,
+            ),
+            &[
+                shared_escrow.shared_escrow.clone(),
+                escrow_payment_account.clone(),
+                system_program.clone(),
+            ],
+            &[&shared_escrow_seeds],
+        )?;
+    } else {
+        transfer_treasury_tokens(
+            shared_escrow.shared_escrow,
+            escrow_payment_account,
+            shared_escrow.shared_escrow,
+            treasury_mint,
+            treasury_token_program,
+            draw,
+            &[&shared_escrow_seeds],
+        )?;
+    }
+
+    emit!(SharedEscrowDrawEvent {
+        auction_house: *auction_house,
+        wallet: *wallet,
+        shared_escrow: shared_escrow.shared_escrow.key(),
+        amount: draw,
+    });
+
+    Ok(())
+}
+
+/// Accounts for the [`set_accepts_shared_escrow` handler](auction_house/fn.set_accepts_shared_escrow.html).
+#[derive(Accounts)]
+pub struct SetAcceptsSharedEscrow<'info> {
+    /// Auction House authority.
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+}
+
+/// Set whether sales of the Auction House draw from the shared escrows of buyers that approved it. The flag is part
+/// of the version 1 layout, so legacy Auction Houses are migrated first.
+pub fn set_accepts_shared_escrow(
+    ctx: Context<SetAcceptsSharedEscrow>,
+    accepts_shared_escrow: bool,
+) -> Result<()> {
+    let auction_house = &mut ctx.accounts.auction_house;
+    if auction_house.has_governance {
+        return Err(AuctionHouseError::GovernanceRequired.into());
+    }
+    assert_migrated(auction_house)?;
+
+    auction_house.accepts_shared_escrow = accepts_shared_escrow;
+
+    Ok(())
+}
+
+/// Accounts for the [`deposit_shared_escrow` handler](auction_house/fn.deposit_shared_escrow.html).
+#[derive(Accounts)]
+pub struct DepositSharedEscrow<'info> {
+    /// User wallet account. Pays for the rent of the shared escrow.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Validated in deposit_shared_escrow.
+    /// User SOL or SPL account to transfer funds from.
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the token program.
+    /// SPL token account transfer authority.
+    pub transfer_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Part of the shared escrow seeds.
+    /// Treasury mint of the shared escrow.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Shared escrow PDA account of the wallet.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            SHARED_ESCROW.as_bytes(),
+            treasury_mint.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    pub shared_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the treasury mint in deposit_shared_escrow.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    pub token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Deposit `amount` into the shared escrow of the wallet, creating it on the first deposit.
+pub fn deposit_shared_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositSharedEscrow<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let payment_account = &ctx.accounts.payment_account;
+    let transfer_authority = &ctx.accounts.transfer_authority;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let shared_escrow = &ctx.accounts.shared_escrow;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    let bump = *ctx
+        .bumps
+        .get("shared_escrow")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let treasury_mint_key = treasury_mint.key();
+    let wallet_key = wallet.key();
+    let shared_escrow_seeds = [
+        PREFIX.as_bytes(),
+        SHARED_ESCROW.as_bytes(),
+        treasury_mint_key.as_ref(),
+        wallet_key.as_ref(),
+        &[bump],
+    ];
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    if is_native {
+        assert_keys_equal(wallet.key(), payment_account.key())?;
+        let rent_shortfall = verify_deposit(shared_escrow.to_account_info(), amount)?;
+        let checked_amount = amount
+            .checked_add(rent_shortfall)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+        invoke(
+            &system_instruction::transfer(
+                &payment_account.key(),
+                &shared_escrow.key(),
+                checked_amount,
+            ),
+            &[
+                payment_account.to_account_info(),
+                shared_escrow.to_account_info(),
+                system_program.to_account_info(),
+            ],
+        )?;
+    } else {
+        assert_treasury_token_program(treasury_mint, token_program)?;
+        create_program_token_account_if_not_present(
+            shared_escrow,
+            system_program,
+            &wallet.to_account_info(),
+            token_program,
+            treasury_mint,
+            &shared_escrow.to_account_info(),
+            rent,
+            &shared_escrow_seeds,
+            &[],
+            is_native,
+        )?;
+        // The wallet covers any transfer fee so the shared escrow is credited exactly `amount`.
+        transfer_treasury_tokens(
+            payment_account,
+            shared_escrow,
+            transfer_authority,
+            treasury_mint,
+            token_program,
+            get_amount_with_transfer_fee(treasury_mint, amount)?,
+            &[],
+        )?;
+    }
+
+    emit!(SharedEscrowEvent {
+        wallet: wallet.key(),
+        treasury_mint: treasury_mint.key(),
+        shared_escrow: shared_escrow.key(),
+        amount,
+        deposit: true,
+    });
+
+    Ok(())
+}
+
+/// Accounts for the [`withdraw_shared_escrow` handler](auction_house/fn.withdraw_shared_escrow.html).
+#[derive(Accounts)]
+pub struct WithdrawSharedEscrow<'info> {
+    /// User wallet account. Pays for the rent of its treasury mint token account when it does not exist yet.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Validated in withdraw_shared_escrow.
+    /// SPL token account or native SOL account to transfer funds to. If the account is a native SOL account, this is the same as the wallet address.
+    #[account(mut)]
+    pub receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Part of the shared escrow seeds.
+    /// Treasury mint of the shared escrow.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Shared escrow PDA account of the wallet.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            SHARED_ESCROW.as_bytes(),
+            treasury_mint.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    pub shared_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Validated against the treasury mint in withdraw_shared_escrow.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    pub token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Withdraw `amount` from the shared escrow of the wallet.
+pub fn withdraw_shared_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawSharedEscrow<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let receipt_account = &ctx.accounts.receipt_account;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let shared_escrow = &ctx.accounts.shared_escrow;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let ata_program = &ctx.accounts.ata_program;
+    let rent = &ctx.accounts.rent;
+
+    let bump = *ctx
+        .bumps
+        .get("shared_escrow")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let treasury_mint_key = treasury_mint.key();
+    let wallet_key = wallet.key();
+    let shared_escrow_seeds = [
+        PREFIX.as_bytes(),
+        SHARED_ESCROW.as_bytes(),
+        treasury_mint_key.as_ref(),
+        wallet_key.as_ref(),
+        &[bump],
+    ];
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    if is_native {
+        assert_keys_equal(receipt_account.key(), wallet.key())?;
+        let rent_shortfall = verify_withdrawal(shared_escrow.to_account_info(), amount)?;
+        let checked_amount = amount
+            .checked_sub(rent_shortfall)
+            .ok_or(AuctionHouseError::InsufficientFunds)?;
+
+        invoke_signed(
+            &system_instruction::transfer(
+                &shared_escrow.key(),
+                &receipt_account.key(),
+                checked_amount,
+            ),
+            &[
+                shared_escrow.to_account_info(),
+                receipt_account.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            &[&shared_escrow_seeds],
+        )?;
+    } else {
+        assert_treasury_token_program(treasury_mint, token_program)?;
+        if receipt_account.data_is_empty() {
+            make_ata(
+                receipt_account.to_account_info(),
+                wallet.to_account_info(),
+                treasury_mint.to_account_info(),
+                wallet.to_account_info(),
+                ata_program.to_account_info(),
+                token_program.to_account_info(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                &[],
+            )?;
+        }
+
+        let rec_acct = assert_is_treasury_ata(
+            &receipt_account.to_account_info(),
+            &wallet.key(),
+            treasury_mint,
+        )?;
+
+        // make sure you cant get rugged
+        if rec_acct.delegate.is_some() {
+            return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+        }
+
+        transfer_treasury_tokens(
+            shared_escrow,
+            receipt_account,
+            shared_escrow,
+            treasury_mint,
+            token_program,
+            amount,
+            &[&shared_escrow_seeds],
+        )?;
+    }
+
+    emit!(SharedEscrowEvent {
+        wallet: wallet.key(),
+        treasury_mint: treasury_mint.key(),
+        shared_escrow: shared_escrow.key(),
+        amount,
+        deposit: false,
+    });
+
+    Ok(())
+}
+
+/// Accounts for the [`approve_shared_escrow` handler](auction_house/fn.approve_shared_escrow.html).
+#[derive(Accounts)]
+pub struct ApproveSharedEscrow<'info> {
+    /// User wallet account. Pays for the approval account.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Shared escrow approval PDA account of the wallet.
+    #[account(
+        init,
+        payer = wallet,
+        space = SHARED_ESCROW_APPROVAL_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            SHARED_ESCROW_APPROVAL.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    pub shared_escrow_approval: Box<Account<'info, SharedEscrowApproval>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Let sales of the Auction House draw from the shared escrow of the wallet.
+pub fn approve_shared_escrow(ctx: Context<ApproveSharedEscrow>) -> Result<()> {
+    let approval = &mut ctx.accounts.shared_escrow_approval;
+
+    approval.auction_house = ctx.accounts.auction_house.key();
+    approval.wallet = ctx.accounts.wallet.key();
+    approval.bump = *ctx
+        .bumps
+        .get("shared_escrow_approval")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
+
+/// Accounts for the [`revoke_shared_escrow` handler](auction_house/fn.revoke_shared_escrow.html).
+#[derive(Accounts)]
+pub struct RevokeSharedEscrow<'info> {
+    /// User wallet account.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Shared escrow approval PDA account of the wallet. Closed to the wallet.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            SHARED_ESCROW_APPROVAL.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump=shared_escrow_approval.bump,
+        has_one=auction_house,
+        has_one=wallet,
+        close=wallet
+    )]
+    pub shared_escrow_approval: Box<Account<'info, SharedEscrowApproval>>,
+}
+
+/// Stop sales of the Auction House from drawing from the shared escrow of the wallet.
+pub fn revoke_shared_escrow(_ctx: Context<RevokeSharedEscrow>) -> Result<()> {
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
 
-use crate::constants::*;
+use crate::{
+    constants::*, fee_tier::FeeTier, gateway::GatekeeperConfig, reservation::EscrowReservationMode,
+    royalty::RoyaltyPolicy,
+};
 
 #[account]
 pub struct AuctionHouse {
@@ -21,6 +24,32 @@ pub struct AuctionHouse {
     pub has_auctioneer: bool,
     pub auctioneer_address: Pubkey,
     pub scopes: [bool; MAX_NUM_SCOPES],
+    /// Fee charged on a sale to the side whose order was resting, or to both sides of a sale
+    /// signed only by the authority, in basis points.
+    pub maker_fee_basis_points: u16,
+    /// Fee charged on a sale to the side that signed the execute sale, in basis points.
+    pub taker_fee_basis_points: u16,
+    /// Share of the sale price paid out of the house fees to a referrer, in basis points.
+    pub referral_fee_basis_points: u16,
+    /// Trading is restricted by the collection list of the Auction House.
+    pub has_collection_list: bool,
+    /// Whether open bids reserve funds of the buyer escrow.
+    pub escrow_reservation_mode: EscrowReservationMode,
+    /// Sales are recorded in the stats account of the Auction House.
+    pub has_stats: bool,
+    /// Sensitive changes go through the governance of the Auction House.
+    pub has_governance: bool,
+    /// Layout version of the account, 0 for Auction Houses created before versioning. The fields after it need the
+    /// room of the version 1 layout to be set.
+    pub version: u8,
+    /// Sales draw from the shared escrow of buyers that approved the Auction House.
+    pub accepts_shared_escrow: bool,
+    /// Listings and bids require a gateway token of this gatekeeper network.
+    pub gatekeeper: Option<GatekeeperConfig>,
+    /// Share of the metadata royalty paid to creators on sales.
+    pub royalty_policy: RoyaltyPolicy,
+    /// Lower Auction House fees for takers holding a membership token.
+    pub fee_tiers: Vec<FeeTier>,
 }
 
 #[account]
@@ -28,6 +57,17 @@ pub struct Auctioneer {
     pub auctioneer_authority: Pubkey,
     pub auction_house: Pubkey,
     pub bump: u8,
+    /// Scopes of this auctioneer. The auctioneer set with `delegate_auctioneer` uses the Auction House scopes instead.
+    pub scopes: [bool; MAX_NUM_SCOPES],
+}
+
+/// A single order of a `batch_sell` or `batch_cancel` instruction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct BatchOrder {
+    pub buyer_price: u64,
+    pub token_size: u64,
+    /// The order's accounts are followed by the remaining accounts needed for a programmable NFT.
+    pub programmable: bool,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
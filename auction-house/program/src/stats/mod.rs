@@ -0,0 +1,149 @@
+//! Sale statistics of an Auction House, kept on chain so marketplaces can show volume without an indexer.
+//! Once an Auction House has a stats account, every sale, bundle sale and swap passes it and records its price,
+//! royalties and fees in it. Purchase receipts printed right after a sale read the royalty it paid from it.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, pda::find_auction_house_stats_address, AuctionHouse,
+};
+
+pub const AUCTION_HOUSE_STATS_SIZE: usize = 8 + // key
+32 + // auction_house
+8 + // total_volume
+8 + // sale_count
+8 + // royalties_paid
+8 + // fees_collected
+8 + // last_sale_price
+8 + // last_sale_time
+1 + // bump
+8 + // last_sale_royalty
+56; // padding
+
+/// Running totals of the sales of an Auction House, in units of its treasury mint.
+#[account]
+pub struct AuctionHouseStats {
+    pub auction_house: Pubkey,
+    /// Sum of the prices of every sale.
+    pub total_volume: u64,
+    /// Number of sales, counting each partial sale.
+    pub sale_count: u64,
+    /// Royalties paid to creators.
+    pub royalties_paid: u64,
+    /// Fees kept by the Auction House treasury, after any referral fee.
+    pub fees_collected: u64,
+    pub last_sale_price: u64,
+    pub last_sale_time: i64,
+    pub bump: u8,
+    /// Royalties paid to creators on the last sale.
+    pub last_sale_royalty: u64,
+}
+
+impl AuctionHouseStats {
+    /// Add a sale to the totals. Totals saturate rather than fail, so stats can never block a sale.
+    pub fn record_sale(&mut self, price: u64, royalties_paid: u64, fees_collected: u64, now: i64) {
+        self.total_volume = self.total_volume.saturating_add(price);
+        self.sale_count = self.sale_count.saturating_add(1);
+        self.royalties_paid = self.royalties_paid.saturating_add(royalties_paid);
+        self.fees_collected = self.fees_collected.saturating_add(fees_collected);
+        self.last_sale_price = price;
+        self.last_sale_time = now;
+        self.last_sale_royalty = royalties_paid;
+    }
+}
+
+/// Split the stats account off the end of `remaining_accounts` when the Auction House has one.
+/// Returns the stats account and the remaining accounts left for the handler.
+pub fn split_auction_house_stats<'c, 'info>(
+    auction_house: &Account<'info, AuctionHouse>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+) -> Result<(Option<&'c AccountInfo<'info>>, &'c [AccountInfo<'info>])> {
+    if !auction_house.has_stats {
+        return Ok((None, remaining_accounts));
+    }
+
+    let (stats, remaining_accounts) = remaining_accounts
+        .split_last()
+        .ok_or(AuctionHouseError::InvalidAuctionHouseStats)?;
+    let (expected, _) = find_auction_house_stats_address(&auction_house.key());
+    if stats.key() != expected || *stats.owner != crate::id() || !stats.is_writable {
+        return Err(AuctionHouseError::InvalidAuctionHouseStats.into());
+    }
+
+    Ok((Some(stats), remaining_accounts))
+}
+
+/// Record a sale in the stats account of the Auction House.
+pub fn record_sale(
+    stats_info: &AccountInfo,
+    price: u64,
+    royalties_paid: u64,
+    fees_collected: u64,
+) -> Result<()> {
+    let mut stats = {
+        let data = stats_info.try_borrow_data()?;
+        AuctionHouseStats::try_deserialize(&mut data.as_ref())?
+    };
+    stats.record_sale(
+        price,
+        royalties_paid,
+        fees_collected,
+        Clock::get()?.unix_timestamp,
+    );
+
+    let mut data = stats_info.try_borrow_mut_data()?;
+    stats.try_serialize(&mut *data)
+}
+
+/// Accounts for the [`create_auction_house_stats` handler](auction_house/fn.create_auction_house_stats.html).
+#[derive(Accounts)]
+pub struct CreateAuctionHouseStats<'info> {
+    /// Auction House authority. Pays for the stats account.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Auction House stats PDA account.
+    #[account(
+        init,
+        payer = authority,
+        space = AUCTION_HOUSE_STATS_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            STATS.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump
+    )]
+    pub auction_house_stats: Box<Account<'info, AuctionHouseStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the stats account of an Auction House. Sales record their totals in it from then on.
+pub fn create_auction_house_stats(ctx: Context<CreateAuctionHouseStats>) -> Result<()> {
+    let auction_house = &mut ctx.accounts.auction_house;
+    let stats = &mut ctx.accounts.auction_house_stats;
+
+    stats.auction_house = auction_house.key();
+    stats.bump = *ctx
+        .bumps
+        .get("auction_house_stats")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    auction_house.has_stats = true;
+
+    Ok(())
+}
@@ -0,0 +1,806 @@
+//! Swap offers trade one NFT for another, optionally with a top-up paid from the offerer's escrow.
+//! The offered token is escrowed through the `program_as_signer` delegate, and creator royalties and the
+//! Auction House fee are paid on the top-up only.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, program::invoke_signed, system_instruction},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+use mpl_utils::token::{spl_token_transfer, TokenTransferParams};
+use solana_program::program_pack::Pack;
+use spl_token::{
+    instruction::{approve, revoke},
+    state::Account as SplAccount,
+};
+
+use crate::{
+    constants::*,
+    curation::assert_collection_list_allows,
+    errors::AuctionHouseError,
+    fee_tier::{fee_tier_basis_points, split_fee_tier_holder},
+    gateway::assert_gateway_token,
+    shared_escrow::{draw_shared_escrow, split_shared_escrow},
+    stats::{record_sale, split_auction_house_stats},
+    utils::*,
+    AuctionHouse, AuthorityScope,
+};
+
+pub const SWAP_OFFER_SIZE: usize = 8 + // key
+32 + // auction_house
+32 + // offerer
+32 + // offered_token_account
+32 + // offered_mint
+32 + // requested_mint
+8 + // top_up
+1; // bump
+
+/// Offer of a token in exchange for any token of `requested_mint`, plus `top_up` paid to the taker.
+#[account]
+pub struct SwapOffer {
+    pub auction_house: Pubkey,
+    pub offerer: Pubkey,
+    pub offered_token_account: Pubkey,
+    pub offered_mint: Pubkey,
+    pub requested_mint: Pubkey,
+    pub top_up: u64,
+    pub bump: u8,
+}
+
+/// Accounts for the [`make_swap_offer` handler](auction_house/fn.make_swap_offer.html).
+#[derive(Accounts)]
+pub struct MakeSwapOffer<'info> {
+    /// User wallet account making the offer. Pays for the swap offer account.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Validated as a signer in make_swap_offer when the Auction House requires sign off.
+    /// Auction House authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Token account of the offered token.
+    #[account(mut)]
+    pub offered_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated by assert_metadata_valid.
+    /// Metaplex metadata account of the offered token.
+    pub offered_metadata: UncheckedAccount<'info>,
+
+    /// Mint of the token requested in exchange.
+    pub requested_mint: Box<Account<'info, Mint>>,
+
+    /// Swap offer PDA account.
+    #[account(
+        init,
+        payer = wallet,
+        space = SWAP_OFFER_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            SWAP.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            offered_token_account.key().as_ref(),
+            requested_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub swap_offer: Box<Account<'info, SwapOffer>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts:
+    // [collection list if curated], [...gateway accounts if gated]
+}
+
+/// Accounts for the [`accept_swap_offer` handler](auction_house/fn.accept_swap_offer.html).
+#[derive(Accounts)]
+pub struct AcceptSwapOffer<'info> {
+    /// User wallet account holding the requested token.
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// CHECK: Validated by the swap offer constraint.
+    /// User wallet account that made the offer.
+    #[account(mut)]
+    pub offerer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the auction house constraint.
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Offerer escrow payment account the top-up is paid from.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            offerer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap_offer.
+    /// Taker SOL or SPL account to receive the top-up at.
+    #[account(mut)]
+    pub taker_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap_offer.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// Swap offer PDA account. Closed to the offerer once the swap is executed.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            SWAP.as_bytes(),
+            offerer.key().as_ref(),
+            auction_house.key().as_ref(),
+            offered_token_account.key().as_ref(),
+            requested_mint.key().as_ref()
+        ],
+        bump=swap_offer.bump,
+        has_one=auction_house,
+        has_one=offerer,
+        has_one=offered_token_account,
+        has_one=offered_mint,
+        has_one=requested_mint,
+        close=offerer
+    )]
+    pub swap_offer: Box<Account<'info, SwapOffer>>,
+
+    /// CHECK: Validated by the swap offer constraint and in accept_swap_offer.
+    /// Offerer token account of the offered token.
+    #[account(mut)]
+    pub offered_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the swap offer constraint.
+    /// Mint of the offered token.
+    pub offered_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap_offer.
+    /// Taker token account to receive the offered token at.
+    #[account(mut)]
+    pub taker_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap_offer.
+    /// Taker token account of the requested token.
+    #[account(mut)]
+    pub requested_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the swap offer constraint.
+    /// Mint of the requested token.
+    pub requested_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap_offer.
+    /// Metaplex metadata account of the requested token. Royalties on the top-up are paid to its creators.
+    pub requested_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap_offer.
+    /// Offerer token account to receive the requested token at.
+    #[account(mut)]
+    pub offerer_receipt_token_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    // remaining accounts:
+    // the Token-2022 program for Token-2022 treasury mints, ...creator accounts of the requested token,
+    // then the optional referrer wallet and its treasury mint ATA, [stats if tracked], [collection list if curated],
+    // [...fee tier holder accounts if tiered], [...shared escrow accounts of the offerer if accepted],
+    // [...gateway accounts of the taker if gated]
+}
+
+/// Accounts for the [`cancel_swap_offer` handler](auction_house/fn.cancel_swap_offer.html).
+#[derive(Accounts)]
+pub struct CancelSwapOffer<'info> {
+    /// User wallet account that made the offer.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Swap offer PDA account. Closed to the offerer.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            SWAP.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            offered_token_account.key().as_ref(),
+            swap_offer.requested_mint.as_ref()
+        ],
+        bump=swap_offer.bump,
+        has_one=auction_house,
+        has_one=offered_token_account,
+        constraint = swap_offer.offerer == wallet.key() @ AuctionHouseError::PublicKeyMismatch,
+        close=wallet
+    )]
+    pub swap_offer: Box<Account<'info, SwapOffer>>,
+
+    /// CHECK: Validated by the swap offer constraint.
+    /// Offerer token account of the offered token.
+    #[account(mut)]
+    pub offered_token_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Offer the token in `offered_token_account` for any token of `requested_mint`, adding `top_up` from the
+/// wallet's escrow. The top-up must be deposited in the escrow before the offer is accepted.
+/// Only SPL tokens are supported, so programmable NFTs can not be swapped.
+pub fn make_swap_offer<'info>(
+    ctx: Context<'_, '_, '_, 'info, MakeSwapOffer<'info>>,
+    top_up: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let offered_token_account = &ctx.accounts.offered_token_account;
+    let offered_metadata = &ctx.accounts.offered_metadata;
+    let requested_mint = &ctx.accounts.requested_mint;
+    let swap_offer = &mut ctx.accounts.swap_offer;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let token_program = &ctx.accounts.token_program;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Sell as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if auction_house.requires_sign_off && !authority.is_signer {
+        return Err(AuctionHouseError::CannotTakeThisActionWithoutAuctionHouseSignOff.into());
+    }
+
+    if offered_token_account.mint == requested_mint.key() {
+        return Err(AuctionHouseError::InvalidSwapOffer.into());
+    }
+
+    assert_is_ata(
+        &offered_token_account.to_account_info(),
+        &wallet.key(),
+        &offered_token_account.mint,
+    )?;
+    assert_metadata_valid(offered_metadata, offered_token_account)?;
+    let remaining_accounts = assert_gateway_token(
+        auction_house,
+        &wallet.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    assert_collection_list_allows(auction_house, offered_metadata, remaining_accounts)?;
+
+    if offered_token_account.amount < 1 {
+        return Err(AuctionHouseError::InvalidTokenAmount.into());
+    }
+
+    invoke(
+        &approve(
+            &token_program.key(),
+            &offered_token_account.key(),
+            &program_as_signer.key(),
+            &wallet.key(),
+            &[],
+            1,
+        )?,
+        &[
+            token_program.to_account_info(),
+            offered_token_account.to_account_info(),
+            program_as_signer.to_account_info(),
+            wallet.to_account_info(),
+        ],
+    )?;
+
+    swap_offer.auction_house = auction_house.key();
+    swap_offer.offerer = wallet.key();
+    swap_offer.offered_token_account = offered_token_account.key();
+    swap_offer.offered_mint = offered_token_account.mint;
+    swap_offer.requested_mint = requested_mint.key();
+    swap_offer.top_up = top_up;
+    swap_offer.bump = *ctx
+        .bumps
+        .get("swap_offer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
+
+/// Accept a swap offer with a token of the requested mint. Both tokens change hands, and the top-up is paid from
+/// the offerer's escrow to the taker after creator royalties of the requested token and the Auction House fee.
+pub fn accept_swap_offer<'info>(
+    ctx: Context<'_, '_, '_, 'info, AcceptSwapOffer<'info>>,
+) -> Result<()> {
+    let taker = &ctx.accounts.taker;
+    let offerer = &ctx.accounts.offerer;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let escrow_payment_account = &ctx.accounts.escrow_payment_account;
+    let taker_payment_receipt_account = &ctx.accounts.taker_payment_receipt_account;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let auction_house_treasury = &ctx.accounts.auction_house_treasury;
+    let swap_offer = &ctx.accounts.swap_offer;
+    let offered_token_account = &ctx.accounts.offered_token_account;
+    let offered_mint = &ctx.accounts.offered_mint;
+    let taker_receipt_token_account = &ctx.accounts.taker_receipt_token_account;
+    let requested_token_account = &ctx.accounts.requested_token_account;
+    let requested_mint = &ctx.accounts.requested_mint;
+    let requested_metadata = &ctx.accounts.requested_metadata;
+    let offerer_receipt_token_account = &ctx.accounts.offerer_receipt_token_account;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let ata_program = &ctx.accounts.ata_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let rent = &ctx.accounts.rent;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let escrow_payment_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    let offered_token_account_data =
+        assert_is_ata(offered_token_account, &offerer.key(), &offered_mint.key())?;
+    if offered_token_account_data.delegate != Some(program_as_signer.key()).into() {
+        msg!("No delegate detected on token account.");
+        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+    }
+    if offered_token_account_data.amount < 1 {
+        return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+    }
+
+    let requested_token_account_data =
+        assert_is_ata(requested_token_account, &taker.key(), &requested_mint.key())?;
+    if requested_token_account_data.amount < 1 {
+        return Err(AuctionHouseError::InvalidTokenAmount.into());
+    }
+
+    assert_derivation(
+        &mpl_token_metadata::id(),
+        requested_metadata,
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            requested_mint.key().as_ref(),
+        ],
+    )?;
+    if requested_metadata.data_is_empty() {
+        return Err(AuctionHouseError::MetadataDoesntExist.into());
+    }
+    // The taker sells the requested token, so gated Auction Houses check its gateway token.
+    let remaining_accounts = assert_gateway_token(
+        auction_house,
+        &taker.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    let (shared_escrow, remaining_accounts) =
+        split_shared_escrow(auction_house, &offerer.key(), remaining_accounts)?;
+    let (fee_tier_holder, remaining_accounts) =
+        split_fee_tier_holder(auction_house, remaining_accounts)?;
+    let remaining_accounts =
+        assert_collection_list_allows(auction_house, requested_metadata, remaining_accounts)?;
+    let (auction_house_stats, remaining_accounts) =
+        split_auction_house_stats(auction_house, remaining_accounts)?;
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    let top_up = swap_offer.top_up;
+
+    // The taker sells the requested token to the offerer for the top-up, and takes the offer unless the offerer
+    // signed as well.
+    let sale_taker = get_sale_taker(offerer, taker);
+    let taker_fee_tier = fee_tier_basis_points(
+        auction_house,
+        sale_taker.map(|(_, key)| key),
+        fee_tier_holder.as_ref(),
+    )?;
+    let mut sale_fees = get_sale_fees(
+        auction_house,
+        top_up,
+        sale_taker.map(|(side, _)| side),
+        taker_fee_tier,
+    )?;
+    let offerer_total = top_up
+        .checked_add(sale_fees.buyer_fee)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        taker.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let (royalties_paid, fees_collected) = if top_up > 0 {
+        // An offerer that approved the Auction House tops up its escrow from its shared escrow. Token-2022 treasury
+        // mints find their token program among the remaining accounts.
+        if let Some(shared_escrow) = shared_escrow {
+            let token_clone = token_program.to_account_info();
+            let treasury_token_program = remaining_accounts
+                .iter()
+                .find(|account| account.key() == *treasury_mint.owner)
+                .unwrap_or(&token_clone);
+            draw_shared_escrow(
+                &shared_escrow,
+                &escrow_payment_account.to_account_info(),
+                &auction_house_key,
+                &offerer.key(),
+                &treasury_mint.to_account_info(),
+                treasury_token_program,
+                &system_program.to_account_info(),
+                offerer_total,
+            )?;
+        }
+
+        // For native purchases, verify that the amount in escrow is sufficient to actually pay the top-up.
+        if is_native {
+            let rent_shortfall =
+                verify_withdrawal(escrow_payment_account.to_account_info(), offerer_total)?;
+            if rent_shortfall > 0 {
+                invoke_signed(
+                    &system_instruction::transfer(
+                        fee_payer.key,
+                        escrow_payment_account.key,
+                        rent_shortfall,
+                    ),
+                    &[
+                        fee_payer.to_account_info(),
+                        escrow_payment_account.to_account_info(),
+                        system_program.to_account_info(),
+                    ],
+                    &[fee_payer_seeds],
+                )?;
+            }
+        }
+
+        let offerer_key = offerer.key();
+        let escrow_signer_seeds = [
+            PREFIX.as_bytes(),
+            auction_house_key.as_ref(),
+            offerer_key.as_ref(),
+            &[escrow_payment_bump],
+        ];
+
+        let ah_seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            &[auction_house.bump],
+        ];
+
+        // with the native account, the escrow is its own owner,
+        // whereas with token, it is the auction house that is owner.
+        let signer_seeds_for_royalties = if is_native {
+            escrow_signer_seeds
+        } else {
+            ah_seeds
+        };
+
+        let escrow_clone = escrow_payment_account.to_account_info();
+        let treasury_clone = auction_house_treasury.to_account_info();
+        let token_clone = token_program.to_account_info();
+        let sys_clone = system_program.to_account_info();
+
+        let treasury_mint_clone = treasury_mint.to_account_info();
+        let (treasury_token_clone, remaining_accounts) =
+            split_treasury_token_program(&treasury_mint_clone, &token_clone, remaining_accounts)?;
+        let remaining_accounts = &mut remaining_accounts.iter();
+        let taker_leftover_after_royalties = pay_creator_fees(
+            remaining_accounts,
+            requested_metadata,
+            &escrow_clone,
+            &auction_house.to_account_info(),
+            &fee_payer,
+            &treasury_mint_clone,
+            &ata_program.to_account_info(),
+            &treasury_token_clone,
+            &sys_clone,
+            &rent.to_account_info(),
+            &signer_seeds_for_royalties,
+            fee_payer_seeds,
+            top_up,
+            is_native,
+            &auction_house.royalty_policy,
+            None,
+        )?;
+
+        // The accounts left after the creators are the optional referrer wallet and, for SPL treasury mints,
+        // its treasury mint ATA.
+        pay_sale_fees(
+            auction_house,
+            &mut sale_fees,
+            remaining_accounts.as_slice(),
+            &offerer_key,
+            &taker.key(),
+            &treasury_clone,
+            &escrow_clone,
+            &treasury_mint_clone,
+            &treasury_token_clone,
+            &sys_clone,
+            &ata_program.to_account_info(),
+            &rent.to_account_info(),
+            &fee_payer,
+            fee_payer_seeds,
+            &signer_seeds_for_royalties,
+            is_native,
+        )?;
+
+        let taker_leftover_after_royalties_and_house_fee = taker_leftover_after_royalties
+            .checked_sub(sale_fees.seller_fee)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+        if !is_native {
+            if taker_payment_receipt_account.data_is_empty() {
+                make_ata(
+                    taker_payment_receipt_account.to_account_info(),
+                    taker.to_account_info(),
+                    treasury_mint.to_account_info(),
+                    fee_payer.to_account_info(),
+                    ata_program.to_account_info(),
+                    treasury_token_clone.clone(),
+                    system_program.to_account_info(),
+                    rent.to_account_info(),
+                    fee_payer_seeds,
+                )?;
+            }
+
+            let taker_rec_acct = assert_is_treasury_ata(
+                &taker_payment_receipt_account.to_account_info(),
+                &taker.key(),
+                &treasury_mint_clone,
+            )?;
+
+            // make sure you cant get rugged
+            if taker_rec_acct.delegate.is_some() {
+                return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
+            }
+
+            transfer_treasury_tokens(
+                &escrow_clone,
+                &taker_payment_receipt_account.to_account_info(),
+                &auction_house.to_account_info(),
+                &treasury_mint_clone,
+                &treasury_token_clone,
+                taker_leftover_after_royalties_and_house_fee,
+                &[&ah_seeds],
+            )?;
+        } else {
+            assert_keys_equal(taker_payment_receipt_account.key(), taker.key())?;
+            invoke_signed(
+                &system_instruction::transfer(
+                    escrow_payment_account.key,
+                    taker_payment_receipt_account.key,
+                    taker_leftover_after_royalties_and_house_fee,
+                ),
+                &[
+                    escrow_payment_account.to_account_info(),
+                    taker_payment_receipt_account.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+                &[&escrow_signer_seeds],
+            )?;
+        }
+
+        let royalty_paid = top_up
+            .checked_sub(taker_leftover_after_royalties)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        (royalty_paid, sale_fees.treasury_fee()?)
+    } else {
+        (0, 0)
+    };
+
+    // Offered token to the taker, moved by the program delegate.
+    if taker_receipt_token_account.data_is_empty() {
+        make_ata(
+            taker_receipt_token_account.to_account_info(),
+            taker.to_account_info(),
+            offered_mint.to_account_info(),
+            fee_payer.to_account_info(),
+            ata_program.to_account_info(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+            fee_payer_seeds,
+        )?;
+    }
+
+    let taker_rec_acct = assert_is_ata(
+        taker_receipt_token_account,
+        &taker.key(),
+        &offered_mint.key(),
+    )?;
+
+    // make sure you cant get rugged
+    if taker_rec_acct.delegate.is_some() {
+        return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+    }
+
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    spl_token_transfer(TokenTransferParams {
+        mint: offered_mint.to_account_info(),
+        source: offered_token_account.to_account_info(),
+        destination: taker_receipt_token_account.to_account_info(),
+        amount: 1,
+        authority: program_as_signer.to_account_info(),
+        authority_signer_seeds: Some(&program_as_signer_seeds),
+        token_program: token_program.to_account_info(),
+    })?;
+
+    // Requested token to the offerer, moved by the taker.
+    if offerer_receipt_token_account.data_is_empty() {
+        make_ata(
+            offerer_receipt_token_account.to_account_info(),
+            offerer.to_account_info(),
+            requested_mint.to_account_info(),
+            fee_payer.to_account_info(),
+            ata_program.to_account_info(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+            fee_payer_seeds,
+        )?;
+    }
+
+    let offerer_rec_acct = assert_is_ata(
+        offerer_receipt_token_account,
+        &offerer.key(),
+        &requested_mint.key(),
+    )?;
+
+    // make sure you cant get rugged
+    if offerer_rec_acct.delegate.is_some() {
+        return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+    }
+
+    spl_token_transfer(TokenTransferParams {
+        mint: requested_mint.to_account_info(),
+        source: requested_token_account.to_account_info(),
+        destination: offerer_receipt_token_account.to_account_info(),
+        amount: 1,
+        authority: taker.to_account_info(),
+        authority_signer_seeds: None,
+        token_program: token_program.to_account_info(),
+    })?;
+
+    // A swap counts as a sale of the requested token for the top-up.
+    if let Some(auction_house_stats) = auction_house_stats {
+        record_sale(auction_house_stats, top_up, royalties_paid, fees_collected)?;
+    }
+
+    Ok(())
+}
+
+/// Cancel a swap offer, revoking the program delegate of the offered token if the offerer still holds it.
+pub fn cancel_swap_offer<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelSwapOffer<'info>>,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let auction_house = &ctx.accounts.auction_house;
+    let offered_token_account = &ctx.accounts.offered_token_account;
+    let token_program = &ctx.accounts.token_program;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Cancel as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    // A token that was moved or closed since the offer has nothing left to revoke.
+    match SplAccount::unpack(&offered_token_account.data.borrow()) {
+        Ok(data) if data.owner == wallet.key() && data.delegate.is_some() => {}
+        _ => return Ok(()),
+    };
+
+    invoke(
+        &revoke(
+            &token_program.key(),
+            &offered_token_account.key(),
+            &wallet.key(),
+            &[],
+        )?,
+        &[
+            token_program.to_account_info(),
+            offered_token_account.to_account_info(),
+            wallet.to_account_info(),
+        ],
+    )?;
+
+    Ok(())
+}
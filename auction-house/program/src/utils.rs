@@ -1,5 +1,6 @@
 use crate::{
-    constants::*, errors::AuctionHouseError, AuctionHouse, Auctioneer, AuthorityScope, PREFIX,
+    constants::*, errors::AuctionHouseError, merkle_proof, royalty::RoyaltyPolicy, AuctionHouse,
+    Auctioneer, AuthorityScope, PREFIX,
 };
 
 use anchor_lang::{
@@ -13,10 +14,18 @@ use anchor_lang::{
         system_instruction,
     },
 };
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token::{Mint, TokenAccount};
 use arrayref::array_ref;
 use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
-use spl_token::{instruction::initialize_account2, state::Account as SplAccount};
+use spl_token::state::Account as SplAccount;
+use spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+    instruction::{initialize_account2, transfer_checked},
+    state::{Account as TreasuryTokenAccount, Mint as TreasuryMint},
+};
 use std::{convert::TryInto, slice::Iter};
 
 pub fn assert_is_ata(ata: &AccountInfo, wallet: &Pubkey, mint: &Pubkey) -> Result<SplAccount> {
@@ -52,7 +61,7 @@ pub fn make_ata<'a>(
             fee_payer.key,
             wallet.key,
             mint.key,
-            &spl_token::ID,
+            token_program.key,
         ),
         &[
             ata,
@@ -162,6 +171,180 @@ pub fn assert_valid_delegation(
     Ok(())
 }
 
+/// Checks that `token_program` is the SPL Token or Token-2022 program owning `treasury_mint`.
+pub fn assert_treasury_token_program(
+    treasury_mint: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<()> {
+    if (*token_program.key != spl_token::id() && *token_program.key != spl_token_2022::id())
+        || treasury_mint.owner != token_program.key
+    {
+        return Err(AuctionHouseError::InvalidTreasuryTokenProgram.into());
+    }
+
+    Ok(())
+}
+
+/// `assert_is_ata` for treasury mint token accounts, which may belong to Token-2022 and carry extensions.
+pub fn assert_is_treasury_ata(
+    ata: &AccountInfo,
+    wallet: &Pubkey,
+    treasury_mint: &AccountInfo,
+) -> Result<TreasuryTokenAccount> {
+    assert_owned_by(ata, treasury_mint.owner)?;
+    let ata_account = get_treasury_token_account(ata)?;
+    assert_keys_equal(ata_account.owner, *wallet)?;
+    assert_keys_equal(ata_account.mint, treasury_mint.key())?;
+
+    Ok(ata_account)
+}
+
+/// Unpack an SPL Token or Token-2022 token account, ignoring any extensions.
+pub fn get_treasury_token_account(account: &AccountInfo) -> Result<TreasuryTokenAccount> {
+    let data = account.try_borrow_data()?;
+    Ok(StateWithExtensions::<TreasuryTokenAccount>::unpack(&data)?.base)
+}
+
+/// Amount to send for the destination to be credited exactly `amount` once the Token-2022 transfer
+/// fee of `treasury_mint` is withheld. SPL Token mints and mints without a transfer fee return `amount`.
+pub fn get_amount_with_transfer_fee(treasury_mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *treasury_mint.owner != spl_token_2022::id() {
+        return Ok(amount);
+    }
+
+    let data = treasury_mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<TreasuryMint>::unpack(&data)?;
+    let transfer_fee_config = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config,
+        Err(_) => return Ok(amount),
+    };
+
+    let transfer_fee = transfer_fee_config.get_epoch_fee(Clock::get()?.epoch);
+    transfer_fee
+        .calculate_pre_fee_amount(amount)
+        .filter(|pre_fee_amount| {
+            transfer_fee.calculate_post_fee_amount(*pre_fee_amount) == Some(amount)
+        })
+        .ok_or_else(|| AuctionHouseError::NumericalOverflow.into())
+}
+
+/// `token_program` moves the NFT, so Token-2022 treasury mints pass their token program as the first remaining
+/// account. Returns the program moving treasury mint tokens and the accounts after it.
+pub fn split_treasury_token_program<'a, 'b>(
+    treasury_mint: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    remaining_accounts: &'b [AccountInfo<'a>],
+) -> Result<(AccountInfo<'a>, &'b [AccountInfo<'a>])> {
+    if *treasury_mint.owner != spl_token_2022::id() {
+        return Ok((token_program.clone(), remaining_accounts));
+    }
+
+    let (treasury_token_program, remaining_accounts) = remaining_accounts
+        .split_first()
+        .ok_or(AuctionHouseError::InvalidTreasuryTokenProgram)?;
+    assert_keys_equal(treasury_token_program.key(), spl_token_2022::id())?;
+
+    Ok((treasury_token_program.clone(), remaining_accounts))
+}
+
+/// Move `amount` treasury mint tokens with `transfer_checked` through the program owning the mint.
+pub fn transfer_treasury_tokens<'a>(
+    source: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    assert_treasury_token_program(treasury_mint, token_program)?;
+    let decimals = {
+        let data = treasury_mint.try_borrow_data()?;
+        StateWithExtensions::<TreasuryMint>::unpack(&data)?
+            .base
+            .decimals
+    };
+
+    invoke_signed(
+        &transfer_checked(
+            token_program.key,
+            source.key,
+            treasury_mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            source.clone(),
+            treasury_mint.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+/// Transfer `amount` from the Auction House treasury to `destination`, in native SOL or treasury tokens.
+pub fn withdraw_treasury_funds<'a>(
+    auction_house: &Account<'a, AuctionHouse>,
+    treasury_mint: &AccountInfo<'a>,
+    auction_house_treasury: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    amount: u64,
+) -> Result<()> {
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    assert_treasury_token_program(treasury_mint, token_program)?;
+
+    let auction_house_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    let ah_key = auction_house.key();
+    let auction_house_treasury_seeds = [
+        PREFIX.as_bytes(),
+        ah_key.as_ref(),
+        TREASURY.as_bytes(),
+        &[auction_house.treasury_bump],
+    ];
+    if !is_native {
+        transfer_treasury_tokens(
+            auction_house_treasury,
+            destination,
+            &auction_house.to_account_info(),
+            treasury_mint,
+            token_program,
+            amount,
+            &[&auction_house_seeds],
+        )?;
+    } else {
+        invoke_signed(
+            &system_instruction::transfer(
+                &auction_house_treasury.key(),
+                &destination.key(),
+                amount,
+            ),
+            &[
+                auction_house_treasury.clone(),
+                destination.clone(),
+                system_program.clone(),
+            ],
+            &[&auction_house_treasury_seeds],
+        )?;
+    }
+
+    Ok(())
+}
+
 pub fn assert_keys_equal(key1: Pubkey, key2: Pubkey) -> Result<()> {
     if sol_memcmp(key1.as_ref(), key2.as_ref(), PUBKEY_BYTES) != 0 {
         err!(AuctionHouseError::PublicKeyMismatch)
@@ -184,24 +367,30 @@ pub enum BidType {
     PrivateSale,
     AuctioneerPublicSale,
     AuctioneerPrivateSale,
+    CollectionSale,
 }
 
 #[derive(Debug, Clone)]
 pub enum ListingType {
     Sell,
     AuctioneerSell,
+    SellDutch,
 }
 
 #[derive(Debug, Clone)]
 pub enum PurchaseType {
     ExecuteSale,
     AuctioneerExecuteSale,
+    ExecuteCollectionSale,
+    ExecuteDutchSale,
 }
 
 #[derive(Debug, Clone)]
 pub enum CancelType {
     Cancel,
     AuctioneerCancel,
+    CancelCollectionBuy,
+    CancelDutch,
 }
 
 pub fn assert_program_bid_instruction(sighash: &[u8]) -> Result<BidType> {
@@ -210,6 +399,7 @@ pub fn assert_program_bid_instruction(sighash: &[u8]) -> Result<BidType> {
         [102, 6, 61, 18, 1, 218, 235, 234] => Ok(BidType::PrivateSale),
         [221, 239, 99, 240, 86, 46, 213, 126] => Ok(BidType::AuctioneerPublicSale),
         [17, 106, 133, 46, 229, 48, 45, 208] => Ok(BidType::AuctioneerPrivateSale),
+        [53, 107, 148, 41, 184, 45, 177, 113] => Ok(BidType::CollectionSale),
         _ => Err(AuctionHouseError::InstructionMismatch.into()),
     }
 }
@@ -218,14 +408,30 @@ pub fn assert_program_listing_instruction(sighash: &[u8]) -> Result<ListingType>
     match sighash {
         [51, 230, 133, 164, 1, 127, 131, 173] => Ok(ListingType::Sell),
         [251, 60, 142, 195, 121, 203, 26, 183] => Ok(ListingType::AuctioneerSell),
+        [108, 165, 98, 136, 130, 154, 86, 181] => Ok(ListingType::SellDutch),
         _ => Err(AuctionHouseError::InstructionMismatch.into()),
     }
 }
 
+/// Whether `sighash` belongs to `set_allowed_buyer`.
+pub fn is_program_set_allowed_buyer_instruction(sighash: &[u8]) -> bool {
+    sighash == [228, 5, 13, 108, 114, 71, 32, 20]
+}
+
+/// Whether `sighash` belongs to `print_listing_receipt` or `print_bid_receipt`.
+pub fn is_program_print_receipt_instruction(sighash: &[u8]) -> bool {
+    matches!(
+        sighash,
+        [207, 107, 44, 160, 75, 222, 195, 27] | [94, 249, 90, 230, 239, 64, 68, 218]
+    )
+}
+
 pub fn assert_program_purchase_instruction(sighash: &[u8]) -> Result<PurchaseType> {
     match sighash {
         [37, 74, 217, 157, 79, 49, 35, 6] => Ok(PurchaseType::ExecuteSale),
         [68, 125, 32, 65, 251, 43, 35, 53] => Ok(PurchaseType::AuctioneerExecuteSale),
+        [213, 13, 253, 255, 139, 53, 120, 16] => Ok(PurchaseType::ExecuteCollectionSale),
+        [2, 139, 27, 40, 242, 101, 137, 183] => Ok(PurchaseType::ExecuteDutchSale),
         _ => Err(AuctionHouseError::InstructionMismatch.into()),
     }
 }
@@ -234,6 +440,8 @@ pub fn assert_program_cancel_instruction(sighash: &[u8]) -> Result<CancelType> {
     match sighash {
         [232, 219, 223, 41, 219, 236, 220, 190] => Ok(CancelType::Cancel),
         [197, 97, 152, 196, 115, 204, 64, 215] => Ok(CancelType::AuctioneerCancel),
+        [90, 118, 170, 66, 37, 219, 106, 231] => Ok(CancelType::CancelCollectionBuy),
+        [136, 121, 208, 234, 234, 149, 228, 2] => Ok(CancelType::CancelDutch),
         _ => Err(AuctionHouseError::InstructionMismatch.into()),
     }
 }
@@ -263,65 +471,284 @@ pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> Result<()> {
     }
 }
 
+/// Fees owed to the Auction House and a referrer on a single sale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaleFees {
+    /// Seller fee plus the seller's maker or taker fee, deducted from the sale price.
+    pub seller_fee: u64,
+    /// The buyer's maker or taker fee, charged from escrow on top of the sale price.
+    pub buyer_fee: u64,
+    /// Part of `seller_fee + buyer_fee` paid to the referrer instead of the treasury, if there is one.
+    pub referral_fee: u64,
+}
+
+impl SaleFees {
+    /// Amount left for the treasury once the referrer is paid.
+    pub fn treasury_fee(&self) -> Result<u64> {
+        self.seller_fee
+            .checked_add(self.buyer_fee)
+            .and_then(|fee| fee.checked_sub(self.referral_fee))
+            .ok_or_else(|| AuctionHouseError::NumericalOverflow.into())
+    }
+}
+
+/// Set the fee basis points of the Auction House, `None` keeping the current value, and check that the
+/// resulting fees can be paid out of a sale.
+pub fn set_fee_basis_points(
+    auction_house: &mut AuctionHouse,
+    seller_fee_basis_points: Option<u16>,
+    maker_fee_basis_points: Option<u16>,
+    taker_fee_basis_points: Option<u16>,
+    referral_fee_basis_points: Option<u16>,
+) -> Result<()> {
+    if let Some(sfbp) = seller_fee_basis_points {
+        if sfbp > 10000 {
+            return Err(AuctionHouseError::InvalidBasisPoints.into());
+        }
+
+        auction_house.seller_fee_basis_points = sfbp;
+    }
+
+    if let Some(mfbp) = maker_fee_basis_points {
+        auction_house.maker_fee_basis_points = mfbp;
+    }
+    if let Some(tfbp) = taker_fee_basis_points {
+        auction_house.taker_fee_basis_points = tfbp;
+    }
+    if let Some(rfbp) = referral_fee_basis_points {
+        auction_house.referral_fee_basis_points = rfbp;
+    }
+
+    // The seller pays the seller fee plus its maker or taker fee out of the sale price.
+    let max_seller_fee_basis_points = auction_house.seller_fee_basis_points as u32
+        + auction_house
+            .maker_fee_basis_points
+            .max(auction_house.taker_fee_basis_points) as u32;
+    if max_seller_fee_basis_points > 10000 || auction_house.referral_fee_basis_points > 10000 {
+        return Err(AuctionHouseError::InvalidBasisPoints.into());
+    }
+
+    Ok(())
+}
+
+/// Side of a sale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaleSide {
+    Buyer,
+    Seller,
+}
+
+/// Side that took the resting order of a sale, i.e. the one that signed it, the buyer when both did, and its wallet.
+/// A sale matched by the Auction House authority alone has no taker.
+pub fn get_sale_taker<'a>(
+    buyer: &'a AccountInfo,
+    seller: &'a AccountInfo,
+) -> Option<(SaleSide, &'a Pubkey)> {
+    if buyer.is_signer {
+        Some((SaleSide::Buyer, buyer.key))
+    } else if seller.is_signer {
+        Some((SaleSide::Seller, seller.key))
+    } else {
+        None
+    }
+}
+
+/// Fees for a sale of `size`. The `taker` side pays the taker fee and the other side the maker fee, so a sale
+/// without a taker charges both sides the maker fee. The fee tier of the taker, in basis points, caps the fee the
+/// taker pays.
+pub fn get_sale_fees(
+    auction_house: &AuctionHouse,
+    size: u64,
+    taker: Option<SaleSide>,
+    taker_fee_tier: Option<u16>,
+) -> Result<SaleFees> {
+    let buyer_is_taker = taker == Some(SaleSide::Buyer);
+    let seller_is_taker = taker == Some(SaleSide::Seller);
+    let role_basis_points = |is_taker: bool| {
+        if is_taker {
+            auction_house.taker_fee_basis_points
+        } else {
+            auction_house.maker_fee_basis_points
+        }
+    };
+
+    let mut seller_fee = get_basis_points_fee(auction_house.seller_fee_basis_points, size)?
+        .checked_add(get_basis_points_fee(
+            role_basis_points(seller_is_taker),
+            size,
+        )?)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    let mut buyer_fee = get_basis_points_fee(role_basis_points(buyer_is_taker), size)?;
+
+    if let Some(fee_basis_points) = taker_fee_tier {
+        let tier_fee = get_basis_points_fee(fee_basis_points, size)?;
+        if buyer_is_taker {
+            buyer_fee = buyer_fee.min(tier_fee);
+        } else if seller_is_taker {
+            seller_fee = seller_fee.min(tier_fee);
+        }
+    }
+
+    let referral_fee = get_basis_points_fee(auction_house.referral_fee_basis_points, size)?
+        .min(seller_fee.saturating_add(buyer_fee));
+
+    Ok(SaleFees {
+        seller_fee,
+        buyer_fee,
+        referral_fee,
+    })
+}
+
+/// Most a buyer pays for a bid of `price`: the price plus the larger of the maker and taker fee, since the bid can be
+/// filled as either side.
+pub fn get_bid_total(auction_house: &AuctionHouse, price: u64) -> Result<u64> {
+    let fee_basis_points = auction_house
+        .maker_fee_basis_points
+        .max(auction_house.taker_fee_basis_points);
+    price
+        .checked_add(get_basis_points_fee(fee_basis_points, price)?)
+        .ok_or_else(|| AuctionHouseError::NumericalOverflow.into())
+}
+
+/// Pay the fees of a sale from escrow: the referral fee to the referrer in `referrer_accounts`, if there is one,
+/// and the rest to the treasury. `referrer_accounts` holds the referrer wallet, followed for SPL treasury mints by
+/// its treasury mint ATA, which is created if needed. Without a referrer the whole fee goes to the treasury.
 #[allow(clippy::too_many_arguments)]
-pub fn pay_auction_house_fees<'a>(
+pub fn pay_sale_fees<'a>(
     auction_house: &anchor_lang::prelude::Account<'a, AuctionHouse>,
+    sale_fees: &mut SaleFees,
+    referrer_accounts: &[AccountInfo<'a>],
+    buyer: &Pubkey,
+    seller: &Pubkey,
     auction_house_treasury: &AccountInfo<'a>,
     escrow_payment_account: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
     token_program: &AccountInfo<'a>,
     system_program: &AccountInfo<'a>,
+    ata_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    fee_payer: &AccountInfo<'a>,
+    fee_payer_seeds: &[&[u8]],
     signer_seeds: &[&[u8]],
-    size: u64,
     is_native: bool,
-) -> Result<u64> {
-    let fees = auction_house.seller_fee_basis_points;
-    let total_fee = (fees as u128)
+) -> Result<()> {
+    let referrer = match referrer_accounts {
+        [] => None,
+        [referrer] if is_native => Some((referrer, referrer)),
+        [referrer, referrer_payment_account] if !is_native => {
+            Some((referrer, referrer_payment_account))
+        }
+        _ => return Err(AuctionHouseError::InvalidReferrer.into()),
+    };
+
+    if let Some((referrer, referrer_payment_account)) = referrer {
+        if referrer.key == seller || referrer.key == buyer {
+            return Err(AuctionHouseError::InvalidReferrer.into());
+        }
+
+        if !is_native {
+            if referrer_payment_account.data_is_empty() {
+                make_ata(
+                    referrer_payment_account.clone(),
+                    referrer.clone(),
+                    treasury_mint.clone(),
+                    fee_payer.clone(),
+                    ata_program.clone(),
+                    token_program.clone(),
+                    system_program.clone(),
+                    rent.clone(),
+                    fee_payer_seeds,
+                )?;
+            }
+
+            assert_is_treasury_ata(referrer_payment_account, referrer.key, treasury_mint)?;
+        }
+    } else {
+        sale_fees.referral_fee = 0;
+    }
+
+    pay_from_escrow(
+        auction_house,
+        auction_house_treasury,
+        escrow_payment_account,
+        treasury_mint,
+        token_program,
+        system_program,
+        signer_seeds,
+        sale_fees.treasury_fee()?,
+        is_native,
+    )?;
+
+    if let Some((_, referrer_payment_account)) = referrer {
+        pay_from_escrow(
+            auction_house,
+            referrer_payment_account,
+            escrow_payment_account,
+            treasury_mint,
+            token_program,
+            system_program,
+            signer_seeds,
+            sale_fees.referral_fee,
+            is_native,
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn get_basis_points_fee(basis_points: u16, size: u64) -> Result<u64> {
+    Ok((basis_points as u128)
         .checked_mul(size as u128)
         .ok_or(AuctionHouseError::NumericalOverflow)?
         .checked_div(10000)
-        .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+        .ok_or(AuctionHouseError::NumericalOverflow)? as u64)
+}
+
+/// Transfer `amount` out of a buyer escrow. The escrow signs for native mints, the Auction House for SPL mints.
+/// Escrows only hold what the sale pays out, so Token-2022 transfer fees are withheld from what `destination`
+/// is credited.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_from_escrow<'a>(
+    auction_house: &anchor_lang::prelude::Account<'a, AuctionHouse>,
+    destination: &AccountInfo<'a>,
+    escrow_payment_account: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+    amount: u64,
+    is_native: bool,
+) -> Result<()> {
     if !is_native {
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                escrow_payment_account.key,
-                auction_house_treasury.key,
-                &auction_house.key(),
-                &[],
-                total_fee,
-            )?,
-            &[
-                escrow_payment_account.clone(),
-                auction_house_treasury.clone(),
-                token_program.clone(),
-                auction_house.to_account_info(),
-            ],
+        transfer_treasury_tokens(
+            escrow_payment_account,
+            destination,
+            &auction_house.to_account_info(),
+            treasury_mint,
+            token_program,
+            amount,
             &[signer_seeds],
         )?;
     } else {
         invoke_signed(
-            &system_instruction::transfer(
-                escrow_payment_account.key,
-                auction_house_treasury.key,
-                total_fee,
-            ),
+            &system_instruction::transfer(escrow_payment_account.key, destination.key, amount),
             &[
                 escrow_payment_account.clone(),
-                auction_house_treasury.clone(),
+                destination.clone(),
                 system_program.clone(),
             ],
             &[signer_seeds],
         )?;
     }
-    Ok(total_fee)
+    Ok(())
 }
 
 pub fn create_program_token_account_if_not_present<'a>(
     payment_account: &UncheckedAccount<'a>,
     system_program: &Program<'a, System>,
     fee_payer: &AccountInfo<'a>,
-    token_program: &Program<'a, Token>,
-    treasury_mint: &anchor_lang::prelude::Account<'a, Mint>,
+    token_program: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
     owner: &AccountInfo<'a>,
     rent: &Sysvar<'a, Rent>,
     signer_seeds: &[&[u8]],
@@ -329,13 +756,24 @@ pub fn create_program_token_account_if_not_present<'a>(
     is_native: bool,
 ) -> Result<()> {
     if !is_native && payment_account.data_is_empty() {
+        assert_treasury_token_program(treasury_mint, token_program)?;
+
+        // Token-2022 mints may require extensions, such as the withheld transfer fee, on their accounts.
+        let account_len = {
+            let data = treasury_mint.try_borrow_data()?;
+            let mint = StateWithExtensions::<TreasuryMint>::unpack(&data)?;
+            ExtensionType::get_account_len::<TreasuryTokenAccount>(
+                &ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?),
+            )
+        };
+
         create_or_allocate_account_raw(
             *token_program.key,
             &payment_account.to_account_info(),
             &rent.to_account_info(),
             system_program,
             fee_payer,
-            spl_token::state::Account::LEN,
+            account_len,
             fee_seeds,
             signer_seeds,
         )?;
@@ -362,6 +800,9 @@ pub fn create_program_token_account_if_not_present<'a>(
     Ok(())
 }
 
+/// Pay the creators of `metadata_info` the royalty `royalty_policy` sets on a sale at `size`, given the share of the
+/// royalty elected by the buyer. Returns what is left of `size` for the seller, who forgoes the royalty of a buyer
+/// without an election whatever share is paid.
 #[allow(clippy::too_many_arguments)]
 pub fn pay_creator_fees<'a>(
     remaining_accounts: &mut Iter<AccountInfo<'a>>,
@@ -378,17 +819,14 @@ pub fn pay_creator_fees<'a>(
     fee_payer_seeds: &[&[u8]],
     size: u64,
     is_native: bool,
+    royalty_policy: &RoyaltyPolicy,
+    elected_royalty_basis_points: Option<u16>,
 ) -> Result<u64> {
     let metadata = Metadata::from_account_info(metadata_info)?;
-    let fees = metadata.data.seller_fee_basis_points;
-    let total_fee = (fees as u128)
-        .checked_mul(size as u128)
-        .ok_or(AuctionHouseError::NumericalOverflow)?
-        .checked_div(10000)
-        .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+    let total_fee = royalty_policy.royalty(&metadata, elected_royalty_basis_points, size)?;
     let mut remaining_fee = total_fee;
     let remaining_size = size
-        .checked_sub(total_fee)
+        .checked_sub(royalty_policy.royalty(&metadata, None, size)?)
         .ok_or(AuctionHouseError::NumericalOverflow)?;
     match metadata.data.creators {
         Some(creators) => {
@@ -433,27 +871,19 @@ pub fn pay_creator_fees<'a>(
                             fee_payer_seeds,
                         )?;
                     }
-                    assert_is_ata(
+                    assert_is_treasury_ata(
                         current_creator_token_account_info,
                         current_creator_info.key,
-                        &treasury_mint.key(),
+                        treasury_mint,
                     )?;
                     if creator_fee > 0 {
-                        invoke_signed(
-                            &spl_token::instruction::transfer(
-                                token_program.key,
-                                escrow_payment_account.key,
-                                current_creator_token_account_info.key,
-                                payment_account_owner.key,
-                                &[],
-                                creator_fee,
-                            )?,
-                            &[
-                                escrow_payment_account.clone(),
-                                current_creator_token_account_info.clone(),
-                                token_program.clone(),
-                                payment_account_owner.clone(),
-                            ],
+                        transfer_treasury_tokens(
+                            escrow_payment_account,
+                            current_creator_token_account_info,
+                            payment_account_owner,
+                            treasury_mint,
+                            token_program,
+                            creator_fee,
                             &[signer_seeds],
                         )?;
                     }
@@ -631,6 +1061,302 @@ pub fn assert_valid_trade_state(
     }
 }
 
+/// Verify that `trade_state` is the trade state of a bid keyed by `target` under the `kind` seed,
+/// i.e. a collection bid or a trait bid. Returns the bump seed.
+#[allow(clippy::too_many_arguments)]
+pub fn assert_valid_targeted_trade_state(
+    wallet: &Pubkey,
+    auction_house: &Account<AuctionHouse>,
+    buyer_price: u64,
+    token_size: u64,
+    trade_state: &AccountInfo,
+    kind: &str,
+    target: &[u8],
+    ts_bump: u8,
+) -> Result<u8> {
+    let ah_pubkey = auction_house.key();
+    let bump = assert_derivation(
+        &crate::id(),
+        trade_state,
+        &[
+            PREFIX.as_bytes(),
+            kind.as_bytes(),
+            wallet.as_ref(),
+            ah_pubkey.as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            target,
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+        ],
+    )?;
+
+    if bump != ts_bump {
+        return Err(AuctionHouseError::DerivedKeyInvalid.into());
+    }
+
+    Ok(bump)
+}
+
+/// What a buyer trade state was derived against.
+#[derive(Debug, Clone, Copy)]
+pub enum BidTarget {
+    /// A public or private bid on a specific token mint.
+    Token,
+    /// A bid on any token of a verified collection, keyed by the collection mint.
+    Collection(Pubkey),
+    /// A bid on any token whose mint is a leaf of a merkle tree, keyed by the tree root.
+    Trait([u8; 32]),
+}
+
+/// Verify the buyer trade state of a sale according to the kind of bid that created it.
+#[allow(clippy::too_many_arguments)]
+pub fn assert_valid_buyer_trade_state(
+    bid_target: BidTarget,
+    wallet: &Pubkey,
+    auction_house: &Account<AuctionHouse>,
+    buyer_price: u64,
+    token_size: u64,
+    trade_state: &AccountInfo,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    ts_bump: u8,
+) -> Result<u8> {
+    match bid_target {
+        BidTarget::Token => assert_valid_trade_state(
+            wallet,
+            auction_house,
+            buyer_price,
+            token_size,
+            trade_state,
+            mint,
+            token_holder,
+            ts_bump,
+        ),
+        BidTarget::Collection(collection_mint) => assert_valid_targeted_trade_state(
+            wallet,
+            auction_house,
+            buyer_price,
+            token_size,
+            trade_state,
+            COLLECTION,
+            collection_mint.as_ref(),
+            ts_bump,
+        ),
+        BidTarget::Trait(root) => assert_valid_targeted_trade_state(
+            wallet,
+            auction_house,
+            buyer_price,
+            token_size,
+            trade_state,
+            TRAIT,
+            &root,
+            ts_bump,
+        ),
+    }
+}
+
+/// Verify that the metadata belongs to the verified collection of `collection_mint`.
+pub fn assert_metadata_in_collection(
+    metadata_info: &AccountInfo,
+    collection_mint: &Pubkey,
+) -> Result<()> {
+    let metadata = Metadata::from_account_info(metadata_info)?;
+    match metadata.collection {
+        Some(collection) if collection.verified && collection.key == *collection_mint => Ok(()),
+        _ => Err(AuctionHouseError::CollectionMismatch.into()),
+    }
+}
+
+/// Verify that `mint` is a leaf of the trait bid merkle tree defined by `root`.
+/// Leaves and branches are hashed the same way as in gumdrop, so the same off-chain tools build both trees.
+pub fn assert_mint_in_trait_tree(
+    mint: &Pubkey,
+    root: [u8; 32],
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let leaf = solana_program::keccak::hashv(&[&[0x00], &mint.to_bytes()]);
+    require!(
+        merkle_proof::verify(proof, root, leaf.0),
+        AuctionHouseError::InvalidTraitProof
+    );
+    Ok(())
+}
+
+/// Optional fields of a trade state, decoded from the tag that follows its bump.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct TradeStateFields {
+    /// Expiry timestamp and the account that paid the trade state rent.
+    pub expiry: Option<(i64, Pubkey)>,
+    /// Auctioneer that created the listing or bid.
+    pub auctioneer: Option<Pubkey>,
+    /// Only buyer allowed to fill the listing.
+    pub allowed_buyer: Option<Pubkey>,
+}
+
+impl TradeStateFields {
+    fn tag(&self) -> u8 {
+        let mut tag = 0;
+        if self.expiry.is_some() {
+            tag |= TRADE_STATE_HAS_EXPIRY;
+        }
+        if self.auctioneer.is_some() {
+            tag |= TRADE_STATE_HAS_AUCTIONEER;
+        }
+        if self.allowed_buyer.is_some() {
+            tag |= TRADE_STATE_HAS_ALLOWED_BUYER;
+        }
+        tag
+    }
+
+    /// Size of a trade state holding the fields, untagged when there are none.
+    pub fn size(&self) -> usize {
+        if self.tag() == 0 {
+            return TRADE_STATE_SIZE;
+        }
+
+        let mut size = TRADE_STATE_TAGGED_SIZE;
+        if self.expiry.is_some() {
+            size += TRADE_STATE_EXPIRY_SIZE;
+        }
+        if self.auctioneer.is_some() {
+            size += TRADE_STATE_AUCTIONEER_SIZE;
+        }
+        if self.allowed_buyer.is_some() {
+            size += TRADE_STATE_ALLOWED_BUYER_SIZE;
+        }
+        size
+    }
+
+    /// Write the tag and the fields after the bump of a trade state of `self.size()` bytes.
+    pub fn write(&self, data: &mut [u8]) {
+        if self.tag() == 0 {
+            return;
+        }
+
+        data[TRADE_STATE_SIZE] = self.tag();
+        let mut offset = TRADE_STATE_TAGGED_SIZE;
+        if let Some((expires_at, payer)) = self.expiry {
+            data[offset..offset + 8].copy_from_slice(&expires_at.to_le_bytes());
+            data[offset + 8..offset + TRADE_STATE_EXPIRY_SIZE].copy_from_slice(payer.as_ref());
+            offset += TRADE_STATE_EXPIRY_SIZE;
+        }
+        if let Some(auctioneer) = self.auctioneer {
+            data[offset..offset + TRADE_STATE_AUCTIONEER_SIZE].copy_from_slice(auctioneer.as_ref());
+            offset += TRADE_STATE_AUCTIONEER_SIZE;
+        }
+        if let Some(allowed_buyer) = self.allowed_buyer {
+            data[offset..offset + TRADE_STATE_ALLOWED_BUYER_SIZE]
+                .copy_from_slice(allowed_buyer.as_ref());
+        }
+    }
+}
+
+/// Decode the optional fields of a trade state. Fails on unknown tag flags and on a size that does not match the
+/// tag, so that no other account of the program reads as a trade state with fields.
+pub fn get_trade_state_fields(trade_state: &AccountInfo) -> Result<TradeStateFields> {
+    let data = trade_state.try_borrow_data()?;
+    if data.len() <= TRADE_STATE_SIZE {
+        return Ok(TradeStateFields::default());
+    }
+
+    let tag = data[TRADE_STATE_SIZE];
+    if tag & !(TRADE_STATE_HAS_EXPIRY | TRADE_STATE_HAS_AUCTIONEER | TRADE_STATE_HAS_ALLOWED_BUYER)
+        != 0
+    {
+        return Err(AuctionHouseError::InvalidTradeState.into());
+    }
+
+    let mut fields = TradeStateFields::default();
+    let mut offset = TRADE_STATE_TAGGED_SIZE;
+    let mut read = |size: usize| -> Result<&[u8]> {
+        let field = data
+            .get(offset..offset + size)
+            .ok_or(AuctionHouseError::InvalidTradeState)?;
+        offset += size;
+        Ok(field)
+    };
+    if tag & TRADE_STATE_HAS_EXPIRY != 0 {
+        let field = read(TRADE_STATE_EXPIRY_SIZE)?;
+        fields.expiry = Some((
+            i64::from_le_bytes(*array_ref![field, 0, 8]),
+            Pubkey::new_from_array(*array_ref![field, 8, 32]),
+        ));
+    }
+    if tag & TRADE_STATE_HAS_AUCTIONEER != 0 {
+        let field = read(TRADE_STATE_AUCTIONEER_SIZE)?;
+        fields.auctioneer = Some(Pubkey::new_from_array(*array_ref![field, 0, 32]));
+    }
+    if tag & TRADE_STATE_HAS_ALLOWED_BUYER != 0 {
+        let field = read(TRADE_STATE_ALLOWED_BUYER_SIZE)?;
+        fields.allowed_buyer = Some(Pubkey::new_from_array(*array_ref![field, 0, 32]));
+    }
+
+    if fields.size() != data.len() {
+        return Err(AuctionHouseError::InvalidTradeState.into());
+    }
+
+    Ok(fields)
+}
+
+/// Resize an existing trade state to hold `fields` and write them. `payer` tops up the rent, signing with
+/// `payer_seeds` when it is a PDA.
+pub fn set_trade_state_fields<'info>(
+    trade_state: &AccountInfo<'info>,
+    fields: &TradeStateFields,
+    payer: &AccountInfo<'info>,
+    payer_seeds: &[&[u8]],
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let size = fields.size();
+    let top_up = Rent::get()?
+        .minimum_balance(size)
+        .saturating_sub(trade_state.lamports());
+    if top_up > 0 {
+        let signer_seeds: &[&[&[u8]]] = if payer_seeds.is_empty() {
+            &[]
+        } else {
+            &[payer_seeds]
+        };
+        invoke_signed(
+            &system_instruction::transfer(payer.key, trade_state.key, top_up),
+            &[payer.clone(), trade_state.clone(), system_program.clone()],
+            signer_seeds,
+        )?;
+    }
+    trade_state.realloc(size, false)?;
+
+    let mut data = trade_state.try_borrow_mut_data()?;
+    fields.write(&mut data);
+
+    Ok(())
+}
+
+/// Return the expiry timestamp and rent payer stored in a trade state, if an expiry was set.
+pub fn get_trade_state_expiry(trade_state: &AccountInfo) -> Result<Option<(i64, Pubkey)>> {
+    Ok(get_trade_state_fields(trade_state)?.expiry)
+}
+
+/// Fail if the trade state has an expiry that has already passed.
+pub fn assert_trade_state_not_expired(trade_state: &AccountInfo) -> Result<()> {
+    if let Some((expires_at, _)) = get_trade_state_expiry(trade_state)? {
+        if Clock::get()?.unix_timestamp >= expires_at {
+            return Err(AuctionHouseError::TradeStateExpired.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Fail if the seller trade state is a private listing for another buyer.
+pub fn assert_allowed_buyer(seller_trade_state: &AccountInfo, buyer: &Pubkey) -> Result<()> {
+    match get_trade_state_fields(seller_trade_state)?.allowed_buyer {
+        Some(allowed_buyer) if allowed_buyer != *buyer => {
+            Err(AuctionHouseError::BuyerNotAllowed.into())
+        }
+        _ => Ok(()),
+    }
+}
+
 // This function verifies that there are enough funds in `account` such that `amount` can be
 // withdrawn.  If there are not sufficent funds it returns an error.  If there are sufficient
 // funds, it returns any additional amount needed to keep the account above the rent exempt
@@ -664,12 +1390,6 @@ pub fn assert_valid_auctioneer_and_scope(
     auctioneer_pda: &Account<Auctioneer>,
     scope: AuthorityScope,
 ) -> Result<()> {
-    // Assert the Auctioneer is tagged on the auction house
-    assert_keys_equal(
-        auction_house_instance.auctioneer_address,
-        auctioneer_pda.key(),
-    )
-    .map_err(|_e| AuctionHouseError::InvalidAuctioneer)?;
     // Assert the auctioneer_authority is tagged in the Auctioneer
     assert_keys_equal(
         auctioneer_pda.auctioneer_authority,
@@ -680,13 +1400,41 @@ pub fn assert_valid_auctioneer_and_scope(
     assert_keys_equal(auctioneer_pda.auction_house, auction_house_instance.key())
         .map_err(|_e| AuctionHouseError::InvalidAuctioneer)?;
 
-    if !(auction_house_instance.scopes[scope as usize]) {
+    // The auctioneer tagged on the auction house keeps its scopes there, added auctioneers in their own PDA.
+    let scopes = if auction_house_instance.auctioneer_address == auctioneer_pda.key() {
+        &auction_house_instance.scopes
+    } else {
+        &auctioneer_pda.scopes
+    };
+    if !(scopes[scope as usize]) {
         return Err(AuctionHouseError::MissingAuctioneerScope.into());
     }
 
     Ok(())
 }
 
+/// Fail unless `auctioneer_pda` owns the listing or bid. Trade states without a recorded auctioneer belong to the auctioneer tagged on the auction house.
+pub fn assert_trade_state_auctioneer(
+    auction_house_instance: &Account<AuctionHouse>,
+    trade_state: &AccountInfo,
+    auctioneer_pda: &Account<Auctioneer>,
+) -> Result<()> {
+    let owner = get_trade_state_fields(trade_state)?
+        .auctioneer
+        .unwrap_or(auction_house_instance.auctioneer_address);
+    assert_keys_equal(owner, auctioneer_pda.key())
+        .map_err(|_e| AuctionHouseError::InvalidAuctioneer.into())
+}
+
+/// Fail if an auctioneer owns the listing or bid, which then has to go through the auctioneer handlers.
+pub fn assert_no_trade_state_auctioneer(trade_state: &AccountInfo) -> Result<()> {
+    if get_trade_state_fields(trade_state)?.auctioneer.is_some() {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    Ok(())
+}
+
 pub fn assert_scopes_eq(
     scopes: Vec<AuthorityScope>,
     scopes_array: [bool; MAX_NUM_SCOPES],
@@ -28,8 +28,9 @@ pub struct Withdraw<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Validated by the auction house has_one constraint.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in withdraw_logic.
     /// Auction House instance authority account.
@@ -62,7 +63,9 @@ pub struct Withdraw<'info> {
     )]
     pub auction_house_fee_account: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in withdraw_logic.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -108,7 +111,12 @@ pub fn withdraw<'info>(
         return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
     }
 
-    withdraw_logic(ctx.accounts, escrow_payment_bump, amount)
+    withdraw_logic(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        escrow_payment_bump,
+        amount,
+    )
 }
 
 /// Accounts for the [`auctioneer_withdraw` handler](auction_house/fn.auctioneer_withdraw.html).
@@ -137,8 +145,9 @@ pub struct AuctioneerWithdraw<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Validated by the auction house has_one constraint.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in withdraw_logic.
     /// Auction House instance authority account.
@@ -186,7 +195,9 @@ pub struct AuctioneerWithdraw<'info> {
         bump = ah_auctioneer_pda.bump
     )]
     pub ah_auctioneer_pda: Account<'info, Auctioneer>,
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Validated against the treasury mint in withdraw_logic.
+    /// SPL Token or Token-2022 program of the treasury mint.
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -224,12 +235,18 @@ pub fn auctioneer_withdraw<'info>(
 
     let mut accounts: Withdraw<'info> = (*ctx.accounts).clone().into();
 
-    withdraw_logic(&mut accounts, escrow_payment_bump, amount)
+    withdraw_logic(
+        &mut accounts,
+        ctx.remaining_accounts,
+        escrow_payment_bump,
+        amount,
+    )
 }
 
 #[allow(clippy::needless_lifetimes)]
-fn withdraw_logic<'info>(
+fn withdraw_logic<'c, 'info>(
     accounts: &mut Withdraw<'info>,
+    remaining_accounts: &'c [AccountInfo<'info>],
     escrow_payment_bump: u8,
     amount: u64,
 ) -> Result<()> {
@@ -283,6 +300,15 @@ fn withdraw_logic<'info>(
     )?;
 
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    assert_treasury_token_program(treasury_mint, token_program)?;
+
+    let (escrow_reservation, _) =
+        split_escrow_reservation(auction_house, &wallet.key(), remaining_accounts)?;
+    assert_escrow_unreserved(
+        escrow_reservation,
+        escrow_balance(escrow_payment_account, is_native)?,
+        amount,
+    )?;
 
     if !is_native {
         if receipt_account.data_is_empty() {
@@ -299,10 +325,10 @@ fn withdraw_logic<'info>(
             )?;
         }
 
-        let rec_acct = assert_is_ata(
+        let rec_acct = assert_is_treasury_ata(
             &receipt_account.to_account_info(),
             &wallet.key(),
-            &treasury_mint.key(),
+            treasury_mint,
         )?;
 
         // make sure you cant get rugged
@@ -310,22 +336,13 @@ fn withdraw_logic<'info>(
             return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
         }
 
-        assert_is_ata(receipt_account, &wallet.key(), &treasury_mint.key())?;
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                amount,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
+        transfer_treasury_tokens(
+            escrow_payment_account,
+            receipt_account,
+            &auction_house.to_account_info(),
+            treasury_mint,
+            token_program,
+            amount,
             &[&ah_seeds],
         )?;
     } else {
@@ -350,5 +367,12 @@ fn withdraw_logic<'info>(
         )?;
     }
 
+    emit!(WithdrawEvent {
+        auction_house: auction_house.key(),
+        wallet: wallet.key(),
+        escrow_payment_account: escrow_payment_account.key(),
+        amount,
+    });
+
     Ok(())
 }
@@ -0,0 +1,156 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use solana_program::program_pack::Pack;
+use spl_token::state::Account;
+
+async fn create_item(context: &mut ProgramTestContext) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    item
+}
+
+async fn place_bid(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    item: &Metadata,
+    buyer: &Keypair,
+    buyer_price: u64,
+) -> mpl_auction_house::accounts::Buy {
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        context,
+        ahkey,
+        ah,
+        item,
+        &item.token.pubkey(),
+        buyer,
+        buyer_price,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    bid_acc
+}
+
+#[tokio::test]
+async fn accept_bid_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+    let bid_acc = place_bid(&mut context, &ahkey, &ah, &item, &buyer, ONE_SOL).await;
+
+    let seller_before = context
+        .banks_client
+        .get_account(item.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let (acc, tx) = accept_bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &buyer.pubkey(),
+        &bid_acc.buyer_trade_state,
+        ONE_SOL,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer_token_account = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(acc.buyer_receipt_token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token_account.amount, 1);
+
+    let seller_after = context
+        .banks_client
+        .get_account(item.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(seller_after.lamports > seller_before.lamports);
+
+    for trade_state in [acc.buyer_trade_state, acc.seller_trade_state] {
+        let account = context.banks_client.get_account(trade_state).await.unwrap();
+        assert!(account.is_none());
+    }
+}
+
+#[tokio::test]
+async fn accept_bid_without_seller_signature_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+    let bid_acc = place_bid(&mut context, &ahkey, &ah, &item, &buyer, ONE_SOL).await;
+
+    let (acc, tx) = accept_bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &buyer.pubkey(),
+        &bid_acc.buyer_trade_state,
+        ONE_SOL,
+    );
+    let mut instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: tx.message.instructions[0].data.clone(),
+        accounts: acc.to_account_metas(None),
+    };
+    instruction.accounts[1].is_signer = false;
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, SALE_REQUIRES_SIGNER);
+}
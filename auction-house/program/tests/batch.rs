@@ -0,0 +1,267 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::DirtyClone, setup_functions::*};
+
+use mpl_auction_house::pda::find_program_as_signer_address;
+use mpl_token_metadata::{
+    pda::find_token_record_account,
+    state::{PrintSupply, TokenStandard},
+};
+use solana_program::program_pack::Pack;
+use spl_token::state::Account;
+
+// The largest batch that fits in a legacy transaction.
+const MAX_BATCH_SIZE: usize = 6;
+const DEFAULT_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+
+/// Create a Metadata whose token account is owned by `owner`.
+fn owned_by(owner: &Keypair) -> Metadata {
+    let mut item = Metadata::new();
+    item.token = owner.dirty_clone();
+    item.ata = get_associated_token_address(&owner.pubkey(), &item.mint.pubkey());
+    item.token_record = find_token_record_account(&item.mint.pubkey(), &item.ata).0;
+    item
+}
+
+async fn create_items(
+    context: &mut ProgramTestContext,
+    owner: &Keypair,
+    count: usize,
+) -> Vec<Metadata> {
+    let mut items = vec![];
+    for _ in 0..count {
+        let item = owned_by(owner);
+        item.create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+        items.push(item);
+    }
+
+    items
+}
+
+async fn token_account(context: &mut ProgramTestContext, address: &Pubkey) -> Account {
+    Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn batch_sell_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let items = create_items(&mut context, &seller, 3).await;
+
+    let (trade_states, tx) = batch_sell(&mut context, &ahkey, &ah, &seller, &items, ONE_SOL, None);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let (pas, _) = find_program_as_signer_address();
+    for (item, trade_state) in items.iter().zip(trade_states) {
+        let ts = context
+            .banks_client
+            .get_account(trade_state)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ts.owner, mpl_auction_house::id());
+        assert_eq!(ts.data.len(), 1);
+
+        let token = token_account(&mut context, &item.ata).await;
+        assert_eq!(token.delegate, Some(pas).into());
+        assert_eq!(token.delegated_amount, 1);
+    }
+}
+
+#[tokio::test]
+async fn batch_cancel_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let items = create_items(&mut context, &seller, 3).await;
+
+    let (trade_states, tx) = batch_sell(&mut context, &ahkey, &ah, &seller, &items, ONE_SOL, None);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let tx = batch_cancel(&mut context, &ahkey, &ah, &seller, &items, ONE_SOL, None);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    for (item, trade_state) in items.iter().zip(trade_states) {
+        let ts = context.banks_client.get_account(trade_state).await.unwrap();
+        assert!(ts.is_none());
+
+        let token = token_account(&mut context, &item.ata).await;
+        assert!(token.delegate.is_none());
+    }
+}
+
+#[tokio::test]
+async fn batch_cancel_wrong_price_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let items = create_items(&mut context, &seller, 2).await;
+
+    let (_, tx) = batch_sell(&mut context, &ahkey, &ah, &seller, &items, ONE_SOL, None);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let tx = batch_cancel(&mut context, &ahkey, &ah, &seller, &items, TEN_SOL, None);
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, DERIVED_KEY_INVALID);
+}
+
+#[tokio::test]
+async fn batch_sell_and_cancel_within_compute_budget() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let items = create_items(&mut context, &seller, MAX_BATCH_SIZE).await;
+
+    let (_, tx) = batch_sell(&mut context, &ahkey, &ah, &seller, &items, ONE_SOL, None);
+    let simulation = context
+        .banks_client
+        .simulate_transaction(tx.clone())
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let units_consumed = simulation.simulation_details.unwrap().units_consumed;
+    assert!(units_consumed < DEFAULT_COMPUTE_UNIT_LIMIT);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let tx = batch_cancel(&mut context, &ahkey, &ah, &seller, &items, ONE_SOL, None);
+    let simulation = context
+        .banks_client
+        .simulate_transaction(tx.clone())
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let units_consumed = simulation.simulation_details.unwrap().units_consumed;
+    assert!(units_consumed < DEFAULT_COMPUTE_UNIT_LIMIT);
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+#[tokio::test]
+async fn batch_sell_and_cancel_pnft_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    let payer = context.payer.dirty_clone();
+    let (rule_set, auth_data) = create_sale_delegate_rule_set(&mut context, payer).await;
+
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+
+    let mut items = vec![];
+    for _ in 0..2 {
+        let item = owned_by(&seller);
+        item.create_via_builder(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            None,
+            None,
+            true,
+            TokenStandard::ProgrammableNonFungible,
+            None,
+            Some(rule_set),
+            Some(0),
+            Some(PrintSupply::Zero),
+        )
+        .await
+        .unwrap();
+        item.mint_via_builder(&mut context, 1, Some(auth_data.clone()))
+            .await
+            .unwrap();
+        items.push(item);
+    }
+
+    let (trade_states, tx) = batch_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &seller,
+        &items,
+        ONE_SOL,
+        Some(&rule_set),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let (pas, _) = find_program_as_signer_address();
+    for item in &items {
+        let token = token_account(&mut context, &item.ata).await;
+        assert_eq!(token.delegate, Some(pas).into());
+        assert!(token.is_frozen());
+    }
+
+    let tx = batch_cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &seller,
+        &items,
+        ONE_SOL,
+        Some(&rule_set),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    for (item, trade_state) in items.iter().zip(trade_states) {
+        let ts = context.banks_client.get_account(trade_state).await.unwrap();
+        assert!(ts.is_none());
+
+        let token = token_account(&mut context, &item.ata).await;
+        assert!(token.delegate.is_none());
+    }
+}
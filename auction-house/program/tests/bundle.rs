@@ -0,0 +1,450 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::DirtyClone, setup_functions::*};
+
+use mpl_auction_house::{bundle::BundleListing, pda::find_program_as_signer_address};
+use mpl_token_metadata::{pda::find_token_record_account, state::Creator};
+use solana_program::program_pack::Pack;
+use spl_token::state::Account;
+
+async fn create_item(
+    context: &mut ProgramTestContext,
+    owner: &Keypair,
+    creator: &Pubkey,
+    seller_fee_basis_points: u16,
+) -> Metadata {
+    let mut item = Metadata::new();
+    item.token = owner.dirty_clone();
+    item.ata = get_associated_token_address(&owner.pubkey(), &item.mint.pubkey());
+    item.token_record = find_token_record_account(&item.mint.pubkey(), &item.ata).0;
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        Some(vec![Creator {
+            address: *creator,
+            verified: false,
+            share: 100,
+        }]),
+        seller_fee_basis_points,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    item
+}
+
+async fn token_account(context: &mut ProgramTestContext, address: &Pubkey) -> Account {
+    Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap()
+}
+
+async fn lamports(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    context
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports
+}
+
+#[tokio::test]
+async fn sell_bundle_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let creator = Pubkey::new_unique();
+    let items = vec![
+        create_item(&mut context, &seller, &creator, 500).await,
+        create_item(&mut context, &seller, &creator, 500).await,
+    ];
+
+    let (acc, tx) = sell_bundle(
+        &mut context,
+        &ahkey,
+        &ah,
+        &seller,
+        &items,
+        ONE_SOL,
+        vec![1, 1],
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let bundle_account = context
+        .banks_client
+        .get_account(acc.bundle_listing)
+        .await
+        .unwrap()
+        .unwrap();
+    let bundle = BundleListing::try_deserialize(&mut bundle_account.data.as_ref()).unwrap();
+    assert_eq!(bundle.auction_house, ahkey);
+    assert_eq!(bundle.seller, seller.pubkey());
+    assert_eq!(bundle.price, ONE_SOL);
+    assert_eq!(bundle.items.len(), 2);
+    assert_eq!(
+        bundle.item_prices().unwrap(),
+        vec![ONE_SOL / 2, ONE_SOL / 2]
+    );
+
+    let (pas, _) = find_program_as_signer_address();
+    for (item, bundle_item) in items.iter().zip(bundle.items) {
+        assert_eq!(bundle_item.token_account, item.ata);
+        assert_eq!(bundle_item.mint, item.mint.pubkey());
+
+        let token = token_account(&mut context, &item.ata).await;
+        assert_eq!(token.delegate, Some(pas).into());
+        assert_eq!(token.delegated_amount, 1);
+    }
+}
+
+#[tokio::test]
+async fn sell_bundle_single_item_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let items = vec![create_item(&mut context, &seller, &Pubkey::new_unique(), 500).await];
+
+    let (_, tx) = sell_bundle(&mut context, &ahkey, &ah, &seller, &items, ONE_SOL, vec![1]);
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_BUNDLE_ITEMS);
+}
+
+#[tokio::test]
+async fn execute_bundle_sale_prorates_royalties() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let creators = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    for creator in &creators {
+        airdrop(&mut context, creator, ONE_SOL).await.unwrap();
+    }
+    let items = vec![
+        create_item(&mut context, &seller, &creators[0], 1000).await,
+        create_item(&mut context, &seller, &creators[1], 500).await,
+    ];
+
+    let price = 4 * ONE_SOL;
+    let (acc, tx) = sell_bundle(
+        &mut context,
+        &ahkey,
+        &ah,
+        &seller,
+        &items,
+        price,
+        vec![3, 1],
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &items[0], &buyer, price);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let seller_before = lamports(&mut context, &seller.pubkey()).await;
+    let bundle_rent = lamports(&mut context, &acc.bundle_listing).await;
+    let creator0_before = lamports(&mut context, &creators[0]).await;
+    let creator1_before = lamports(&mut context, &creators[1]).await;
+
+    let (_, tx) = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &buyer,
+        &seller.pubkey(),
+        &items,
+        &[vec![creators[0]], vec![creators[1]]],
+        None,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // The first item carries 3/4 of the price at 10% royalties, the second 1/4 at 5%.
+    let creator0_fee = 3 * ONE_SOL / 10;
+    let creator1_fee = ONE_SOL / 20;
+    let house_fee = (ah.seller_fee_basis_points as u64 * price) / 10000;
+    assert_eq!(
+        lamports(&mut context, &creators[0]).await,
+        creator0_before + creator0_fee
+    );
+    assert_eq!(
+        lamports(&mut context, &creators[1]).await,
+        creator1_before + creator1_fee
+    );
+    assert_eq!(
+        lamports(&mut context, &seller.pubkey()).await,
+        seller_before + bundle_rent + price - creator0_fee - creator1_fee - house_fee
+    );
+
+    for item in &items {
+        let token = token_account(
+            &mut context,
+            &get_associated_token_address(&buyer.pubkey(), &item.mint.pubkey()),
+        )
+        .await;
+        assert_eq!(token.amount, 1);
+    }
+
+    let bundle_account = context
+        .banks_client
+        .get_account(acc.bundle_listing)
+        .await
+        .unwrap();
+    assert!(bundle_account.is_none());
+}
+
+#[tokio::test]
+async fn execute_bundle_sale_charges_taker_fee_and_pays_referrer() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (_, tx) = update_auction_house_fees(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        Some(0),
+        Some(200),
+        Some(50),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let creator = Pubkey::new_unique();
+    airdrop(&mut context, &creator, ONE_SOL).await.unwrap();
+    let items = vec![
+        create_item(&mut context, &seller, &creator, 0).await,
+        create_item(&mut context, &seller, &creator, 0).await,
+    ];
+
+    let price = 2 * ONE_SOL;
+    let (_, tx) = sell_bundle(
+        &mut context,
+        &ahkey,
+        &ah,
+        &seller,
+        &items,
+        price,
+        vec![1, 1],
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // The buyer takes the bundle listing and pays the taker fee on top of the price.
+    let taker_fee = price * 200 / 10000;
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &ahkey,
+        &ah,
+        &items[0],
+        &buyer,
+        price + taker_fee,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let referrer = Pubkey::new_unique();
+    airdrop(&mut context, &referrer, ONE_SOL).await.unwrap();
+    let seller_before = lamports(&mut context, &seller.pubkey()).await;
+    let treasury_before = lamports(&mut context, &ah.auction_house_treasury).await;
+    let referrer_before = lamports(&mut context, &referrer).await;
+
+    let (acc, tx) = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &buyer,
+        &seller.pubkey(),
+        &items,
+        &[vec![creator], vec![creator]],
+        Some(&referrer),
+    );
+    let bundle_rent = lamports(&mut context, &acc.bundle_listing).await;
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let seller_fee = (ah.seller_fee_basis_points as u64 * price) / 10000;
+    let referral_fee = price * 50 / 10000;
+    assert_eq!(
+        lamports(&mut context, &seller.pubkey()).await,
+        seller_before + bundle_rent + price - seller_fee
+    );
+    assert_eq!(
+        lamports(&mut context, &ah.auction_house_treasury).await,
+        treasury_before + seller_fee + taker_fee - referral_fee
+    );
+    assert_eq!(
+        lamports(&mut context, &referrer).await,
+        referrer_before + referral_fee
+    );
+}
+
+#[tokio::test]
+async fn execute_bundle_sale_item_mismatch_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let creator = Pubkey::new_unique();
+    airdrop(&mut context, &creator, ONE_SOL).await.unwrap();
+    let mut items = vec![
+        create_item(&mut context, &seller, &creator, 500).await,
+        create_item(&mut context, &seller, &creator, 500).await,
+    ];
+    let other = create_item(&mut context, &seller, &creator, 500).await;
+
+    let (_, tx) = sell_bundle(
+        &mut context,
+        &ahkey,
+        &ah,
+        &seller,
+        &items,
+        ONE_SOL,
+        vec![1, 1],
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &items[0], &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    items[1] = other;
+    let (_, tx) = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &buyer,
+        &seller.pubkey(),
+        &items,
+        &[vec![creator], vec![creator]],
+        None,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, BUNDLE_ITEM_MISMATCH);
+}
+
+#[tokio::test]
+async fn cancel_bundle_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let creator = Pubkey::new_unique();
+    let items = vec![
+        create_item(&mut context, &seller, &creator, 500).await,
+        create_item(&mut context, &seller, &creator, 500).await,
+    ];
+
+    let (acc, tx) = sell_bundle(
+        &mut context,
+        &ahkey,
+        &ah,
+        &seller,
+        &items,
+        ONE_SOL,
+        vec![1, 1],
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let mut accounts = mpl_auction_house::accounts::CancelBundle {
+        wallet: seller.pubkey(),
+        auction_house: ahkey,
+        bundle_listing: acc.bundle_listing,
+        token_program: spl_token::id(),
+    }
+    .to_account_metas(None);
+    for item in &items {
+        accounts.push(AccountMeta::new(item.ata, false));
+    }
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelBundle {}.data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&seller.pubkey()),
+        &[&seller],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    for item in &items {
+        let token = token_account(&mut context, &item.ata).await;
+        assert!(token.delegate.is_none());
+    }
+
+    let bundle_account = context
+        .banks_client
+        .get_account(acc.bundle_listing)
+        .await
+        .unwrap();
+    assert!(bundle_account.is_none());
+}
@@ -0,0 +1,408 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{
+    helpers::{default_scopes, DirtyClone},
+    setup_functions::*,
+};
+
+use mpl_auction_house::{
+    client::{self, BuyBuilder, ExecuteSaleBuilder, SellBuilder},
+    pda::{find_auctioneer_pda, find_listing_receipt_address, find_purchase_receipt_address},
+};
+use mpl_token_metadata::state::{Creator, PrintSupply, TokenStandard};
+use solana_program::program_pack::Pack;
+use spl_token::state::Account;
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &signers.to_vec(),
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+async fn token_amount(context: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    Account::unpack_from_slice(&account.data).unwrap().amount
+}
+
+async fn funded_buyer(context: &mut ProgramTestContext) -> Keypair {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    buyer
+}
+
+#[tokio::test]
+async fn client_sale_with_receipts_pays_creators() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let creator = Keypair::new();
+    let item = Metadata::new();
+    airdrop(&mut context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        &mut context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        Some(vec![Creator {
+            address: creator.pubkey(),
+            verified: false,
+            share: 100,
+        }]),
+        1000,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+    let metadata = item.get_data(&mut context).await;
+    let seller = item.token.pubkey();
+
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, seller, ONE_SOL);
+    process(
+        &mut context,
+        &[
+            sell.instruction(),
+            client::print_listing_receipt(&sell.seller_trade_state(), &seller),
+        ],
+        &item.token,
+        &[&item.token],
+    )
+    .await;
+
+    let buyer = funded_buyer(&mut context).await;
+    let buy = BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL);
+    process(
+        &mut context,
+        &[
+            buy.instruction(),
+            client::print_bid_receipt(&buy.buyer_trade_state(), &buyer.pubkey()),
+        ],
+        &buyer,
+        &[&buyer],
+    )
+    .await;
+
+    let sale = ExecuteSaleBuilder::new(
+        ahkey,
+        &ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        ONE_SOL,
+    );
+    assert_eq!(sale.seller_trade_state(), sell.seller_trade_state());
+    assert_eq!(sale.buyer_trade_state(), buy.buyer_trade_state());
+    process(
+        &mut context,
+        &[
+            sale.instruction(),
+            client::print_purchase_receipt(
+                &sale.seller_trade_state(),
+                &sale.buyer_trade_state(),
+                &authority.pubkey(),
+            ),
+        ],
+        &authority,
+        &[&authority],
+    )
+    .await;
+
+    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &item.mint.pubkey());
+    assert_eq!(token_amount(&mut context, &buyer_token_account).await, 1);
+    let creator_balance = context
+        .banks_client
+        .get_balance(creator.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(creator_balance, ONE_SOL / 10);
+
+    let (purchase_receipt, _) =
+        find_purchase_receipt_address(&sale.seller_trade_state(), &sale.buyer_trade_state());
+    assert!(context
+        .banks_client
+        .get_account(purchase_receipt)
+        .await
+        .unwrap()
+        .is_some());
+
+    process(
+        &mut context,
+        &[client::close_listing_receipt(
+            &sell.seller_trade_state(),
+            &seller,
+            false,
+        )],
+        &item.token,
+        &[&item.token],
+    )
+    .await;
+    let (listing_receipt, _) = find_listing_receipt_address(&sell.seller_trade_state());
+    assert!(context
+        .banks_client
+        .get_account(listing_receipt)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn client_public_partial_sale() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let item = Metadata::new();
+    airdrop(&mut context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        &mut context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        6,
+    )
+    .await
+    .unwrap();
+    let metadata = item.get_data(&mut context).await;
+    let seller = item.token.pubkey();
+
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, seller, 6 * ONE_SOL).token_size(6);
+    process(
+        &mut context,
+        &[sell.instruction()],
+        &item.token,
+        &[&item.token],
+    )
+    .await;
+
+    let buyer = funded_buyer(&mut context).await;
+    let buy = BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, 2 * ONE_SOL)
+        .token_size(2)
+        .public();
+    process(&mut context, &[buy.instruction()], &buyer, &[&buyer]).await;
+
+    let sale = ExecuteSaleBuilder::new(
+        ahkey,
+        &ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        6 * ONE_SOL,
+    )
+    .token_size(6)
+    .partial_order(2, 2 * ONE_SOL)
+    .public_bid();
+    assert_eq!(sale.buyer_trade_state(), buy.buyer_trade_state());
+    process(
+        &mut context,
+        &[sale.instruction()],
+        &authority,
+        &[&authority],
+    )
+    .await;
+
+    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &item.mint.pubkey());
+    assert_eq!(token_amount(&mut context, &buyer_token_account).await, 2);
+    assert_eq!(token_amount(&mut context, &item.ata).await, 4);
+}
+
+#[tokio::test]
+async fn client_auctioneer_sale() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let auctioneer_authority = Keypair::new();
+    airdrop(&mut context, &auctioneer_authority.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let (auctioneer_pda, _) = find_auctioneer_pda(&ahkey, &auctioneer_authority.pubkey());
+    delegate_auctioneer(
+        &mut context,
+        ahkey,
+        &authority,
+        auctioneer_authority.pubkey(),
+        auctioneer_pda,
+        default_scopes(),
+    )
+    .await
+    .unwrap();
+
+    let item = Metadata::new();
+    airdrop(&mut context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        &mut context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+    let metadata = item.get_data(&mut context).await;
+    let seller = item.token.pubkey();
+
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, seller, ONE_SOL)
+        .auctioneer(auctioneer_authority.pubkey());
+    process(
+        &mut context,
+        &[sell.instruction()],
+        &item.token,
+        &[&item.token, &auctioneer_authority],
+    )
+    .await;
+
+    let buyer = funded_buyer(&mut context).await;
+    let buy = BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL)
+        .auctioneer(auctioneer_authority.pubkey());
+    process(
+        &mut context,
+        &[buy.instruction()],
+        &buyer,
+        &[&buyer, &auctioneer_authority],
+    )
+    .await;
+
+    let sale = ExecuteSaleBuilder::new(
+        ahkey,
+        &ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        ONE_SOL,
+    )
+    .auctioneer(auctioneer_authority.pubkey());
+    assert_eq!(sale.seller_trade_state(), sell.seller_trade_state());
+    process(
+        &mut context,
+        &[sale.instruction()],
+        &authority,
+        &[&authority, &auctioneer_authority],
+    )
+    .await;
+
+    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &item.mint.pubkey());
+    assert_eq!(token_amount(&mut context, &buyer_token_account).await, 1);
+}
+
+#[tokio::test]
+async fn client_pnft_sale() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let payer = context.payer.dirty_clone();
+    let (rule_set, auth_data) = create_sale_delegate_rule_set(&mut context, payer).await;
+
+    let item = Metadata::new();
+    airdrop(&mut context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create_via_builder(
+        &mut context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        None,
+        None,
+        true,
+        TokenStandard::ProgrammableNonFungible,
+        None,
+        Some(rule_set),
+        Some(0),
+        Some(PrintSupply::Zero),
+    )
+    .await
+    .unwrap();
+    item.mint_via_builder(&mut context, 1, Some(auth_data))
+        .await
+        .unwrap();
+    let metadata = item.get_data(&mut context).await;
+    let seller = item.token.pubkey();
+
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, seller, ONE_SOL);
+    process(
+        &mut context,
+        &[sell.instruction()],
+        &item.token,
+        &[&item.token],
+    )
+    .await;
+
+    let buyer = funded_buyer(&mut context).await;
+    let buy = BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL);
+    process(&mut context, &[buy.instruction()], &buyer, &[&buyer]).await;
+
+    let sale = ExecuteSaleBuilder::new(
+        ahkey,
+        &ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        ONE_SOL,
+    );
+    process(
+        &mut context,
+        &[sale.instruction()],
+        &authority,
+        &[&authority],
+    )
+    .await;
+
+    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &item.mint.pubkey());
+    assert_eq!(token_amount(&mut context, &buyer_token_account).await, 1);
+    assert_eq!(token_amount(&mut context, &item.ata).await, 0);
+}
@@ -0,0 +1,216 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use solana_sdk::sysvar;
+use utils::setup_functions::*;
+
+use anchor_lang::{__private::base64, Discriminator};
+use mpl_auction_house::{events::ReceiptClosedEvent, pda::find_listing_receipt_address};
+
+async fn create_listing(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+) -> (Metadata, mpl_auction_house::accounts::Sell) {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    let ((acc, _), sell_tx) = sell(context, ahkey, ah, &item, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    (item, acc)
+}
+
+async fn cancel_listing(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    item: &Metadata,
+    acc: &mpl_auction_house::accounts::Sell,
+) {
+    let cancel = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Cancel {
+            buyer_price: ONE_SOL,
+            token_size: 1,
+        }
+        .data(),
+        accounts: mpl_auction_house::accounts::Cancel {
+            auction_house: *ahkey,
+            wallet: item.token.pubkey(),
+            token_account: acc.token_account,
+            authority: ah.authority,
+            trade_state: acc.seller_trade_state,
+            token_program: spl_token::id(),
+            token_mint: item.mint.pubkey(),
+            auction_house_fee_account: ah.auction_house_fee_account,
+        }
+        .to_account_metas(None),
+    };
+
+    let (listing_receipt, _) = find_listing_receipt_address(&acc.seller_trade_state);
+    let cancel_receipt = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelListingReceipt {}.data(),
+        accounts: mpl_auction_house::accounts::CancelListingReceipt {
+            receipt: listing_receipt,
+            system_program: solana_program::system_program::id(),
+            instruction: sysvar::instructions::id(),
+        }
+        .to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[cancel, cancel_receipt],
+        Some(&item.token.pubkey()),
+        &[&item.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+fn close_listing_receipt(
+    context: &ProgramTestContext,
+    item: &Metadata,
+    acc: &mpl_auction_house::accounts::Sell,
+    emit_event: bool,
+) -> Transaction {
+    let (listing_receipt, _) = find_listing_receipt_address(&acc.seller_trade_state);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CloseListingReceipt { emit_event }.data(),
+        accounts: mpl_auction_house::accounts::CloseOrderReceipt {
+            receipt: listing_receipt,
+            trade_state: acc.seller_trade_state,
+            bookkeeper: item.token.pubkey(),
+        }
+        .to_account_metas(None),
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&item.token.pubkey()),
+        &[&item.token],
+        context.last_blockhash,
+    )
+}
+
+#[tokio::test]
+async fn close_listing_receipt_after_cancel_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (item, acc) = create_listing(&mut context, &ahkey, &ah).await;
+    cancel_listing(&mut context, &ahkey, &ah, &item, &acc).await;
+
+    let (listing_receipt, _) = find_listing_receipt_address(&acc.seller_trade_state);
+    let receipt_rent = context
+        .banks_client
+        .get_account(listing_receipt)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    let bookkeeper_before = context
+        .banks_client
+        .get_balance(item.token.pubkey())
+        .await
+        .unwrap();
+
+    let tx = close_listing_receipt(&context, &item, &acc, false);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let receipt_account = context
+        .banks_client
+        .get_account(listing_receipt)
+        .await
+        .unwrap();
+    assert!(receipt_account.is_none());
+
+    let bookkeeper_after = context
+        .banks_client
+        .get_balance(item.token.pubkey())
+        .await
+        .unwrap();
+    // The bookkeeper also pays the transaction fee.
+    assert!(bookkeeper_after > bookkeeper_before);
+    assert!(bookkeeper_after <= bookkeeper_before + receipt_rent);
+}
+
+#[tokio::test]
+async fn close_listing_receipt_emits_receipt_data() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (item, acc) = create_listing(&mut context, &ahkey, &ah).await;
+    cancel_listing(&mut context, &ahkey, &ah, &item, &acc).await;
+
+    let (listing_receipt, _) = find_listing_receipt_address(&acc.seller_trade_state);
+    let receipt_data = context
+        .banks_client
+        .get_account(listing_receipt)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+
+    let tx = close_listing_receipt(&context, &item, &acc, true);
+    let simulation = context.banks_client.simulate_transaction(tx).await.unwrap();
+    assert!(matches!(simulation.result, Some(Ok(()))));
+
+    let events: Vec<ReceiptClosedEvent> = simulation
+        .simulation_details
+        .unwrap()
+        .logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| base64::decode(data).ok())
+        .filter(|data| data.len() >= 8 && data[..8] == ReceiptClosedEvent::discriminator())
+        .map(|data| ReceiptClosedEvent::deserialize(&mut &data[8..]).unwrap())
+        .collect();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].receipt, listing_receipt);
+    assert_eq!(events[0].bookkeeper, item.token.pubkey());
+    assert_eq!(events[0].data, receipt_data);
+}
+
+#[tokio::test]
+async fn close_open_listing_receipt_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (item, acc) = create_listing(&mut context, &ahkey, &ah).await;
+
+    let tx = close_listing_receipt(&context, &item, &acc, false);
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, RECEIPT_NOT_FINALIZED);
+}
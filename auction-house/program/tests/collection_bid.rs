@@ -0,0 +1,431 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::DirtyClone, setup_functions::*};
+
+use mpl_auction_house::{
+    pda::find_collection_bid_trade_state_address,
+    receipt::{BidReceipt, PurchaseReceipt},
+};
+use mpl_testing_utils::utils::MasterEditionV2;
+use mpl_token_metadata::state::Collection;
+use solana_program::program_pack::Pack;
+use spl_token::state::Account;
+
+async fn create_collection(context: &mut ProgramTestContext) -> (Metadata, MasterEditionV2) {
+    let collection = Metadata::new();
+    collection
+        .create(
+            context,
+            "Collection".to_string(),
+            "COL".to_string(),
+            "uri".to_string(),
+            None,
+            0,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let master_edition = MasterEditionV2::new(&collection);
+    master_edition.create(context, Some(0)).await.unwrap();
+
+    (collection, master_edition)
+}
+
+async fn create_collection_item(
+    context: &mut ProgramTestContext,
+    collection: &Metadata,
+    master_edition: &MasterEditionV2,
+    verified: bool,
+) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        true,
+        1,
+    )
+    .await
+    .unwrap();
+    item.update_v2(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        true,
+        Some(Collection {
+            verified: false,
+            key: collection.mint.pubkey(),
+        }),
+        None,
+    )
+    .await
+    .unwrap();
+
+    if verified {
+        let payer = context.payer.dirty_clone();
+        item.verify_collection(
+            context,
+            collection.pubkey,
+            payer,
+            collection.mint.pubkey(),
+            master_edition.pubkey,
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    item
+}
+
+#[tokio::test]
+async fn collection_buy_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, _) = create_collection(&mut context).await;
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((acc, print_bid_acc), buy_tx) =
+        collection_buy(&mut context, &ahkey, &ah, &collection, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (expected_bts, _) = find_collection_bid_trade_state_address(
+        &buyer.pubkey(),
+        &ahkey,
+        &ah.treasury_mint,
+        &collection.mint.pubkey(),
+        ONE_SOL,
+        1,
+    );
+    assert_eq!(acc.buyer_trade_state, expected_bts);
+
+    let escrow = context
+        .banks_client
+        .get_account(acc.escrow_payment_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(escrow.lamports, ONE_SOL + rent.minimum_balance(0));
+
+    let bid_receipt_account = context
+        .banks_client
+        .get_account(print_bid_acc.receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let bid_receipt = BidReceipt::try_deserialize(&mut bid_receipt_account.data.as_ref()).unwrap();
+
+    assert_eq!(bid_receipt.trade_state, acc.buyer_trade_state);
+    assert_eq!(bid_receipt.buyer, buyer.pubkey());
+    assert_eq!(bid_receipt.metadata, collection.pubkey);
+    assert_eq!(bid_receipt.token_account, None);
+    assert_eq!(bid_receipt.price, ONE_SOL);
+    assert_eq!(bid_receipt.token_size, 1);
+}
+
+#[tokio::test]
+async fn execute_collection_sale_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, master_edition) = create_collection(&mut context).await;
+    let item = create_collection_item(&mut context, &collection, &master_edition, true).await;
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) =
+        collection_buy(&mut context, &ahkey, &ah, &collection, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &item, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let seller_before = context
+        .banks_client
+        .get_account(item.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let ((_, purchase_receipt_acc), execute_tx) = execute_collection_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &item,
+        &collection.mint.pubkey(),
+        &buyer.pubkey(),
+        &item.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(execute_tx)
+        .await
+        .unwrap();
+
+    let seller_after = context
+        .banks_client
+        .get_account(item.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let buyer_token_after = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(get_associated_token_address(
+                &buyer.pubkey(),
+                &item.mint.pubkey(),
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    let fee_minus: u64 = ONE_SOL - ((ah.seller_fee_basis_points as u64 * ONE_SOL) / 10000);
+    assert_eq!(seller_before.lamports + fee_minus, seller_after.lamports);
+    assert_eq!(buyer_token_after.amount, 1);
+
+    let buyer_trade_state = context
+        .banks_client
+        .get_account(bid_acc.buyer_trade_state)
+        .await
+        .unwrap();
+    assert!(buyer_trade_state.is_none());
+
+    let purchase_receipt_account = context
+        .banks_client
+        .get_account(purchase_receipt_acc.purchase_receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let purchase_receipt =
+        PurchaseReceipt::try_deserialize(&mut purchase_receipt_account.data.as_ref()).unwrap();
+    assert_eq!(purchase_receipt.buyer, buyer.pubkey());
+    assert_eq!(purchase_receipt.seller, item.token.pubkey());
+    assert_eq!(purchase_receipt.metadata, item.pubkey);
+    assert_eq!(purchase_receipt.price, ONE_SOL);
+}
+
+#[tokio::test]
+async fn execute_collection_sale_unverified_item_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, master_edition) = create_collection(&mut context).await;
+    let item = create_collection_item(&mut context, &collection, &master_edition, false).await;
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) =
+        collection_buy(&mut context, &ahkey, &ah, &collection, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &item, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let (_, execute_tx) = execute_collection_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &item,
+        &collection.mint.pubkey(),
+        &buyer.pubkey(),
+        &item.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        ONE_SOL,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(execute_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, COLLECTION_MISMATCH);
+}
+
+#[tokio::test]
+async fn execute_collection_sale_other_collection_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, _) = create_collection(&mut context).await;
+    let (other_collection, other_master_edition) = create_collection(&mut context).await;
+    let item =
+        create_collection_item(&mut context, &other_collection, &other_master_edition, true).await;
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) =
+        collection_buy(&mut context, &ahkey, &ah, &collection, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &item, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let (_, execute_tx) = execute_collection_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &item,
+        &collection.mint.pubkey(),
+        &buyer.pubkey(),
+        &item.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        ONE_SOL,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(execute_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, COLLECTION_MISMATCH);
+}
+
+#[tokio::test]
+async fn cancel_collection_buy_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, _) = create_collection(&mut context).await;
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((bid_acc, print_bid_acc), buy_tx) =
+        collection_buy(&mut context, &ahkey, &ah, &collection, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let accounts = mpl_auction_house::accounts::CancelCollectionBuy {
+        wallet: buyer.pubkey(),
+        collection_mint: collection.mint.pubkey(),
+        authority: ah.authority,
+        auction_house: ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        trade_state: bid_acc.buyer_trade_state,
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelCollectionBuy {
+            buyer_price: ONE_SOL,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let cancel_receipt_accounts = mpl_auction_house::accounts::CancelBidReceipt {
+        receipt: print_bid_acc.receipt,
+        system_program: solana_program::system_program::id(),
+        instruction: solana_program::sysvar::instructions::id(),
+    }
+    .to_account_metas(None);
+    let cancel_receipt_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelBidReceipt {}.data(),
+        accounts: cancel_receipt_accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction, cancel_receipt_instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer_trade_state = context
+        .banks_client
+        .get_account(bid_acc.buyer_trade_state)
+        .await
+        .unwrap();
+    assert!(buyer_trade_state.is_none());
+
+    let bid_receipt_account = context
+        .banks_client
+        .get_account(print_bid_acc.receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let bid_receipt = BidReceipt::try_deserialize(&mut bid_receipt_account.data.as_ref()).unwrap();
+    assert!(bid_receipt.canceled_at.is_some());
+}
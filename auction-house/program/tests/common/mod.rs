@@ -23,7 +23,13 @@ pub use std::assert_eq;
 pub const HAS_ONE_CONSTRAINT_VIOLATION: u32 = 2001;
 pub const INVALID_SEEDS: u32 = 2006;
 pub const ACCOUNT_NOT_INITIALIZED: u32 = 3012;
+pub const PUBLIC_KEY_MISMATCH: u32 = 6000;
+pub const DERIVED_KEY_INVALID: u32 = 6013;
+pub const SALE_REQUIRES_SIGNER: u32 = 6018;
+pub const INVALID_BASIS_POINTS: u32 = 6023;
+pub const INVALID_AUCTIONEER: u32 = 6028;
 pub const MISSING_AUCTIONEER_SCOPE: u32 = 6029;
+pub const MUST_USE_AUCTIONEER_HANDLER: u32 = 6030;
 pub const NO_AUCTIONEER_PROGRAM_SET: u32 = 6031;
 pub const TOO_MANY_SCOPES: u32 = 6032;
 pub const BUMP_SEED_NOT_IN_HASHMAP: u32 = 6034;
@@ -34,6 +40,39 @@ pub const PARTIAL_BUY_PRICE_MISMATCH: u32 = 6040;
 pub const MISSING_ELEMENTS_NEEDED_FOR_PARTIAL_BUY: u32 = 6038;
 pub const AUCTIONEER_ALREADY_DELEGATED: u32 = 6041;
 pub const INSUFFICIENT_FUNDS: u32 = 6043;
+pub const COLLECTION_MISMATCH: u32 = 6045;
+pub const INVALID_TRAIT_PROOF: u32 = 6046;
+pub const INVALID_EXPIRY: u32 = 6047;
+pub const TRADE_STATE_EXPIRED: u32 = 6048;
+pub const TRADE_STATE_NOT_EXPIRED: u32 = 6049;
+pub const INVALID_BUNDLE_ITEMS: u32 = 6052;
+pub const BUNDLE_ITEM_MISMATCH: u32 = 6053;
+pub const INVALID_SWAP_OFFER: u32 = 6055;
+pub const INVALID_DUTCH_LISTING: u32 = 6056;
+pub const BID_BELOW_DUTCH_PRICE: u32 = 6057;
+pub const INVALID_REFERRER: u32 = 6058;
+pub const INVALID_TREASURY_TOKEN_PROGRAM: u32 = 6059;
+pub const COLLECTION_NOT_ALLOWED: u32 = 6060;
+pub const INVALID_COLLECTION_LIST: u32 = 6061;
+pub const BUYER_NOT_ALLOWED: u32 = 6062;
+pub const INVALID_ESCROW_RESERVATION: u32 = 6063;
+pub const ESCROW_OVERCOMMITTED: u32 = 6064;
+pub const ESCROW_FUNDS_RESERVED: u32 = 6065;
+pub const RECEIPT_NOT_FINALIZED: u32 = 6066;
+pub const INVALID_AUCTION_HOUSE_STATS: u32 = 6067;
+pub const GOVERNANCE_REQUIRED: u32 = 6068;
+pub const INVALID_GOVERNANCE: u32 = 6069;
+pub const NOT_GOVERNANCE_SIGNER: u32 = 6070;
+pub const CHANGE_ALREADY_APPROVED: u32 = 6071;
+pub const CHANGE_NOT_APPROVED: u32 = 6072;
+pub const CHANGE_TIMELOCKED: u32 = 6073;
+pub const INVALID_GATEWAY_TOKEN: u32 = 6074;
+pub const INVALID_ROYALTY_ELECTION: u32 = 6075;
+pub const INVALID_FEE_TIER_HOLDER: u32 = 6076;
+pub const TOO_MANY_FEE_TIERS: u32 = 6077;
+pub const AUCTION_HOUSE_ALREADY_MIGRATED: u32 = 6078;
+pub const AUCTION_HOUSE_NOT_MIGRATED: u32 = 6079;
+pub const INVALID_SHARED_ESCROW: u32 = 6080;
 
 pub const TEN_SOL: u64 = 10_000_000_000;
 pub const ONE_SOL: u64 = 1_000_000_000;
@@ -0,0 +1,416 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::DirtyClone, setup_functions::*};
+
+use mpl_auction_house::{
+    curation::{CollectionList, CollectionListMode},
+    pda::{
+        find_collection_list_address, find_escrow_payment_address, find_program_as_signer_address,
+        find_trade_state_address,
+    },
+};
+use mpl_testing_utils::utils::MasterEditionV2;
+use mpl_token_metadata::state::Collection;
+use solana_program::instruction::AccountMeta;
+
+async fn setup_collection_list(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    mode: CollectionListMode,
+    collection_mints: &[Pubkey],
+) -> Pubkey {
+    let (acc, tx) = create_collection_list(context, ahkey, authority, mode);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    for collection_mint in collection_mints {
+        let (_, tx) = update_collection_list(context, ahkey, authority, *collection_mint, true);
+        context.banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    acc.collection_list
+}
+
+async fn create_token(context: &mut ProgramTestContext) -> Metadata {
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            true,
+            1,
+        )
+        .await
+        .unwrap();
+
+    test_metadata
+}
+
+/// Creates a collection NFT and a token verified as part of it.
+async fn create_verified_collection_token(context: &mut ProgramTestContext) -> (Metadata, Pubkey) {
+    let collection = Metadata::new();
+    collection
+        .create(
+            context,
+            "Collection".to_string(),
+            "COL".to_string(),
+            "uri".to_string(),
+            None,
+            0,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let master_edition = MasterEditionV2::new(&collection);
+    master_edition.create(context, Some(0)).await.unwrap();
+
+    let item = create_token(context).await;
+    item.update_v2(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        true,
+        Some(Collection {
+            verified: false,
+            key: collection.mint.pubkey(),
+        }),
+        None,
+    )
+    .await
+    .unwrap();
+    let payer = context.payer.dirty_clone();
+    item.verify_collection(
+        context,
+        collection.pubkey,
+        payer,
+        collection.mint.pubkey(),
+        master_edition.pubkey,
+        None,
+    )
+    .await
+    .unwrap();
+
+    (item, collection.mint.pubkey())
+}
+
+/// Builds a `sell` transaction with `collection_list` as the last remaining account.
+fn sell_with_collection_list(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    sale_price: u64,
+    collection_list: Option<Pubkey>,
+) -> Transaction {
+    let ((acc, _), _) = sell(context, ahkey, ah, test_metadata, sale_price, 1);
+    let (_, sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        ahkey,
+        &acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (_, free_sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        ahkey,
+        &acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, pas_bump) = find_program_as_signer_address();
+
+    let mut accounts = acc.to_account_metas(None);
+    if let Some(collection_list) = collection_list {
+        accounts.push(AccountMeta::new_readonly(collection_list, false));
+    }
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Sell {
+            trade_state_bump: sts_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+            buyer_price: sale_price,
+        }
+        .data(),
+        accounts,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    )
+}
+
+#[tokio::test]
+async fn collection_list_add_and_remove_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let collection_mint = Pubkey::new_unique();
+    let other_collection_mint = Pubkey::new_unique();
+
+    let collection_list = setup_collection_list(
+        &mut context,
+        &ahkey,
+        &authority,
+        CollectionListMode::Allowlist,
+        &[collection_mint, other_collection_mint],
+    )
+    .await;
+    assert_eq!(collection_list, find_collection_list_address(&ahkey).0);
+
+    let (_, tx) = update_collection_list(&mut context, &ahkey, &authority, collection_mint, false);
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(collection_list)
+        .await
+        .unwrap()
+        .unwrap();
+    let list = CollectionList::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(list.auction_house, ahkey);
+    assert_eq!(list.mode, CollectionListMode::Allowlist);
+    assert_eq!(list.collections, vec![other_collection_mint]);
+
+    let account = context
+        .banks_client
+        .get_account(ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let ah = AuctionHouse::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert!(ah.has_collection_list);
+}
+
+#[tokio::test]
+async fn collection_list_add_duplicate_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let collection_mint = Pubkey::new_unique();
+    setup_collection_list(
+        &mut context,
+        &ahkey,
+        &authority,
+        CollectionListMode::Allowlist,
+        &[collection_mint],
+    )
+    .await;
+    context.warp_to_slot(100).unwrap();
+
+    let (_, tx) = update_collection_list(&mut context, &ahkey, &authority, collection_mint, true);
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_COLLECTION_LIST);
+}
+
+#[tokio::test]
+async fn sell_allowlisted_collection_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (test_metadata, collection_mint) = create_verified_collection_token(&mut context).await;
+    let collection_list = setup_collection_list(
+        &mut context,
+        &ahkey,
+        &authority,
+        CollectionListMode::Allowlist,
+        &[collection_mint],
+    )
+    .await;
+
+    let tx = sell_with_collection_list(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        ONE_SOL,
+        Some(collection_list),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+#[tokio::test]
+async fn sell_outside_allowlist_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_token(&mut context).await;
+    let collection_list = setup_collection_list(
+        &mut context,
+        &ahkey,
+        &authority,
+        CollectionListMode::Allowlist,
+        &[Pubkey::new_unique()],
+    )
+    .await;
+
+    let tx = sell_with_collection_list(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        ONE_SOL,
+        Some(collection_list),
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, COLLECTION_NOT_ALLOWED);
+}
+
+#[tokio::test]
+async fn sell_denylisted_collection_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (test_metadata, collection_mint) = create_verified_collection_token(&mut context).await;
+    let collection_list = setup_collection_list(
+        &mut context,
+        &ahkey,
+        &authority,
+        CollectionListMode::Denylist,
+        &[collection_mint],
+    )
+    .await;
+
+    let tx = sell_with_collection_list(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        ONE_SOL,
+        Some(collection_list),
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, COLLECTION_NOT_ALLOWED);
+}
+
+#[tokio::test]
+async fn sell_without_collection_list_account_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_token(&mut context).await;
+    setup_collection_list(
+        &mut context,
+        &ahkey,
+        &authority,
+        CollectionListMode::Denylist,
+        &[],
+    )
+    .await;
+
+    let tx = sell_with_collection_list(&mut context, &ahkey, &ah, &test_metadata, ONE_SOL, None);
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_COLLECTION_LIST);
+}
+
+#[tokio::test]
+async fn buy_outside_allowlist_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_token(&mut context).await;
+    let collection_list = setup_collection_list(
+        &mut context,
+        &ahkey,
+        &authority,
+        CollectionListMode::Allowlist,
+        &[Pubkey::new_unique()],
+    )
+    .await;
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((acc, _), _) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        ONE_SOL,
+        1,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let (_, bts_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        &ahkey,
+        &acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        ONE_SOL,
+        1,
+    );
+    let mut accounts = acc.to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(collection_list, false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Buy {
+            trade_state_bump: bts_bump,
+            escrow_payment_bump: escrow_bump,
+            buyer_price: ONE_SOL,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, COLLECTION_NOT_ALLOWED);
+}
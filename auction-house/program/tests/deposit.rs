@@ -3,7 +3,7 @@ pub mod common;
 pub mod utils;
 
 use common::*;
-use mpl_auction_house::pda::find_auctioneer_pda;
+use mpl_auction_house::pda::{find_auctioneer_pda, find_escrow_payment_address};
 use mpl_testing_utils::{solana::airdrop, utils::Metadata};
 use solana_sdk::{signature::Keypair, signer::Signer};
 use std::assert_eq;
@@ -248,3 +248,45 @@ async fn auctioneer_deposit_no_delegate_fails() {
 
     assert_error!(error, ACCOUNT_NOT_INITIALIZED);
 }
+
+#[tokio::test]
+async fn deposit_invalid_treasury_token_program_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    // Payer Wallet
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), ONE_SOL * 2)
+        .await
+        .unwrap();
+    let (mut acc, _) = deposit(&mut context, &ahkey, &ah, &test_metadata, &buyer, ONE_SOL);
+
+    // The native treasury mint is owned by the SPL Token program, not Token-2022.
+    acc.token_program = spl_token_2022::id();
+    let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Deposit {
+            amount: ONE_SOL,
+            escrow_payment_bump: escrow_bump,
+        }
+        .data(),
+        accounts: acc.to_account_metas(None),
+    };
+    let deposit_tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+
+    let error = context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap_err();
+
+    assert_error!(error, INVALID_TREASURY_TOKEN_PROGRAM);
+}
@@ -0,0 +1,468 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::{
+    dutch::DutchListing,
+    pda::find_program_as_signer_address,
+    receipt::{ListingReceipt, PurchaseReceipt},
+};
+use solana_program::program_pack::Pack;
+use spl_token::state::Account;
+
+const START_PRICE: u64 = 2 * ONE_SOL;
+const FLOOR_PRICE: u64 = ONE_SOL;
+const DURATION: i64 = 1000;
+
+async fn now(context: &mut ProgramTestContext) -> i64 {
+    context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+}
+
+async fn warp_to(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+async fn create_item(context: &mut ProgramTestContext) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    item
+}
+
+async fn place_bid(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Keypair,
+    price: u64,
+) -> Pubkey {
+    let ((bid_acc, _), buy_tx) = buy(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        &test_metadata.token.pubkey(),
+        buyer,
+        price,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    bid_acc.buyer_trade_state
+}
+
+#[tokio::test]
+async fn sell_dutch_prints_listing_receipt() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_item(&mut context).await;
+    let start_time = now(&mut context).await;
+
+    let ((acc, receipt_acc), tx) = sell_dutch(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        FLOOR_PRICE,
+        start_time,
+        start_time + DURATION,
+        0,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let listing_account = context
+        .banks_client
+        .get_account(acc.dutch_listing)
+        .await
+        .unwrap()
+        .unwrap();
+    let listing = DutchListing::try_deserialize(&mut listing_account.data.as_ref()).unwrap();
+    assert_eq!(listing.auction_house, ahkey);
+    assert_eq!(listing.seller, test_metadata.token.pubkey());
+    assert_eq!(listing.token_account, acc.token_account);
+    assert_eq!(listing.token_mint, test_metadata.mint.pubkey());
+    assert_eq!(listing.current_price(start_time).unwrap(), START_PRICE);
+    assert_eq!(
+        listing.current_price(start_time + DURATION / 2).unwrap(),
+        (START_PRICE + FLOOR_PRICE) / 2
+    );
+    assert_eq!(
+        listing.current_price(start_time + DURATION * 2).unwrap(),
+        FLOOR_PRICE
+    );
+
+    let receipt_account = context
+        .banks_client
+        .get_account(receipt_acc.receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let receipt = ListingReceipt::try_deserialize(&mut receipt_account.data.as_ref()).unwrap();
+    assert_eq!(receipt.trade_state, acc.dutch_listing);
+    assert_eq!(receipt.seller, test_metadata.token.pubkey());
+    assert_eq!(receipt.price, START_PRICE);
+    assert_eq!(receipt.token_size, 1);
+
+    let (pas, _) = find_program_as_signer_address();
+    let token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(acc.token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(token.delegate, Some(pas).into());
+}
+
+#[tokio::test]
+async fn sell_dutch_invalid_window_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_item(&mut context).await;
+    let start_time = now(&mut context).await;
+
+    let (_, tx) = sell_dutch(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        FLOOR_PRICE,
+        start_time,
+        start_time,
+        0,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_DUTCH_LISTING);
+}
+
+#[tokio::test]
+async fn execute_dutch_sale_linear_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_item(&mut context).await;
+    let start_time = now(&mut context).await;
+
+    let ((acc, _), tx) = sell_dutch(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        FLOOR_PRICE,
+        start_time,
+        start_time + DURATION,
+        0,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let price = (START_PRICE + FLOOR_PRICE) / 2;
+    let buyer_trade_state =
+        place_bid(&mut context, &ahkey, &ah, &test_metadata, &buyer, price).await;
+
+    warp_to(&mut context, start_time + DURATION / 2).await;
+
+    let ((_, receipt_acc), tx) = execute_dutch_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &buyer_trade_state,
+        price,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer_token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(get_associated_token_address(
+                &buyer.pubkey(),
+                &test_metadata.mint.pubkey(),
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token.amount, 1);
+
+    let listing_account = context
+        .banks_client
+        .get_account(acc.dutch_listing)
+        .await
+        .unwrap();
+    assert!(listing_account.is_none());
+
+    let purchase_receipt_account = context
+        .banks_client
+        .get_account(receipt_acc.purchase_receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let purchase_receipt =
+        PurchaseReceipt::try_deserialize(&mut purchase_receipt_account.data.as_ref()).unwrap();
+    assert_eq!(purchase_receipt.price, price);
+    assert_eq!(purchase_receipt.buyer, buyer.pubkey());
+
+    let listing_receipt_account = context
+        .banks_client
+        .get_account(receipt_acc.listing_receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let listing_receipt =
+        ListingReceipt::try_deserialize(&mut listing_receipt_account.data.as_ref()).unwrap();
+    assert_eq!(
+        listing_receipt.purchase_receipt,
+        Some(receipt_acc.purchase_receipt)
+    );
+}
+
+#[tokio::test]
+async fn execute_dutch_sale_bid_below_price_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_item(&mut context).await;
+    let start_time = now(&mut context).await;
+
+    let (_, tx) = sell_dutch(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        FLOOR_PRICE,
+        start_time,
+        start_time + DURATION,
+        0,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let price = (START_PRICE + FLOOR_PRICE) / 2;
+    let buyer_trade_state =
+        place_bid(&mut context, &ahkey, &ah, &test_metadata, &buyer, price).await;
+
+    // A quarter of the window in, the price is still above the bid.
+    warp_to(&mut context, start_time + DURATION / 4).await;
+
+    let (_, tx) = execute_dutch_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &buyer_trade_state,
+        price,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, BID_BELOW_DUTCH_PRICE);
+}
+
+#[tokio::test]
+async fn execute_dutch_sale_stepwise_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_item(&mut context).await;
+    let start_time = now(&mut context).await;
+    let step_interval = DURATION / 5;
+
+    let (_, tx) = sell_dutch(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        FLOOR_PRICE,
+        start_time,
+        start_time + DURATION,
+        step_interval,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+
+    // Halfway through the window only two of five steps have passed.
+    let step_price = START_PRICE - 2 * (START_PRICE - FLOOR_PRICE) / 5;
+    let linear_price = (START_PRICE + FLOOR_PRICE) / 2;
+    let low_bid = place_bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer,
+        linear_price,
+    )
+    .await;
+    let step_bid = place_bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer,
+        step_price,
+    )
+    .await;
+
+    warp_to(&mut context, start_time + DURATION / 2).await;
+
+    let (_, tx) = execute_dutch_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &low_bid,
+        linear_price,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, BID_BELOW_DUTCH_PRICE);
+
+    let (_, tx) = execute_dutch_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &step_bid,
+        step_price,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+#[tokio::test]
+async fn cancel_dutch_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_item(&mut context).await;
+    let start_time = now(&mut context).await;
+
+    let ((acc, _), tx) = sell_dutch(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        FLOOR_PRICE,
+        start_time,
+        start_time + DURATION,
+        0,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelDutch {}.data(),
+        accounts: mpl_auction_house::accounts::CancelDutch {
+            wallet: test_metadata.token.pubkey(),
+            token_account: acc.token_account,
+            auction_house: ahkey,
+            dutch_listing: acc.dutch_listing,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(acc.token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert!(token.delegate.is_none());
+
+    let listing_account = context
+        .banks_client
+        .get_account(acc.dutch_listing)
+        .await
+        .unwrap();
+    assert!(listing_account.is_none());
+}
@@ -0,0 +1,161 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use anchor_lang::{__private::base64, Discriminator};
+use mpl_auction_house::events::{DepositEvent, ListingEvent, SaleEvent};
+
+/// Simulate `tx` and decode every event of type `T` it emits.
+async fn simulate_events<T: AnchorDeserialize + Discriminator>(
+    context: &mut ProgramTestContext,
+    tx: Transaction,
+) -> Vec<T> {
+    let simulation = context.banks_client.simulate_transaction(tx).await.unwrap();
+    assert!(matches!(simulation.result, Some(Ok(()))));
+
+    simulation
+        .simulation_details
+        .unwrap()
+        .logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| base64::decode(data).ok())
+        .filter(|data| data.len() >= 8 && data[..8] == T::discriminator())
+        .map(|data| T::deserialize(&mut &data[8..]).unwrap())
+        .collect()
+}
+
+async fn create_item(context: &mut ProgramTestContext) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    item
+}
+
+#[tokio::test]
+async fn deposit_emits_event() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+
+    let (acc, tx) = deposit(&mut context, &ahkey, &ah, &item, &buyer, ONE_SOL);
+    let events = simulate_events::<DepositEvent>(&mut context, tx).await;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].auction_house, ahkey);
+    assert_eq!(events[0].wallet, buyer.pubkey());
+    assert_eq!(events[0].escrow_payment_account, acc.escrow_payment_account);
+    assert_eq!(events[0].amount, ONE_SOL);
+}
+
+#[tokio::test]
+async fn sell_emits_event() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+
+    let ((acc, _), tx) = sell(&mut context, &ahkey, &ah, &item, ONE_SOL, 1);
+    let events = simulate_events::<ListingEvent>(&mut context, tx).await;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].seller, item.token.pubkey());
+    assert_eq!(events[0].token_mint, item.mint.pubkey());
+    assert_eq!(events[0].trade_state, acc.seller_trade_state);
+    assert_eq!(events[0].price, ONE_SOL);
+    assert_eq!(events[0].token_size, 1);
+}
+
+#[tokio::test]
+async fn execute_sale_emits_event() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &item, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &item.token.pubkey(),
+        &buyer,
+        ONE_SOL,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (_, tx) = execute_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &item,
+        &buyer.pubkey(),
+        &sell_acc.wallet,
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        ONE_SOL,
+    );
+    let events = simulate_events::<SaleEvent>(&mut context, tx).await;
+
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.buyer, buyer.pubkey());
+    assert_eq!(event.seller, sell_acc.wallet);
+    assert_eq!(event.buyer_trade_state, bid_acc.buyer_trade_state);
+    assert_eq!(event.seller_trade_state, sell_acc.seller_trade_state);
+    assert_eq!(event.price, ONE_SOL);
+    assert_eq!(event.token_size, 1);
+    assert_eq!(
+        event.auction_house_fee,
+        ONE_SOL * ah.seller_fee_basis_points as u64 / 10000
+    );
+    assert!(!event.partial);
+}
@@ -0,0 +1,821 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::{
+    constants::{TRADE_STATE_HAS_AUCTIONEER, TRADE_STATE_HAS_EXPIRY},
+    pda::{
+        find_auctioneer_trade_state_address, find_escrow_payment_address,
+        find_escrow_reservation_address, find_program_as_signer_address,
+        find_public_bid_trade_state_address, find_trade_state_address,
+    },
+    reservation::{EscrowReservation, EscrowReservationMode},
+};
+use solana_program::{program_pack::Pack, system_program, sysvar};
+use spl_token::state::Account;
+
+const EXPIRY: i64 = 60 * 60;
+
+async fn now(context: &mut ProgramTestContext) -> i64 {
+    context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+}
+
+async fn warp_past(context: &mut ProgramTestContext, expires_at: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = expires_at;
+    context.set_sysvar(&clock);
+}
+
+async fn create_item(context: &mut ProgramTestContext) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    item
+}
+
+fn sell_with_expiry(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    sale_price: u64,
+    expires_at: i64,
+) -> (mpl_auction_house::accounts::Sell, Transaction) {
+    let wallet = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&wallet, &test_metadata.mint.pubkey());
+    let (seller_trade_state, sts_bump) = find_trade_state_address(
+        &wallet,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
+        &wallet,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let accounts = mpl_auction_house::accounts::Sell {
+        wallet,
+        token_account,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        seller_trade_state,
+        free_seller_trade_state,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+    let sell_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Sell {
+            trade_state_bump: sts_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+            buyer_price: sale_price,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+    let (_, expiry_instruction) = set_trade_state_expiry(
+        &wallet,
+        ahkey,
+        ah,
+        &seller_trade_state,
+        &test_metadata.mint.pubkey(),
+        expires_at,
+    );
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[sell_instruction, expiry_instruction],
+            Some(&wallet),
+            &[&test_metadata.token],
+            context.last_blockhash,
+        ),
+    )
+}
+
+/// Auctioneer listing of `test_metadata` with an expiry attached in the same transaction.
+fn auctioneer_sell_with_expiry(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    auctioneer_authority: &Keypair,
+    expires_at: i64,
+) -> (Pubkey, Transaction) {
+    let wallet = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&wallet, &test_metadata.mint.pubkey());
+    let (seller_trade_state, sts_bump) = find_auctioneer_trade_state_address(
+        &wallet,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        1,
+    );
+    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
+        &wallet,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let sell_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::AuctioneerSell {
+            trade_state_bump: sts_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+        }
+        .data(),
+        accounts: mpl_auction_house::accounts::AuctioneerSell {
+            wallet,
+            token_account,
+            metadata: test_metadata.pubkey,
+            authority: ah.authority,
+            auctioneer_authority: auctioneer_authority.pubkey(),
+            auction_house: *ahkey,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            seller_trade_state,
+            free_seller_trade_state,
+            ah_auctioneer_pda: find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey()).0,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            program_as_signer,
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+    let (_, expiry_instruction) = set_trade_state_expiry(
+        &wallet,
+        ahkey,
+        ah,
+        &seller_trade_state,
+        &test_metadata.mint.pubkey(),
+        expires_at,
+    );
+
+    (
+        seller_trade_state,
+        Transaction::new_signed_with_payer(
+            &[sell_instruction, expiry_instruction],
+            Some(&wallet),
+            &[&test_metadata.token, auctioneer_authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+fn public_buy_with_expiry(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Keypair,
+    sale_price: u64,
+    expires_at: i64,
+    escrow_reservation: Option<Pubkey>,
+) -> (mpl_auction_house::accounts::PublicBuy, Transaction) {
+    let (buyer_trade_state, bts_bump) = find_public_bid_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+
+    let accounts = mpl_auction_house::accounts::PublicBuy {
+        wallet: buyer.pubkey(),
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        token_account: get_associated_token_address(
+            &test_metadata.token.pubkey(),
+            &test_metadata.mint.pubkey(),
+        ),
+        metadata: test_metadata.pubkey,
+        escrow_payment_account,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        buyer_trade_state,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    let mut buy_accounts = accounts.to_account_metas(None);
+    if let Some(escrow_reservation) = escrow_reservation {
+        buy_accounts.push(AccountMeta::new(escrow_reservation, false));
+    }
+    let buy_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::PublicBuy {
+            trade_state_bump: bts_bump,
+            escrow_payment_bump: escrow_bump,
+            token_size: 1,
+            buyer_price: sale_price,
+        }
+        .data(),
+        accounts: buy_accounts,
+    };
+    let (_, expiry_instruction) = set_trade_state_expiry(
+        &buyer.pubkey(),
+        ahkey,
+        ah,
+        &buyer_trade_state,
+        &test_metadata.mint.pubkey(),
+        expires_at,
+    );
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[buy_instruction, expiry_instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+fn close_expired_trade_state(
+    context: &mut ProgramTestContext,
+    trade_state: &Pubkey,
+    payer: &Pubkey,
+    signer: &Keypair,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Transaction {
+    let mut accounts = mpl_auction_house::accounts::CloseExpiredTradeState {
+        trade_state: *trade_state,
+        payer: *payer,
+    }
+    .to_account_metas(None);
+    accounts.extend(remaining_accounts);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CloseExpiredTradeState {}.data(),
+        accounts,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&signer.pubkey()),
+        &[signer],
+        context.last_blockhash,
+    )
+}
+
+#[tokio::test]
+async fn set_trade_state_expiry_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let expires_at = now(&mut context).await + EXPIRY;
+
+    let (sell_acc, sell_tx) =
+        sell_with_expiry(&mut context, &ahkey, &ah, &item, ONE_SOL, expires_at);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let trade_state = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trade_state.data.len(), 42);
+    assert_eq!(trade_state.data[1], TRADE_STATE_HAS_EXPIRY);
+    assert_eq!(
+        i64::from_le_bytes(trade_state.data[2..10].try_into().unwrap()),
+        expires_at
+    );
+    assert_eq!(&trade_state.data[10..42], item.token.pubkey().as_ref());
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(trade_state.lamports, rent.minimum_balance(42));
+}
+
+#[tokio::test]
+async fn set_trade_state_expiry_on_auctioneer_listing_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let auctioneer_authority = Keypair::new();
+    let (auctioneer_pda, _) = find_auctioneer_pda(&ahkey, &auctioneer_authority.pubkey());
+    add_auctioneer(
+        &mut context,
+        ahkey,
+        &authority,
+        auctioneer_authority.pubkey(),
+        auctioneer_pda,
+        vec![AuthorityScope::Sell],
+    )
+    .await
+    .unwrap();
+    let item = create_item(&mut context).await;
+    let expires_at = now(&mut context).await + EXPIRY;
+
+    let (seller_trade_state, tx) = auctioneer_sell_with_expiry(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &auctioneer_authority,
+        expires_at,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // The expiry comes before the auctioneer that created the listing.
+    let trade_state = context
+        .banks_client
+        .get_account(seller_trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trade_state.data.len(), 74);
+    assert_eq!(
+        trade_state.data[1],
+        TRADE_STATE_HAS_EXPIRY | TRADE_STATE_HAS_AUCTIONEER
+    );
+    assert_eq!(
+        i64::from_le_bytes(trade_state.data[2..10].try_into().unwrap()),
+        expires_at
+    );
+    assert_eq!(&trade_state.data[10..42], item.token.pubkey().as_ref());
+    assert_eq!(&trade_state.data[42..74], auctioneer_pda.as_ref());
+
+    warp_past(&mut context, expires_at).await;
+    let close_tx = close_expired_trade_state(
+        &mut context,
+        &seller_trade_state,
+        &item.token.pubkey(),
+        &item.token,
+        vec![],
+    );
+    context
+        .banks_client
+        .process_transaction(close_tx)
+        .await
+        .unwrap();
+    assert!(context
+        .banks_client
+        .get_account(seller_trade_state)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn set_trade_state_expiry_in_the_past_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let expires_at = now(&mut context).await - 1;
+
+    let (_, sell_tx) = sell_with_expiry(&mut context, &ahkey, &ah, &item, ONE_SOL, expires_at);
+    let error = context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_EXPIRY);
+}
+
+#[tokio::test]
+async fn set_trade_state_expiry_other_mint_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let expires_at = now(&mut context).await + EXPIRY;
+
+    // The mint is only referenced by the expiry instruction.
+    let (_, mut sell_tx) = sell_with_expiry(&mut context, &ahkey, &ah, &item, ONE_SOL, expires_at);
+    let mint_index = sell_tx
+        .message
+        .account_keys
+        .iter()
+        .position(|key| *key == item.mint.pubkey())
+        .unwrap();
+    sell_tx.message.account_keys[mint_index] = Pubkey::new_unique();
+    sell_tx.sign(&[&item.token], context.last_blockhash);
+    let error = context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, PUBLIC_KEY_MISMATCH);
+}
+
+#[tokio::test]
+async fn execute_sale_expired_listing_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let expires_at = now(&mut context).await + EXPIRY;
+
+    let (sell_acc, sell_tx) =
+        sell_with_expiry(&mut context, &ahkey, &ah, &item, ONE_SOL, expires_at);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) = public_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &item.token.pubkey(),
+        &buyer,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    warp_past(&mut context, expires_at).await;
+
+    let (_, execute_tx) = execute_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &item,
+        &buyer.pubkey(),
+        &item.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        ONE_SOL,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(execute_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, TRADE_STATE_EXPIRED);
+}
+
+#[tokio::test]
+async fn execute_sale_before_bid_expiry_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let expires_at = now(&mut context).await + EXPIRY;
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &item, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, buy_tx) = public_buy_with_expiry(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &buyer,
+        ONE_SOL,
+        expires_at,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let (_, execute_tx) = execute_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &item,
+        &buyer.pubkey(),
+        &item.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(execute_tx)
+        .await
+        .unwrap();
+
+    let buyer_token_account = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(get_associated_token_address(
+                &buyer.pubkey(),
+                &item.mint.pubkey(),
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token_account.amount, 1);
+}
+
+#[tokio::test]
+async fn close_expired_trade_state_before_expiry_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let expires_at = now(&mut context).await + EXPIRY;
+
+    let (sell_acc, sell_tx) =
+        sell_with_expiry(&mut context, &ahkey, &ah, &item, ONE_SOL, expires_at);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let close_tx = close_expired_trade_state(
+        &mut context,
+        &sell_acc.seller_trade_state,
+        &item.token.pubkey(),
+        &cranker,
+        vec![],
+    );
+    let error = context
+        .banks_client
+        .process_transaction(close_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, TRADE_STATE_NOT_EXPIRED);
+}
+
+#[tokio::test]
+async fn close_expired_trade_state_permissionless_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let expires_at = now(&mut context).await + EXPIRY;
+
+    let (sell_acc, sell_tx) =
+        sell_with_expiry(&mut context, &ahkey, &ah, &item, ONE_SOL, expires_at);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    warp_past(&mut context, expires_at).await;
+
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let seller_before = context
+        .banks_client
+        .get_account(item.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let trade_state_lamports = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+
+    let close_tx = close_expired_trade_state(
+        &mut context,
+        &sell_acc.seller_trade_state,
+        &item.token.pubkey(),
+        &cranker,
+        vec![],
+    );
+    context
+        .banks_client
+        .process_transaction(close_tx)
+        .await
+        .unwrap();
+
+    let seller_after = context
+        .banks_client
+        .get_account(item.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        seller_before.lamports + trade_state_lamports,
+        seller_after.lamports
+    );
+
+    let trade_state = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .unwrap();
+    assert!(trade_state.is_none());
+}
+
+#[tokio::test]
+async fn close_expired_trade_state_revokes_delegate_when_seller_signs() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let expires_at = now(&mut context).await + EXPIRY;
+
+    let (sell_acc, sell_tx) =
+        sell_with_expiry(&mut context, &ahkey, &ah, &item, ONE_SOL, expires_at);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    warp_past(&mut context, expires_at).await;
+
+    let close_tx = close_expired_trade_state(
+        &mut context,
+        &sell_acc.seller_trade_state,
+        &item.token.pubkey(),
+        &item.token,
+        vec![
+            AccountMeta::new(item.token.pubkey(), true),
+            AccountMeta::new(sell_acc.token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    );
+    context
+        .banks_client
+        .process_transaction(close_tx)
+        .await
+        .unwrap();
+
+    let token_account = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(sell_acc.token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert!(token_account.delegate.is_none());
+    assert_eq!(token_account.delegated_amount, 0);
+}
+
+#[tokio::test]
+async fn close_expired_trade_state_releases_escrow_reservation() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (_, tx) = update_escrow_reservation_mode(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        EscrowReservationMode::Track,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (escrow_reservation, _) = find_escrow_reservation_address(&ahkey, &buyer.pubkey());
+    let expires_at = now(&mut context).await + EXPIRY;
+
+    let (bid_acc, buy_tx) = public_buy_with_expiry(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &buyer,
+        ONE_SOL,
+        expires_at,
+        Some(escrow_reservation),
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    warp_past(&mut context, expires_at).await;
+
+    let close_tx = close_expired_trade_state(
+        &mut context,
+        &bid_acc.buyer_trade_state,
+        &buyer.pubkey(),
+        &buyer,
+        vec![AccountMeta::new(escrow_reservation, false)],
+    );
+    context
+        .banks_client
+        .process_transaction(close_tx)
+        .await
+        .unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(escrow_reservation)
+        .await
+        .unwrap()
+        .unwrap();
+    let reservation = EscrowReservation::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert!(reservation.bids.is_empty());
+}
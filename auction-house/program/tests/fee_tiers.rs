@@ -0,0 +1,410 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::DirtyClone, setup_functions::*};
+
+use mpl_auction_house::{
+    client::{self, BuyBuilder, ExecuteSaleBuilder, SellBuilder},
+    constants::MAX_FEE_TIERS,
+    fee_tier::{FeeTier, FeeTierRequirement},
+};
+use mpl_testing_utils::{solana::mint_tokens, utils::MasterEditionV2};
+use mpl_token_metadata::state::Collection;
+use std::result::Result as StdResult;
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+) -> StdResult<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn auction_house(context: &mut ProgramTestContext, ahkey: &Pubkey) -> AuctionHouse {
+    let account = context
+        .banks_client
+        .get_account(*ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    AuctionHouse::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+const TAKER_FEE_BASIS_POINTS: u16 = 100;
+
+/// Auction House charging a 1% seller fee and a 1% taker fee, with a funded fee account and `fee_tiers`.
+async fn auction_house_with_tiers(
+    context: &mut ProgramTestContext,
+    fee_tiers: Vec<FeeTier>,
+) -> (AuctionHouse, Pubkey) {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    assert!(ah.fee_tiers.is_empty());
+    airdrop(context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (_, tx) = update_auction_house_fees(
+        context,
+        &ahkey,
+        &ah,
+        &authority,
+        Some(0),
+        Some(TAKER_FEE_BASIS_POINTS),
+        Some(0),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    process(
+        context,
+        &[client::set_fee_tiers(
+            &ahkey,
+            &authority.pubkey(),
+            fee_tiers.clone(),
+        )],
+        &authority,
+    )
+    .await
+    .unwrap();
+
+    let ah = auction_house(context, &ahkey).await;
+    assert_eq!(ah.fee_tiers, fee_tiers);
+
+    (ah, ahkey)
+}
+
+/// Token of `amount` held by a funded wallet, `token`, in its associated token account.
+async fn create_token(context: &mut ProgramTestContext, amount: u64) -> Metadata {
+    let token = Metadata::new();
+    airdrop(context, &token.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    token
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            0,
+            true,
+            amount,
+        )
+        .await
+        .unwrap();
+    token
+}
+
+/// Collection NFT and a token in it, verified when `verified` is set. Returns the token and the collection mint.
+async fn create_collection_token(
+    context: &mut ProgramTestContext,
+    verified: bool,
+) -> (Metadata, Pubkey) {
+    let collection = create_token(context, 1).await;
+    let master_edition = MasterEditionV2::new(&collection);
+    master_edition.create(context, Some(0)).await.unwrap();
+
+    let item = create_token(context, 1).await;
+    item.update_v2(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        0,
+        true,
+        Some(Collection {
+            verified: false,
+            key: collection.mint.pubkey(),
+        }),
+        None,
+    )
+    .await
+    .unwrap();
+    if verified {
+        let payer = context.payer.dirty_clone();
+        item.verify_collection(
+            context,
+            collection.pubkey,
+            payer,
+            collection.mint.pubkey(),
+            master_edition.pubkey,
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    (item, collection.mint.pubkey())
+}
+
+/// List a new NFT at one SOL and have `buyer` take the listing, claiming a fee tier with the holder token account
+/// and mint of `holder`. Returns the Auction House fee paid to the treasury, of which the seller pays the 1% seller
+/// fee and the buyer its taker fee.
+async fn take_listing(
+    context: &mut ProgramTestContext,
+    ah: &AuctionHouse,
+    ahkey: &Pubkey,
+    buyer: &Keypair,
+    holder: Option<(Pubkey, Pubkey)>,
+) -> StdResult<u64, BanksClientError> {
+    let item = create_token(context, 1).await;
+    let metadata = item.get_data(context).await;
+    let seller = item.token.pubkey();
+    let sell = SellBuilder::new(*ahkey, ah, &metadata, seller, ONE_SOL);
+    process(context, &[sell.instruction()], &item.token)
+        .await
+        .unwrap();
+
+    let buy = BuyBuilder::new(*ahkey, ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL);
+    process(context, &[buy.instruction()], buyer).await.unwrap();
+    airdrop(
+        context,
+        &buy.escrow_payment_account(),
+        ONE_SOL * TAKER_FEE_BASIS_POINTS as u64 / 10000,
+    )
+    .await
+    .unwrap();
+
+    let mut sale = ExecuteSaleBuilder::new(
+        *ahkey,
+        ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        ONE_SOL,
+    );
+    if let Some((token_account, mint)) = holder {
+        sale = sale.fee_tier_holder(token_account, mint);
+    }
+    let mut instruction = sale.instruction();
+    instruction.accounts[0].is_signer = true;
+
+    let treasury_before = context
+        .banks_client
+        .get_balance(ah.auction_house_treasury)
+        .await
+        .unwrap();
+    process(context, &[instruction], buyer).await?;
+    let treasury_after = context
+        .banks_client
+        .get_balance(ah.auction_house_treasury)
+        .await
+        .unwrap();
+
+    Ok(treasury_after - treasury_before)
+}
+
+/// Holder token account and mint of `token`.
+fn holder(token: &Metadata) -> (Pubkey, Pubkey) {
+    (token.ata, token.mint.pubkey())
+}
+
+fn mint_tier(mint: Pubkey, min_balance: u64, fee_basis_points: u16) -> FeeTier {
+    FeeTier {
+        mint,
+        requirement: FeeTierRequirement::MinimumBalance { min_balance },
+        fee_basis_points,
+    }
+}
+
+fn collection_tier(collection_mint: Pubkey, fee_basis_points: u16) -> FeeTier {
+    FeeTier {
+        mint: collection_mint,
+        requirement: FeeTierRequirement::VerifiedCollection,
+        fee_basis_points,
+    }
+}
+
+#[tokio::test]
+async fn taker_pays_fee_of_best_tier_held() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let membership = create_token(&mut context, 50).await;
+    let mint = membership.mint.pubkey();
+    let (ah, ahkey) = auction_house_with_tiers(
+        &mut context,
+        vec![mint_tier(mint, 10, 50), mint_tier(mint, 100, 0)],
+    )
+    .await;
+
+    // 50 tokens qualify for the 0.5% tier but not the free one, lowering the taker fee of the buyer.
+    let fee = take_listing(
+        &mut context,
+        &ah,
+        &ahkey,
+        &membership.token,
+        Some(holder(&membership)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(fee, ONE_SOL * (100 + 50) / 10000);
+
+    let payer = context.payer.dirty_clone();
+    mint_tokens(
+        &mut context,
+        &mint,
+        &membership.ata,
+        50,
+        &payer.pubkey(),
+        None,
+    )
+    .await
+    .unwrap();
+    let fee = take_listing(
+        &mut context,
+        &ah,
+        &ahkey,
+        &membership.token,
+        Some(holder(&membership)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(fee, ONE_SOL * 100 / 10000);
+}
+
+#[tokio::test]
+async fn taker_without_holder_pays_taker_fee() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let membership = create_token(&mut context, 100).await;
+    let (ah, ahkey) = auction_house_with_tiers(
+        &mut context,
+        vec![mint_tier(membership.mint.pubkey(), 1, 0)],
+    )
+    .await;
+
+    let fee = take_listing(&mut context, &ah, &ahkey, &membership.token, None)
+        .await
+        .unwrap();
+    assert_eq!(fee, ONE_SOL * (100 + 100) / 10000);
+}
+
+#[tokio::test]
+async fn verified_collection_holder_pays_collection_tier() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (member, collection_mint) = create_collection_token(&mut context, true).await;
+    let (ah, ahkey) =
+        auction_house_with_tiers(&mut context, vec![collection_tier(collection_mint, 25)]).await;
+
+    let fee = take_listing(
+        &mut context,
+        &ah,
+        &ahkey,
+        &member.token,
+        Some(holder(&member)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(fee, ONE_SOL * (100 + 25) / 10000);
+}
+
+#[tokio::test]
+async fn unverified_collection_holder_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (member, collection_mint) = create_collection_token(&mut context, false).await;
+    let (ah, ahkey) =
+        auction_house_with_tiers(&mut context, vec![collection_tier(collection_mint, 0)]).await;
+
+    let err = take_listing(
+        &mut context,
+        &ah,
+        &ahkey,
+        &member.token,
+        Some(holder(&member)),
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, INVALID_FEE_TIER_HOLDER);
+}
+
+#[tokio::test]
+async fn holder_of_another_wallet_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let membership = create_token(&mut context, 100).await;
+    let (ah, ahkey) = auction_house_with_tiers(
+        &mut context,
+        vec![mint_tier(membership.mint.pubkey(), 1, 0)],
+    )
+    .await;
+
+    // The buyer claims the tier with the membership tokens of someone else.
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let err = take_listing(&mut context, &ah, &ahkey, &buyer, Some(holder(&membership)))
+        .await
+        .unwrap_err();
+    assert_error!(err, INVALID_FEE_TIER_HOLDER);
+}
+
+#[tokio::test]
+async fn holder_below_minimum_balance_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let membership = create_token(&mut context, 9).await;
+    let (ah, ahkey) = auction_house_with_tiers(
+        &mut context,
+        vec![mint_tier(membership.mint.pubkey(), 10, 0)],
+    )
+    .await;
+
+    let err = take_listing(
+        &mut context,
+        &ah,
+        &ahkey,
+        &membership.token,
+        Some(holder(&membership)),
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, INVALID_FEE_TIER_HOLDER);
+}
+
+#[tokio::test]
+async fn holder_not_owned_by_token_program_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let membership = create_token(&mut context, 100).await;
+    let (ah, ahkey) = auction_house_with_tiers(
+        &mut context,
+        vec![mint_tier(membership.mint.pubkey(), 1, 0)],
+    )
+    .await;
+
+    // The metadata account stands in for the holder token account.
+    let spoofed = (membership.pubkey, membership.mint.pubkey());
+    let err = take_listing(&mut context, &ah, &ahkey, &membership.token, Some(spoofed))
+        .await
+        .unwrap_err();
+    assert_error!(err, INVALID_FEE_TIER_HOLDER);
+}
+
+#[tokio::test]
+async fn set_too_many_fee_tiers_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    let mint = Pubkey::new_unique();
+    let err = process(
+        &mut context,
+        &[client::set_fee_tiers(
+            &ahkey,
+            &authority.pubkey(),
+            (0..=MAX_FEE_TIERS as u64)
+                .map(|i| mint_tier(mint, i + 1, 50))
+                .collect(),
+        )],
+        &authority,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, TOO_MANY_FEE_TIERS);
+}
@@ -0,0 +1,482 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::pda::{
+    find_escrow_payment_address, find_program_as_signer_address, find_trade_state_address,
+};
+use solana_program::{system_program, sysvar};
+
+async fn lamports(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    context
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .map(|account| account.lamports)
+        .unwrap_or_default()
+}
+
+async fn auction_house(context: &mut ProgramTestContext, ahkey: &Pubkey) -> AuctionHouse {
+    let account = context
+        .banks_client
+        .get_account(*ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    AuctionHouse::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+async fn set_fees(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    maker_fee_basis_points: u16,
+    taker_fee_basis_points: u16,
+    referral_fee_basis_points: u16,
+) -> AuctionHouse {
+    let ah = auction_house(context, ahkey).await;
+    let (_, tx) = update_auction_house_fees(
+        context,
+        ahkey,
+        &ah,
+        authority,
+        Some(maker_fee_basis_points),
+        Some(taker_fee_basis_points),
+        Some(referral_fee_basis_points),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+    auction_house(context, ahkey).await
+}
+
+/// Lists a token at `price` and places a matching bid, depositing `extra_deposit` on top of what the bid escrows.
+async fn list_and_bid(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    price: u64,
+    extra_deposit: u64,
+) -> (
+    Metadata,
+    Keypair,
+    mpl_auction_house::accounts::Sell,
+    mpl_auction_house::accounts::Buy,
+) {
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((sell_acc, _), sell_tx) = sell(context, ahkey, ah, &test_metadata, price, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        context,
+        ahkey,
+        ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        price,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    if extra_deposit > 0 {
+        let (_, deposit_tx) = deposit(context, ahkey, ah, &test_metadata, &buyer, extra_deposit);
+        context
+            .banks_client
+            .process_transaction(deposit_tx)
+            .await
+            .unwrap();
+    }
+
+    (test_metadata, buyer, sell_acc, bid_acc)
+}
+
+/// Builds an `execute_sale` instruction with `referrer_accounts` appended to the account metas.
+#[allow(clippy::too_many_arguments)]
+fn execute_sale_instruction(
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    sell_acc: &mpl_auction_house::accounts::Sell,
+    bid_acc: &mpl_auction_house::accounts::Buy,
+    price: u64,
+    buyer_signs: bool,
+    referrer_accounts: &[AccountMeta],
+) -> Instruction {
+    let seller = test_metadata.token.pubkey();
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: *buyer,
+        seller,
+        auction_house: *ahkey,
+        metadata: test_metadata.pubkey,
+        token_account: sell_acc.token_account,
+        authority: ah.authority,
+        seller_trade_state: sell_acc.seller_trade_state,
+        buyer_trade_state: bid_acc.buyer_trade_state,
+        token_program: spl_token::id(),
+        free_trade_state: sell_acc.free_seller_trade_state,
+        seller_payment_receipt_account: seller,
+        buyer_receipt_token_account: get_associated_token_address(
+            buyer,
+            &test_metadata.mint.pubkey(),
+        ),
+        escrow_payment_account: bid_acc.escrow_payment_account,
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        treasury_mint: ah.treasury_mint,
+        program_as_signer: sell_acc.program_as_signer,
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    if buyer_signs {
+        accounts[0].is_signer = true;
+    }
+    accounts.extend_from_slice(referrer_accounts);
+
+    let (_, free_sts_bump) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+    let (_, pas_bump) = find_program_as_signer_address();
+
+    Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+            buyer_price: price,
+        }
+        .data(),
+        accounts,
+    }
+}
+
+#[tokio::test]
+async fn update_auction_house_fees_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    let ah = set_fees(&mut context, &ahkey, &authority, 50, 200, 25).await;
+    assert_eq!(ah.maker_fee_basis_points, 50);
+    assert_eq!(ah.taker_fee_basis_points, 200);
+    assert_eq!(ah.referral_fee_basis_points, 25);
+    assert_eq!(ah.seller_fee_basis_points, 100);
+}
+
+#[tokio::test]
+async fn update_auction_house_fees_invalid_basis_points_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    // The seller would pay the 1% seller fee on top of a 100% taker fee.
+    let (_, tx) = update_auction_house_fees(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        Some(10000),
+        None,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_BASIS_POINTS);
+}
+
+#[tokio::test]
+async fn execute_sale_charges_maker_and_taker_fees() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let ah = set_fees(&mut context, &ahkey, &authority, 50, 200, 0).await;
+
+    let price = ONE_SOL;
+    let taker_fee = price * 200 / 10000;
+    let (test_metadata, buyer, sell_acc, bid_acc) =
+        list_and_bid(&mut context, &ahkey, &ah, price, taker_fee).await;
+
+    let seller = test_metadata.token.pubkey();
+    let seller_before = lamports(&mut context, &seller).await;
+    let treasury_before = lamports(&mut context, &ah.auction_house_treasury).await;
+
+    // The buyer takes the listing, so the seller pays the maker fee on top of the seller fee.
+    let instruction = execute_sale_instruction(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer.pubkey(),
+        &sell_acc,
+        &bid_acc,
+        price,
+        true,
+        &[],
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let seller_fee = price * (100 + 50) / 10000;
+    assert_eq!(
+        lamports(&mut context, &seller).await,
+        seller_before + price - seller_fee
+    );
+    assert_eq!(
+        lamports(&mut context, &ah.auction_house_treasury).await,
+        treasury_before + seller_fee + taker_fee
+    );
+}
+
+#[tokio::test]
+async fn buyer_takes_listing_with_bid_funded_escrow() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let ah = set_fees(&mut context, &ahkey, &authority, 50, 200, 0).await;
+
+    // The bid alone escrows the price and the larger of the maker and taker fee.
+    let price = ONE_SOL;
+    let taker_fee = price * 200 / 10000;
+    let (test_metadata, buyer, sell_acc, bid_acc) =
+        list_and_bid(&mut context, &ahkey, &ah, price, 0).await;
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(
+        lamports(&mut context, &bid_acc.escrow_payment_account).await,
+        rent.minimum_balance(0) + price + taker_fee
+    );
+
+    let seller = test_metadata.token.pubkey();
+    let seller_before = lamports(&mut context, &seller).await;
+    let treasury_before = lamports(&mut context, &ah.auction_house_treasury).await;
+
+    let instruction = execute_sale_instruction(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer.pubkey(),
+        &sell_acc,
+        &bid_acc,
+        price,
+        true,
+        &[],
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let seller_fee = price * (100 + 50) / 10000;
+    assert_eq!(
+        lamports(&mut context, &seller).await,
+        seller_before + price - seller_fee
+    );
+    assert_eq!(
+        lamports(&mut context, &ah.auction_house_treasury).await,
+        treasury_before + seller_fee + taker_fee
+    );
+}
+
+#[tokio::test]
+async fn authority_sale_charges_both_sides_maker_fee() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let ah = set_fees(&mut context, &ahkey, &authority, 50, 200, 0).await;
+
+    let price = ONE_SOL;
+    let (test_metadata, buyer, sell_acc, bid_acc) =
+        list_and_bid(&mut context, &ahkey, &ah, price, 0).await;
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let seller = test_metadata.token.pubkey();
+    let seller_before = lamports(&mut context, &seller).await;
+    let treasury_before = lamports(&mut context, &ah.auction_house_treasury).await;
+
+    // Neither side signed, so neither took the other's order.
+    let instruction = execute_sale_instruction(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer.pubkey(),
+        &sell_acc,
+        &bid_acc,
+        price,
+        false,
+        &[],
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let maker_fee = price * 50 / 10000;
+    let seller_fee = price * 100 / 10000 + maker_fee;
+    assert_eq!(
+        lamports(&mut context, &seller).await,
+        seller_before + price - seller_fee
+    );
+    assert_eq!(
+        lamports(&mut context, &ah.auction_house_treasury).await,
+        treasury_before + seller_fee + maker_fee
+    );
+}
+
+#[tokio::test]
+async fn execute_sale_pays_referrer() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let ah = set_fees(&mut context, &ahkey, &authority, 0, 0, 50).await;
+
+    let price = ONE_SOL;
+    let (test_metadata, buyer, sell_acc, bid_acc) =
+        list_and_bid(&mut context, &ahkey, &ah, price, 0).await;
+    let referrer = Pubkey::new_unique();
+    airdrop(&mut context, &referrer, ONE_SOL).await.unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let seller = test_metadata.token.pubkey();
+    let seller_before = lamports(&mut context, &seller).await;
+    let treasury_before = lamports(&mut context, &ah.auction_house_treasury).await;
+    let referrer_before = lamports(&mut context, &referrer).await;
+
+    let instruction = execute_sale_instruction(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer.pubkey(),
+        &sell_acc,
+        &bid_acc,
+        price,
+        false,
+        &[AccountMeta::new(referrer, false)],
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // The referral is paid out of the house fee, so the seller's proceeds are unchanged.
+    let house_fee = price * 100 / 10000;
+    let referral_fee = price * 50 / 10000;
+    assert_eq!(
+        lamports(&mut context, &seller).await,
+        seller_before + price - house_fee
+    );
+    assert_eq!(
+        lamports(&mut context, &referrer).await,
+        referrer_before + referral_fee
+    );
+    assert_eq!(
+        lamports(&mut context, &ah.auction_house_treasury).await,
+        treasury_before + house_fee - referral_fee
+    );
+}
+
+#[tokio::test]
+async fn execute_sale_seller_as_referrer_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let ah = set_fees(&mut context, &ahkey, &authority, 0, 0, 50).await;
+
+    let price = ONE_SOL;
+    let (test_metadata, buyer, sell_acc, bid_acc) =
+        list_and_bid(&mut context, &ahkey, &ah, price, 0).await;
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let instruction = execute_sale_instruction(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer.pubkey(),
+        &sell_acc,
+        &bid_acc,
+        price,
+        false,
+        &[AccountMeta::new(test_metadata.token.pubkey(), false)],
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_REFERRER);
+}
@@ -0,0 +1,233 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::{
+    client::{BuyBuilder, SellBuilder},
+    gateway::GatekeeperConfig,
+};
+use std::result::Result as StdResult;
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+) -> StdResult<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn auction_house(context: &mut ProgramTestContext, ahkey: &Pubkey) -> AuctionHouse {
+    let account = context
+        .banks_client
+        .get_account(*ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    AuctionHouse::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+fn set_gatekeeper(
+    ahkey: &Pubkey,
+    authority: &Pubkey,
+    gatekeeper: Option<GatekeeperConfig>,
+) -> Instruction {
+    Instruction {
+        program_id: mpl_auction_house::id(),
+        accounts: mpl_auction_house::accounts::SetGatekeeper {
+            authority: *authority,
+            auction_house: *ahkey,
+        }
+        .to_account_metas(None),
+        data: mpl_auction_house::instruction::SetGatekeeper { gatekeeper }.data(),
+    }
+}
+
+async fn create_item(context: &mut ProgramTestContext) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+    item
+}
+
+#[tokio::test]
+async fn set_gatekeeper_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    assert_eq!(ah.gatekeeper, None);
+
+    let gatekeeper = GatekeeperConfig {
+        gatekeeper_network: Pubkey::new_unique(),
+        expire_on_use: true,
+    };
+    process(
+        &mut context,
+        &[set_gatekeeper(
+            &ahkey,
+            &authority.pubkey(),
+            Some(gatekeeper),
+        )],
+        &authority,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        auction_house(&mut context, &ahkey).await.gatekeeper,
+        Some(gatekeeper)
+    );
+
+    process(
+        &mut context,
+        &[set_gatekeeper(&ahkey, &authority.pubkey(), None)],
+        &authority,
+    )
+    .await
+    .unwrap();
+    assert_eq!(auction_house(&mut context, &ahkey).await.gatekeeper, None);
+}
+
+#[tokio::test]
+async fn set_gatekeeper_requires_authority() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let impostor = Keypair::new();
+    airdrop(&mut context, &impostor.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+
+    let err = process(
+        &mut context,
+        &[set_gatekeeper(
+            &ahkey,
+            &impostor.pubkey(),
+            Some(GatekeeperConfig {
+                gatekeeper_network: Pubkey::new_unique(),
+                expire_on_use: false,
+            }),
+        )],
+        &impostor,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, HAS_ONE_CONSTRAINT_VIOLATION);
+}
+
+#[tokio::test]
+async fn gated_orders_require_gateway_token() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    process(
+        &mut context,
+        &[set_gatekeeper(
+            &ahkey,
+            &authority.pubkey(),
+            Some(GatekeeperConfig {
+                gatekeeper_network: Pubkey::new_unique(),
+                expire_on_use: false,
+            }),
+        )],
+        &authority,
+    )
+    .await
+    .unwrap();
+    let ah = auction_house(&mut context, &ahkey).await;
+
+    let item = create_item(&mut context).await;
+    let metadata = item.get_data(&mut context).await;
+
+    // The wallet was never issued a gateway token by the network.
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, item.token.pubkey(), ONE_SOL);
+    let err = process(&mut context, &[sell.instruction()], &item.token)
+        .await
+        .unwrap_err();
+    assert_error!(err, INVALID_GATEWAY_TOKEN);
+
+    // Leaving the gateway token out is rejected as well.
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let mut buy =
+        BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL).instruction();
+    buy.accounts.pop();
+    let err = process(&mut context, &[buy], &buyer).await.unwrap_err();
+    assert_error!(err, INVALID_GATEWAY_TOKEN);
+
+    // Dutch listings and swap offers list the token as well.
+    let (_, tx) = sell_dutch(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        2 * ONE_SOL,
+        ONE_SOL,
+        0,
+        i64::MAX,
+        0,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, INVALID_GATEWAY_TOKEN);
+
+    let requested = create_item(&mut context).await;
+    let (_, tx) = make_swap_offer(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item.token,
+        &item,
+        &requested.mint.pubkey(),
+        0,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, INVALID_GATEWAY_TOKEN);
+
+    // Orders go through once the gatekeeper is lifted.
+    process(
+        &mut context,
+        &[set_gatekeeper(&ahkey, &authority.pubkey(), None)],
+        &authority,
+    )
+    .await
+    .unwrap();
+    let ah = auction_house(&mut context, &ahkey).await;
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, item.token.pubkey(), ONE_SOL);
+    process(&mut context, &[sell.instruction()], &item.token)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,733 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::default_scopes, setup_functions::*};
+
+use mpl_auction_house::{
+    client,
+    gateway::GatekeeperConfig,
+    governance::{Governance, GovernedChange, PendingChange, WITHDRAWAL_PERIOD},
+    pda::{find_governance_address, find_pending_change_address},
+    royalty::RoyaltyPolicy,
+};
+use solana_sdk::{instruction::AccountMeta, system_program, sysvar};
+use std::result::Result as StdResult;
+
+const DELAY: i64 = 3600;
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> StdResult<(), BanksClientError> {
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn warp_to(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+async fn auction_house(context: &mut ProgramTestContext, ahkey: &Pubkey) -> AuctionHouse {
+    let account = context
+        .banks_client
+        .get_account(*ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    AuctionHouse::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+async fn governance(context: &mut ProgramTestContext, ahkey: &Pubkey) -> Governance {
+    let (governance, _) = find_governance_address(ahkey);
+    let account = context
+        .banks_client
+        .get_account(governance)
+        .await
+        .unwrap()
+        .unwrap();
+    client::read_governance(&account.data).unwrap()
+}
+
+async fn pending_change(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    nonce: u64,
+) -> Option<PendingChange> {
+    let (pending_change, _) = find_pending_change_address(ahkey, nonce);
+    context
+        .banks_client
+        .get_account(pending_change)
+        .await
+        .unwrap()
+        .map(|account| client::read_pending_change(&account.data).unwrap())
+}
+
+/// Auction House governed by 2 of its authority and a second signer, with a funded treasury and fee account.
+/// `withdrawal_threshold` is also the fee withdrawal threshold.
+async fn governed_auction_house(
+    context: &mut ProgramTestContext,
+    withdrawal_threshold: u64,
+) -> (AuctionHouse, Pubkey, Keypair, Keypair) {
+    let (_, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    let signer = Keypair::new();
+    airdrop(context, &signer.pubkey(), TEN_SOL).await.unwrap();
+
+    process(
+        context,
+        &[client::enable_governance(
+            &ahkey,
+            &authority.pubkey(),
+            vec![authority.pubkey(), signer.pubkey()],
+            2,
+            DELAY,
+            withdrawal_threshold,
+            withdrawal_threshold,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let ah = auction_house(context, &ahkey).await;
+    assert!(ah.has_governance);
+    airdrop(context, &ah.auction_house_treasury, TEN_SOL)
+        .await
+        .unwrap();
+    airdrop(context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    (ah, ahkey, authority, signer)
+}
+
+fn update_auction_house(
+    ah: &AuctionHouse,
+    ahkey: &Pubkey,
+    authority: &Pubkey,
+    seller_fee_basis_points: Option<u16>,
+    requires_sign_off: Option<bool>,
+) -> Instruction {
+    Instruction {
+        program_id: mpl_auction_house::id(),
+        accounts: mpl_auction_house::accounts::UpdateAuctionHouse {
+            treasury_mint: ah.treasury_mint,
+            payer: *authority,
+            authority: *authority,
+            new_authority: ah.authority,
+            fee_withdrawal_destination: ah.fee_withdrawal_destination,
+            treasury_withdrawal_destination: ah.treasury_withdrawal_destination,
+            treasury_withdrawal_destination_owner: ah.treasury_withdrawal_destination,
+            auction_house: *ahkey,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            ata_program: spl_associated_token_account::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: mpl_auction_house::instruction::UpdateAuctionHouse {
+            seller_fee_basis_points,
+            requires_sign_off,
+            can_change_sale_price: None,
+            maker_fee_basis_points: None,
+            taker_fee_basis_points: None,
+            referral_fee_basis_points: None,
+            escrow_reservation_mode: None,
+        }
+        .data(),
+    }
+}
+
+fn withdraw_from_treasury(
+    ah: &AuctionHouse,
+    ahkey: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut accounts = mpl_auction_house::accounts::WithdrawFromTreasury {
+        treasury_mint: ah.treasury_mint,
+        authority: *authority,
+        treasury_withdrawal_destination: ah.treasury_withdrawal_destination,
+        auction_house_treasury: ah.auction_house_treasury,
+        auction_house: *ahkey,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+    let (governance, _) = find_governance_address(ahkey);
+    accounts.push(AccountMeta::new(governance, false));
+
+    Instruction {
+        program_id: mpl_auction_house::id(),
+        accounts,
+        data: mpl_auction_house::instruction::WithdrawFromTreasury { amount }.data(),
+    }
+}
+
+fn withdraw_from_fee(
+    ah: &AuctionHouse,
+    ahkey: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut accounts = mpl_auction_house::accounts::WithdrawFromFee {
+        authority: *authority,
+        fee_withdrawal_destination: ah.fee_withdrawal_destination,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house: *ahkey,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+    let (governance, _) = find_governance_address(ahkey);
+    accounts.push(AccountMeta::new(governance, false));
+
+    Instruction {
+        program_id: mpl_auction_house::id(),
+        accounts,
+        data: mpl_auction_house::instruction::WithdrawFromFee { amount }.data(),
+    }
+}
+
+#[tokio::test]
+async fn governed_update_rejects_sensitive_changes() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority, _) = governed_auction_house(&mut context, ONE_SOL).await;
+
+    let err = process(
+        &mut context,
+        &[update_auction_house(
+            &ah,
+            &ahkey,
+            &authority.pubkey(),
+            Some(200),
+            None,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, GOVERNANCE_REQUIRED);
+
+    // Settings outside of governance still change immediately.
+    process(
+        &mut context,
+        &[update_auction_house(
+            &ah,
+            &ahkey,
+            &authority.pubkey(),
+            None,
+            Some(true),
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let ah = auction_house(&mut context, &ahkey).await;
+    assert!(ah.requires_sign_off);
+    assert_eq!(ah.seller_fee_basis_points, 100);
+}
+
+#[tokio::test]
+async fn governed_auction_house_rejects_authority_settings() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority, _) = governed_auction_house(&mut context, ONE_SOL).await;
+
+    let auctioneer_authority = Keypair::new();
+    let (auctioneer_pda, _) = find_auctioneer_pda(&ahkey, &auctioneer_authority.pubkey());
+    let err = delegate_auctioneer(
+        &mut context,
+        ahkey,
+        &authority,
+        auctioneer_authority.pubkey(),
+        auctioneer_pda,
+        default_scopes(),
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, GOVERNANCE_REQUIRED);
+
+    let err = add_auctioneer(
+        &mut context,
+        ahkey,
+        &authority,
+        auctioneer_authority.pubkey(),
+        auctioneer_pda,
+        default_scopes(),
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, GOVERNANCE_REQUIRED);
+
+    let set_gatekeeper = Instruction {
+        program_id: mpl_auction_house::id(),
+        accounts: mpl_auction_house::accounts::SetGatekeeper {
+            authority: authority.pubkey(),
+            auction_house: ahkey,
+        }
+        .to_account_metas(None),
+        data: mpl_auction_house::instruction::SetGatekeeper {
+            gatekeeper: Some(GatekeeperConfig {
+                gatekeeper_network: Pubkey::new_unique(),
+                expire_on_use: false,
+            }),
+        }
+        .data(),
+    };
+    let err = process(&mut context, &[set_gatekeeper], &authority, &[])
+        .await
+        .unwrap_err();
+    assert_error!(err, GOVERNANCE_REQUIRED);
+
+    let err = process(
+        &mut context,
+        &[client::set_royalty_policy(
+            &ahkey,
+            &authority.pubkey(),
+            RoyaltyPolicy::Optional {
+                floor_basis_points: 0,
+            },
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, GOVERNANCE_REQUIRED);
+
+    let err = process(
+        &mut context,
+        &[client::set_accepts_shared_escrow(
+            &ahkey,
+            &authority.pubkey(),
+            true,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, GOVERNANCE_REQUIRED);
+}
+
+#[tokio::test]
+async fn fee_change_applies_after_approvals_and_delay() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority, signer) = governed_auction_house(&mut context, ONE_SOL).await;
+    let anyone = Keypair::new();
+    airdrop(&mut context, &anyone.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+
+    let gov = governance(&mut context, &ahkey).await;
+    process(
+        &mut context,
+        &[client::propose_change(
+            &ahkey,
+            &gov,
+            &authority.pubkey(),
+            GovernedChange::Fees {
+                seller_fee_basis_points: Some(200),
+                maker_fee_basis_points: None,
+                taker_fee_basis_points: None,
+                referral_fee_basis_points: None,
+            },
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let change = pending_change(&mut context, &ahkey, 0).await.unwrap();
+    assert_eq!(change.approvals, vec![authority.pubkey()]);
+    assert_eq!(governance(&mut context, &ahkey).await.proposal_count, 1);
+
+    let err = process(
+        &mut context,
+        &[client::apply_change(&ahkey, &ah, &change, &spl_token::id())],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, CHANGE_NOT_APPROVED);
+
+    let err = process(
+        &mut context,
+        &[client::approve_change(&ahkey, &change, &authority.pubkey())],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, CHANGE_ALREADY_APPROVED);
+
+    process(
+        &mut context,
+        &[client::approve_change(&ahkey, &change, &signer.pubkey())],
+        &signer,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // Anyone can apply an approved change, but only once its delay has passed.
+    let err = process(
+        &mut context,
+        &[client::apply_change(&ahkey, &ah, &change, &spl_token::id())],
+        &anyone,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, CHANGE_TIMELOCKED);
+
+    warp_to(&mut context, change.executable_at).await;
+    process(
+        &mut context,
+        &[client::apply_change(&ahkey, &ah, &change, &spl_token::id())],
+        &signer,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let ah = auction_house(&mut context, &ahkey).await;
+    assert_eq!(ah.seller_fee_basis_points, 200);
+    assert!(pending_change(&mut context, &ahkey, 0).await.is_none());
+}
+
+#[tokio::test]
+async fn treasury_withdrawal_above_threshold_requires_governance() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority, signer) = governed_auction_house(&mut context, ONE_SOL).await;
+
+    process(
+        &mut context,
+        &[withdraw_from_treasury(
+            &ah,
+            &ahkey,
+            &authority.pubkey(),
+            ONE_SOL,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let err = process(
+        &mut context,
+        &[withdraw_from_treasury(
+            &ah,
+            &ahkey,
+            &authority.pubkey(),
+            2 * ONE_SOL,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, GOVERNANCE_REQUIRED);
+
+    let gov = governance(&mut context, &ahkey).await;
+    process(
+        &mut context,
+        &[client::propose_change(
+            &ahkey,
+            &gov,
+            &signer.pubkey(),
+            GovernedChange::WithdrawFromTreasury {
+                amount: 2 * ONE_SOL,
+            },
+        )],
+        &signer,
+        &[],
+    )
+    .await
+    .unwrap();
+    let change = pending_change(&mut context, &ahkey, 0).await.unwrap();
+    process(
+        &mut context,
+        &[client::approve_change(&ahkey, &change, &authority.pubkey())],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let destination_before = context
+        .banks_client
+        .get_balance(ah.treasury_withdrawal_destination)
+        .await
+        .unwrap();
+
+    warp_to(&mut context, change.executable_at).await;
+    process(
+        &mut context,
+        &[client::apply_change(&ahkey, &ah, &change, &spl_token::id())],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let destination_after = context
+        .banks_client
+        .get_balance(ah.treasury_withdrawal_destination)
+        .await
+        .unwrap();
+    assert_eq!(destination_after - destination_before, 2 * ONE_SOL);
+}
+
+#[tokio::test]
+async fn treasury_withdrawals_add_up_per_period() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority, _) = governed_auction_house(&mut context, ONE_SOL).await;
+
+    process(
+        &mut context,
+        &[withdraw_from_treasury(
+            &ah,
+            &ahkey,
+            &authority.pubkey(),
+            ONE_SOL / 2,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // Each withdrawal is below the threshold, but together they exceed it.
+    let err = process(
+        &mut context,
+        &[withdraw_from_treasury(
+            &ah,
+            &ahkey,
+            &authority.pubkey(),
+            ONE_SOL / 2 + 1,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, GOVERNANCE_REQUIRED);
+
+    let gov = governance(&mut context, &ahkey).await;
+    assert_eq!(gov.withdrawn_in_period, ONE_SOL / 2);
+    warp_to(
+        &mut context,
+        gov.withdrawal_period_start + WITHDRAWAL_PERIOD,
+    )
+    .await;
+    process(
+        &mut context,
+        &[withdraw_from_treasury(
+            &ah,
+            &ahkey,
+            &authority.pubkey(),
+            ONE_SOL,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        governance(&mut context, &ahkey).await.withdrawn_in_period,
+        ONE_SOL
+    );
+}
+
+#[tokio::test]
+async fn fee_withdrawals_add_up_per_period() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority, _) = governed_auction_house(&mut context, ONE_SOL).await;
+
+    // The governance account keeping the running total is required.
+    let mut instruction = withdraw_from_fee(&ah, &ahkey, &authority.pubkey(), ONE_SOL / 2);
+    instruction.accounts.pop();
+    let err = process(&mut context, &[instruction], &authority, &[])
+        .await
+        .unwrap_err();
+    assert_error!(err, INVALID_GOVERNANCE);
+
+    process(
+        &mut context,
+        &[withdraw_from_fee(
+            &ah,
+            &ahkey,
+            &authority.pubkey(),
+            ONE_SOL / 2,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let err = process(
+        &mut context,
+        &[withdraw_from_fee(
+            &ah,
+            &ahkey,
+            &authority.pubkey(),
+            ONE_SOL / 2 + 1,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, GOVERNANCE_REQUIRED);
+
+    // Treasury withdrawals add up separately.
+    process(
+        &mut context,
+        &[withdraw_from_treasury(
+            &ah,
+            &ahkey,
+            &authority.pubkey(),
+            ONE_SOL,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let gov = governance(&mut context, &ahkey).await;
+    assert_eq!(gov.fee_withdrawn_in_period, ONE_SOL / 2);
+    assert_eq!(gov.withdrawn_in_period, ONE_SOL);
+    warp_to(
+        &mut context,
+        gov.withdrawal_period_start + WITHDRAWAL_PERIOD,
+    )
+    .await;
+    process(
+        &mut context,
+        &[withdraw_from_fee(&ah, &ahkey, &authority.pubkey(), ONE_SOL)],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+    let gov = governance(&mut context, &ahkey).await;
+    assert_eq!(gov.fee_withdrawn_in_period, ONE_SOL);
+    assert_eq!(gov.withdrawn_in_period, 0);
+}
+
+#[tokio::test]
+async fn governance_signers_only() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority, signer) = governed_auction_house(&mut context, ONE_SOL).await;
+    let outsider = Keypair::new();
+    airdrop(&mut context, &outsider.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+
+    let gov = governance(&mut context, &ahkey).await;
+    let change = GovernedChange::Authority {
+        new_authority: outsider.pubkey(),
+    };
+    let err = process(
+        &mut context,
+        &[client::propose_change(
+            &ahkey,
+            &gov,
+            &outsider.pubkey(),
+            change.clone(),
+        )],
+        &outsider,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, NOT_GOVERNANCE_SIGNER);
+
+    process(
+        &mut context,
+        &[client::propose_change(
+            &ahkey,
+            &gov,
+            &authority.pubkey(),
+            change,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap();
+    let pending = pending_change(&mut context, &ahkey, 0).await.unwrap();
+
+    let err = process(
+        &mut context,
+        &[client::approve_change(&ahkey, &pending, &outsider.pubkey())],
+        &outsider,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, NOT_GOVERNANCE_SIGNER);
+
+    // Any signer can veto a pending change.
+    process(
+        &mut context,
+        &[client::cancel_change(&ahkey, &pending, &signer.pubkey())],
+        &signer,
+        &[],
+    )
+    .await
+    .unwrap();
+    assert!(pending_change(&mut context, &ahkey, 0).await.is_none());
+    assert_eq!(
+        auction_house(&mut context, &ahkey).await.authority,
+        authority.pubkey()
+    );
+}
+
+#[tokio::test]
+async fn enable_governance_rejects_invalid_threshold() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    let err = process(
+        &mut context,
+        &[client::enable_governance(
+            &ahkey,
+            &authority.pubkey(),
+            vec![authority.pubkey()],
+            2,
+            DELAY,
+            ONE_SOL,
+            ONE_SOL,
+        )],
+        &authority,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, INVALID_GOVERNANCE);
+}
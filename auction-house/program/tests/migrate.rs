@@ -0,0 +1,223 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::{
+    client::{self, BuyBuilder, ExecuteSaleBuilder, SellBuilder},
+    constants::{AUCTION_HOUSE_SIZE, AUCTION_HOUSE_V0_SIZE, AUCTION_HOUSE_VERSION, MAX_FEE_TIERS},
+    fee_tier::{FeeTier, FeeTierRequirement},
+};
+use solana_program::program_pack::Pack;
+use solana_sdk::account::{Account as SolanaAccount, AccountSharedData};
+use std::result::Result as StdResult;
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+) -> StdResult<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn auction_house_account(context: &mut ProgramTestContext, ahkey: &Pubkey) -> SolanaAccount {
+    context
+        .banks_client
+        .get_account(*ahkey)
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+async fn auction_house(context: &mut ProgramTestContext, ahkey: &Pubkey) -> AuctionHouse {
+    let account = auction_house_account(context, ahkey).await;
+    AuctionHouse::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+/// Auction House rewritten in the layout it had before versioning.
+async fn legacy_auction_house(context: &mut ProgramTestContext) -> (AuctionHouse, Pubkey, Keypair) {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    airdrop(context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let legacy = AuctionHouse { version: 0, ..ah };
+    let mut data = Vec::new();
+    legacy.try_serialize(&mut data).unwrap();
+    data.resize(AUCTION_HOUSE_V0_SIZE, 0);
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let account = SolanaAccount {
+        lamports: rent.minimum_balance(AUCTION_HOUSE_V0_SIZE),
+        data,
+        owner: mpl_auction_house::id(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    context.set_account(&ahkey, &AccountSharedData::from(account));
+
+    let ah = auction_house(context, &ahkey).await;
+    assert_eq!(ah.version, 0);
+
+    (ah, ahkey, authority)
+}
+
+#[tokio::test]
+async fn new_auction_house_is_latest_version() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    assert_eq!(ah.version, AUCTION_HOUSE_VERSION);
+    let account = auction_house_account(&mut context, &ahkey).await;
+    assert_eq!(account.data.len(), AUCTION_HOUSE_SIZE);
+
+    let err = process(
+        &mut context,
+        &[client::migrate_auction_house(&ahkey, &authority.pubkey())],
+        &authority,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, AUCTION_HOUSE_ALREADY_MIGRATED);
+}
+
+#[tokio::test]
+async fn legacy_auction_house_migrates_in_place() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = legacy_auction_house(&mut context).await;
+
+    // Handlers keep updating the legacy layout, but the version 1 fields need the room of the migrated account.
+    let fee_tiers: Vec<FeeTier> = (0..MAX_FEE_TIERS)
+        .map(|_| FeeTier {
+            mint: Pubkey::new_unique(),
+            requirement: FeeTierRequirement::MinimumBalance { min_balance: 1 },
+            fee_basis_points: 50,
+        })
+        .collect();
+    let err = process(
+        &mut context,
+        &[client::set_fee_tiers(
+            &ahkey,
+            &authority.pubkey(),
+            fee_tiers.clone(),
+        )],
+        &authority,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, AUCTION_HOUSE_NOT_MIGRATED);
+    let account = auction_house_account(&mut context, &ahkey).await;
+    assert_eq!(account.data.len(), AUCTION_HOUSE_V0_SIZE);
+
+    process(
+        &mut context,
+        &[client::migrate_auction_house(&ahkey, &authority.pubkey())],
+        &authority,
+    )
+    .await
+    .unwrap();
+
+    let account = auction_house_account(&mut context, &ahkey).await;
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(account.data.len(), AUCTION_HOUSE_SIZE);
+    assert!(rent.is_exempt(account.lamports, AUCTION_HOUSE_SIZE));
+
+    let ah = auction_house(&mut context, &ahkey).await;
+    assert_eq!(ah.version, AUCTION_HOUSE_VERSION);
+    assert_eq!(ah.authority, authority.pubkey());
+    assert_eq!(ah.seller_fee_basis_points, 100);
+
+    // The migrated account holds the full fee tier list.
+    process(
+        &mut context,
+        &[client::set_fee_tiers(
+            &ahkey,
+            &authority.pubkey(),
+            fee_tiers.clone(),
+        )],
+        &authority,
+    )
+    .await
+    .unwrap();
+    let ah = auction_house(&mut context, &ahkey).await;
+    assert_eq!(ah.fee_tiers, fee_tiers);
+}
+
+#[tokio::test]
+async fn legacy_auction_house_executes_sale() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = legacy_auction_house(&mut context).await;
+
+    let item = Metadata::new();
+    airdrop(&mut context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        &mut context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        0,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+    let metadata = item.get_data(&mut context).await;
+    let seller = item.token.pubkey();
+
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, seller, ONE_SOL);
+    process(&mut context, &[sell.instruction()], &item.token)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let buy = BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL);
+    process(&mut context, &[buy.instruction()], &buyer)
+        .await
+        .unwrap();
+
+    let sale = ExecuteSaleBuilder::new(
+        ahkey,
+        &ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        ONE_SOL,
+    );
+    process(&mut context, &[sale.instruction()], &authority)
+        .await
+        .unwrap();
+
+    let buyer_token_account = spl_token::state::Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(get_associated_token_address(
+                &buyer.pubkey(),
+                &metadata.mint,
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token_account.amount, 1);
+}
@@ -0,0 +1,480 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{
+    helpers::{assert_scopes_eq, default_scopes},
+    setup_functions::*,
+};
+
+use mpl_auction_house::constants::TRADE_STATE_HAS_AUCTIONEER;
+
+async fn create_item(context: &mut ProgramTestContext) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), 100_000_000_000_000)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Tests".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    item
+}
+
+async fn add_test_auctioneer(
+    context: &mut ProgramTestContext,
+    ahkey: Pubkey,
+    ah_auth: &Keypair,
+    scopes: Vec<AuthorityScope>,
+) -> (Keypair, Pubkey) {
+    let auctioneer_authority = Keypair::new();
+    let (auctioneer_pda, _) = find_auctioneer_pda(&ahkey, &auctioneer_authority.pubkey());
+    add_auctioneer(
+        context,
+        ahkey,
+        ah_auth,
+        auctioneer_authority.pubkey(),
+        auctioneer_pda,
+        scopes,
+    )
+    .await
+    .unwrap();
+
+    (auctioneer_authority, auctioneer_pda)
+}
+
+fn auctioneer_cancel_listing(
+    context: &mut ProgramTestContext,
+    ahkey: Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    seller_trade_state: Pubkey,
+    auctioneer_authority: &Keypair,
+) -> Transaction {
+    let (auctioneer_pda, _) = find_auctioneer_pda(&ahkey, &auctioneer_authority.pubkey());
+    let accounts = mpl_auction_house::accounts::AuctioneerCancel {
+        auction_house: ahkey,
+        wallet: test_metadata.token.pubkey(),
+        token_account: get_associated_token_address(
+            &test_metadata.token.pubkey(),
+            &test_metadata.mint.pubkey(),
+        ),
+        authority: ah.authority,
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        trade_state: seller_trade_state,
+        ah_auctioneer_pda: auctioneer_pda,
+        token_program: spl_token::id(),
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::AuctioneerCancel {
+            buyer_price: u64::MAX,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token, auctioneer_authority],
+        context.last_blockhash,
+    )
+}
+
+#[tokio::test]
+async fn add_auctioneer_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, ah_auth) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    let delegated_authority = Keypair::new();
+    let (delegated_pda, _) = find_auctioneer_pda(&ahkey, &delegated_authority.pubkey());
+    delegate_auctioneer(
+        &mut context,
+        ahkey,
+        &ah_auth,
+        delegated_authority.pubkey(),
+        delegated_pda,
+        default_scopes(),
+    )
+    .await
+    .unwrap();
+
+    let scopes = vec![AuthorityScope::Sell, AuthorityScope::ExecuteSale];
+    let (added_authority, added_pda) =
+        add_test_auctioneer(&mut context, ahkey, &ah_auth, scopes.clone()).await;
+
+    let ah_account = context
+        .banks_client
+        .get_account(ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let ah = AuctionHouse::try_deserialize(&mut ah_account.data.as_ref()).unwrap();
+    assert!(ah.has_auctioneer);
+    assert_eq!(ah.auctioneer_address, delegated_pda);
+    assert_scopes_eq(default_scopes(), ah.scopes);
+
+    let auctioneer_account = context
+        .banks_client
+        .get_account(added_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let auctioneer = Auctioneer::try_deserialize(&mut auctioneer_account.data.as_ref()).unwrap();
+    assert_eq!(auctioneer.auctioneer_authority, added_authority.pubkey());
+    assert_eq!(auctioneer.auction_house, ahkey);
+    assert_scopes_eq(scopes, auctioneer.scopes);
+    assert!(!auctioneer.scopes[AuthorityScope::Cancel as usize]);
+}
+
+#[tokio::test]
+async fn added_auctioneer_sell_records_auctioneer() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, ah_auth) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (auctioneer_authority, auctioneer_pda) =
+        add_test_auctioneer(&mut context, ahkey, &ah_auth, vec![AuthorityScope::Sell]).await;
+
+    let test_metadata = create_item(&mut context).await;
+    let (acc, sell_tx) = auctioneer_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &auctioneer_authority,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let trade_state = context
+        .banks_client
+        .get_account(acc.seller_trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trade_state.data.len(), 34);
+    assert_eq!(trade_state.data[1], TRADE_STATE_HAS_AUCTIONEER);
+    assert_eq!(&trade_state.data[2..], auctioneer_pda.as_ref());
+
+    // Listings no auctioneer owns keep using the regular handlers.
+    let other_metadata = create_item(&mut context).await;
+    let (_, sell_tx) = sell(&mut context, &ahkey, &ah, &other_metadata, 100_000_000, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn added_auctioneer_missing_scope_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, ah_auth) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (auctioneer_authority, _) =
+        add_test_auctioneer(&mut context, ahkey, &ah_auth, vec![AuthorityScope::Cancel]).await;
+
+    let test_metadata = create_item(&mut context).await;
+    let (_, sell_tx) = auctioneer_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &auctioneer_authority,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, MISSING_AUCTIONEER_SCOPE);
+}
+
+#[tokio::test]
+async fn other_auctioneer_cancel_listing_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, ah_auth) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (english_authority, _) = add_test_auctioneer(
+        &mut context,
+        ahkey,
+        &ah_auth,
+        vec![AuthorityScope::Sell, AuthorityScope::Cancel],
+    )
+    .await;
+    let (dutch_authority, _) = add_test_auctioneer(
+        &mut context,
+        ahkey,
+        &ah_auth,
+        vec![AuthorityScope::Sell, AuthorityScope::Cancel],
+    )
+    .await;
+
+    let test_metadata = create_item(&mut context).await;
+    let (acc, sell_tx) = auctioneer_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &english_authority,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let tx = auctioneer_cancel_listing(
+        &mut context,
+        ahkey,
+        &ah,
+        &test_metadata,
+        acc.seller_trade_state,
+        &dutch_authority,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_AUCTIONEER);
+
+    // The seller can not bypass the owning auctioneer with the regular cancel.
+    let accounts = mpl_auction_house::accounts::Cancel {
+        auction_house: ahkey,
+        wallet: test_metadata.token.pubkey(),
+        token_account: acc.token_account,
+        authority: ah.authority,
+        trade_state: acc.seller_trade_state,
+        token_program: spl_token::id(),
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Cancel {
+            buyer_price: u64::MAX,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, MUST_USE_AUCTIONEER_HANDLER);
+
+    let tx = auctioneer_cancel_listing(
+        &mut context,
+        ahkey,
+        &ah,
+        &test_metadata,
+        acc.seller_trade_state,
+        &english_authority,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+/// Cancel the bid of `buyer` on `test_metadata` through `auctioneer_authority`, or through the regular handler
+/// without one.
+fn cancel_bid(
+    context: &mut ProgramTestContext,
+    ahkey: Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Keypair,
+    buyer_trade_state: Pubkey,
+    sale_price: u64,
+    auctioneer_authority: Option<&Keypair>,
+) -> Transaction {
+    let token_account =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let mut signers = vec![buyer];
+    let instruction = match auctioneer_authority {
+        Some(auctioneer_authority) => {
+            signers.push(auctioneer_authority);
+            Instruction {
+                program_id: mpl_auction_house::id(),
+                data: mpl_auction_house::instruction::AuctioneerCancel {
+                    buyer_price: sale_price,
+                    token_size: 1,
+                }
+                .data(),
+                accounts: mpl_auction_house::accounts::AuctioneerCancel {
+                    auction_house: ahkey,
+                    wallet: buyer.pubkey(),
+                    token_account,
+                    authority: ah.authority,
+                    auctioneer_authority: auctioneer_authority.pubkey(),
+                    trade_state: buyer_trade_state,
+                    ah_auctioneer_pda: find_auctioneer_pda(&ahkey, &auctioneer_authority.pubkey())
+                        .0,
+                    token_program: spl_token::id(),
+                    token_mint: test_metadata.mint.pubkey(),
+                    auction_house_fee_account: ah.auction_house_fee_account,
+                }
+                .to_account_metas(None),
+            }
+        }
+        None => Instruction {
+            program_id: mpl_auction_house::id(),
+            data: mpl_auction_house::instruction::Cancel {
+                buyer_price: sale_price,
+                token_size: 1,
+            }
+            .data(),
+            accounts: mpl_auction_house::accounts::Cancel {
+                auction_house: ahkey,
+                wallet: buyer.pubkey(),
+                token_account,
+                authority: ah.authority,
+                trade_state: buyer_trade_state,
+                token_program: spl_token::id(),
+                token_mint: test_metadata.mint.pubkey(),
+                auction_house_fee_account: ah.auction_house_fee_account,
+            }
+            .to_account_metas(None),
+        },
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &signers,
+        context.last_blockhash,
+    )
+}
+
+#[tokio::test]
+async fn other_auctioneer_cancel_bid_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, ah_auth) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (english_authority, english_pda) = add_test_auctioneer(
+        &mut context,
+        ahkey,
+        &ah_auth,
+        vec![AuthorityScope::Buy, AuthorityScope::Cancel],
+    )
+    .await;
+    let (dutch_authority, _) = add_test_auctioneer(
+        &mut context,
+        ahkey,
+        &ah_auth,
+        vec![AuthorityScope::Buy, AuthorityScope::Cancel],
+    )
+    .await;
+
+    let test_metadata = create_item(&mut context).await;
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let sale_price = 100_000_000;
+    let (acc, buy_tx) = auctioneer_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        &english_authority,
+        sale_price,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // The bid records the auctioneer that created it, like a listing.
+    let trade_state = context
+        .banks_client
+        .get_account(acc.buyer_trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trade_state.data.len(), 34);
+    assert_eq!(trade_state.data[1], TRADE_STATE_HAS_AUCTIONEER);
+    assert_eq!(&trade_state.data[2..], english_pda.as_ref());
+
+    let tx = cancel_bid(
+        &mut context,
+        ahkey,
+        &ah,
+        &test_metadata,
+        &buyer,
+        acc.buyer_trade_state,
+        sale_price,
+        Some(&dutch_authority),
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_AUCTIONEER);
+
+    // The buyer can not bypass the owning auctioneer with the regular cancel.
+    let tx = cancel_bid(
+        &mut context,
+        ahkey,
+        &ah,
+        &test_metadata,
+        &buyer,
+        acc.buyer_trade_state,
+        sale_price,
+        None,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, MUST_USE_AUCTIONEER_HANDLER);
+
+    let tx = cancel_bid(
+        &mut context,
+        ahkey,
+        &ah,
+        &test_metadata,
+        &buyer,
+        acc.buyer_trade_state,
+        sale_price,
+        Some(&english_authority),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
@@ -0,0 +1,463 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::{
+    constants::{
+        TRADE_STATE_HAS_ALLOWED_BUYER, TRADE_STATE_HAS_AUCTIONEER, TRADE_STATE_HAS_EXPIRY,
+    },
+    pda::{
+        find_auctioneer_trade_state_address, find_listing_receipt_address,
+        find_program_as_signer_address, find_trade_state_address,
+    },
+    receipt::ListingReceipt,
+};
+use std::result::Result as StdResult;
+
+async fn create_item(context: &mut ProgramTestContext) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    item
+}
+
+/// Lists `test_metadata` reserved for `allowed_buyer` and prints the listing receipt.
+/// The listing is signed by `authority` as well when given.
+async fn private_sell(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    sale_price: u64,
+    allowed_buyer: &Pubkey,
+    authority: Option<&Keypair>,
+) -> (
+    mpl_auction_house::accounts::Sell,
+    mpl_auction_house::accounts::PrintListingReceipt,
+) {
+    let ((sell_acc, receipt_acc), _) = sell(context, ahkey, ah, test_metadata, sale_price, 1);
+    let (_, sts_bump) = find_trade_state_address(
+        &sell_acc.wallet,
+        ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (_, free_sts_bump) = find_trade_state_address(
+        &sell_acc.wallet,
+        ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, pas_bump) = find_program_as_signer_address();
+    let (_, receipt_bump) = find_listing_receipt_address(&sell_acc.seller_trade_state);
+
+    let mut sell_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Sell {
+            trade_state_bump: sts_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+            buyer_price: sale_price,
+        }
+        .data(),
+        accounts: sell_acc.to_account_metas(None),
+    };
+    let mut signers = vec![&test_metadata.token];
+    if let Some(authority) = authority {
+        sell_instruction.accounts[3].is_signer = true;
+        signers.push(authority);
+    }
+    let (_, allowed_buyer_instruction) = set_allowed_buyer(
+        &sell_acc.wallet,
+        ahkey,
+        ah,
+        &sell_acc.seller_trade_state,
+        allowed_buyer,
+    );
+    let receipt_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::PrintListingReceipt { receipt_bump }.data(),
+        accounts: receipt_acc.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            sell_instruction,
+            allowed_buyer_instruction,
+            receipt_instruction,
+        ],
+        Some(&sell_acc.wallet),
+        &signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    (sell_acc, receipt_acc)
+}
+
+/// Lists `test_metadata` through `auctioneer_authority` reserved for `allowed_buyer`.
+async fn private_auctioneer_sell(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    auctioneer_authority: &Keypair,
+    allowed_buyer: &Pubkey,
+) -> Pubkey {
+    let wallet = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&wallet, &test_metadata.mint.pubkey());
+    let (seller_trade_state, sts_bump) = find_auctioneer_trade_state_address(
+        &wallet,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        1,
+    );
+    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
+        &wallet,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let sell_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::AuctioneerSell {
+            trade_state_bump: sts_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+        }
+        .data(),
+        accounts: mpl_auction_house::accounts::AuctioneerSell {
+            wallet,
+            token_account,
+            metadata: test_metadata.pubkey,
+            authority: ah.authority,
+            auctioneer_authority: auctioneer_authority.pubkey(),
+            auction_house: *ahkey,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            seller_trade_state,
+            free_seller_trade_state,
+            ah_auctioneer_pda: find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey()).0,
+            token_program: spl_token::id(),
+            system_program: solana_program::system_program::id(),
+            program_as_signer,
+            rent: solana_program::sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+    let (_, allowed_buyer_instruction) =
+        set_allowed_buyer(&wallet, ahkey, ah, &seller_trade_state, allowed_buyer);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[sell_instruction, allowed_buyer_instruction],
+        Some(&wallet),
+        &[&test_metadata.token, auctioneer_authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    seller_trade_state
+}
+
+async fn buy_and_execute(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    sell_acc: &mpl_auction_house::accounts::Sell,
+    buyer: &Keypair,
+    sale_price: u64,
+) -> StdResult<(), BanksClientError> {
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        &sell_acc.wallet,
+        buyer,
+        sale_price,
+        1,
+    );
+    context.banks_client.process_transaction(buy_tx).await?;
+
+    let (_, tx) = execute_sale(
+        context,
+        ahkey,
+        ah,
+        authority,
+        test_metadata,
+        &buyer.pubkey(),
+        &sell_acc.wallet,
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        sale_price,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn set_allowed_buyer_on_auctioneer_listing_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let auctioneer_authority = Keypair::new();
+    let (auctioneer_pda, _) = find_auctioneer_pda(&ahkey, &auctioneer_authority.pubkey());
+    add_auctioneer(
+        &mut context,
+        ahkey,
+        &authority,
+        auctioneer_authority.pubkey(),
+        auctioneer_pda,
+        vec![AuthorityScope::Sell],
+    )
+    .await
+    .unwrap();
+    let item = create_item(&mut context).await;
+    let allowed_buyer = Pubkey::new_unique();
+
+    let seller_trade_state = private_auctioneer_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &auctioneer_authority,
+        &allowed_buyer,
+    )
+    .await;
+
+    // The auctioneer that created the listing is kept between the expiry and the allowed buyer.
+    let trade_state = context
+        .banks_client
+        .get_account(seller_trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trade_state.data.len(), 106);
+    assert_eq!(
+        trade_state.data[1],
+        TRADE_STATE_HAS_EXPIRY | TRADE_STATE_HAS_AUCTIONEER | TRADE_STATE_HAS_ALLOWED_BUYER
+    );
+    assert_eq!(
+        i64::from_le_bytes(trade_state.data[2..10].try_into().unwrap()),
+        i64::MAX
+    );
+    assert_eq!(&trade_state.data[10..42], item.token.pubkey().as_ref());
+    assert_eq!(&trade_state.data[42..74], auctioneer_pda.as_ref());
+    assert_eq!(&trade_state.data[74..106], allowed_buyer.as_ref());
+}
+
+#[tokio::test]
+async fn set_allowed_buyer_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let allowed_buyer = Pubkey::new_unique();
+
+    let (sell_acc, receipt_acc) = private_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        ONE_SOL,
+        &allowed_buyer,
+        None,
+    )
+    .await;
+
+    let trade_state = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trade_state.data.len(), 74);
+    assert_eq!(
+        trade_state.data[1],
+        TRADE_STATE_HAS_EXPIRY | TRADE_STATE_HAS_ALLOWED_BUYER
+    );
+    assert_eq!(
+        i64::from_le_bytes(trade_state.data[2..10].try_into().unwrap()),
+        i64::MAX
+    );
+    assert_eq!(&trade_state.data[10..42], item.token.pubkey().as_ref());
+    assert_eq!(&trade_state.data[42..74], allowed_buyer.as_ref());
+
+    let receipt_account = context
+        .banks_client
+        .get_account(receipt_acc.receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let receipt = ListingReceipt::try_deserialize(&mut receipt_account.data.as_ref()).unwrap();
+    assert_eq!(receipt.allowed_buyer, Some(allowed_buyer));
+    assert_eq!(receipt.trade_state, sell_acc.seller_trade_state);
+}
+
+#[tokio::test]
+async fn set_allowed_buyer_on_authority_listing_records_fee_account() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let allowed_buyer = Pubkey::new_unique();
+    let seller_balance = context
+        .banks_client
+        .get_balance(item.token.pubkey())
+        .await
+        .unwrap();
+
+    let (sell_acc, _) = private_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        ONE_SOL,
+        &allowed_buyer,
+        Some(&authority),
+    )
+    .await;
+
+    // The fee account paid for the trade state in the sell instruction, so it pays for the growth too.
+    let trade_state = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(trade_state.lamports, rent.minimum_balance(74));
+    assert_eq!(
+        &trade_state.data[10..42],
+        ah.auction_house_fee_account.as_ref()
+    );
+    assert_eq!(&trade_state.data[42..74], allowed_buyer.as_ref());
+
+    // The seller only paid the two signature fees and the listing receipt.
+    let (receipt, _) = find_listing_receipt_address(&sell_acc.seller_trade_state);
+    let receipt_lamports = context.banks_client.get_balance(receipt).await.unwrap();
+    let seller_spent = seller_balance
+        - context
+            .banks_client
+            .get_balance(item.token.pubkey())
+            .await
+            .unwrap();
+    assert!(seller_spent <= receipt_lamports + 10_000);
+}
+
+#[tokio::test]
+async fn execute_sale_allowed_buyer_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+
+    let (sell_acc, _) = private_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        ONE_SOL,
+        &buyer.pubkey(),
+        None,
+    )
+    .await;
+    buy_and_execute(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &item,
+        &sell_acc,
+        &buyer,
+        ONE_SOL,
+    )
+    .await
+    .unwrap();
+
+    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &item.mint.pubkey());
+    let token_account = context
+        .banks_client
+        .get_account(buyer_token_account)
+        .await
+        .unwrap();
+    assert!(token_account.is_some());
+}
+
+#[tokio::test]
+async fn execute_sale_other_buyer_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+
+    let (sell_acc, _) = private_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        ONE_SOL,
+        &Pubkey::new_unique(),
+        None,
+    )
+    .await;
+    let error = buy_and_execute(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &item,
+        &sell_acc,
+        &buyer,
+        ONE_SOL,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(error, BUYER_NOT_ALLOWED);
+}
@@ -0,0 +1,385 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::{
+    pda::{find_escrow_payment_address, find_escrow_reservation_address, find_trade_state_address},
+    reservation::{EscrowReservation, EscrowReservationMode, ReservedBid},
+};
+use solana_program::instruction::AccountMeta;
+
+async fn setup_escrow_reservation_mode(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    mode: EscrowReservationMode,
+) {
+    let (_, tx) = update_escrow_reservation_mode(context, ahkey, ah, authority, mode);
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+async fn create_item(context: &mut ProgramTestContext) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    item
+}
+
+/// Builds a private `buy` transaction with `escrow_reservation` as the last remaining account.
+fn buy_with_escrow_reservation(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Keypair,
+    sale_price: u64,
+    escrow_reservation: Option<Pubkey>,
+) -> (mpl_auction_house::accounts::Buy, Transaction) {
+    let ((acc, _), _) = buy(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        &test_metadata.token.pubkey(),
+        buyer,
+        sale_price,
+        1,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let (_, bts_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+
+    let mut accounts = acc.to_account_metas(None);
+    if let Some(escrow_reservation) = escrow_reservation {
+        accounts.push(AccountMeta::new(escrow_reservation, false));
+    }
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Buy {
+            trade_state_bump: bts_bump,
+            escrow_payment_bump: escrow_bump,
+            buyer_price: sale_price,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+
+    (
+        acc,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+async fn get_escrow_reservation(
+    context: &mut ProgramTestContext,
+    escrow_reservation: Pubkey,
+) -> EscrowReservation {
+    let account = context
+        .banks_client
+        .get_account(escrow_reservation)
+        .await
+        .unwrap()
+        .unwrap();
+    EscrowReservation::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+#[tokio::test]
+async fn bid_reserves_escrow_and_cancel_releases_it() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    setup_escrow_reservation_mode(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        EscrowReservationMode::Track,
+    )
+    .await;
+    let item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (escrow_reservation, _) = find_escrow_reservation_address(&ahkey, &buyer.pubkey());
+
+    let (bid_acc, tx) = buy_with_escrow_reservation(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &buyer,
+        ONE_SOL,
+        Some(escrow_reservation),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let reservation = get_escrow_reservation(&mut context, escrow_reservation).await;
+    assert_eq!(reservation.auction_house, ahkey);
+    assert_eq!(reservation.wallet, buyer.pubkey());
+    assert_eq!(
+        reservation.bids,
+        vec![ReservedBid {
+            trade_state: bid_acc.buyer_trade_state,
+            amount: ONE_SOL,
+        }]
+    );
+
+    let mut accounts = mpl_auction_house::accounts::Cancel {
+        auction_house: ahkey,
+        wallet: buyer.pubkey(),
+        token_account: bid_acc.token_account,
+        authority: ah.authority,
+        trade_state: bid_acc.buyer_trade_state,
+        token_program: spl_token::id(),
+        token_mint: item.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(escrow_reservation, false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Cancel {
+            buyer_price: ONE_SOL,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let reservation = get_escrow_reservation(&mut context, escrow_reservation).await;
+    assert!(reservation.bids.is_empty());
+}
+
+#[tokio::test]
+async fn bid_reserves_price_and_buyer_fee() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    setup_escrow_reservation_mode(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        EscrowReservationMode::Track,
+    )
+    .await;
+    let (_, tx) = update_auction_house_fees(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        Some(50),
+        Some(200),
+        None,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (escrow_reservation, _) = find_escrow_reservation_address(&ahkey, &buyer.pubkey());
+
+    let (bid_acc, tx) = buy_with_escrow_reservation(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &buyer,
+        ONE_SOL,
+        Some(escrow_reservation),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // The sale that fills the bid withdraws the taker fee on top of the price.
+    let reservation = get_escrow_reservation(&mut context, escrow_reservation).await;
+    assert_eq!(
+        reservation.bids,
+        vec![ReservedBid {
+            trade_state: bid_acc.buyer_trade_state,
+            amount: ONE_SOL + ONE_SOL * 200 / 10000,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn bid_without_escrow_reservation_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    setup_escrow_reservation_mode(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        EscrowReservationMode::Track,
+    )
+    .await;
+    let item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+
+    let (_, tx) =
+        buy_with_escrow_reservation(&mut context, &ahkey, &ah, &item, &buyer, ONE_SOL, None);
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_ESCROW_RESERVATION);
+}
+
+#[tokio::test]
+async fn bid_over_escrow_balance_fails_when_enforced() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    setup_escrow_reservation_mode(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        EscrowReservationMode::Enforce,
+    )
+    .await;
+    let item = create_item(&mut context).await;
+    let other_item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (escrow_reservation, _) = find_escrow_reservation_address(&ahkey, &buyer.pubkey());
+
+    // The first bid funds the escrow with exactly its amount.
+    let (_, tx) = buy_with_escrow_reservation(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &buyer,
+        ONE_SOL,
+        Some(escrow_reservation),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let (_, tx) = buy_with_escrow_reservation(
+        &mut context,
+        &ahkey,
+        &ah,
+        &other_item,
+        &buyer,
+        ONE_SOL,
+        Some(escrow_reservation),
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, ESCROW_OVERCOMMITTED);
+}
+
+#[tokio::test]
+async fn withdraw_reserved_escrow_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    setup_escrow_reservation_mode(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        EscrowReservationMode::Track,
+    )
+    .await;
+    let item = create_item(&mut context).await;
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (escrow_reservation, _) = find_escrow_reservation_address(&ahkey, &buyer.pubkey());
+
+    let (_, tx) = buy_with_escrow_reservation(
+        &mut context,
+        &ahkey,
+        &ah,
+        &item,
+        &buyer,
+        ONE_SOL,
+        Some(escrow_reservation),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let ((acc,), _) = withdraw(&mut context, &buyer, &ahkey, &ah, &item, ONE_SOL, ONE_SOL);
+    let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let mut accounts = acc.to_account_metas(None);
+    accounts.push(AccountMeta::new(escrow_reservation, false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Withdraw {
+            escrow_payment_bump: escrow_bump,
+            amount: ONE_SOL,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, ESCROW_FUNDS_RESERVED);
+}
@@ -0,0 +1,412 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::DirtyClone, setup_functions::*};
+
+use mpl_auction_house::{
+    client::{self, BuyBuilder, ExecuteSaleBuilder, SellBuilder},
+    pda::{find_escrow_payment_address, find_purchase_receipt_address},
+    receipt::{PurchaseReceipt, PURCHASE_RECEIPT_SIZE},
+    royalty::RoyaltyPolicy,
+};
+use mpl_token_metadata::state::{Creator, PrintSupply, TokenStandard};
+use std::result::Result as StdResult;
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+) -> StdResult<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn auction_house(context: &mut ProgramTestContext, ahkey: &Pubkey) -> AuctionHouse {
+    let account = context
+        .banks_client
+        .get_account(*ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    AuctionHouse::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+/// Auction House with a funded fee account, a stats account and `royalty_policy`.
+async fn auction_house_with_policy(
+    context: &mut ProgramTestContext,
+    royalty_policy: RoyaltyPolicy,
+) -> (AuctionHouse, Pubkey, Keypair) {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    assert_eq!(ah.royalty_policy, RoyaltyPolicy::Enforced);
+    airdrop(context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    process(
+        context,
+        &[
+            client::set_royalty_policy(&ahkey, &authority.pubkey(), royalty_policy),
+            client::create_auction_house_stats(&ahkey, &authority.pubkey()),
+        ],
+        &authority,
+    )
+    .await
+    .unwrap();
+
+    let ah = auction_house(context, &ahkey).await;
+    assert_eq!(ah.royalty_policy, royalty_policy);
+
+    (ah, ahkey, authority)
+}
+
+/// NFT with a 10% royalty paid to `creator`.
+async fn create_item(context: &mut ProgramTestContext, creator: &Pubkey) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        Some(vec![Creator {
+            address: *creator,
+            verified: false,
+            share: 100,
+        }]),
+        1000,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+    item
+}
+
+/// List and buy `item` at one SOL, then execute the sale and print its purchase receipt with the royalty.
+/// Returns the purchase receipt.
+async fn sell_item(
+    context: &mut ProgramTestContext,
+    ah: &AuctionHouse,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    item: &Metadata,
+    buyer: &Keypair,
+) -> PurchaseReceipt {
+    let metadata = item.get_data(context).await;
+    let seller = item.token.pubkey();
+
+    let sell = SellBuilder::new(*ahkey, ah, &metadata, seller, ONE_SOL);
+    process(
+        context,
+        &[
+            sell.instruction(),
+            client::print_listing_receipt(&sell.seller_trade_state(), &seller),
+        ],
+        &item.token,
+    )
+    .await
+    .unwrap();
+
+    let buy = BuyBuilder::new(*ahkey, ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL);
+    process(
+        context,
+        &[
+            buy.instruction(),
+            client::print_bid_receipt(&buy.buyer_trade_state(), &buyer.pubkey()),
+        ],
+        buyer,
+    )
+    .await
+    .unwrap();
+
+    let sale = ExecuteSaleBuilder::new(
+        *ahkey,
+        ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        ONE_SOL,
+    );
+    let mut receipt = client::print_purchase_receipt(
+        &sale.seller_trade_state(),
+        &sale.buyer_trade_state(),
+        &authority.pubkey(),
+    );
+    receipt
+        .accounts
+        .append(&mut client::purchase_royalty_accounts(ahkey, ah));
+    process(context, &[sale.instruction(), receipt], authority)
+        .await
+        .unwrap();
+
+    let (purchase_receipt, _) =
+        find_purchase_receipt_address(&sale.seller_trade_state(), &sale.buyer_trade_state());
+    let account = context
+        .banks_client
+        .get_account(purchase_receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    client::read_purchase_receipt(&account.data).unwrap()
+}
+
+async fn funded_buyer(context: &mut ProgramTestContext) -> Keypair {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    buyer
+}
+
+#[tokio::test]
+async fn minimum_policy_pays_minimum_share() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) =
+        auction_house_with_policy(&mut context, RoyaltyPolicy::Minimum { basis_points: 5000 })
+            .await;
+
+    let creator = Keypair::new();
+    let item = create_item(&mut context, &creator.pubkey()).await;
+    let buyer = funded_buyer(&mut context).await;
+    let receipt = sell_item(&mut context, &ah, &ahkey, &authority, &item, &buyer).await;
+
+    // Half of the 10% royalty.
+    let creator_balance = context
+        .banks_client
+        .get_balance(creator.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(creator_balance, ONE_SOL / 20);
+    assert_eq!(receipt.royalty, Some(ONE_SOL / 20));
+}
+
+#[tokio::test]
+async fn optional_policy_pays_buyer_election_above_floor() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = auction_house_with_policy(
+        &mut context,
+        RoyaltyPolicy::Optional {
+            floor_basis_points: 2000,
+        },
+    )
+    .await;
+
+    let buyer = funded_buyer(&mut context).await;
+    process(
+        &mut context,
+        &[client::set_royalty_election(&ahkey, &buyer.pubkey(), 0)],
+        &buyer,
+    )
+    .await
+    .unwrap();
+
+    let creator = Keypair::new();
+    let item = create_item(&mut context, &creator.pubkey()).await;
+    let receipt = sell_item(&mut context, &ah, &ahkey, &authority, &item, &buyer).await;
+
+    // The buyer opted out of royalties, so creators get the floor of 20% of the 10% royalty.
+    let creator_balance = context
+        .banks_client
+        .get_balance(creator.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(creator_balance, ONE_SOL / 50);
+    assert_eq!(receipt.royalty, Some(ONE_SOL / 50));
+
+    // The seller forgoes the full royalty, and the royalty the buyer opted out of stays in its escrow.
+    let (escrow, _) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let escrow_balance = context.banks_client.get_balance(escrow).await.unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(
+        escrow_balance,
+        rent.minimum_balance(0) + ONE_SOL / 10 - ONE_SOL / 50
+    );
+}
+
+#[tokio::test]
+async fn optional_policy_defaults_to_full_royalty() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = auction_house_with_policy(
+        &mut context,
+        RoyaltyPolicy::Optional {
+            floor_basis_points: 0,
+        },
+    )
+    .await;
+
+    let creator = Keypair::new();
+    let item = create_item(&mut context, &creator.pubkey()).await;
+    let buyer = funded_buyer(&mut context).await;
+    let receipt = sell_item(&mut context, &ah, &ahkey, &authority, &item, &buyer).await;
+
+    let creator_balance = context
+        .banks_client
+        .get_balance(creator.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(creator_balance, ONE_SOL / 10);
+    assert_eq!(receipt.royalty, Some(ONE_SOL / 10));
+}
+
+#[tokio::test]
+async fn pnft_sale_pays_full_royalty() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) =
+        auction_house_with_policy(&mut context, RoyaltyPolicy::Minimum { basis_points: 0 }).await;
+
+    let payer = context.payer.dirty_clone();
+    let (rule_set, auth_data) = create_sale_delegate_rule_set(&mut context, payer).await;
+
+    let creator = Keypair::new();
+    let item = Metadata::new();
+    airdrop(&mut context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create_via_builder(
+        &mut context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        Some(vec![Creator {
+            address: creator.pubkey(),
+            verified: false,
+            share: 100,
+        }]),
+        1000,
+        false,
+        None,
+        None,
+        true,
+        TokenStandard::ProgrammableNonFungible,
+        None,
+        Some(rule_set),
+        Some(0),
+        Some(PrintSupply::Zero),
+    )
+    .await
+    .unwrap();
+    item.mint_via_builder(&mut context, 1, Some(auth_data))
+        .await
+        .unwrap();
+
+    let buyer = funded_buyer(&mut context).await;
+    let receipt = sell_item(&mut context, &ah, &ahkey, &authority, &item, &buyer).await;
+
+    let creator_balance = context
+        .banks_client
+        .get_balance(creator.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(creator_balance, ONE_SOL / 10);
+    assert_eq!(receipt.royalty, Some(ONE_SOL / 10));
+}
+
+#[tokio::test]
+async fn invalid_royalty_basis_points() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    let err = process(
+        &mut context,
+        &[client::set_royalty_policy(
+            &ahkey,
+            &authority.pubkey(),
+            RoyaltyPolicy::Minimum {
+                basis_points: 10001,
+            },
+        )],
+        &authority,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, INVALID_BASIS_POINTS);
+
+    let buyer = funded_buyer(&mut context).await;
+    let err = process(
+        &mut context,
+        &[client::set_royalty_election(&ahkey, &buyer.pubkey(), 10001)],
+        &buyer,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, INVALID_BASIS_POINTS);
+}
+
+#[tokio::test]
+async fn sale_without_royalty_election_account_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = auction_house_with_policy(
+        &mut context,
+        RoyaltyPolicy::Optional {
+            floor_basis_points: 0,
+        },
+    )
+    .await;
+
+    let creator = Keypair::new();
+    let item = create_item(&mut context, &creator.pubkey()).await;
+    let metadata = item.get_data(&mut context).await;
+    let seller = item.token.pubkey();
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, seller, ONE_SOL);
+    process(&mut context, &[sell.instruction()], &item.token)
+        .await
+        .unwrap();
+
+    let buyer = funded_buyer(&mut context).await;
+    let buy = BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL);
+    process(&mut context, &[buy.instruction()], &buyer)
+        .await
+        .unwrap();
+
+    // Built against the Auction House before it stopped enforcing royalties.
+    let enforced = AuctionHouse {
+        royalty_policy: RoyaltyPolicy::Enforced,
+        ..ah
+    };
+    let sale = ExecuteSaleBuilder::new(
+        ahkey,
+        &enforced,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        ONE_SOL,
+    );
+    let err = process(&mut context, &[sale.instruction()], &authority)
+        .await
+        .unwrap_err();
+    assert_error!(err, INVALID_ROYALTY_ELECTION);
+}
+
+#[tokio::test]
+async fn legacy_purchase_receipt_reads_without_royalty() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) =
+        auction_house_with_policy(&mut context, RoyaltyPolicy::Enforced).await;
+
+    let creator = Keypair::new();
+    let item = create_item(&mut context, &creator.pubkey()).await;
+    let buyer = funded_buyer(&mut context).await;
+    let receipt = sell_item(&mut context, &ah, &ahkey, &authority, &item, &buyer).await;
+    assert_eq!(receipt.royalty, Some(ONE_SOL / 10));
+
+    // Receipts printed before the royalty was recorded end right before it.
+    let mut data = Vec::new();
+    receipt.try_serialize(&mut data).unwrap();
+    data.truncate(PURCHASE_RECEIPT_SIZE - 9);
+    let legacy = client::read_purchase_receipt(&data).unwrap();
+    assert_eq!(legacy.price, receipt.price);
+    assert_eq!(legacy.created_at, receipt.created_at);
+    assert_eq!(legacy.royalty, None);
+}
@@ -0,0 +1,308 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::{
+    client::{self, BuyBuilder, ExecuteSaleBuilder, SellBuilder},
+    pda::{find_escrow_payment_address, find_shared_escrow_address},
+};
+use solana_sdk::account::AccountSharedData;
+use std::result::Result as StdResult;
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+) -> StdResult<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn auction_house(context: &mut ProgramTestContext, ahkey: &Pubkey) -> AuctionHouse {
+    let account = context
+        .banks_client
+        .get_account(*ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    AuctionHouse::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+/// Native Auction House accepting shared escrows.
+async fn accepting_auction_house(context: &mut ProgramTestContext) -> (AuctionHouse, Pubkey) {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    assert!(!ah.accepts_shared_escrow);
+
+    process(
+        context,
+        &[client::set_accepts_shared_escrow(
+            &ahkey,
+            &authority.pubkey(),
+            true,
+        )],
+        &authority,
+    )
+    .await
+    .unwrap();
+
+    let ah = auction_house(context, &ahkey).await;
+    assert!(ah.accepts_shared_escrow);
+
+    (ah, ahkey)
+}
+
+/// Funded buyer wallet.
+async fn create_buyer(context: &mut ProgramTestContext) -> Keypair {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    buyer
+}
+
+/// List a new NFT at one SOL and have `buyer` bid on it. Returns the sale of the NFT to `buyer`, signed by the buyer.
+async fn bid_on_listing(
+    context: &mut ProgramTestContext,
+    ah: &AuctionHouse,
+    ahkey: &Pubkey,
+    buyer: &Keypair,
+) -> Instruction {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        0,
+        true,
+        1,
+    )
+    .await
+    .unwrap();
+    let metadata = item.get_data(context).await;
+    let seller = item.token.pubkey();
+
+    let sell = SellBuilder::new(*ahkey, ah, &metadata, seller, ONE_SOL);
+    process(context, &[sell.instruction()], &item.token)
+        .await
+        .unwrap();
+
+    let buy = BuyBuilder::new(*ahkey, ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL);
+    process(context, &[buy.instruction()], buyer).await.unwrap();
+
+    let mut instruction = ExecuteSaleBuilder::new(
+        *ahkey,
+        ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        ONE_SOL,
+    )
+    .instruction();
+    instruction.accounts[0].is_signer = true;
+    instruction
+}
+
+/// Bid twice at one SOL and take the first bid, leaving the escrow of `buyer` empty for the second one.
+async fn unfunded_sale(
+    context: &mut ProgramTestContext,
+    ah: &AuctionHouse,
+    ahkey: &Pubkey,
+    buyer: &Keypair,
+) -> Instruction {
+    let first_sale = bid_on_listing(context, ah, ahkey, buyer).await;
+    let second_sale = bid_on_listing(context, ah, ahkey, buyer).await;
+    process(context, &[first_sale], buyer).await.unwrap();
+    second_sale
+}
+
+async fn deposit(context: &mut ProgramTestContext, wallet: &Keypair, amount: u64) {
+    process(
+        context,
+        &[client::deposit_shared_escrow(
+            &wallet.pubkey(),
+            &spl_token::native_mint::id(),
+            &spl_token::id(),
+            amount,
+        )],
+        wallet,
+    )
+    .await
+    .unwrap();
+}
+
+async fn shared_escrow_balance(context: &mut ProgramTestContext, wallet: &Pubkey) -> u64 {
+    let (shared_escrow, _) = find_shared_escrow_address(&spl_token::native_mint::id(), wallet);
+    context
+        .banks_client
+        .get_balance(shared_escrow)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn legacy_auction_house_cannot_accept_shared_escrow() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    let mut account = context
+        .banks_client
+        .get_account(ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let legacy = AuctionHouse { version: 0, ..ah };
+    let mut data = Vec::new();
+    legacy.try_serialize(&mut data).unwrap();
+    account.data[..data.len()].copy_from_slice(&data);
+    context.set_account(&ahkey, &AccountSharedData::from(account));
+
+    let err = process(
+        &mut context,
+        &[client::set_accepts_shared_escrow(
+            &ahkey,
+            &authority.pubkey(),
+            true,
+        )],
+        &authority,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(err, AUCTION_HOUSE_NOT_MIGRATED);
+}
+
+#[tokio::test]
+async fn bids_and_sales_of_approving_buyer_use_shared_escrow() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey) = accepting_auction_house(&mut context).await;
+    let buyer = create_buyer(&mut context).await;
+
+    deposit(&mut context, &buyer, 2 * ONE_SOL).await;
+    process(
+        &mut context,
+        &[client::approve_shared_escrow(&ahkey, &buyer.pubkey())],
+        &buyer,
+    )
+    .await
+    .unwrap();
+
+    // The shared escrow backs both bids, so the escrow payment account only holds its rent.
+    let first_sale = bid_on_listing(&mut context, &ah, &ahkey, &buyer).await;
+    let second_sale = bid_on_listing(&mut context, &ah, &ahkey, &buyer).await;
+    let (escrow_payment_account, _) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(escrow_payment_account)
+            .await
+            .unwrap(),
+        rent.minimum_balance(0)
+    );
+
+    // Each sale draws its price from the shared escrow.
+    let shared_before = shared_escrow_balance(&mut context, &buyer.pubkey()).await;
+    process(&mut context, &[first_sale], &buyer).await.unwrap();
+    assert_eq!(
+        shared_escrow_balance(&mut context, &buyer.pubkey()).await,
+        shared_before - ONE_SOL
+    );
+    process(&mut context, &[second_sale], &buyer).await.unwrap();
+    assert_eq!(
+        shared_escrow_balance(&mut context, &buyer.pubkey()).await,
+        shared_before - 2 * ONE_SOL
+    );
+}
+
+#[tokio::test]
+async fn sale_does_not_draw_without_approval() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey) = accepting_auction_house(&mut context).await;
+    let buyer = create_buyer(&mut context).await;
+    deposit(&mut context, &buyer, 2 * ONE_SOL).await;
+
+    let sale = unfunded_sale(&mut context, &ah, &ahkey, &buyer).await;
+    let err = process(&mut context, std::slice::from_ref(&sale), &buyer)
+        .await
+        .unwrap_err();
+    assert_error!(err, INSUFFICIENT_FUNDS);
+
+    process(
+        &mut context,
+        &[
+            client::approve_shared_escrow(&ahkey, &buyer.pubkey()),
+            client::revoke_shared_escrow(&ahkey, &buyer.pubkey()),
+        ],
+        &buyer,
+    )
+    .await
+    .unwrap();
+    context.warp_to_slot(100).unwrap();
+    let err = process(&mut context, &[sale], &buyer).await.unwrap_err();
+    assert_error!(err, INSUFFICIENT_FUNDS);
+}
+
+#[tokio::test]
+async fn sale_rejects_shared_escrow_of_another_wallet() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey) = accepting_auction_house(&mut context).await;
+    let buyer = create_buyer(&mut context).await;
+    let other = create_buyer(&mut context).await;
+    deposit(&mut context, &other, 2 * ONE_SOL).await;
+    process(
+        &mut context,
+        &[client::approve_shared_escrow(&ahkey, &buyer.pubkey())],
+        &buyer,
+    )
+    .await
+    .unwrap();
+
+    let mut sale = unfunded_sale(&mut context, &ah, &ahkey, &buyer).await;
+    let shared_escrow_index = sale.accounts.len() - 2;
+    sale.accounts[shared_escrow_index].pubkey =
+        find_shared_escrow_address(&spl_token::native_mint::id(), &other.pubkey()).0;
+
+    let err = process(&mut context, &[sale], &buyer).await.unwrap_err();
+    assert_error!(err, INVALID_SHARED_ESCROW);
+}
+
+#[tokio::test]
+async fn withdraw_shared_escrow() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let wallet = create_buyer(&mut context).await;
+
+    deposit(&mut context, &wallet, 2 * ONE_SOL).await;
+    let shared_before = shared_escrow_balance(&mut context, &wallet.pubkey()).await;
+    assert!(shared_before >= 2 * ONE_SOL);
+
+    process(
+        &mut context,
+        &[client::withdraw_shared_escrow(
+            &wallet.pubkey(),
+            &spl_token::native_mint::id(),
+            &spl_token::id(),
+            ONE_SOL,
+        )],
+        &wallet,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        shared_escrow_balance(&mut context, &wallet.pubkey()).await,
+        shared_before - ONE_SOL
+    );
+}
@@ -0,0 +1,329 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::{
+    client::{self, BuyBuilder, ExecuteSaleBuilder, SellBuilder},
+    pda::find_auction_house_stats_address,
+    stats::AuctionHouseStats,
+};
+use mpl_token_metadata::state::Creator;
+use std::result::Result as StdResult;
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+) -> StdResult<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn auction_house(context: &mut ProgramTestContext, ahkey: &Pubkey) -> AuctionHouse {
+    let account = context
+        .banks_client
+        .get_account(*ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    AuctionHouse::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+async fn auction_house_stats(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+) -> AuctionHouseStats {
+    let (stats, _) = find_auction_house_stats_address(ahkey);
+    let account = context
+        .banks_client
+        .get_account(stats)
+        .await
+        .unwrap()
+        .unwrap();
+    client::read_auction_house_stats(&account.data).unwrap()
+}
+
+/// Auction House with a stats account and a funded fee account.
+async fn auction_house_with_stats(
+    context: &mut ProgramTestContext,
+) -> (AuctionHouse, Pubkey, Keypair) {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    airdrop(context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    process(
+        context,
+        &[client::create_auction_house_stats(
+            &ahkey,
+            &authority.pubkey(),
+        )],
+        &authority,
+    )
+    .await
+    .unwrap();
+
+    let ah = auction_house(context, &ahkey).await;
+    assert!(ah.has_stats);
+
+    (ah, ahkey, authority)
+}
+
+async fn create_item(
+    context: &mut ProgramTestContext,
+    creators: Option<Vec<Creator>>,
+    seller_fee_basis_points: u16,
+    amount: u64,
+) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        creators,
+        seller_fee_basis_points,
+        false,
+        amount,
+    )
+    .await
+    .unwrap();
+    item
+}
+
+#[tokio::test]
+async fn execute_sale_records_stats() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = auction_house_with_stats(&mut context).await;
+
+    let stats = auction_house_stats(&mut context, &ahkey).await;
+    assert_eq!(stats.auction_house, ahkey);
+    assert_eq!(stats.total_volume, 0);
+    assert_eq!(stats.sale_count, 0);
+
+    let creator = Keypair::new();
+    let item = create_item(
+        &mut context,
+        Some(vec![Creator {
+            address: creator.pubkey(),
+            verified: false,
+            share: 100,
+        }]),
+        1000,
+        1,
+    )
+    .await;
+    let metadata = item.get_data(&mut context).await;
+    let seller = item.token.pubkey();
+
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, seller, ONE_SOL);
+    process(&mut context, &[sell.instruction()], &item.token)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let buy = BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL);
+    process(&mut context, &[buy.instruction()], &buyer)
+        .await
+        .unwrap();
+
+    let sale = ExecuteSaleBuilder::new(
+        ahkey,
+        &ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        ONE_SOL,
+    );
+    process(&mut context, &[sale.instruction()], &authority)
+        .await
+        .unwrap();
+
+    let stats = auction_house_stats(&mut context, &ahkey).await;
+    assert_eq!(stats.total_volume, ONE_SOL);
+    assert_eq!(stats.sale_count, 1);
+    assert_eq!(stats.royalties_paid, ONE_SOL / 10);
+    assert_eq!(
+        stats.fees_collected,
+        ONE_SOL * ah.seller_fee_basis_points as u64 / 10000
+    );
+    assert_eq!(stats.last_sale_price, ONE_SOL);
+    assert_eq!(stats.last_sale_royalty, ONE_SOL / 10);
+    assert!(stats.last_sale_time > 0);
+}
+
+#[tokio::test]
+async fn execute_partial_sale_records_stats() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = auction_house_with_stats(&mut context).await;
+
+    let item = create_item(&mut context, None, 10, 6).await;
+    let metadata = item.get_data(&mut context).await;
+    let seller = item.token.pubkey();
+
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, seller, 6 * ONE_SOL).token_size(6);
+    process(&mut context, &[sell.instruction()], &item.token)
+        .await
+        .unwrap();
+
+    for _ in 0..2 {
+        let buyer = Keypair::new();
+        airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+            .await
+            .unwrap();
+        let buy = BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, 2 * ONE_SOL)
+            .token_size(2);
+        process(&mut context, &[buy.instruction()], &buyer)
+            .await
+            .unwrap();
+
+        let sale = ExecuteSaleBuilder::new(
+            ahkey,
+            &ah,
+            &metadata,
+            buyer.pubkey(),
+            seller,
+            item.ata,
+            6 * ONE_SOL,
+        )
+        .token_size(6)
+        .partial_order(2, 2 * ONE_SOL);
+        process(&mut context, &[sale.instruction()], &authority)
+            .await
+            .unwrap();
+    }
+
+    let stats = auction_house_stats(&mut context, &ahkey).await;
+    assert_eq!(stats.total_volume, 4 * ONE_SOL);
+    assert_eq!(stats.sale_count, 2);
+    assert_eq!(stats.last_sale_price, 2 * ONE_SOL);
+}
+
+#[tokio::test]
+async fn execute_sale_without_stats_account_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = auction_house_with_stats(&mut context).await;
+
+    let item = create_item(&mut context, None, 10, 1).await;
+    let metadata = item.get_data(&mut context).await;
+    let seller = item.token.pubkey();
+
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, seller, ONE_SOL);
+    process(&mut context, &[sell.instruction()], &item.token)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let buy = BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, ONE_SOL);
+    process(&mut context, &[buy.instruction()], &buyer)
+        .await
+        .unwrap();
+
+    let mut instruction = ExecuteSaleBuilder::new(
+        ahkey,
+        &ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        ONE_SOL,
+    )
+    .instruction();
+    let (stats, _) = find_auction_house_stats_address(&ahkey);
+    instruction
+        .accounts
+        .retain(|account| account.pubkey != stats);
+
+    let error = process(&mut context, &[instruction], &authority)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_AUCTION_HOUSE_STATS);
+}
+
+#[tokio::test]
+async fn accept_swap_offer_records_stats() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = auction_house_with_stats(&mut context).await;
+
+    let offered = create_item(&mut context, None, 0, 1).await;
+    let creator = Keypair::new();
+    let requested = create_item(
+        &mut context,
+        Some(vec![Creator {
+            address: creator.pubkey(),
+            verified: false,
+            share: 100,
+        }]),
+        1000,
+        1,
+    )
+    .await;
+
+    let top_up = 2 * ONE_SOL;
+    let (_, tx) = deposit(&mut context, &ahkey, &ah, &offered, &offered.token, top_up);
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let (_, tx) = make_swap_offer(
+        &mut context,
+        &ahkey,
+        &ah,
+        &offered.token,
+        &offered,
+        &requested.mint.pubkey(),
+        top_up,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let (accounts, _) = accept_swap_offer(
+        &mut context,
+        &ahkey,
+        &ah,
+        &requested.token,
+        &offered.token.pubkey(),
+        &offered,
+        &requested,
+        &[creator.pubkey()],
+    );
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.push(AccountMeta::new(creator.pubkey(), false));
+    account_metas.push(AccountMeta::new(
+        find_auction_house_stats_address(&ahkey).0,
+        false,
+    ));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::AcceptSwapOffer {}.data(),
+        accounts: account_metas,
+    };
+    process(&mut context, &[instruction], &requested.token)
+        .await
+        .unwrap();
+
+    let stats = auction_house_stats(&mut context, &ahkey).await;
+    assert_eq!(stats.total_volume, top_up);
+    assert_eq!(stats.sale_count, 1);
+    assert_eq!(stats.royalties_paid, top_up / 10);
+    assert_eq!(
+        stats.fees_collected,
+        top_up * ah.seller_fee_basis_points as u64 / 10000
+    );
+    assert_eq!(stats.last_sale_price, top_up);
+}
@@ -0,0 +1,339 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::DirtyClone, setup_functions::*};
+
+use mpl_auction_house::{pda::find_program_as_signer_address, swap::SwapOffer};
+use mpl_token_metadata::{pda::find_token_record_account, state::Creator};
+use solana_program::program_pack::Pack;
+use spl_token::state::Account;
+
+async fn create_item(
+    context: &mut ProgramTestContext,
+    owner: &Keypair,
+    creator: &Pubkey,
+    seller_fee_basis_points: u16,
+) -> Metadata {
+    let mut item = Metadata::new();
+    item.token = owner.dirty_clone();
+    item.ata = get_associated_token_address(&owner.pubkey(), &item.mint.pubkey());
+    item.token_record = find_token_record_account(&item.mint.pubkey(), &item.ata).0;
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        Some(vec![Creator {
+            address: *creator,
+            verified: false,
+            share: 100,
+        }]),
+        seller_fee_basis_points,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    item
+}
+
+async fn token_account(context: &mut ProgramTestContext, address: &Pubkey) -> Account {
+    Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap()
+}
+
+async fn lamports(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    context
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports
+}
+
+#[tokio::test]
+async fn make_swap_offer_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let offerer = Keypair::new();
+    let taker = Keypair::new();
+    airdrop(&mut context, &offerer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    airdrop(&mut context, &taker.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let creator = Pubkey::new_unique();
+    let offered = create_item(&mut context, &offerer, &creator, 500).await;
+    let requested = create_item(&mut context, &taker, &creator, 500).await;
+
+    let (acc, tx) = make_swap_offer(
+        &mut context,
+        &ahkey,
+        &ah,
+        &offerer,
+        &offered,
+        &requested.mint.pubkey(),
+        ONE_SOL,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let swap_offer_account = context
+        .banks_client
+        .get_account(acc.swap_offer)
+        .await
+        .unwrap()
+        .unwrap();
+    let swap_offer = SwapOffer::try_deserialize(&mut swap_offer_account.data.as_ref()).unwrap();
+    assert_eq!(swap_offer.auction_house, ahkey);
+    assert_eq!(swap_offer.offerer, offerer.pubkey());
+    assert_eq!(swap_offer.offered_token_account, offered.ata);
+    assert_eq!(swap_offer.offered_mint, offered.mint.pubkey());
+    assert_eq!(swap_offer.requested_mint, requested.mint.pubkey());
+    assert_eq!(swap_offer.top_up, ONE_SOL);
+
+    let (pas, _) = find_program_as_signer_address();
+    let token = token_account(&mut context, &offered.ata).await;
+    assert_eq!(token.delegate, Some(pas).into());
+    assert_eq!(token.delegated_amount, 1);
+}
+
+#[tokio::test]
+async fn make_swap_offer_same_mint_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let offerer = Keypair::new();
+    airdrop(&mut context, &offerer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let offered = create_item(&mut context, &offerer, &Pubkey::new_unique(), 500).await;
+
+    let (_, tx) = make_swap_offer(
+        &mut context,
+        &ahkey,
+        &ah,
+        &offerer,
+        &offered,
+        &offered.mint.pubkey(),
+        0,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_SWAP_OFFER);
+}
+
+#[tokio::test]
+async fn accept_swap_offer_with_top_up_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let offerer = Keypair::new();
+    let taker = Keypair::new();
+    airdrop(&mut context, &offerer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    airdrop(&mut context, &taker.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let offered_creator = Pubkey::new_unique();
+    let requested_creator = Pubkey::new_unique();
+    airdrop(&mut context, &requested_creator, ONE_SOL)
+        .await
+        .unwrap();
+    let offered = create_item(&mut context, &offerer, &offered_creator, 500).await;
+    let requested = create_item(&mut context, &taker, &requested_creator, 1000).await;
+
+    let top_up = 2 * ONE_SOL;
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &offered, &offerer, top_up);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (acc, tx) = make_swap_offer(
+        &mut context,
+        &ahkey,
+        &ah,
+        &offerer,
+        &offered,
+        &requested.mint.pubkey(),
+        top_up,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let taker_before = lamports(&mut context, &taker.pubkey()).await;
+    let creator_before = lamports(&mut context, &requested_creator).await;
+
+    let (_, tx) = accept_swap_offer(
+        &mut context,
+        &ahkey,
+        &ah,
+        &taker,
+        &offerer.pubkey(),
+        &offered,
+        &requested,
+        &[requested_creator],
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // Royalties of the requested token are paid on the top-up only.
+    let creator_fee = top_up / 10;
+    let house_fee = (ah.seller_fee_basis_points as u64 * top_up) / 10000;
+    assert_eq!(
+        lamports(&mut context, &requested_creator).await,
+        creator_before + creator_fee
+    );
+    let taker_after = lamports(&mut context, &taker.pubkey()).await;
+    // The taker pays fees and rent for the receipt accounts out of the top-up it receives.
+    assert!(taker_after > taker_before);
+    assert!(taker_after <= taker_before + top_up - creator_fee - house_fee);
+
+    let taker_token = token_account(
+        &mut context,
+        &get_associated_token_address(&taker.pubkey(), &offered.mint.pubkey()),
+    )
+    .await;
+    assert_eq!(taker_token.amount, 1);
+    let offerer_token = token_account(
+        &mut context,
+        &get_associated_token_address(&offerer.pubkey(), &requested.mint.pubkey()),
+    )
+    .await;
+    assert_eq!(offerer_token.amount, 1);
+
+    let swap_offer_account = context
+        .banks_client
+        .get_account(acc.swap_offer)
+        .await
+        .unwrap();
+    assert!(swap_offer_account.is_none());
+}
+
+#[tokio::test]
+async fn accept_swap_offer_wrong_mint_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let offerer = Keypair::new();
+    let taker = Keypair::new();
+    airdrop(&mut context, &offerer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    airdrop(&mut context, &taker.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let creator = Pubkey::new_unique();
+    let offered = create_item(&mut context, &offerer, &creator, 500).await;
+    let requested = create_item(&mut context, &taker, &creator, 500).await;
+    let other = create_item(&mut context, &taker, &creator, 500).await;
+
+    let (_, tx) = make_swap_offer(
+        &mut context,
+        &ahkey,
+        &ah,
+        &offerer,
+        &offered,
+        &requested.mint.pubkey(),
+        0,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // The swap offer PDA is derived from the requested mint, so another mint does not resolve to it.
+    let (_, tx) = accept_swap_offer(
+        &mut context,
+        &ahkey,
+        &ah,
+        &taker,
+        &offerer.pubkey(),
+        &offered,
+        &other,
+        &[],
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, ACCOUNT_NOT_INITIALIZED);
+}
+
+#[tokio::test]
+async fn cancel_swap_offer_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let offerer = Keypair::new();
+    airdrop(&mut context, &offerer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let creator = Pubkey::new_unique();
+    let offered = create_item(&mut context, &offerer, &creator, 500).await;
+    let holder = Keypair::new();
+    let requested = create_item(&mut context, &holder, &creator, 500).await;
+
+    let (acc, tx) = make_swap_offer(
+        &mut context,
+        &ahkey,
+        &ah,
+        &offerer,
+        &offered,
+        &requested.mint.pubkey(),
+        0,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelSwapOffer {}.data(),
+        accounts: mpl_auction_house::accounts::CancelSwapOffer {
+            wallet: offerer.pubkey(),
+            auction_house: ahkey,
+            swap_offer: acc.swap_offer,
+            offered_token_account: offered.ata,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&offerer.pubkey()),
+        &[&offerer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let token = token_account(&mut context, &offered.ata).await;
+    assert!(token.delegate.is_none());
+
+    let swap_offer_account = context
+        .banks_client
+        .get_account(acc.swap_offer)
+        .await
+        .unwrap();
+    assert!(swap_offer_account.is_none());
+}
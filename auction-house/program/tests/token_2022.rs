@@ -0,0 +1,303 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::DirtyClone, setup_functions::*};
+
+use mpl_auction_house::{
+    client::{BuyBuilder, ExecuteSaleBuilder, SellBuilder},
+    pda::{
+        find_auction_house_address, find_auction_house_fee_account_address,
+        find_auction_house_treasury_address,
+    },
+};
+use mpl_token_metadata::state::Creator;
+use solana_program::{program_pack::Pack, system_instruction, system_program, sysvar};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id, instruction::create_associated_token_account,
+};
+use spl_token_2022::{
+    extension::{transfer_fee, ExtensionType, StateWithExtensions},
+    state::{Account as Token2022Account, Mint as Token2022Mint},
+};
+use std::result::Result as StdResult;
+
+/// Price of the NFT, with every payout of the sale a multiple of 10000 so transfer fees do not round.
+const PRICE: u64 = 1_000_000_000;
+const TRANSFER_FEE_BASIS_POINTS: u16 = 100;
+const SELLER_FEE_BASIS_POINTS: u16 = 100;
+const ROYALTY_BASIS_POINTS: u16 = 500;
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> StdResult<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&signers[0].pubkey()),
+        &signers.to_vec(),
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+/// What `amount` credits once the transfer fee of the treasury mint is withheld.
+fn net_of_transfer_fee(amount: u64) -> u64 {
+    amount - amount * TRANSFER_FEE_BASIS_POINTS as u64 / 10000
+}
+
+fn token_2022_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(wallet, mint, &spl_token_2022::id())
+}
+
+async fn token_2022_balance(context: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    StateWithExtensions::<Token2022Account>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+/// Token-2022 mint withholding `TRANSFER_FEE_BASIS_POINTS` of every transfer, minted by the context payer.
+async fn create_transfer_fee_mint(context: &mut ProgramTestContext) -> Pubkey {
+    let payer = context.payer.pubkey();
+    let mint = Keypair::new();
+    let space =
+        ExtensionType::get_account_len::<Token2022Mint>(&[ExtensionType::TransferFeeConfig]);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::id(),
+        ),
+        transfer_fee::instruction::initialize_transfer_fee_config(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            Some(&payer),
+            Some(&payer),
+            TRANSFER_FEE_BASIS_POINTS,
+            u64::MAX,
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            &payer,
+            None,
+            9,
+        )
+        .unwrap(),
+    ];
+    let payer = context.payer.dirty_clone();
+    process(context, &instructions, &[&payer, &mint])
+        .await
+        .unwrap();
+
+    mint.pubkey()
+}
+
+/// Mint `amount` tokens of `mint` to the associated token account of `wallet`, creating it.
+async fn mint_token_2022(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    wallet: &Pubkey,
+    amount: u64,
+) {
+    let payer = context.payer.dirty_clone();
+    let instructions = [
+        create_associated_token_account(&payer.pubkey(), wallet, mint, &spl_token_2022::id()),
+        spl_token_2022::instruction::mint_to(
+            &spl_token_2022::id(),
+            mint,
+            &token_2022_address(wallet, mint),
+            &payer.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap(),
+    ];
+    process(context, &instructions, &[&payer]).await.unwrap();
+}
+
+/// Auction House trading in `treasury_mint` with a `SELLER_FEE_BASIS_POINTS` seller fee.
+async fn create_token_2022_auction_house(
+    context: &mut ProgramTestContext,
+    treasury_mint: &Pubkey,
+) -> (AuctionHouse, Pubkey) {
+    let authority = Keypair::new();
+    airdrop(context, &authority.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+
+    let (auction_house, bump) = find_auction_house_address(&authority.pubkey(), treasury_mint);
+    let (auction_house_fee_account, fee_payer_bump) =
+        find_auction_house_fee_account_address(&auction_house);
+    let (auction_house_treasury, treasury_bump) =
+        find_auction_house_treasury_address(&auction_house);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CreateAuctionHouse {
+            _bump: bump,
+            fee_payer_bump,
+            treasury_bump,
+            seller_fee_basis_points: SELLER_FEE_BASIS_POINTS,
+            requires_sign_off: false,
+            can_change_sale_price: false,
+        }
+        .data(),
+        accounts: mpl_auction_house::accounts::CreateAuctionHouse {
+            treasury_mint: *treasury_mint,
+            payer: authority.pubkey(),
+            authority: authority.pubkey(),
+            fee_withdrawal_destination: authority.pubkey(),
+            treasury_withdrawal_destination: token_2022_address(&authority.pubkey(), treasury_mint),
+            treasury_withdrawal_destination_owner: authority.pubkey(),
+            auction_house,
+            auction_house_fee_account,
+            auction_house_treasury,
+            token_program: spl_token_2022::id(),
+            system_program: system_program::id(),
+            ata_program: spl_associated_token_account::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+    process(context, &[instruction], &[&authority])
+        .await
+        .unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(auction_house)
+        .await
+        .unwrap()
+        .unwrap();
+    let ah = AuctionHouse::try_deserialize(&mut account.data.as_ref()).unwrap();
+
+    (ah, auction_house)
+}
+
+#[tokio::test]
+async fn execute_sale_with_transfer_fee_pays_out_escrow_net_of_fee() {
+    let mut program = auction_house_program_test();
+    program.add_program("spl_token_2022", spl_token_2022::id(), None);
+    let mut context = program.start_with_context().await;
+
+    let treasury_mint = create_transfer_fee_mint(&mut context).await;
+    let (ah, ahkey) = create_token_2022_auction_house(&mut context, &treasury_mint).await;
+
+    let creator = Keypair::new();
+    let item = Metadata::new();
+    airdrop(&mut context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        &mut context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        Some(vec![Creator {
+            address: creator.pubkey(),
+            verified: false,
+            share: 100,
+        }]),
+        ROYALTY_BASIS_POINTS,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+    let metadata = item.get_data(&mut context).await;
+    let seller = item.token.pubkey();
+    let sell = SellBuilder::new(ahkey, &ah, &metadata, seller, PRICE);
+    process(&mut context, &[sell.instruction()], &[&item.token])
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    mint_token_2022(&mut context, &treasury_mint, &buyer.pubkey(), PRICE * 2).await;
+    let buy = BuyBuilder::new(ahkey, &ah, &metadata, buyer.pubkey(), item.ata, PRICE)
+        .treasury_token_program(spl_token_2022::id());
+    process(&mut context, &[buy.instruction()], &[&buyer])
+        .await
+        .unwrap();
+
+    // The buyer covers the transfer fee of funding the bid, so the escrow holds exactly the price.
+    let escrow_payment_account = buy.escrow_payment_account();
+    assert_eq!(
+        token_2022_balance(&mut context, &escrow_payment_account).await,
+        PRICE
+    );
+
+    let mut instruction = ExecuteSaleBuilder::new(
+        ahkey,
+        &ah,
+        &metadata,
+        buyer.pubkey(),
+        seller,
+        item.ata,
+        PRICE,
+    )
+    .treasury_token_program(spl_token_2022::id())
+    .instruction();
+    instruction.accounts[0].is_signer = true;
+    let treasury_before = token_2022_balance(&mut context, &ah.auction_house_treasury).await;
+    process(&mut context, &[instruction], &[&buyer])
+        .await
+        .unwrap();
+
+    // The escrow pays out the price, and each payout is credited net of the transfer fee.
+    let royalty = PRICE * ROYALTY_BASIS_POINTS as u64 / 10000;
+    let auction_house_fee = PRICE * SELLER_FEE_BASIS_POINTS as u64 / 10000;
+    let proceeds = PRICE - royalty - auction_house_fee;
+    assert_eq!(
+        token_2022_balance(&mut context, &escrow_payment_account).await,
+        0
+    );
+    assert_eq!(
+        token_2022_balance(
+            &mut context,
+            &token_2022_address(&creator.pubkey(), &treasury_mint)
+        )
+        .await,
+        net_of_transfer_fee(royalty)
+    );
+    assert_eq!(
+        token_2022_balance(&mut context, &ah.auction_house_treasury).await - treasury_before,
+        net_of_transfer_fee(auction_house_fee)
+    );
+    assert_eq!(
+        token_2022_balance(&mut context, &token_2022_address(&seller, &treasury_mint)).await,
+        net_of_transfer_fee(proceeds)
+    );
+
+    let buyer_token_account = context
+        .banks_client
+        .get_account(get_associated_token_address(
+            &buyer.pubkey(),
+            &item.mint.pubkey(),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        spl_token::state::Account::unpack(&buyer_token_account.data)
+            .unwrap()
+            .amount,
+        1
+    );
+}
@@ -0,0 +1,319 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::pda::find_trait_bid_trade_state_address;
+use solana_program::{keccak, program_pack::Pack};
+use spl_token::state::Account;
+
+fn leaf(mint: &Pubkey) -> [u8; 32] {
+    keccak::hashv(&[&[0x00], &mint.to_bytes()]).0
+}
+
+fn branch(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    if a <= b {
+        keccak::hashv(&[&[0x01], &a, &b]).0
+    } else {
+        keccak::hashv(&[&[0x01], &b, &a]).0
+    }
+}
+
+/// Build a merkle tree over `mints` and return the root with the proof of every mint.
+fn build_tree(mints: &[Pubkey]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let mut proofs = vec![vec![]; mints.len()];
+    let mut positions: Vec<usize> = (0..mints.len()).collect();
+    let mut layer: Vec<[u8; 32]> = mints.iter().map(leaf).collect();
+
+    while layer.len() > 1 {
+        for (i, position) in positions.iter_mut().enumerate() {
+            let sibling = *position ^ 1;
+            if sibling < layer.len() {
+                proofs[i].push(layer[sibling]);
+            }
+            *position /= 2;
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => branch(*a, *b),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    (layer[0], proofs)
+}
+
+async fn create_item(context: &mut ProgramTestContext) -> Metadata {
+    let item = Metadata::new();
+    airdrop(context, &item.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    item.create(
+        context,
+        "Test".to_string(),
+        "TST".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    item
+}
+
+#[tokio::test]
+async fn trait_buy_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (root, _) = build_tree(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (acc, buy_tx) = trait_buy(&mut context, &ahkey, &ah, root, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (expected_bts, bts_bump) = find_trait_bid_trade_state_address(
+        &buyer.pubkey(),
+        &ahkey,
+        &ah.treasury_mint,
+        &root,
+        ONE_SOL,
+        1,
+    );
+    assert_eq!(acc.buyer_trade_state, expected_bts);
+
+    let trade_state = context
+        .banks_client
+        .get_account(acc.buyer_trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trade_state.data[0], bts_bump);
+
+    let escrow = context
+        .banks_client
+        .get_account(acc.escrow_payment_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(escrow.lamports, ONE_SOL + rent.minimum_balance(0));
+}
+
+#[tokio::test]
+async fn execute_trait_sale_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let (root, proofs) = build_tree(&[
+        Pubkey::new_unique(),
+        item.mint.pubkey(),
+        Pubkey::new_unique(),
+    ]);
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, buy_tx) = trait_buy(&mut context, &ahkey, &ah, root, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &item, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let seller_before = context
+        .banks_client
+        .get_account(item.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let (_, execute_tx) = execute_trait_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &item,
+        &buyer.pubkey(),
+        &item.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        ONE_SOL,
+        root,
+        proofs[1].clone(),
+    );
+    context
+        .banks_client
+        .process_transaction(execute_tx)
+        .await
+        .unwrap();
+
+    let seller_after = context
+        .banks_client
+        .get_account(item.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let buyer_token_after = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(get_associated_token_address(
+                &buyer.pubkey(),
+                &item.mint.pubkey(),
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    let fee_minus: u64 = ONE_SOL - ((ah.seller_fee_basis_points as u64 * ONE_SOL) / 10000);
+    assert_eq!(seller_before.lamports + fee_minus, seller_after.lamports);
+    assert_eq!(buyer_token_after.amount, 1);
+
+    let buyer_trade_state = context
+        .banks_client
+        .get_account(bid_acc.buyer_trade_state)
+        .await
+        .unwrap();
+    assert!(buyer_trade_state.is_none());
+}
+
+#[tokio::test]
+async fn execute_trait_sale_mint_not_in_tree_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_item(&mut context).await;
+    let (root, proofs) = build_tree(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, buy_tx) = trait_buy(&mut context, &ahkey, &ah, root, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &item, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // Reuse the proof of another leaf for a mint outside of the tree.
+    let (_, execute_tx) = execute_trait_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &item,
+        &buyer.pubkey(),
+        &item.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        ONE_SOL,
+        root,
+        proofs[0].clone(),
+    );
+    let error = context
+        .banks_client
+        .process_transaction(execute_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_TRAIT_PROOF);
+}
+
+#[tokio::test]
+async fn cancel_trait_buy_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (root, _) = build_tree(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, buy_tx) = trait_buy(&mut context, &ahkey, &ah, root, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let accounts = mpl_auction_house::accounts::CancelTraitBuy {
+        wallet: buyer.pubkey(),
+        authority: ah.authority,
+        auction_house: ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        trade_state: bid_acc.buyer_trade_state,
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelTraitBuy {
+            buyer_price: ONE_SOL,
+            token_size: 1,
+            root,
+        }
+        .data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer_trade_state = context
+        .banks_client
+        .get_account(bid_acc.buyer_trade_state)
+        .await
+        .unwrap();
+    assert!(buyer_trade_state.is_none());
+}
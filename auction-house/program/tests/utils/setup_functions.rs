@@ -7,15 +7,19 @@ use anchor_client::solana_sdk::{
 };
 use anchor_lang::*;
 use mpl_auction_house::{
+    curation::CollectionListMode,
     pda::{
         find_auction_house_address, find_auction_house_fee_account_address,
         find_auction_house_treasury_address, find_auctioneer_pda,
-        find_auctioneer_trade_state_address, find_bid_receipt_address, find_escrow_payment_address,
-        find_listing_receipt_address, find_program_as_signer_address,
-        find_public_bid_trade_state_address, find_purchase_receipt_address,
-        find_trade_state_address,
+        find_auctioneer_trade_state_address, find_bid_receipt_address, find_bundle_listing_address,
+        find_collection_bid_trade_state_address, find_collection_list_address,
+        find_dutch_listing_address, find_escrow_payment_address, find_listing_receipt_address,
+        find_program_as_signer_address, find_public_bid_trade_state_address,
+        find_purchase_receipt_address, find_swap_offer_address, find_trade_state_address,
+        find_trait_bid_trade_state_address,
     },
-    AuctionHouse, AuthorityScope,
+    reservation::EscrowReservationMode,
+    AuctionHouse, AuthorityScope, BatchOrder,
 };
 
 use mpl_testing_utils::{solana::airdrop, utils::Metadata};
@@ -38,7 +42,9 @@ use rmp_serde::Serializer;
 use serde::Serialize;
 use solana_program_test::*;
 use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction, instruction::Instruction, transaction::Transaction,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction},
+    transaction::Transaction,
 };
 use spl_associated_token_account::get_associated_token_address;
 
@@ -564,6 +570,151 @@ pub fn auctioneer_public_buy(
     )
 }
 
+pub fn collection_buy(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    collection_metadata: &Metadata,
+    buyer: &Keypair,
+    sale_price: u64,
+) -> (
+    (
+        mpl_auction_house::accounts::CollectionBuy,
+        mpl_auction_house::accounts::PrintBidReceipt,
+    ),
+    Transaction,
+) {
+    let (bts, bts_bump) = find_collection_bid_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &ah.treasury_mint,
+        &collection_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+
+    let accounts = mpl_auction_house::accounts::CollectionBuy {
+        wallet: buyer.pubkey(),
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        collection_mint: collection_metadata.mint.pubkey(),
+        collection_metadata: collection_metadata.pubkey,
+        escrow_payment_account: escrow,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        buyer_trade_state: bts,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    let account_metas = accounts.to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::CollectionBuy {
+        trade_state_bump: bts_bump,
+        escrow_payment_bump: escrow_bump,
+        token_size: 1,
+        buyer_price: sale_price,
+    }
+    .data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    let (bid_receipt, bid_receipt_bump) = find_bid_receipt_address(&bts);
+    let print_receipt_accounts = mpl_auction_house::accounts::PrintBidReceipt {
+        receipt: bid_receipt,
+        bookkeeper: buyer.pubkey(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let print_bid_receipt_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::PrintBidReceipt {
+            receipt_bump: bid_receipt_bump,
+        }
+        .data(),
+        accounts: print_receipt_accounts.to_account_metas(None),
+    };
+
+    (
+        (accounts, print_receipt_accounts),
+        Transaction::new_signed_with_payer(
+            &[instruction, print_bid_receipt_instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn trait_buy(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    root: [u8; 32],
+    buyer: &Keypair,
+    sale_price: u64,
+) -> (mpl_auction_house::accounts::TraitBuy, Transaction) {
+    let (bts, bts_bump) = find_trait_bid_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &ah.treasury_mint,
+        &root,
+        sale_price,
+        1,
+    );
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+
+    let accounts = mpl_auction_house::accounts::TraitBuy {
+        wallet: buyer.pubkey(),
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account: escrow,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        buyer_trade_state: bts,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    let account_metas = accounts.to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::TraitBuy {
+        trade_state_bump: bts_bump,
+        escrow_payment_bump: escrow_bump,
+        token_size: 1,
+        buyer_price: sale_price,
+        root,
+    }
+    .data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
 pub fn execute_sale(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
@@ -671,13 +822,98 @@ pub fn execute_sale(
     ((execute_sale_accounts, print_purchase_receipt_accounts), tx)
 }
 
-pub fn auctioneer_execute_sale(
+pub fn accept_bid(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    buyer_price: u64,
+) -> (mpl_auction_house::accounts::AcceptBid, Transaction) {
+    let seller = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let (seller_trade_state, sts_bump) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        buyer_price,
+        1,
+    );
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let accounts = mpl_auction_house::accounts::AcceptBid {
+        buyer: *buyer,
+        seller,
+        token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        metadata: test_metadata.pubkey,
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account,
+        seller_payment_receipt_account: seller,
+        buyer_receipt_token_account: get_associated_token_address(
+            buyer,
+            &test_metadata.mint.pubkey(),
+        ),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        buyer_trade_state: *buyer_trade_state,
+        seller_trade_state,
+        free_trade_state,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::AcceptBid {
+            trade_state_bump: sts_bump,
+            escrow_payment_bump: escrow_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price,
+            token_size: 1,
+            programmable: false,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&seller),
+            &[&test_metadata.token],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn execute_collection_sale(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
     ah: &AuctionHouse,
     authority: &Keypair,
-    auctioneer_authority: &Keypair,
     test_metadata: &Metadata,
+    collection_mint: &Pubkey,
     buyer: &Pubkey,
     seller: &Pubkey,
     token_account: &Pubkey,
@@ -686,7 +922,10 @@ pub fn auctioneer_execute_sale(
     token_size: u64,
     buyer_price: u64,
 ) -> (
-    mpl_auction_house::accounts::AuctioneerExecuteSale,
+    (
+        mpl_auction_house::accounts::ExecuteCollectionSale,
+        mpl_auction_house::accounts::PrintPurchaseReceipt,
+    ),
     Transaction,
 ) {
     let program_id = mpl_auction_house::id();
@@ -703,11 +942,12 @@ pub fn auctioneer_execute_sale(
         0,
         token_size,
     );
-
-    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
     let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
-
-    let execute_sale_accounts = mpl_auction_house::accounts::AuctioneerExecuteSale {
+    let (purchase_receipt, purchase_receipt_bump) =
+        find_purchase_receipt_address(seller_trade_state, buyer_trade_state);
+    let (listing_receipt, _listing_receipt_bump) = find_listing_receipt_address(seller_trade_state);
+    let (bid_receipt, _public_bid_receipt_bump) = find_bid_receipt_address(buyer_trade_state);
+    let execute_sale_accounts = mpl_auction_house::accounts::ExecuteCollectionSale {
         buyer: *buyer,
         seller: *seller,
         auction_house: *ahkey,
@@ -716,17 +956,16 @@ pub fn auctioneer_execute_sale(
         treasury_mint: ah.treasury_mint,
         metadata: test_metadata.pubkey,
         authority: ah.authority,
-        auctioneer_authority: auctioneer_authority.pubkey(),
         seller_trade_state: *seller_trade_state,
         buyer_trade_state: *buyer_trade_state,
         free_trade_state,
+        collection_mint: *collection_mint,
         seller_payment_receipt_account: *seller,
         buyer_receipt_token_account: buyer_token_account,
         escrow_payment_account,
         auction_house_fee_account: ah.auction_house_fee_account,
         auction_house_treasury: ah.auction_house_treasury,
         program_as_signer,
-        ah_auctioneer_pda: auctioneer_pda,
         token_program: spl_token::id(),
         system_program: system_program::id(),
         ata_program: spl_associated_token_account::id(),
@@ -737,9 +976,9 @@ pub fn auctioneer_execute_sale(
 
     let execute_sale_instruction = Instruction {
         program_id,
-        data: mpl_auction_house::instruction::AuctioneerExecuteSale {
+        data: mpl_auction_house::instruction::ExecuteCollectionSale {
             escrow_payment_bump: escrow_bump,
-            _free_trade_state_bump: free_sts_bump,
+            free_trade_state_bump: free_sts_bump,
             program_as_signer_bump: pas_bump,
             token_size,
             buyer_price,
@@ -748,43 +987,228 @@ pub fn auctioneer_execute_sale(
         accounts: execute_sale_account_metas,
     };
 
+    let print_purchase_receipt_accounts = mpl_auction_house::accounts::PrintPurchaseReceipt {
+        purchase_receipt,
+        listing_receipt,
+        bid_receipt,
+        bookkeeper: authority.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let print_purchase_receipt_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::PrintPurchaseReceipt {
+            purchase_receipt_bump,
+        }
+        .data(),
+        accounts: print_purchase_receipt_accounts.to_account_metas(None),
+    };
+
     let tx = Transaction::new_signed_with_payer(
-        &[execute_sale_instruction],
+        &[execute_sale_instruction, print_purchase_receipt_instruction],
         Some(&authority.pubkey()),
-        &[authority, auctioneer_authority],
+        &[authority],
         context.last_blockhash,
     );
 
-    (execute_sale_accounts, tx)
+    ((execute_sale_accounts, print_purchase_receipt_accounts), tx)
 }
 
-pub fn sell_mint(
+pub fn execute_trait_sale(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
     ah: &AuctionHouse,
-    test_metadata_mint: &Pubkey,
-    seller: &Keypair,
-    sale_price: u64,
-) -> (
-    (
-        mpl_auction_house::accounts::Sell,
-        mpl_auction_house::accounts::PrintListingReceipt,
-    ),
-    Transaction,
-) {
-    let token = get_associated_token_address(&seller.pubkey(), test_metadata_mint);
-    let (metadata, _) = find_metadata_account(test_metadata_mint);
-    let (seller_trade_state, sts_bump) = find_trade_state_address(
-        &seller.pubkey(),
-        ahkey,
-        &token,
-        &ah.treasury_mint,
-        test_metadata_mint,
-        sale_price,
-        1,
-    );
-    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
-        &seller.pubkey(),
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    token_account: &Pubkey,
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    token_size: u64,
+    buyer_price: u64,
+    root: [u8; 32],
+    proof: Vec<[u8; 32]>,
+) -> (mpl_auction_house::accounts::ExecuteSale, Transaction) {
+    let buyer_token_account = get_associated_token_address(buyer, &test_metadata.mint.pubkey());
+
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        seller,
+        ahkey,
+        token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        token_size,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+    let execute_sale_accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: *buyer,
+        seller: *seller,
+        auction_house: *ahkey,
+        token_account: *token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        seller_trade_state: *seller_trade_state,
+        buyer_trade_state: *buyer_trade_state,
+        free_trade_state,
+        seller_payment_receipt_account: *seller,
+        buyer_receipt_token_account: buyer_token_account,
+        escrow_payment_account,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let execute_sale_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteTraitSale {
+            escrow_payment_bump: escrow_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size,
+            buyer_price,
+            root,
+            proof,
+        }
+        .data(),
+        accounts: execute_sale_accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[execute_sale_instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    (execute_sale_accounts, tx)
+}
+
+pub fn auctioneer_execute_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    auctioneer_authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    token_account: &Pubkey,
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    token_size: u64,
+    buyer_price: u64,
+) -> (
+    mpl_auction_house::accounts::AuctioneerExecuteSale,
+    Transaction,
+) {
+    let program_id = mpl_auction_house::id();
+    let buyer_token_account = get_associated_token_address(buyer, &test_metadata.mint.pubkey());
+
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        seller,
+        ahkey,
+        token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        token_size,
+    );
+
+    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+
+    let execute_sale_accounts = mpl_auction_house::accounts::AuctioneerExecuteSale {
+        buyer: *buyer,
+        seller: *seller,
+        auction_house: *ahkey,
+        token_account: *token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        seller_trade_state: *seller_trade_state,
+        buyer_trade_state: *buyer_trade_state,
+        free_trade_state,
+        seller_payment_receipt_account: *seller,
+        buyer_receipt_token_account: buyer_token_account,
+        escrow_payment_account,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        program_as_signer,
+        ah_auctioneer_pda: auctioneer_pda,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let execute_sale_account_metas = execute_sale_accounts.to_account_metas(None);
+
+    let execute_sale_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::AuctioneerExecuteSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size,
+            buyer_price,
+        }
+        .data(),
+        accounts: execute_sale_account_metas,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[execute_sale_instruction],
+        Some(&authority.pubkey()),
+        &[authority, auctioneer_authority],
+        context.last_blockhash,
+    );
+
+    (execute_sale_accounts, tx)
+}
+
+pub fn sell_mint(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata_mint: &Pubkey,
+    seller: &Keypair,
+    sale_price: u64,
+) -> (
+    (
+        mpl_auction_house::accounts::Sell,
+        mpl_auction_house::accounts::PrintListingReceipt,
+    ),
+    Transaction,
+) {
+    let token = get_associated_token_address(&seller.pubkey(), test_metadata_mint);
+    let (metadata, _) = find_metadata_account(test_metadata_mint);
+    let (seller_trade_state, sts_bump) = find_trade_state_address(
+        &seller.pubkey(),
+        ahkey,
+        &token,
+        &ah.treasury_mint,
+        test_metadata_mint,
+        sale_price,
+        1,
+    );
+    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
+        &seller.pubkey(),
         ahkey,
         &token,
         &ah.treasury_mint,
@@ -1418,24 +1842,859 @@ pub fn auctioneer_sell(
     )
 }
 
-pub async fn delegate_auctioneer(
+pub fn batch_sell(
     context: &mut ProgramTestContext,
-    auction_house: Pubkey,
-    authority: &Keypair,
-    auctioneer_authority: Pubkey,
-    ah_auctioneer_pda: Pubkey,
-    scopes: Vec<AuthorityScope>,
-) -> StdResult<(), BanksClientError> {
-    let accounts = mpl_auction_house::accounts::DelegateAuctioneer {
-        auction_house,
-        authority: authority.pubkey(),
-        auctioneer_authority,
-        ah_auctioneer_pda,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    wallet: &Keypair,
+    items: &[Metadata],
+    sale_price: u64,
+    auth_rules: Option<&Pubkey>,
+) -> (Vec<Pubkey>, Transaction) {
+    let (pas, _) = find_program_as_signer_address();
+    let accounts = mpl_auction_house::accounts::BatchSell {
+        wallet: wallet.pubkey(),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: spl_token::id(),
         system_program: system_program::id(),
+        program_as_signer: pas,
+        rent: sysvar::rent::id(),
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    let mut orders = vec![];
+    let mut trade_states = vec![];
+
+    for item in items {
+        let (seller_trade_state, _) = find_trade_state_address(
+            &wallet.pubkey(),
+            ahkey,
+            &item.ata,
+            &ah.treasury_mint,
+            &item.mint.pubkey(),
+            sale_price,
+            1,
+        );
+        account_metas.push(AccountMeta::new(item.ata, false));
+        // Token Metadata writes to the metadata of a programmable NFT when delegating and revoking.
+        account_metas.push(match auth_rules {
+            Some(_) => AccountMeta::new(item.pubkey, false),
+            None => AccountMeta::new_readonly(item.pubkey, false),
+        });
+        account_metas.push(AccountMeta::new(seller_trade_state, false));
+
+        if let Some(auth_rules) = auth_rules {
+            let pas_token = get_associated_token_address(&pas, &item.mint.pubkey());
+            let (delegate_record, _) = find_token_record_account(&item.mint.pubkey(), &pas_token);
+            let p_nft_accounts = mpl_auction_house::accounts::SellRemainingAccounts {
+                metadata_program: mpl_token_metadata::id(),
+                delegate_record,
+                token_record: item.token_record,
+                token_mint: item.mint.pubkey(),
+                edition: item.master_edition,
+                auth_rules_program: mpl_token_auth_rules::id(),
+                auth_rules: *auth_rules,
+                sysvar_instructions: sysvar::instructions::id(),
+            };
+            account_metas.append(&mut p_nft_accounts.to_account_metas(None));
+        }
+
+        orders.push(BatchOrder {
+            buyer_price: sale_price,
+            token_size: 1,
+            programmable: auth_rules.is_some(),
+        });
+        trade_states.push(seller_trade_state);
     }
-    .to_account_metas(None);
 
-    let data = mpl_auction_house::instruction::DelegateAuctioneer { scopes }.data();
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::BatchSell { orders }.data(),
+        accounts: account_metas,
+    };
+    // Token Metadata delegate and revoke CPIs of programmable NFTs need more than the default compute budget.
+    let instructions = match auth_rules {
+        Some(_) => vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(400_000),
+            instruction,
+        ],
+        None => vec![instruction],
+    };
+
+    (
+        trade_states,
+        Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&wallet.pubkey()),
+            &[wallet],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn batch_cancel(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    wallet: &Keypair,
+    items: &[Metadata],
+    sale_price: u64,
+    auth_rules: Option<&Pubkey>,
+) -> Transaction {
+    let (pas, _) = find_program_as_signer_address();
+    let accounts = mpl_auction_house::accounts::BatchCancel {
+        wallet: wallet.pubkey(),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: spl_token::id(),
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    let mut orders = vec![];
+
+    for item in items {
+        let (seller_trade_state, _) = find_trade_state_address(
+            &wallet.pubkey(),
+            ahkey,
+            &item.ata,
+            &ah.treasury_mint,
+            &item.mint.pubkey(),
+            sale_price,
+            1,
+        );
+        account_metas.push(AccountMeta::new(item.ata, false));
+        // Token Metadata writes to the metadata of a programmable NFT when delegating and revoking.
+        account_metas.push(match auth_rules {
+            Some(_) => AccountMeta::new(item.pubkey, false),
+            None => AccountMeta::new_readonly(item.pubkey, false),
+        });
+        account_metas.push(AccountMeta::new(seller_trade_state, false));
+
+        if let Some(auth_rules) = auth_rules {
+            let pas_token = get_associated_token_address(&pas, &item.mint.pubkey());
+            let (delegate_record, _) = find_token_record_account(&item.mint.pubkey(), &pas_token);
+            let p_nft_accounts = mpl_auction_house::accounts::CancelRemainingAccounts {
+                metadata_program: mpl_token_metadata::id(),
+                delegate_record,
+                program_as_signer: pas,
+                metadata: item.pubkey,
+                edition: item.master_edition,
+                token_record: item.token_record,
+                token_mint: item.mint.pubkey(),
+                auth_rules_program: mpl_token_auth_rules::id(),
+                auth_rules: *auth_rules,
+                sysvar_instructions: sysvar::instructions::id(),
+                system_program: system_program::id(),
+            };
+            account_metas.append(&mut p_nft_accounts.to_account_metas(None));
+        }
+
+        orders.push(BatchOrder {
+            buyer_price: sale_price,
+            token_size: 1,
+            programmable: auth_rules.is_some(),
+        });
+    }
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::BatchCancel { orders }.data(),
+        accounts: account_metas,
+    };
+    // Token Metadata delegate and revoke CPIs of programmable NFTs need more than the default compute budget.
+    let instructions = match auth_rules {
+        Some(_) => vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(400_000),
+            instruction,
+        ],
+        None => vec![instruction],
+    };
+
+    Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&wallet.pubkey()),
+        &[wallet],
+        context.last_blockhash,
+    )
+}
+
+pub fn sell_bundle(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    seller: &Keypair,
+    items: &[Metadata],
+    price: u64,
+    weights: Vec<u64>,
+) -> (mpl_auction_house::accounts::SellBundle, Transaction) {
+    let (bundle_listing, _) = find_bundle_listing_address(&seller.pubkey(), ahkey, &items[0].ata);
+    let (program_as_signer, _) = find_program_as_signer_address();
+    let accounts = mpl_auction_house::accounts::SellBundle {
+        wallet: seller.pubkey(),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        bundle_listing,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    for item in items {
+        account_metas.push(AccountMeta::new(item.ata, false));
+        account_metas.push(AccountMeta::new_readonly(item.pubkey, false));
+    }
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::SellBundle { price, weights }.data(),
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&seller.pubkey()),
+            &[seller],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn execute_bundle_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    buyer: &Keypair,
+    seller: &Pubkey,
+    items: &[Metadata],
+    creators: &[Vec<Pubkey>],
+    referrer: Option<&Pubkey>,
+) -> (mpl_auction_house::accounts::ExecuteBundleSale, Transaction) {
+    let (bundle_listing, _) = find_bundle_listing_address(seller, ahkey, &items[0].ata);
+    let (escrow_payment_account, _) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let (program_as_signer, _) = find_program_as_signer_address();
+    let accounts = mpl_auction_house::accounts::ExecuteBundleSale {
+        buyer: buyer.pubkey(),
+        seller: *seller,
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account,
+        seller_payment_receipt_account: *seller,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        bundle_listing,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    for (item, item_creators) in items.iter().zip(creators) {
+        account_metas.push(AccountMeta::new(item.ata, false));
+        account_metas.push(AccountMeta::new_readonly(item.mint.pubkey(), false));
+        account_metas.push(AccountMeta::new_readonly(item.pubkey, false));
+        account_metas.push(AccountMeta::new(
+            get_associated_token_address(&buyer.pubkey(), &item.mint.pubkey()),
+            false,
+        ));
+        for creator in item_creators {
+            account_metas.push(AccountMeta::new(*creator, false));
+        }
+    }
+    if let Some(referrer) = referrer {
+        account_metas.push(AccountMeta::new(*referrer, false));
+    }
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteBundleSale {}.data(),
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn make_swap_offer(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    offerer: &Keypair,
+    offered: &Metadata,
+    requested_mint: &Pubkey,
+    top_up: u64,
+) -> (mpl_auction_house::accounts::MakeSwapOffer, Transaction) {
+    let (swap_offer, _) =
+        find_swap_offer_address(&offerer.pubkey(), ahkey, &offered.ata, requested_mint);
+    let (program_as_signer, _) = find_program_as_signer_address();
+    let accounts = mpl_auction_house::accounts::MakeSwapOffer {
+        wallet: offerer.pubkey(),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        offered_token_account: offered.ata,
+        offered_metadata: offered.pubkey,
+        requested_mint: *requested_mint,
+        swap_offer,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::MakeSwapOffer { top_up }.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&offerer.pubkey()),
+            &[offerer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn accept_swap_offer(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    taker: &Keypair,
+    offerer: &Pubkey,
+    offered: &Metadata,
+    requested: &Metadata,
+    creators: &[Pubkey],
+) -> (mpl_auction_house::accounts::AcceptSwapOffer, Transaction) {
+    let requested_mint = requested.mint.pubkey();
+    let (swap_offer, _) = find_swap_offer_address(offerer, ahkey, &offered.ata, &requested_mint);
+    let (escrow_payment_account, _) = find_escrow_payment_address(ahkey, offerer);
+    let (program_as_signer, _) = find_program_as_signer_address();
+    let accounts = mpl_auction_house::accounts::AcceptSwapOffer {
+        taker: taker.pubkey(),
+        offerer: *offerer,
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account,
+        taker_payment_receipt_account: taker.pubkey(),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        swap_offer,
+        offered_token_account: offered.ata,
+        offered_mint: offered.mint.pubkey(),
+        taker_receipt_token_account: get_associated_token_address(
+            &taker.pubkey(),
+            &offered.mint.pubkey(),
+        ),
+        requested_token_account: requested.ata,
+        requested_mint,
+        requested_metadata: requested.pubkey,
+        offerer_receipt_token_account: get_associated_token_address(offerer, &requested_mint),
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    for creator in creators {
+        account_metas.push(AccountMeta::new(*creator, false));
+    }
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::AcceptSwapOffer {}.data(),
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&taker.pubkey()),
+            &[taker],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn sell_dutch(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    start_price: u64,
+    floor_price: u64,
+    start_time: i64,
+    end_time: i64,
+    step_interval: i64,
+) -> (
+    (
+        mpl_auction_house::accounts::SellDutch,
+        mpl_auction_house::accounts::PrintListingReceipt,
+    ),
+    Transaction,
+) {
+    let program_id = mpl_auction_house::id();
+    let token =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (dutch_listing, dutch_listing_bump) =
+        find_dutch_listing_address(&test_metadata.token.pubkey(), ahkey, &token);
+    let (listing_receipt, receipt_bump) = find_listing_receipt_address(&dutch_listing);
+    let (program_as_signer, _) = find_program_as_signer_address();
+
+    let accounts = mpl_auction_house::accounts::SellDutch {
+        wallet: test_metadata.token.pubkey(),
+        token_account: token,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        program_as_signer,
+        dutch_listing,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+    };
+
+    let instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::SellDutch {
+            dutch_listing_bump,
+            start_price,
+            floor_price,
+            start_time,
+            end_time,
+            step_interval,
+            token_size: 1,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let listing_receipt_accounts = mpl_auction_house::accounts::PrintListingReceipt {
+        receipt: listing_receipt,
+        bookkeeper: test_metadata.token.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let print_receipt_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::PrintListingReceipt { receipt_bump }.data(),
+        accounts: listing_receipt_accounts.to_account_metas(None),
+    };
+
+    (
+        (accounts, listing_receipt_accounts),
+        Transaction::new_signed_with_payer(
+            &[instruction, print_receipt_instruction],
+            Some(&test_metadata.token.pubkey()),
+            &[&test_metadata.token],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn execute_dutch_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    buyer_price: u64,
+) -> (
+    (
+        mpl_auction_house::accounts::ExecuteDutchSale,
+        mpl_auction_house::accounts::PrintPurchaseReceipt,
+    ),
+    Transaction,
+) {
+    let program_id = mpl_auction_house::id();
+    let seller = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let buyer_token_account = get_associated_token_address(buyer, &test_metadata.mint.pubkey());
+    let (dutch_listing, _) = find_dutch_listing_address(&seller, ahkey, &token_account);
+
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+    let (purchase_receipt, purchase_receipt_bump) =
+        find_purchase_receipt_address(&dutch_listing, buyer_trade_state);
+    let (listing_receipt, _) = find_listing_receipt_address(&dutch_listing);
+    let (bid_receipt, _) = find_bid_receipt_address(buyer_trade_state);
+    let accounts = mpl_auction_house::accounts::ExecuteDutchSale {
+        buyer: *buyer,
+        seller,
+        token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        metadata: test_metadata.pubkey,
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account,
+        seller_payment_receipt_account: seller,
+        buyer_receipt_token_account: buyer_token_account,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        buyer_trade_state: *buyer_trade_state,
+        dutch_listing,
+        free_trade_state,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::ExecuteDutchSale {
+            escrow_payment_bump: escrow_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price,
+            token_size: 1,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let print_purchase_receipt_accounts = mpl_auction_house::accounts::PrintPurchaseReceipt {
+        purchase_receipt,
+        listing_receipt,
+        bid_receipt,
+        bookkeeper: authority.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let print_purchase_receipt_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::PrintPurchaseReceipt {
+            purchase_receipt_bump,
+        }
+        .data(),
+        accounts: print_purchase_receipt_accounts.to_account_metas(None),
+    };
+
+    (
+        (accounts, print_purchase_receipt_accounts),
+        Transaction::new_signed_with_payer(
+            &[instruction, print_purchase_receipt_instruction],
+            Some(&authority.pubkey()),
+            &[authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn update_auction_house_fees(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    maker_fee_basis_points: Option<u16>,
+    taker_fee_basis_points: Option<u16>,
+    referral_fee_basis_points: Option<u16>,
+) -> (mpl_auction_house::accounts::UpdateAuctionHouse, Transaction) {
+    let accounts = mpl_auction_house::accounts::UpdateAuctionHouse {
+        treasury_mint: ah.treasury_mint,
+        payer: authority.pubkey(),
+        authority: authority.pubkey(),
+        new_authority: authority.pubkey(),
+        fee_withdrawal_destination: ah.fee_withdrawal_destination,
+        treasury_withdrawal_destination: ah.treasury_withdrawal_destination,
+        treasury_withdrawal_destination_owner: ah.treasury_withdrawal_destination,
+        auction_house: *ahkey,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::UpdateAuctionHouse {
+            seller_fee_basis_points: None,
+            requires_sign_off: None,
+            can_change_sale_price: None,
+            maker_fee_basis_points,
+            taker_fee_basis_points,
+            referral_fee_basis_points,
+            escrow_reservation_mode: None,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&authority.pubkey()),
+            &[authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn update_escrow_reservation_mode(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    escrow_reservation_mode: EscrowReservationMode,
+) -> (mpl_auction_house::accounts::UpdateAuctionHouse, Transaction) {
+    let accounts = mpl_auction_house::accounts::UpdateAuctionHouse {
+        treasury_mint: ah.treasury_mint,
+        payer: authority.pubkey(),
+        authority: authority.pubkey(),
+        new_authority: authority.pubkey(),
+        fee_withdrawal_destination: ah.fee_withdrawal_destination,
+        treasury_withdrawal_destination: ah.treasury_withdrawal_destination,
+        treasury_withdrawal_destination_owner: ah.treasury_withdrawal_destination,
+        auction_house: *ahkey,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::UpdateAuctionHouse {
+            seller_fee_basis_points: None,
+            requires_sign_off: None,
+            can_change_sale_price: None,
+            maker_fee_basis_points: None,
+            taker_fee_basis_points: None,
+            referral_fee_basis_points: None,
+            escrow_reservation_mode: Some(escrow_reservation_mode),
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&authority.pubkey()),
+            &[authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn create_collection_list(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    mode: CollectionListMode,
+) -> (
+    mpl_auction_house::accounts::CreateCollectionList,
+    Transaction,
+) {
+    let (collection_list, _) = find_collection_list_address(ahkey);
+    let accounts = mpl_auction_house::accounts::CreateCollectionList {
+        authority: authority.pubkey(),
+        auction_house: *ahkey,
+        collection_list,
+        system_program: system_program::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CreateCollectionList { mode }.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&authority.pubkey()),
+            &[authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn update_collection_list(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    collection_mint: Pubkey,
+    add: bool,
+) -> (
+    mpl_auction_house::accounts::UpdateCollectionList,
+    Transaction,
+) {
+    let (collection_list, _) = find_collection_list_address(ahkey);
+    let accounts = mpl_auction_house::accounts::UpdateCollectionList {
+        authority: authority.pubkey(),
+        auction_house: *ahkey,
+        collection_list,
+    };
+
+    let data = if add {
+        mpl_auction_house::instruction::AddCollection { collection_mint }.data()
+    } else {
+        mpl_auction_house::instruction::RemoveCollection { collection_mint }.data()
+    };
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&authority.pubkey()),
+            &[authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn set_allowed_buyer(
+    wallet: &Pubkey,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    seller_trade_state: &Pubkey,
+    allowed_buyer: &Pubkey,
+) -> (mpl_auction_house::accounts::SetAllowedBuyer, Instruction) {
+    let accounts = mpl_auction_house::accounts::SetAllowedBuyer {
+        wallet: *wallet,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        seller_trade_state: *seller_trade_state,
+        system_program: system_program::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::SetAllowedBuyer {
+            allowed_buyer: *allowed_buyer,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (accounts, instruction)
+}
+
+pub fn set_trade_state_expiry(
+    wallet: &Pubkey,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    trade_state: &Pubkey,
+    token_mint: &Pubkey,
+    expires_at: i64,
+) -> (
+    mpl_auction_house::accounts::SetTradeStateExpiry,
+    Instruction,
+) {
+    let accounts = mpl_auction_house::accounts::SetTradeStateExpiry {
+        wallet: *wallet,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        trade_state: *trade_state,
+        token_mint: *token_mint,
+        system_program: system_program::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::SetTradeStateExpiry { expires_at }.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (accounts, instruction)
+}
+
+pub async fn delegate_auctioneer(
+    context: &mut ProgramTestContext,
+    auction_house: Pubkey,
+    authority: &Keypair,
+    auctioneer_authority: Pubkey,
+    ah_auctioneer_pda: Pubkey,
+    scopes: Vec<AuthorityScope>,
+) -> StdResult<(), BanksClientError> {
+    let accounts = mpl_auction_house::accounts::DelegateAuctioneer {
+        auction_house,
+        authority: authority.pubkey(),
+        auctioneer_authority,
+        ah_auctioneer_pda,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::DelegateAuctioneer { scopes }.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn add_auctioneer(
+    context: &mut ProgramTestContext,
+    auction_house: Pubkey,
+    authority: &Keypair,
+    auctioneer_authority: Pubkey,
+    ah_auctioneer_pda: Pubkey,
+    scopes: Vec<AuthorityScope>,
+) -> StdResult<(), BanksClientError> {
+    let accounts = mpl_auction_house::accounts::AddAuctioneer {
+        auction_house,
+        authority: authority.pubkey(),
+        auctioneer_authority,
+        ah_auctioneer_pda,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::AddAuctioneer { scopes }.data();
 
     let instruction = Instruction {
         program_id: mpl_auction_house::id(),
@@ -0,0 +1,283 @@
+//! Module define CLI structure.
+
+use clap::{ArgEnum, Parser, Subcommand};
+use mpl_auction_house::AuthorityScope;
+use std::env;
+
+/// CLI arguments.
+#[derive(Parser, Debug)]
+#[clap(name = "mpl-auction-house-cli")]
+#[clap(about = "CLI utility for mpl-auction-house program")]
+#[clap(version, author)]
+pub struct CliArgs {
+    /// RPC endpoint.
+    #[clap(short, long, default_value_t = String::from("https://api.mainnet-beta.solana.com"), value_name = "URL")]
+    pub url: String,
+
+    /// Path to transaction payer keypair file.
+    #[clap(short, long, default_value_t = format!("{}/.config/solana/id.json", env::var("HOME").unwrap()), value_name = "FILE")]
+    pub payer_keypair: String,
+
+    #[clap(subcommand)]
+    pub command: Commands,
+}
+
+/// Scope an auctioneer is allowed to act on.
+#[derive(ArgEnum, Clone, Debug)]
+pub enum Scope {
+    Deposit,
+    Buy,
+    PublicBuy,
+    ExecuteSale,
+    Sell,
+    Cancel,
+    Withdraw,
+}
+
+impl From<Scope> for AuthorityScope {
+    fn from(scope: Scope) -> Self {
+        match scope {
+            Scope::Deposit => AuthorityScope::Deposit,
+            Scope::Buy => AuthorityScope::Buy,
+            Scope::PublicBuy => AuthorityScope::PublicBuy,
+            Scope::ExecuteSale => AuthorityScope::ExecuteSale,
+            Scope::Sell => AuthorityScope::Sell,
+            Scope::Cancel => AuthorityScope::Cancel,
+            Scope::Withdraw => AuthorityScope::Withdraw,
+        }
+    }
+}
+
+/// CLI sub-commands.
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Obtain `AuctionHouse` account from `mpl_auction_house` program.
+    GetAuctionHouse {
+        #[clap(short, value_name = "STRING")]
+        account: String,
+    },
+    /// Obtain escrow payment account of `wallet` from `mpl_auction_house` program.
+    GetEscrow {
+        #[clap(long, value_name = "PUBKEY")]
+        auction_house: String,
+
+        #[clap(long, value_name = "PUBKEY")]
+        wallet: String,
+    },
+    /// Obtain `AuctionHouseStats` account of `auction_house` from `mpl_auction_house` program.
+    GetAuctionHouseStats {
+        #[clap(long, value_name = "PUBKEY")]
+        auction_house: String,
+    },
+    /// Obtain `ListingReceipt` account from `mpl_auction_house` program.
+    GetListingReceipt {
+        #[clap(short, value_name = "STRING")]
+        account: String,
+    },
+    /// Obtain `BidReceipt` account from `mpl_auction_house` program.
+    GetBidReceipt {
+        #[clap(short, value_name = "STRING")]
+        account: String,
+    },
+    /// Obtain `PurchaseReceipt` account from `mpl_auction_house` program.
+    GetPurchaseReceipt {
+        #[clap(short, value_name = "STRING")]
+        account: String,
+    },
+    /// Perform `CreateAuctionHouse` instruction of `mpl_auction_house` program.
+    CreateAuctionHouse {
+        #[clap(long, value_name = "FILE")]
+        authority_keypair: Option<String>,
+
+        #[clap(long, value_name = "PUBKEY")]
+        treasury_mint: Option<String>,
+
+        #[clap(long, value_name = "PUBKEY")]
+        fee_withdrawal_destination: Option<String>,
+
+        #[clap(long, value_name = "PUBKEY")]
+        treasury_withdrawal_destination_owner: Option<String>,
+
+        #[clap(long, value_name = "U16")]
+        seller_fee_basis_points: u16,
+
+        #[clap(long)]
+        requires_sign_off: bool,
+
+        #[clap(long)]
+        can_change_sale_price: bool,
+    },
+    /// Perform `UpdateAuctionHouse` instruction of `mpl_auction_house` program.
+    UpdateAuctionHouse {
+        #[clap(long, value_name = "PUBKEY")]
+        auction_house: String,
+
+        #[clap(long, value_name = "FILE")]
+        authority_keypair: Option<String>,
+
+        #[clap(long, value_name = "PUBKEY")]
+        new_authority: Option<String>,
+
+        #[clap(long, value_name = "PUBKEY")]
+        fee_withdrawal_destination: Option<String>,
+
+        #[clap(long, value_name = "PUBKEY")]
+        treasury_withdrawal_destination_owner: Option<String>,
+
+        #[clap(long, value_name = "U16")]
+        seller_fee_basis_points: Option<u16>,
+
+        #[clap(long, value_name = "BOOL")]
+        requires_sign_off: Option<bool>,
+
+        #[clap(long, value_name = "BOOL")]
+        can_change_sale_price: Option<bool>,
+
+        #[clap(long, value_name = "U16")]
+        maker_fee_basis_points: Option<u16>,
+
+        #[clap(long, value_name = "U16")]
+        taker_fee_basis_points: Option<u16>,
+
+        #[clap(long, value_name = "U16")]
+        referral_fee_basis_points: Option<u16>,
+    },
+    /// Perform `WithdrawFromFee` instruction of `mpl_auction_house` program.
+    WithdrawFromFee {
+        #[clap(long, value_name = "PUBKEY")]
+        auction_house: String,
+
+        #[clap(long, value_name = "FILE")]
+        authority_keypair: Option<String>,
+
+        #[clap(long, value_name = "F64")]
+        amount: f64,
+    },
+    /// Perform `WithdrawFromTreasury` instruction of `mpl_auction_house` program.
+    WithdrawFromTreasury {
+        #[clap(long, value_name = "PUBKEY")]
+        auction_house: String,
+
+        #[clap(long, value_name = "FILE")]
+        authority_keypair: Option<String>,
+
+        #[clap(long, value_name = "F64")]
+        amount: f64,
+    },
+    /// Perform `MigrateAuctionHouse` instruction of `mpl_auction_house` program.
+    MigrateAuctionHouse {
+        #[clap(long, value_name = "PUBKEY")]
+        auction_house: String,
+
+        #[clap(long, value_name = "FILE")]
+        authority_keypair: Option<String>,
+    },
+    /// Perform `DelegateAuctioneer` instruction of `mpl_auction_house` program.
+    DelegateAuctioneer {
+        #[clap(long, value_name = "PUBKEY")]
+        auction_house: String,
+
+        #[clap(long, value_name = "FILE")]
+        authority_keypair: Option<String>,
+
+        #[clap(long, value_name = "PUBKEY")]
+        auctioneer_authority: String,
+
+        /// Scopes delegated to the auctioneer, all of them if omitted.
+        #[clap(long, arg_enum, multiple_values = true, value_name = "SCOPE")]
+        scopes: Vec<Scope>,
+    },
+    /// Perform `Sell` instruction of `mpl_auction_house` program.
+    Sell {
+        #[clap(long, value_name = "PUBKEY")]
+        auction_house: String,
+
+        #[clap(long, value_name = "FILE")]
+        wallet_keypair: Option<String>,
+
+        #[clap(long, value_name = "FILE")]
+        authority_keypair: Option<String>,
+
+        #[clap(long, value_name = "FILE")]
+        auctioneer_keypair: Option<String>,
+
+        #[clap(long, value_name = "PUBKEY")]
+        mint: String,
+
+        #[clap(long, value_name = "PUBKEY")]
+        token_account: Option<String>,
+
+        #[clap(long, value_name = "F64")]
+        price: f64,
+
+        #[clap(long, value_name = "U64", default_value_t = 1)]
+        token_size: u64,
+    },
+    /// Perform `Buy` instruction of `mpl_auction_house` program.
+    Buy {
+        #[clap(long, value_name = "PUBKEY")]
+        auction_house: String,
+
+        #[clap(long, value_name = "FILE")]
+        wallet_keypair: Option<String>,
+
+        #[clap(long, value_name = "FILE")]
+        authority_keypair: Option<String>,
+
+        #[clap(long, value_name = "FILE")]
+        auctioneer_keypair: Option<String>,
+
+        #[clap(long, value_name = "PUBKEY")]
+        mint: String,
+
+        #[clap(long, value_name = "PUBKEY")]
+        token_account: String,
+
+        #[clap(long, value_name = "F64")]
+        price: f64,
+
+        #[clap(long, value_name = "U64", default_value_t = 1)]
+        token_size: u64,
+
+        #[clap(long)]
+        public: bool,
+    },
+    /// Perform `ExecuteSale` instruction of `mpl_auction_house` program.
+    ExecuteSale {
+        #[clap(long, value_name = "PUBKEY")]
+        auction_house: String,
+
+        #[clap(long, value_name = "FILE")]
+        authority_keypair: Option<String>,
+
+        #[clap(long, value_name = "FILE")]
+        auctioneer_keypair: Option<String>,
+
+        #[clap(long, value_name = "PUBKEY")]
+        mint: String,
+
+        #[clap(long, value_name = "PUBKEY")]
+        buyer: String,
+
+        #[clap(long, value_name = "PUBKEY")]
+        seller: String,
+
+        #[clap(long, value_name = "PUBKEY")]
+        token_account: Option<String>,
+
+        #[clap(long, value_name = "F64")]
+        price: f64,
+
+        #[clap(long, value_name = "U64", default_value_t = 1)]
+        token_size: u64,
+
+        #[clap(long, value_name = "U64")]
+        partial_order_size: Option<u64>,
+
+        #[clap(long, value_name = "F64")]
+        partial_order_price: Option<f64>,
+
+        #[clap(long)]
+        public_bid: bool,
+    },
+}
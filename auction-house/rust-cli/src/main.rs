@@ -0,0 +1,585 @@
+mod cli_args;
+mod error;
+mod processor;
+mod utils;
+
+use clap::Parser;
+use cli_args::{CliArgs, Commands};
+use mpl_auction_house::{
+    client::read_purchase_receipt,
+    pda::{find_auction_house_stats_address, find_escrow_payment_address},
+    receipt::{BidReceipt, ListingReceipt},
+    stats::AuctionHouseStats,
+    AuctionHouse, AuthorityScope,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signer::{keypair::read_keypair_file, Signer},
+    transaction::Transaction,
+};
+use std::str::FromStr;
+
+fn main() -> Result<(), error::Error> {
+    let args = CliArgs::parse();
+
+    let client = RpcClient::new(args.url);
+    let payer_wallet = read_keypair_file(&args.payer_keypair)?;
+
+    // Handle provided commands
+    // Build transaction
+    let txs_data: Option<Vec<(Transaction, Box<dyn processor::UiTransactionInfo>)>> = match args
+        .command
+    {
+        Commands::GetAuctionHouse { account } => {
+            let auction_house = processor::get_account_state::<AuctionHouse>(
+                &client,
+                &Pubkey::from_str(&account)?,
+            )?;
+
+            println!(
+                "AuctionHouse::auction_house_fee_account - {}",
+                auction_house.auction_house_fee_account
+            );
+            println!(
+                "AuctionHouse::auction_house_treasury - {}",
+                auction_house.auction_house_treasury
+            );
+            println!(
+                "AuctionHouse::treasury_withdrawal_destination - {}",
+                auction_house.treasury_withdrawal_destination
+            );
+            println!(
+                "AuctionHouse::fee_withdrawal_destination - {}",
+                auction_house.fee_withdrawal_destination
+            );
+            println!(
+                "AuctionHouse::treasury_mint - {}",
+                auction_house.treasury_mint
+            );
+            println!("AuctionHouse::authority - {}", auction_house.authority);
+            println!("AuctionHouse::creator - {}", auction_house.creator);
+            println!(
+                "AuctionHouse::seller_fee_basis_points - {}",
+                auction_house.seller_fee_basis_points
+            );
+            println!(
+                "AuctionHouse::maker_fee_basis_points - {}",
+                auction_house.maker_fee_basis_points
+            );
+            println!(
+                "AuctionHouse::taker_fee_basis_points - {}",
+                auction_house.taker_fee_basis_points
+            );
+            println!(
+                "AuctionHouse::referral_fee_basis_points - {}",
+                auction_house.referral_fee_basis_points
+            );
+            println!(
+                "AuctionHouse::requires_sign_off - {}",
+                auction_house.requires_sign_off
+            );
+            println!(
+                "AuctionHouse::can_change_sale_price - {}",
+                auction_house.can_change_sale_price
+            );
+            println!(
+                "AuctionHouse::auctioneer_address - {}",
+                if auction_house.has_auctioneer {
+                    auction_house.auctioneer_address.to_string()
+                } else {
+                    String::from("<none>")
+                }
+            );
+            println!(
+                "AuctionHouse::has_collection_list - {}",
+                auction_house.has_collection_list
+            );
+            println!(
+                "AuctionHouse::escrow_reservation_mode - {:?}",
+                auction_house.escrow_reservation_mode
+            );
+
+            None
+        }
+        Commands::GetEscrow {
+            auction_house,
+            wallet,
+        } => {
+            let auction_house = Pubkey::from_str(&auction_house)?;
+            let auction_house_state =
+                processor::get_account_state::<AuctionHouse>(&client, &auction_house)?;
+            let (escrow_payment_account, _) =
+                find_escrow_payment_address(&auction_house, &Pubkey::from_str(&wallet)?);
+
+            let decimals = utils::get_mint(&client, &auction_house_state.treasury_mint)?.decimals;
+            let balance = utils::get_escrow_balance(
+                &client,
+                &escrow_payment_account,
+                &auction_house_state.treasury_mint,
+            )?;
+
+            println!("Escrow::address - {}", escrow_payment_account);
+            println!(
+                "Escrow::balance - {}",
+                spl_token::amount_to_ui_amount(balance, decimals)
+            );
+
+            None
+        }
+        Commands::GetAuctionHouseStats { auction_house } => {
+            let auction_house = Pubkey::from_str(&auction_house)?;
+            let auction_house_state =
+                processor::get_account_state::<AuctionHouse>(&client, &auction_house)?;
+            let (stats_address, _) = find_auction_house_stats_address(&auction_house);
+            let stats = processor::get_account_state::<AuctionHouseStats>(&client, &stats_address)?;
+
+            let decimals = utils::get_mint(&client, &auction_house_state.treasury_mint)?.decimals;
+
+            println!("AuctionHouseStats::address - {}", stats_address);
+            println!(
+                "AuctionHouseStats::total_volume - {}",
+                spl_token::amount_to_ui_amount(stats.total_volume, decimals)
+            );
+            println!("AuctionHouseStats::sale_count - {}", stats.sale_count);
+            println!(
+                "AuctionHouseStats::royalties_paid - {}",
+                spl_token::amount_to_ui_amount(stats.royalties_paid, decimals)
+            );
+            println!(
+                "AuctionHouseStats::fees_collected - {}",
+                spl_token::amount_to_ui_amount(stats.fees_collected, decimals)
+            );
+            println!(
+                "AuctionHouseStats::last_sale_price - {}",
+                spl_token::amount_to_ui_amount(stats.last_sale_price, decimals)
+            );
+            println!(
+                "AuctionHouseStats::last_sale_time - {}",
+                stats.last_sale_time
+            );
+            println!(
+                "AuctionHouseStats::last_sale_royalty - {}",
+                spl_token::amount_to_ui_amount(stats.last_sale_royalty, decimals)
+            );
+
+            None
+        }
+        Commands::GetListingReceipt { account } => {
+            let receipt = processor::get_account_state::<ListingReceipt>(
+                &client,
+                &Pubkey::from_str(&account)?,
+            )?;
+
+            println!("ListingReceipt::trade_state - {}", receipt.trade_state);
+            println!("ListingReceipt::bookkeeper - {}", receipt.bookkeeper);
+            println!("ListingReceipt::auction_house - {}", receipt.auction_house);
+            println!("ListingReceipt::seller - {}", receipt.seller);
+            println!("ListingReceipt::metadata - {}", receipt.metadata);
+            println!(
+                "ListingReceipt::purchase_receipt - {}",
+                if let Some(x) = receipt.purchase_receipt {
+                    x.to_string()
+                } else {
+                    String::from("<none>")
+                }
+            );
+            println!("ListingReceipt::price - {}", receipt.price);
+            println!("ListingReceipt::token_size - {}", receipt.token_size);
+            println!("ListingReceipt::created_at - {}", receipt.created_at);
+            println!(
+                "ListingReceipt::canceled_at - {}",
+                if let Some(x) = receipt.canceled_at {
+                    x.to_string()
+                } else {
+                    String::from("<none>")
+                }
+            );
+
+            None
+        }
+        Commands::GetBidReceipt { account } => {
+            let receipt =
+                processor::get_account_state::<BidReceipt>(&client, &Pubkey::from_str(&account)?)?;
+
+            println!("BidReceipt::trade_state - {}", receipt.trade_state);
+            println!("BidReceipt::bookkeeper - {}", receipt.bookkeeper);
+            println!("BidReceipt::auction_house - {}", receipt.auction_house);
+            println!("BidReceipt::buyer - {}", receipt.buyer);
+            println!("BidReceipt::metadata - {}", receipt.metadata);
+            println!(
+                "BidReceipt::token_account - {}",
+                if let Some(x) = receipt.token_account {
+                    x.to_string()
+                } else {
+                    String::from("<public>")
+                }
+            );
+            println!(
+                "BidReceipt::purchase_receipt - {}",
+                if let Some(x) = receipt.purchase_receipt {
+                    x.to_string()
+                } else {
+                    String::from("<none>")
+                }
+            );
+            println!("BidReceipt::price - {}", receipt.price);
+            println!("BidReceipt::token_size - {}", receipt.token_size);
+            println!("BidReceipt::created_at - {}", receipt.created_at);
+            println!(
+                "BidReceipt::canceled_at - {}",
+                if let Some(x) = receipt.canceled_at {
+                    x.to_string()
+                } else {
+                    String::from("<none>")
+                }
+            );
+
+            None
+        }
+        Commands::GetPurchaseReceipt { account } => {
+            // Receipts printed before the royalty was recorded are shorter than the current layout.
+            let data = client.get_account_data(&Pubkey::from_str(&account)?)?;
+            let receipt = read_purchase_receipt(&data)
+                .map_err(|err| error::Error::DynamicError(err.to_string()))?;
+
+            println!("PurchaseReceipt::bookkeeper - {}", receipt.bookkeeper);
+            println!("PurchaseReceipt::buyer - {}", receipt.buyer);
+            println!("PurchaseReceipt::seller - {}", receipt.seller);
+            println!("PurchaseReceipt::auction_house - {}", receipt.auction_house);
+            println!("PurchaseReceipt::metadata - {}", receipt.metadata);
+            println!("PurchaseReceipt::token_size - {}", receipt.token_size);
+            println!("PurchaseReceipt::price - {}", receipt.price);
+            println!("PurchaseReceipt::created_at - {}", receipt.created_at);
+            println!(
+                "PurchaseReceipt::royalty - {}",
+                if let Some(x) = receipt.royalty {
+                    x.to_string()
+                } else {
+                    String::from("<none>")
+                }
+            );
+
+            None
+        }
+        Commands::CreateAuctionHouse {
+            authority_keypair,
+            treasury_mint,
+            fee_withdrawal_destination,
+            treasury_withdrawal_destination_owner,
+            seller_fee_basis_points,
+            requires_sign_off,
+            can_change_sale_price,
+        } => {
+            let authority = utils::read_keypair_or(authority_keypair, &payer_wallet)?;
+
+            let treasury_mint = if let Some(treasury_mint) = treasury_mint {
+                Pubkey::from_str(&treasury_mint)?
+            } else {
+                spl_token::native_mint::id()
+            };
+
+            let fee_withdrawal_destination =
+                if let Some(fee_withdrawal_destination) = fee_withdrawal_destination {
+                    Pubkey::from_str(&fee_withdrawal_destination)?
+                } else {
+                    authority.pubkey()
+                };
+
+            let treasury_withdrawal_destination_owner =
+                if let Some(owner) = treasury_withdrawal_destination_owner {
+                    Pubkey::from_str(&owner)?
+                } else {
+                    authority.pubkey()
+                };
+
+            let (tx, ui_info) = processor::create_auction_house(
+                &client,
+                &payer_wallet,
+                &authority,
+                &treasury_mint,
+                &fee_withdrawal_destination,
+                &treasury_withdrawal_destination_owner,
+                seller_fee_basis_points,
+                requires_sign_off,
+                can_change_sale_price,
+            )?;
+
+            Some(vec![(tx, ui_info)])
+        }
+        Commands::UpdateAuctionHouse {
+            auction_house,
+            authority_keypair,
+            new_authority,
+            fee_withdrawal_destination,
+            treasury_withdrawal_destination_owner,
+            seller_fee_basis_points,
+            requires_sign_off,
+            can_change_sale_price,
+            maker_fee_basis_points,
+            taker_fee_basis_points,
+            referral_fee_basis_points,
+        } => {
+            let authority = utils::read_keypair_or(authority_keypair, &payer_wallet)?;
+
+            let changes = processor::AuctionHouseChanges {
+                new_authority: new_authority.map(|x| Pubkey::from_str(&x)).transpose()?,
+                fee_withdrawal_destination: fee_withdrawal_destination
+                    .map(|x| Pubkey::from_str(&x))
+                    .transpose()?,
+                treasury_withdrawal_destination_owner: treasury_withdrawal_destination_owner
+                    .map(|x| Pubkey::from_str(&x))
+                    .transpose()?,
+                seller_fee_basis_points,
+                requires_sign_off,
+                can_change_sale_price,
+                maker_fee_basis_points,
+                taker_fee_basis_points,
+                referral_fee_basis_points,
+            };
+
+            let (tx, ui_info) = processor::update_auction_house(
+                &client,
+                &payer_wallet,
+                &authority,
+                &Pubkey::from_str(&auction_house)?,
+                changes,
+            )?;
+
+            Some(vec![(tx, ui_info)])
+        }
+        Commands::WithdrawFromFee {
+            auction_house,
+            authority_keypair,
+            amount,
+        } => {
+            let authority = utils::read_keypair_or(authority_keypair, &payer_wallet)?;
+
+            // Fee account always holds native SOL
+            let (tx, ui_info) = processor::withdraw_from_fee(
+                &client,
+                &payer_wallet,
+                &authority,
+                &Pubkey::from_str(&auction_house)?,
+                spl_token::ui_amount_to_amount(amount, spl_token::native_mint::DECIMALS),
+            )?;
+
+            Some(vec![(tx, ui_info)])
+        }
+        Commands::WithdrawFromTreasury {
+            auction_house,
+            authority_keypair,
+            amount,
+        } => {
+            let authority = utils::read_keypair_or(authority_keypair, &payer_wallet)?;
+            let auction_house = Pubkey::from_str(&auction_house)?;
+
+            let auction_house_state =
+                processor::get_account_state::<AuctionHouse>(&client, &auction_house)?;
+            let decimals = utils::get_mint(&client, &auction_house_state.treasury_mint)?.decimals;
+
+            let (tx, ui_info) = processor::withdraw_from_treasury(
+                &client,
+                &payer_wallet,
+                &authority,
+                &auction_house,
+                spl_token::ui_amount_to_amount(amount, decimals),
+            )?;
+
+            Some(vec![(tx, ui_info)])
+        }
+        Commands::MigrateAuctionHouse {
+            auction_house,
+            authority_keypair,
+        } => {
+            let authority = utils::read_keypair_or(authority_keypair, &payer_wallet)?;
+
+            let (tx, ui_info) = processor::migrate_auction_house(
+                &client,
+                &payer_wallet,
+                &authority,
+                &Pubkey::from_str(&auction_house)?,
+            )?;
+
+            Some(vec![(tx, ui_info)])
+        }
+        Commands::DelegateAuctioneer {
+            auction_house,
+            authority_keypair,
+            auctioneer_authority,
+            scopes,
+        } => {
+            let authority = utils::read_keypair_or(authority_keypair, &payer_wallet)?;
+
+            let scopes = if scopes.is_empty() {
+                vec![
+                    AuthorityScope::Deposit,
+                    AuthorityScope::Buy,
+                    AuthorityScope::PublicBuy,
+                    AuthorityScope::ExecuteSale,
+                    AuthorityScope::Sell,
+                    AuthorityScope::Cancel,
+                    AuthorityScope::Withdraw,
+                ]
+            } else {
+                scopes.into_iter().map(AuthorityScope::from).collect()
+            };
+
+            let (tx, ui_info) = processor::delegate_auctioneer(
+                &client,
+                &payer_wallet,
+                &authority,
+                &Pubkey::from_str(&auction_house)?,
+                &Pubkey::from_str(&auctioneer_authority)?,
+                scopes,
+            )?;
+
+            Some(vec![(tx, ui_info)])
+        }
+        Commands::Sell {
+            auction_house,
+            wallet_keypair,
+            authority_keypair,
+            auctioneer_keypair,
+            mint,
+            token_account,
+            price,
+            token_size,
+        } => {
+            let wallet = utils::read_keypair_or(wallet_keypair, &payer_wallet)?;
+            let authority = utils::read_keypair_or(authority_keypair, &payer_wallet)?;
+            let auctioneer = auctioneer_keypair.map(read_keypair_file).transpose()?;
+            let auction_house = Pubkey::from_str(&auction_house)?;
+
+            let auction_house_state =
+                processor::get_account_state::<AuctionHouse>(&client, &auction_house)?;
+            let decimals = utils::get_mint(&client, &auction_house_state.treasury_mint)?.decimals;
+
+            let (tx, ui_info) = processor::sell(
+                &client,
+                &payer_wallet,
+                &wallet,
+                &authority,
+                &auction_house,
+                &Pubkey::from_str(&mint)?,
+                token_account.map(|x| Pubkey::from_str(&x)).transpose()?,
+                spl_token::ui_amount_to_amount(price, decimals),
+                token_size,
+                auctioneer.as_ref(),
+            )?;
+
+            Some(vec![(tx, ui_info)])
+        }
+        Commands::Buy {
+            auction_house,
+            wallet_keypair,
+            authority_keypair,
+            auctioneer_keypair,
+            mint,
+            token_account,
+            price,
+            token_size,
+            public,
+        } => {
+            let wallet = utils::read_keypair_or(wallet_keypair, &payer_wallet)?;
+            let authority = utils::read_keypair_or(authority_keypair, &payer_wallet)?;
+            let auctioneer = auctioneer_keypair.map(read_keypair_file).transpose()?;
+            let auction_house = Pubkey::from_str(&auction_house)?;
+
+            let auction_house_state =
+                processor::get_account_state::<AuctionHouse>(&client, &auction_house)?;
+            let decimals = utils::get_mint(&client, &auction_house_state.treasury_mint)?.decimals;
+
+            let (tx, ui_info) = processor::buy(
+                &client,
+                &payer_wallet,
+                &wallet,
+                &authority,
+                &auction_house,
+                &Pubkey::from_str(&mint)?,
+                &Pubkey::from_str(&token_account)?,
+                spl_token::ui_amount_to_amount(price, decimals),
+                token_size,
+                public,
+                auctioneer.as_ref(),
+            )?;
+
+            Some(vec![(tx, ui_info)])
+        }
+        Commands::ExecuteSale {
+            auction_house,
+            authority_keypair,
+            auctioneer_keypair,
+            mint,
+            buyer,
+            seller,
+            token_account,
+            price,
+            token_size,
+            partial_order_size,
+            partial_order_price,
+            public_bid,
+        } => {
+            let authority = utils::read_keypair_or(authority_keypair, &payer_wallet)?;
+            let auctioneer = auctioneer_keypair.map(read_keypair_file).transpose()?;
+            let auction_house = Pubkey::from_str(&auction_house)?;
+            let mint = Pubkey::from_str(&mint)?;
+            let seller = Pubkey::from_str(&seller)?;
+
+            let auction_house_state =
+                processor::get_account_state::<AuctionHouse>(&client, &auction_house)?;
+            let decimals = utils::get_mint(&client, &auction_house_state.treasury_mint)?.decimals;
+
+            let partial_order = match (partial_order_size, partial_order_price) {
+                (Some(size), Some(price)) => {
+                    Some((size, spl_token::ui_amount_to_amount(price, decimals)))
+                }
+                (None, None) => None,
+                _ => {
+                    return Err(error::Error::DynamicError(String::from(
+                        "partial order requires both size and price",
+                    )))
+                }
+            };
+
+            let token_account = if let Some(token_account) = token_account {
+                Pubkey::from_str(&token_account)?
+            } else {
+                spl_associated_token_account::get_associated_token_address(&seller, &mint)
+            };
+
+            let order = processor::SaleOrder {
+                buyer: Pubkey::from_str(&buyer)?,
+                seller,
+                token_account,
+                price: spl_token::ui_amount_to_amount(price, decimals),
+                token_size,
+                partial_order,
+                public_bid,
+            };
+
+            let (tx, ui_info) = processor::execute_sale(
+                &client,
+                &payer_wallet,
+                &authority,
+                &auction_house,
+                &mint,
+                order,
+                auctioneer.as_ref(),
+            )?;
+
+            Some(vec![(tx, ui_info)])
+        }
+    };
+
+    // Send builded transactions
+    if let Some(txs_bundle) = txs_data {
+        for (tx, ui_info) in txs_bundle {
+            client.send_and_confirm_transaction(&tx)?;
+            ui_info.print();
+            println!();
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! Module provide handler for `Buy` command.
+
+use super::{get_account_state, UiTransactionInfo};
+use crate::{error, utils};
+use mpl_auction_house::{
+    client::{print_bid_receipt, BuyBuilder},
+    pda::find_bid_receipt_address,
+    AuctionHouse,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey, signature::Signer, signer::keypair::Keypair, transaction::Transaction,
+};
+
+/// Additional `Buy` instruction info, that need to be displayed in TUI.
+#[derive(Debug)]
+pub struct BuyUiInfo {
+    buyer_trade_state: Pubkey,
+    escrow_payment_account: Pubkey,
+    bid_receipt: Pubkey,
+}
+
+impl UiTransactionInfo for BuyUiInfo {
+    fn print(&self) {
+        println!("Buy::buyer_trade_state - {}", self.buyer_trade_state);
+        println!(
+            "Buy::escrow_payment_account - {}",
+            self.escrow_payment_account
+        );
+        println!("Buy::bid_receipt - {}", self.bid_receipt);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn buy(
+    client: &RpcClient,
+    payer: &Keypair,
+    wallet: &Keypair,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    price: u64,
+    token_size: u64,
+    public: bool,
+    auctioneer: Option<&Keypair>,
+) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
+    let auction_house_state = get_account_state::<AuctionHouse>(client, auction_house)?;
+    let metadata = utils::get_metadata(client, mint)?;
+    let treasury_token_program =
+        utils::get_token_program(client, &auction_house_state.treasury_mint)?;
+
+    let mut builder = BuyBuilder::new(
+        *auction_house,
+        &auction_house_state,
+        &metadata,
+        wallet.pubkey(),
+        *token_account,
+        price,
+    )
+    .token_size(token_size)
+    .treasury_token_program(treasury_token_program);
+    if public {
+        builder = builder.public();
+    }
+    if let Some(auctioneer) = auctioneer {
+        builder = builder.auctioneer(auctioneer.pubkey());
+    }
+
+    let buyer_trade_state = builder.buyer_trade_state();
+    let (bid_receipt, _) = find_bid_receipt_address(&buyer_trade_state);
+
+    let mut signers = vec![payer, wallet];
+    if auction_house_state.requires_sign_off {
+        signers.push(authority);
+    }
+    signers.extend(auctioneer);
+
+    let recent_blockhash = client.get_latest_blockhash()?;
+
+    Ok((
+        Transaction::new_signed_with_payer(
+            &[
+                builder.instruction(),
+                print_bid_receipt(&buyer_trade_state, &payer.pubkey()),
+            ],
+            Some(&payer.pubkey()),
+            &utils::unique_signers(&signers),
+            recent_blockhash,
+        ),
+        Box::new(BuyUiInfo {
+            buyer_trade_state,
+            escrow_payment_account: builder.escrow_payment_account(),
+            bid_receipt,
+        }),
+    ))
+}
@@ -0,0 +1,111 @@
+//! Module provide handler for `CreateAuctionHouse` command.
+
+use super::UiTransactionInfo;
+use crate::error;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use mpl_auction_house::pda::{
+    find_auction_house_address, find_auction_house_fee_account_address,
+    find_auction_house_treasury_address,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+    system_program, sysvar, transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+/// Additional `CreateAuctionHouse` instruction info, that need to be displayed in TUI.
+#[derive(Debug)]
+pub struct CreateAuctionHouseUiInfo {
+    auction_house: Pubkey,
+    auction_house_fee_account: Pubkey,
+    auction_house_treasury: Pubkey,
+}
+
+impl UiTransactionInfo for CreateAuctionHouseUiInfo {
+    fn print(&self) {
+        println!("CreateAuctionHouse::auction_house - {}", self.auction_house);
+        println!(
+            "CreateAuctionHouse::auction_house_fee_account - {}",
+            self.auction_house_fee_account
+        );
+        println!(
+            "CreateAuctionHouse::auction_house_treasury - {}",
+            self.auction_house_treasury
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_auction_house(
+    client: &RpcClient,
+    payer: &Keypair,
+    authority: &Keypair,
+    treasury_mint: &Pubkey,
+    fee_withdrawal_destination: &Pubkey,
+    treasury_withdrawal_destination_owner: &Pubkey,
+    seller_fee_basis_points: u16,
+    requires_sign_off: bool,
+    can_change_sale_price: bool,
+) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
+    let (auction_house, bump) = find_auction_house_address(&authority.pubkey(), treasury_mint);
+    let (auction_house_fee_account, fee_payer_bump) =
+        find_auction_house_fee_account_address(&auction_house);
+    let (auction_house_treasury, treasury_bump) =
+        find_auction_house_treasury_address(&auction_house);
+
+    let treasury_withdrawal_destination = if *treasury_mint == spl_token::native_mint::id() {
+        *treasury_withdrawal_destination_owner
+    } else {
+        get_associated_token_address(treasury_withdrawal_destination_owner, treasury_mint)
+    };
+
+    let accounts = mpl_auction_house::accounts::CreateAuctionHouse {
+        treasury_mint: *treasury_mint,
+        payer: payer.pubkey(),
+        authority: authority.pubkey(),
+        fee_withdrawal_destination: *fee_withdrawal_destination,
+        treasury_withdrawal_destination,
+        treasury_withdrawal_destination_owner: *treasury_withdrawal_destination_owner,
+        auction_house,
+        auction_house_fee_account,
+        auction_house_treasury,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::CreateAuctionHouse {
+        _bump: bump,
+        fee_payer_bump,
+        treasury_bump,
+        seller_fee_basis_points,
+        requires_sign_off,
+        can_change_sale_price,
+    }
+    .data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+
+    let recent_blockhash = client.get_latest_blockhash()?;
+
+    Ok((
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[payer, authority],
+            recent_blockhash,
+        ),
+        Box::new(CreateAuctionHouseUiInfo {
+            auction_house,
+            auction_house_fee_account,
+            auction_house_treasury,
+        }),
+    ))
+}
@@ -0,0 +1,66 @@
+//! Module provide handler for `DelegateAuctioneer` command.
+
+use super::UiTransactionInfo;
+use crate::error;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use mpl_auction_house::{pda::find_auctioneer_pda, AuthorityScope};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+    system_program, transaction::Transaction,
+};
+
+/// Additional `DelegateAuctioneer` instruction info, that need to be displayed in TUI.
+#[derive(Debug)]
+pub struct DelegateAuctioneerUiInfo {
+    ah_auctioneer_pda: Pubkey,
+}
+
+impl UiTransactionInfo for DelegateAuctioneerUiInfo {
+    fn print(&self) {
+        println!(
+            "DelegateAuctioneer::ah_auctioneer_pda - {}",
+            self.ah_auctioneer_pda
+        );
+    }
+}
+
+pub fn delegate_auctioneer(
+    client: &RpcClient,
+    payer: &Keypair,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    auctioneer_authority: &Pubkey,
+    scopes: Vec<AuthorityScope>,
+) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
+    let (ah_auctioneer_pda, _) = find_auctioneer_pda(auction_house, auctioneer_authority);
+
+    let accounts = mpl_auction_house::accounts::DelegateAuctioneer {
+        auction_house: *auction_house,
+        authority: authority.pubkey(),
+        auctioneer_authority: *auctioneer_authority,
+        ah_auctioneer_pda,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::DelegateAuctioneer { scopes }.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+
+    let recent_blockhash = client.get_latest_blockhash()?;
+
+    Ok((
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[payer, authority],
+            recent_blockhash,
+        ),
+        Box::new(DelegateAuctioneerUiInfo { ah_auctioneer_pda }),
+    ))
+}
@@ -0,0 +1,125 @@
+//! Module provide handler for `ExecuteSale` command.
+
+use super::{get_account_state, UiTransactionInfo};
+use crate::{error, utils};
+use mpl_auction_house::{
+    client::{print_purchase_receipt, purchase_royalty_accounts, ExecuteSaleBuilder},
+    pda::{find_bid_receipt_address, find_listing_receipt_address, find_purchase_receipt_address},
+    AuctionHouse,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey, signature::Signer, signer::keypair::Keypair, transaction::Transaction,
+};
+
+/// Additional `ExecuteSale` instruction info, that need to be displayed in TUI.
+#[derive(Debug)]
+pub struct ExecuteSaleUiInfo {
+    buyer_receipt_token_account: Pubkey,
+    purchase_receipt: Option<Pubkey>,
+}
+
+impl UiTransactionInfo for ExecuteSaleUiInfo {
+    fn print(&self) {
+        println!(
+            "ExecuteSale::buyer_receipt_token_account - {}",
+            self.buyer_receipt_token_account
+        );
+        if let Some(purchase_receipt) = self.purchase_receipt {
+            println!("ExecuteSale::purchase_receipt - {}", purchase_receipt);
+        }
+    }
+}
+
+/// Order filled by `ExecuteSale` command.
+#[derive(Debug)]
+pub struct SaleOrder {
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub token_account: Pubkey,
+    pub price: u64,
+    pub token_size: u64,
+    pub partial_order: Option<(u64, u64)>,
+    pub public_bid: bool,
+}
+
+pub fn execute_sale(
+    client: &RpcClient,
+    payer: &Keypair,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    mint: &Pubkey,
+    order: SaleOrder,
+    auctioneer: Option<&Keypair>,
+) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
+    let auction_house_state = get_account_state::<AuctionHouse>(client, auction_house)?;
+    let metadata = utils::get_metadata(client, mint)?;
+    let treasury_token_program =
+        utils::get_token_program(client, &auction_house_state.treasury_mint)?;
+
+    let mut builder = ExecuteSaleBuilder::new(
+        *auction_house,
+        &auction_house_state,
+        &metadata,
+        order.buyer,
+        order.seller,
+        order.token_account,
+        order.price,
+    )
+    .token_size(order.token_size)
+    .treasury_token_program(treasury_token_program);
+    if let Some((size, price)) = order.partial_order {
+        builder = builder.partial_order(size, price);
+    }
+    if order.public_bid {
+        builder = builder.public_bid();
+    }
+    if let Some(auctioneer) = auctioneer {
+        builder = builder.auctioneer(auctioneer.pubkey());
+    }
+
+    let seller_trade_state = builder.seller_trade_state();
+    let buyer_trade_state = builder.buyer_trade_state();
+    let mut instructions = vec![builder.instruction()];
+
+    // Purchase receipts are only printed for full sales of orders that have both receipts.
+    let purchase_receipt = if order.partial_order.is_none()
+        && !utils::is_account_empty(client, &find_listing_receipt_address(&seller_trade_state).0)?
+        && !utils::is_account_empty(client, &find_bid_receipt_address(&buyer_trade_state).0)?
+    {
+        let mut receipt =
+            print_purchase_receipt(&seller_trade_state, &buyer_trade_state, &payer.pubkey());
+        receipt.accounts.append(&mut purchase_royalty_accounts(
+            auction_house,
+            &auction_house_state,
+        ));
+        instructions.push(receipt);
+        Some(find_purchase_receipt_address(&seller_trade_state, &buyer_trade_state).0)
+    } else {
+        None
+    };
+
+    let mut signers = vec![payer];
+    if auction_house_state.requires_sign_off {
+        signers.push(authority);
+    }
+    signers.extend(auctioneer);
+
+    let recent_blockhash = client.get_latest_blockhash()?;
+
+    Ok((
+        Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &utils::unique_signers(&signers),
+            recent_blockhash,
+        ),
+        Box::new(ExecuteSaleUiInfo {
+            buyer_receipt_token_account: spl_associated_token_account::get_associated_token_address(
+                &order.buyer,
+                mint,
+            ),
+            purchase_receipt,
+        }),
+    ))
+}
@@ -0,0 +1,49 @@
+//! Module provide handler for `MigrateAuctionHouse` command.
+
+use super::{get_account_state, UiTransactionInfo};
+use crate::error;
+use mpl_auction_house::{client, constants::AUCTION_HOUSE_VERSION, AuctionHouse};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey, signature::Signer, signer::keypair::Keypair, transaction::Transaction,
+};
+
+/// Additional `MigrateAuctionHouse` instruction info, that need to be displayed in TUI.
+#[derive(Debug)]
+pub struct MigrateAuctionHouseUiInfo {
+    version: u8,
+}
+
+impl UiTransactionInfo for MigrateAuctionHouseUiInfo {
+    fn print(&self) {
+        println!(
+            "MigrateAuctionHouse::version - {} -> {}",
+            self.version, AUCTION_HOUSE_VERSION
+        );
+    }
+}
+
+pub fn migrate_auction_house(
+    client: &RpcClient,
+    payer: &Keypair,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
+    let auction_house_state = get_account_state::<AuctionHouse>(client, auction_house)?;
+
+    let instruction = client::migrate_auction_house(auction_house, &authority.pubkey());
+
+    let recent_blockhash = client.get_latest_blockhash()?;
+
+    Ok((
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[payer, authority],
+            recent_blockhash,
+        ),
+        Box::new(MigrateAuctionHouseUiInfo {
+            version: auction_house_state.version,
+        }),
+    ))
+}
@@ -0,0 +1,28 @@
+//! Module provide instructions builder for `mpl_auction_house` program.
+
+mod buy;
+mod create_auction_house;
+mod delegate_auctioneer;
+mod execute_sale;
+mod get_account_state;
+mod migrate_auction_house;
+mod sell;
+mod update_auction_house;
+mod withdraw_from_fee;
+mod withdraw_from_treasury;
+pub use buy::*;
+pub use create_auction_house::*;
+pub use delegate_auctioneer::*;
+pub use execute_sale::*;
+pub use get_account_state::*;
+pub use migrate_auction_house::*;
+pub use sell::*;
+pub use update_auction_house::*;
+pub use withdraw_from_fee::*;
+pub use withdraw_from_treasury::*;
+
+/// Abstract trait to print additional information in tui.
+/// Can be implemented while building instruction.
+pub trait UiTransactionInfo {
+    fn print(&self);
+}
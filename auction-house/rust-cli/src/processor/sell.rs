@@ -0,0 +1,86 @@
+//! Module provide handler for `Sell` command.
+
+use super::{get_account_state, UiTransactionInfo};
+use crate::{error, utils};
+use mpl_auction_house::{
+    client::{print_listing_receipt, SellBuilder},
+    pda::find_listing_receipt_address,
+    AuctionHouse,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey, signature::Signer, signer::keypair::Keypair, transaction::Transaction,
+};
+
+/// Additional `Sell` instruction info, that need to be displayed in TUI.
+#[derive(Debug)]
+pub struct SellUiInfo {
+    seller_trade_state: Pubkey,
+    listing_receipt: Pubkey,
+}
+
+impl UiTransactionInfo for SellUiInfo {
+    fn print(&self) {
+        println!("Sell::seller_trade_state - {}", self.seller_trade_state);
+        println!("Sell::listing_receipt - {}", self.listing_receipt);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn sell(
+    client: &RpcClient,
+    payer: &Keypair,
+    wallet: &Keypair,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    mint: &Pubkey,
+    token_account: Option<Pubkey>,
+    price: u64,
+    token_size: u64,
+    auctioneer: Option<&Keypair>,
+) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
+    let auction_house_state = get_account_state::<AuctionHouse>(client, auction_house)?;
+    let metadata = utils::get_metadata(client, mint)?;
+
+    let mut builder = SellBuilder::new(
+        *auction_house,
+        &auction_house_state,
+        &metadata,
+        wallet.pubkey(),
+        price,
+    )
+    .token_size(token_size);
+    if let Some(token_account) = token_account {
+        builder = builder.token_account(token_account);
+    }
+    if let Some(auctioneer) = auctioneer {
+        builder = builder.auctioneer(auctioneer.pubkey());
+    }
+
+    let seller_trade_state = builder.seller_trade_state();
+    let (listing_receipt, _) = find_listing_receipt_address(&seller_trade_state);
+
+    let mut signers = vec![payer, wallet];
+    if auction_house_state.requires_sign_off {
+        signers.push(authority);
+    }
+    signers.extend(auctioneer);
+
+    let recent_blockhash = client.get_latest_blockhash()?;
+
+    Ok((
+        Transaction::new_signed_with_payer(
+            &[
+                builder.instruction(),
+                print_listing_receipt(&seller_trade_state, &payer.pubkey()),
+            ],
+            Some(&payer.pubkey()),
+            &utils::unique_signers(&signers),
+            recent_blockhash,
+        ),
+        Box::new(SellUiInfo {
+            seller_trade_state,
+            listing_receipt,
+        }),
+    ))
+}
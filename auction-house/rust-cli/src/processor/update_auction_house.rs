@@ -0,0 +1,107 @@
+//! Module provide handler for `UpdateAuctionHouse` command.
+
+use super::{get_account_state, UiTransactionInfo};
+use crate::error;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use mpl_auction_house::AuctionHouse;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+    system_program, sysvar, transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+/// Additional `UpdateAuctionHouse` instruction info, that need to be displayed in TUI.
+#[derive(Debug)]
+pub struct UpdateAuctionHouseUiInfo {}
+
+impl UiTransactionInfo for UpdateAuctionHouseUiInfo {
+    fn print(&self) {}
+}
+
+/// Fields of the Auction House to change, `None` keeps the current value.
+#[derive(Debug, Default)]
+pub struct AuctionHouseChanges {
+    pub new_authority: Option<Pubkey>,
+    pub fee_withdrawal_destination: Option<Pubkey>,
+    pub treasury_withdrawal_destination_owner: Option<Pubkey>,
+    pub seller_fee_basis_points: Option<u16>,
+    pub requires_sign_off: Option<bool>,
+    pub can_change_sale_price: Option<bool>,
+    pub maker_fee_basis_points: Option<u16>,
+    pub taker_fee_basis_points: Option<u16>,
+    pub referral_fee_basis_points: Option<u16>,
+}
+
+pub fn update_auction_house(
+    client: &RpcClient,
+    payer: &Keypair,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    changes: AuctionHouseChanges,
+) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
+    let auction_house_state = get_account_state::<AuctionHouse>(client, auction_house)?;
+    let treasury_mint = auction_house_state.treasury_mint;
+
+    // Without a new owner the treasury withdrawal destination is kept, and is its own owner.
+    let (treasury_withdrawal_destination, treasury_withdrawal_destination_owner) =
+        match changes.treasury_withdrawal_destination_owner {
+            Some(owner) if treasury_mint != spl_token::native_mint::id() => {
+                (get_associated_token_address(&owner, &treasury_mint), owner)
+            }
+            Some(owner) => (owner, owner),
+            None => (
+                auction_house_state.treasury_withdrawal_destination,
+                auction_house_state.treasury_withdrawal_destination,
+            ),
+        };
+
+    let accounts = mpl_auction_house::accounts::UpdateAuctionHouse {
+        treasury_mint,
+        payer: payer.pubkey(),
+        authority: authority.pubkey(),
+        new_authority: changes
+            .new_authority
+            .unwrap_or(auction_house_state.authority),
+        fee_withdrawal_destination: changes
+            .fee_withdrawal_destination
+            .unwrap_or(auction_house_state.fee_withdrawal_destination),
+        treasury_withdrawal_destination,
+        treasury_withdrawal_destination_owner,
+        auction_house: *auction_house,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::UpdateAuctionHouse {
+        seller_fee_basis_points: changes.seller_fee_basis_points,
+        requires_sign_off: changes.requires_sign_off,
+        can_change_sale_price: changes.can_change_sale_price,
+        maker_fee_basis_points: changes.maker_fee_basis_points,
+        taker_fee_basis_points: changes.taker_fee_basis_points,
+        referral_fee_basis_points: changes.referral_fee_basis_points,
+        escrow_reservation_mode: None,
+    }
+    .data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+
+    let recent_blockhash = client.get_latest_blockhash()?;
+
+    Ok((
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[payer, authority],
+            recent_blockhash,
+        ),
+        Box::new(UpdateAuctionHouseUiInfo {}),
+    ))
+}
@@ -0,0 +1,77 @@
+//! Module provide handler for `WithdrawFromFee` command.
+
+use super::{get_account_state, UiTransactionInfo};
+use crate::error;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use mpl_auction_house::{pda::find_governance_address, AuctionHouse};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    signer::keypair::Keypair,
+    system_program,
+    transaction::Transaction,
+};
+
+/// Additional `WithdrawFromFee` instruction info, that need to be displayed in TUI.
+#[derive(Debug)]
+pub struct WithdrawFromFeeUiInfo {
+    fee_withdrawal_destination: Pubkey,
+}
+
+impl UiTransactionInfo for WithdrawFromFeeUiInfo {
+    fn print(&self) {
+        println!(
+            "WithdrawFromFee::fee_withdrawal_destination - {}",
+            self.fee_withdrawal_destination
+        );
+    }
+}
+
+pub fn withdraw_from_fee(
+    client: &RpcClient,
+    payer: &Keypair,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    amount: u64,
+) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
+    let auction_house_state = get_account_state::<AuctionHouse>(client, auction_house)?;
+
+    let mut accounts = mpl_auction_house::accounts::WithdrawFromFee {
+        authority: authority.pubkey(),
+        fee_withdrawal_destination: auction_house_state.fee_withdrawal_destination,
+        auction_house_fee_account: auction_house_state.auction_house_fee_account,
+        auction_house: *auction_house,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    // Governed houses add the amount to the fee withdrawals of the period kept by their governance.
+    if auction_house_state.has_governance {
+        let (governance, _) = find_governance_address(auction_house);
+        accounts.push(AccountMeta::new(governance, false));
+    }
+
+    let data = mpl_auction_house::instruction::WithdrawFromFee { amount }.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+
+    let recent_blockhash = client.get_latest_blockhash()?;
+
+    Ok((
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[payer, authority],
+            recent_blockhash,
+        ),
+        Box::new(WithdrawFromFeeUiInfo {
+            fee_withdrawal_destination: auction_house_state.fee_withdrawal_destination,
+        }),
+    ))
+}
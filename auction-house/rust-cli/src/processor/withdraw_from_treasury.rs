@@ -0,0 +1,82 @@
+//! Module provide handler for `WithdrawFromTreasury` command.
+
+use super::{get_account_state, UiTransactionInfo};
+use crate::{error, utils};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use mpl_auction_house::{pda::find_governance_address, AuctionHouse};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    signer::keypair::Keypair,
+    system_program,
+    transaction::Transaction,
+};
+
+/// Additional `WithdrawFromTreasury` instruction info, that need to be displayed in TUI.
+#[derive(Debug)]
+pub struct WithdrawFromTreasuryUiInfo {
+    treasury_withdrawal_destination: Pubkey,
+}
+
+impl UiTransactionInfo for WithdrawFromTreasuryUiInfo {
+    fn print(&self) {
+        println!(
+            "WithdrawFromTreasury::treasury_withdrawal_destination - {}",
+            self.treasury_withdrawal_destination
+        );
+    }
+}
+
+pub fn withdraw_from_treasury(
+    client: &RpcClient,
+    payer: &Keypair,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    amount: u64,
+) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
+    let auction_house_state = get_account_state::<AuctionHouse>(client, auction_house)?;
+    let treasury_mint = auction_house_state.treasury_mint;
+
+    let token_program = utils::get_token_program(client, &treasury_mint)?;
+
+    let mut accounts = mpl_auction_house::accounts::WithdrawFromTreasury {
+        treasury_mint,
+        authority: authority.pubkey(),
+        treasury_withdrawal_destination: auction_house_state.treasury_withdrawal_destination,
+        auction_house_treasury: auction_house_state.auction_house_treasury,
+        auction_house: *auction_house,
+        token_program,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    // Governed houses add the amount to the withdrawals of the period kept by their governance.
+    if auction_house_state.has_governance {
+        let (governance, _) = find_governance_address(auction_house);
+        accounts.push(AccountMeta::new(governance, false));
+    }
+
+    let data = mpl_auction_house::instruction::WithdrawFromTreasury { amount }.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+
+    let recent_blockhash = client.get_latest_blockhash()?;
+
+    Ok((
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[payer, authority],
+            recent_blockhash,
+        ),
+        Box::new(WithdrawFromTreasuryUiInfo {
+            treasury_withdrawal_destination: auction_house_state.treasury_withdrawal_destination,
+        }),
+    ))
+}
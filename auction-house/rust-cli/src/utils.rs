@@ -0,0 +1,89 @@
+//! Module define application utils.
+
+use crate::error;
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::Signer,
+    signer::keypair::{read_keypair_file, Keypair},
+};
+use spl_token::state::Mint;
+
+/// Return `Mint` account state from `spl_token` program.
+pub fn get_mint(client: &RpcClient, mint: &Pubkey) -> Result<Mint, error::Error> {
+    let data = client.get_account_data(mint)?;
+    Ok(Mint::unpack(&data)?)
+}
+
+/// Return `Metadata` account state from `mpl_token_metadata` program for `mint`.
+pub fn get_metadata(client: &RpcClient, mint: &Pubkey) -> Result<Metadata, error::Error> {
+    let (metadata, _) = mpl_token_metadata::pda::find_metadata_account(mint);
+    let data = client.get_account_data(&metadata)?;
+
+    Ok(Metadata::safe_deserialize(&data)?)
+}
+
+/// Check if `account` does not exist or is empty.
+pub fn is_account_empty(client: &RpcClient, account: &Pubkey) -> Result<bool, error::Error> {
+    let account = client.get_account_with_commitment(account, client.commitment())?;
+
+    Ok(account
+        .value
+        .map(|account| account.data.is_empty())
+        .unwrap_or(true))
+}
+
+/// Return `Clone`'d `Keypair`.
+pub fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
+
+/// Return `keypairs` without duplicated signers, keeping the first occurrence.
+pub fn unique_signers<'a>(keypairs: &[&'a Keypair]) -> Vec<&'a Keypair> {
+    let mut signers: Vec<&Keypair> = Vec::with_capacity(keypairs.len());
+
+    for keypair in keypairs {
+        if !signers
+            .iter()
+            .any(|signer| signer.pubkey() == keypair.pubkey())
+        {
+            signers.push(keypair);
+        }
+    }
+
+    signers
+}
+
+/// Return the token program owning `mint`, `spl_token` or Token-2022.
+pub fn get_token_program(client: &RpcClient, mint: &Pubkey) -> Result<Pubkey, error::Error> {
+    Ok(client.get_account(mint)?.owner)
+}
+
+/// Read keypair from `path` if provided, otherwise `Clone` the `default` one.
+pub fn read_keypair_or(path: Option<String>, default: &Keypair) -> Result<Keypair, error::Error> {
+    Ok(if let Some(path) = path {
+        read_keypair_file(path)?
+    } else {
+        clone_keypair(default)
+    })
+}
+
+/// Return escrow balance held in `escrow_payment_account`, which is a token account for SPL treasury mints.
+pub fn get_escrow_balance(
+    client: &RpcClient,
+    escrow_payment_account: &Pubkey,
+    treasury_mint: &Pubkey,
+) -> Result<u64, error::Error> {
+    if is_account_empty(client, escrow_payment_account)? {
+        return Ok(0);
+    }
+
+    if *treasury_mint == spl_token::native_mint::id() {
+        Ok(client.get_balance(escrow_payment_account)?)
+    } else {
+        let data = client.get_account_data(escrow_payment_account)?;
+        Ok(spl_token::state::Account::unpack_from_slice(&data)?.amount)
+    }
+}